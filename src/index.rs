@@ -5,7 +5,10 @@ use {
     updater::Updater,
   },
   super::*,
-  bitcoin::block::Header,
+  bitcoin::{
+    block::Header,
+    hashes::{sha256, HashEngine},
+  },
   bitcoincore_rpc::{
     json::{GetBlockHeaderResult, GetBlockResult},
     Client,
@@ -26,7 +29,10 @@ use {
       ord::{self, redb::try_init_tables as try_init_ord, DataStoreReadOnly},
       ScriptKey,
     },
-    protocol::brc20s::params::NATIVE_TOKEN_DECIMAL,
+    protocol::{
+      brc20s::{self as brc20s_proto, params::NATIVE_TOKEN_DECIMAL, OperationCache},
+      DeployWebhook, JsonLimits,
+    },
     reward,
   },
   redb::{
@@ -39,10 +45,9 @@ use {
 
 use crate::okx::datastore::ord::{bitmap::District, collections::CollectionKind};
 
-pub(super) use self::{
-  entry::{InscriptionEntry, InscriptionEntryValue},
-  updater::BlockData,
-};
+pub(super) use self::entry::{InscriptionEntry, InscriptionEntryValue};
+
+pub(crate) use self::reorg::MAX_REORG_DEPTH;
 
 mod entry;
 mod fetcher;
@@ -50,7 +55,7 @@ mod reorg;
 mod rtx;
 mod updater;
 
-const SCHEMA_VERSION: u64 = 6;
+const SCHEMA_VERSION: u64 = 19;
 
 macro_rules! define_table {
   ($name:ident, $key:ty, $value:ty) => {
@@ -70,6 +75,7 @@ define_multimap_table! { SATPOINT_TO_INSCRIPTION_ID, &SatPointValue, &Inscriptio
 define_multimap_table! { SAT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
 define_table! { HEIGHT_TO_BLOCK_HASH, u64, &BlockHashValue }
 define_table! { HEIGHT_TO_LAST_INSCRIPTION_NUMBER, u64, (i64, i64) }
+define_table! { HEIGHT_TO_PENDING_OPERATIONS, u64, &[u8] }
 define_table! { INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
 define_table! { INSCRIPTION_ID_TO_SATPOINT, &InscriptionIdValue, &SatPointValue }
 define_table! { INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, i64, &InscriptionIdValue }
@@ -95,6 +101,8 @@ pub(crate) enum Statistic {
   OutputsTraversed = 3,
   SatRanges = 4,
   UnboundInscriptions = 5,
+  LenientContentType = 6,
+  BurnAddresses = 7,
 }
 
 impl Statistic {
@@ -159,6 +167,8 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 pub(crate) struct Index {
   client: Client,
   database: Database,
+  brc20s_operation_cache: OperationCache,
+  deploy_webhook: Option<DeployWebhook>,
   durability: redb::Durability,
   first_inscription_height: u64,
   genesis_block_coinbase_transaction: Transaction,
@@ -186,14 +196,7 @@ impl Index {
       );
     }
 
-    let db_cache_size = match options.db_cache_size {
-      Some(db_cache_size) => db_cache_size,
-      None => {
-        let mut sys = System::new();
-        sys.refresh_memory();
-        usize::try_from(sys.total_memory() / 4)?
-      }
-    };
+    let db_cache_size = options.db_cache_size()?;
 
     if let Ok(mut file) = fs::OpenOptions::new().read(true).open(&path) {
       // use cberner's quick hack to check the redb recovery bit
@@ -244,6 +247,24 @@ impl Index {
           }
         }
 
+        // the options which change the indexed protocol state must stay the same for an index.
+        let rtx = database.begin_read()?;
+        let statistics = rtx.open_table(STATISTIC_TO_COUNT)?;
+        for (statistic, option, value) in Self::protocol_options(options)? {
+          let indexed = statistics
+            .get(&statistic.key())?
+            .map(|x| x.value())
+            .unwrap_or(0);
+          if indexed != value {
+            bail!(
+              "index at `{}` was built with a different {option} setting, which changes the indexed BRC20 and BRC20S state, consider passing the same setting or deleting and rebuilding the index",
+              path.display()
+            );
+          }
+        }
+        drop(statistics);
+        drop(rtx);
+
         database
       }
       Err(_) => {
@@ -260,6 +281,7 @@ impl Index {
         tx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
         tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
         tx.open_table(HEIGHT_TO_LAST_INSCRIPTION_NUMBER)?;
+        tx.open_table(HEIGHT_TO_PENDING_OPERATIONS)?;
         tx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
@@ -268,8 +290,12 @@ impl Index {
         tx.open_table(SAT_TO_SATPOINT)?;
         tx.open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?;
 
-        tx.open_table(STATISTIC_TO_COUNT)?
-          .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
+        let mut statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+        statistics.insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
+        for (statistic, _, value) in Self::protocol_options(options)? {
+          statistics.insert(&statistic.key(), &value)?;
+        }
+        drop(statistics);
 
         if options.index_sats {
           tx.open_table(OUTPOINT_TO_SAT_RANGES)?
@@ -297,6 +323,12 @@ impl Index {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
       client,
       database,
+      brc20s_operation_cache: OperationCache::new(options.brc20s_operation_cache_size),
+      deploy_webhook: options
+        .deploy_webhook
+        .as_deref()
+        .map(DeployWebhook::new)
+        .transpose()?,
       durability,
       first_inscription_height: options.first_inscription_height(),
       genesis_block_coinbase_transaction,
@@ -388,6 +420,75 @@ impl Index {
     }
   }
 
+  pub(crate) fn has_bitmap_index(&self) -> bool {
+    self.options.enable_index_bitmap
+  }
+
+  pub(crate) fn has_brc20_index(&self) -> bool {
+    self.options.enable_index_brc20
+  }
+
+  pub(crate) fn has_brc20s_index(&self) -> bool {
+    self.options.enable_index_brc20s
+  }
+
+  // the options which change the indexed protocol state, with the statistic recording them when the
+  // index is created. The burn addresses are recorded as a fingerprint independent of their order,
+  // 0 without any.
+  fn protocol_options(options: &Options) -> Result<[(Statistic, &'static str, u64); 2]> {
+    let network = options.chain().network();
+    let mut burn_scripts = options
+      .burn_address
+      .iter()
+      .map(|address| Ok(address.clone().require_network(network)?.script_pubkey()))
+      .collect::<Result<Vec<_>>>()?;
+    burn_scripts.sort();
+    burn_scripts.dedup();
+    let burn_addresses = if burn_scripts.is_empty() {
+      0
+    } else {
+      let mut engine = sha256::Hash::engine();
+      for script in &burn_scripts {
+        engine.input(&u64::try_from(script.len()).unwrap().to_le_bytes());
+        engine.input(script.as_bytes());
+      }
+      u64::from_le_bytes(
+        sha256::Hash::from_engine(engine).to_byte_array()[..8]
+          .try_into()
+          .unwrap(),
+      )
+    };
+
+    Ok([
+      (
+        Statistic::LenientContentType,
+        "--lenient-content-type",
+        options.lenient_content_type.into(),
+      ),
+      (Statistic::BurnAddresses, "--burn-address", burn_addresses),
+    ])
+  }
+
+  pub(crate) fn has_balance_history(&self) -> bool {
+    self.options.enable_balance_history
+  }
+
+  pub(crate) fn has_block_mutations(&self) -> bool {
+    self.options.enable_block_mutations
+  }
+
+  pub(crate) fn has_address_history(&self) -> bool {
+    self.options.enable_address_history
+  }
+
+  pub(crate) fn lenient_content_type(&self) -> bool {
+    self.options.lenient_content_type
+  }
+
+  pub(crate) fn protocol_json_limits(&self) -> JsonLimits {
+    self.options.protocol_json_limits()
+  }
+
   fn require_sat_index(&self, feature: &str) -> Result {
     if !self.has_sat_index()? {
       bail!("{feature} requires index created with `--index-sats` flag")
@@ -543,6 +644,132 @@ impl Index {
     Ok(())
   }
 
+  pub(crate) fn export_brc20_balances(&self, filename: &Path, height: u64) -> Result {
+    let rtx = rtx::Rtx(self.database.begin_read()?);
+
+    let indexed_height = rtx.block_height()?.map(|height| height.n());
+    if indexed_height != Some(height) {
+      bail!(
+        "index is at height {}, cannot export brc20 balances at height {height}",
+        indexed_height.map_or("none".to_string(), |height| height.to_string())
+      );
+    }
+
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "address,tick,available,transferable")?;
+
+    log::info!(
+      "exporting brc20 balances at height {height} to {}",
+      filename.display()
+    );
+
+    brc20_db::DataStoreReader::new(&rtx.0).get_all_acc_balance(|script, balance| {
+      writeln!(
+        writer,
+        "{},{},{},{}",
+        script,
+        csv_escape(balance.tick.as_str()),
+        balance.overall_balance - balance.transferable_balance,
+        balance.transferable_balance
+      )?;
+      Ok(())
+    })?;
+
+    writer.flush()?;
+    Ok(())
+  }
+
+  /// Writes one JSON object per line for every receipt of `protocol` in the blocks from
+  /// `from_height` to `to_height`, block by block, and returns the number of receipts written.
+  pub(crate) fn export_receipts(
+    &self,
+    protocol: ord::Protocol,
+    from_height: u64,
+    to_height: u64,
+    writer: &mut impl Write,
+  ) -> Result<u64> {
+    #[derive(Serialize)]
+    struct ReceiptLine<'a, O: Serialize, R: Serialize> {
+      protocol: &'a str,
+      txid: Txid,
+      height: u64,
+      inscription_id: InscriptionId,
+      operation: &'a O,
+      result: &'a R,
+    }
+
+    let protocol_name = match protocol {
+      ord::Protocol::BRC20 => "brc20",
+      ord::Protocol::BRC20S => "brc20s",
+      ord::Protocol::Ord => bail!("ord operations have no receipts to export"),
+    };
+
+    let indexed_height = self.block_height()?.map(|height| height.n());
+    if indexed_height.map_or(true, |height| to_height > height) {
+      bail!(
+        "index is at height {}, cannot export receipts up to height {to_height}",
+        indexed_height.map_or("none".to_string(), |height| height.to_string())
+      );
+    }
+
+    let mut count = 0;
+    for height in from_height..=to_height {
+      let block = self
+        .get_block_by_height(height)?
+        .ok_or_else(|| anyhow!("block {height} not found"))?;
+
+      // a read transaction per block, so that a long export does not pin an old snapshot.
+      let rtx = self.database.begin_read()?;
+      for tx in &block.txdata {
+        let txid = tx.txid();
+        match protocol {
+          ord::Protocol::BRC20 => {
+            for receipt in brc20_db::DataStoreReader::new(&rtx).get_transaction_receipts(&txid)? {
+              serde_json::to_writer(
+                &mut *writer,
+                &ReceiptLine {
+                  protocol: protocol_name,
+                  txid,
+                  height,
+                  inscription_id: receipt.inscription_id,
+                  operation: &receipt.op,
+                  result: &receipt.result,
+                },
+              )?;
+              writeln!(writer)?;
+              count += 1;
+            }
+          }
+          ord::Protocol::BRC20S => {
+            for receipt in brc20s_db::DataStoreReader::new(&rtx).get_txid_to_receipts(&txid)? {
+              serde_json::to_writer(
+                &mut *writer,
+                &ReceiptLine {
+                  protocol: protocol_name,
+                  txid,
+                  height,
+                  inscription_id: receipt.inscription_id,
+                  operation: &receipt.op,
+                  result: &receipt.result,
+                },
+              )?;
+              writeln!(writer)?;
+              count += 1;
+            }
+          }
+          ord::Protocol::Ord => unreachable!(),
+        }
+      }
+      writer.flush()?;
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    Ok(count)
+  }
+
   pub(crate) fn is_unrecoverably_reorged(&self) -> bool {
     self.unrecoverably_reorged.load(atomic::Ordering::Relaxed)
   }
@@ -572,6 +799,22 @@ impl Index {
     Ok(())
   }
 
+  #[cfg(test)]
+  pub(crate) fn brc20_write(&self, write: impl FnOnce(&brc20_db::DataStore)) -> Result {
+    let wtx = self.begin_write()?;
+    write(&brc20_db::DataStore::new(&wtx));
+    wtx.commit()?;
+    Ok(())
+  }
+
+  #[cfg(test)]
+  pub(crate) fn brc20s_write(&self, write: impl FnOnce(&brc20s_db::DataStore)) -> Result {
+    let wtx = self.begin_write()?;
+    write(&brc20s_db::DataStore::new(&wtx));
+    wtx.commit()?;
+    Ok(())
+  }
+
   #[cfg(test)]
   pub(crate) fn statistic(&self, statistic: Statistic) -> u64 {
     self
@@ -611,6 +854,10 @@ impl Index {
     }
   }
 
+  pub(crate) fn bitcoind_block_count(&self) -> Result<u64> {
+    Ok(self.client.get_block_count()?)
+  }
+
   pub(crate) fn block_count(&self) -> Result<u64> {
     self.begin_read()?.block_count()
   }
@@ -623,6 +870,26 @@ impl Index {
     self.begin_read()?.block_hash(height)
   }
 
+  /// The height of the latest block whose brc20 and brc20s operations are executed. The blocks
+  /// above it wait for `--protocol-confirmations`, so their receipts are not final yet.
+  pub(crate) fn protocol_block_height(&self) -> Result<Option<u64>> {
+    let rtx = self.begin_read()?;
+    let executed = rtx
+      .block_height()?
+      .and_then(|height| height.n().checked_sub(self.options.protocol_confirmations));
+    let first_pending = rtx
+      .0
+      .open_table(HEIGHT_TO_PENDING_OPERATIONS)?
+      .range(0..)?
+      .next()
+      .and_then(|result| result.ok())
+      .map(|(height, _)| height.value());
+    Ok(match first_pending {
+      Some(pending) => executed.min(pending.checked_sub(1)),
+      None => executed,
+    })
+  }
+
   pub(crate) fn latest_block(&self) -> Result<Option<(Height, BlockHash)>> {
     self.begin_read()?.latest_block()
   }
@@ -1033,7 +1300,6 @@ impl Index {
     }
   }
 
-  #[allow(unused)]
   pub(crate) fn get_inscriptions(
     &self,
     utxos: BTreeMap<OutPoint, Amount>,
@@ -1386,6 +1652,12 @@ impl Index {
     )
   }
 
+  pub(crate) fn brc20_audit_transferable(&self) -> Result<Vec<brc20::TransferableMismatch>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    Ok(brc20::audit_transferable(&brc20_db)?)
+  }
+
   pub(crate) fn brc20_get_tick_info(&self, name: &brc20::Tick) -> Result<Option<brc20::TokenInfo>> {
     let rtx = self.database.begin_read().unwrap();
     let brc20_db = brc20_db::DataStoreReader::new(&rtx);
@@ -1393,6 +1665,21 @@ impl Index {
     Ok(info)
   }
 
+  pub(crate) fn brc20_get_tick_receipts(
+    &self,
+    tick: &brc20::Tick,
+    op: Option<brc20::OperationType>,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<Option<(Vec<brc20::TickReceipt>, usize)>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    if brc20_db.get_token_info(tick)?.is_none() {
+      return Ok(None);
+    }
+    Ok(Some(brc20_db.get_tick_receipts(tick, op, start, limit)?))
+  }
+
   pub(crate) fn brc20_get_all_tick_info(&self) -> Result<Vec<brc20::TokenInfo>> {
     let rtx = self.database.begin_read().unwrap();
     let brc20_db = brc20_db::DataStoreReader::new(&rtx);
@@ -1420,6 +1707,29 @@ impl Index {
     Ok(brc20_db.get_balances(&ScriptKey::from_address(address.clone()))?)
   }
 
+  /// Returns the balances of every address in one read, only the balance of `tick` if given.
+  pub(crate) fn brc20_get_balances_by_addresses(
+    &self,
+    addresses: &[bitcoin::Address],
+    tick: Option<&brc20::Tick>,
+  ) -> Result<Vec<Vec<brc20::Balance>>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    addresses
+      .iter()
+      .map(|address| {
+        let script_key = ScriptKey::from_address(address.clone());
+        Ok(match tick {
+          Some(tick) => brc20_db
+            .get_balance(&script_key, tick)?
+            .into_iter()
+            .collect(),
+          None => brc20_db.get_balances(&script_key)?,
+        })
+      })
+      .collect()
+  }
+
   pub(crate) fn get_transaction_info(
     &self,
     txid: &bitcoin::Txid,
@@ -1498,6 +1808,12 @@ impl Index {
     Ok(res)
   }
 
+  pub(crate) fn brc20s_verify(&self) -> Result<brc20s::VerifyReport> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(brc20s::verify(&brc20s_db)?)
+  }
+
   pub(crate) fn brc20s_all_tick_info(
     &self,
     start: usize,
@@ -1509,6 +1825,30 @@ impl Index {
     Ok(all_tick)
   }
 
+  // the tickers deployed from `from` to `to`, ordered by deploy height and then ticker id.
+  pub(crate) fn brc20s_tick_info_by_deploy_height(
+    &self,
+    from: u64,
+    to: u64,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<brc20s::TickInfo>, usize)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(brc20s_db.get_tick_info_by_deploy_height(from, to, start, limit)?)
+  }
+
+  pub(crate) fn brc20s_all_tick_info_after(
+    &self,
+    after: Option<&brc20s::TickId>,
+    limit: Option<usize>,
+  ) -> Result<(Vec<brc20s::TickInfo>, usize)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let all_tick = brc20s_db.get_all_tick_info_after(after, limit)?;
+    Ok(all_tick)
+  }
+
   pub(crate) fn brc20s_tick_info(
     &self,
     tick_id: &brc20s::TickId,
@@ -1525,6 +1865,35 @@ impl Index {
     let info = brc20s_db.get_pid_to_poolinfo(pid)?;
     Ok(info)
   }
+
+  pub(crate) fn brc20s_pool_info_by_stake(
+    &self,
+    tick_id: &brc20s::TickId,
+    pledged: &PledgedTick,
+  ) -> Result<Option<brc20s::PoolInfo>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let Some(pid) = brc20s_db.get_tickid_stake_to_pid(tick_id, pledged)? else {
+      return Ok(None);
+    };
+    let info = brc20s_db.get_pid_to_poolinfo(&pid)?;
+    Ok(info)
+  }
+
+  pub(crate) fn brc20s_pool_staker_count(&self, pid: &brc20s::Pid) -> Result<usize> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let (_, staker_count) = brc20s_db.get_pool_stakers(pid, 0, Some(0))?;
+    Ok(staker_count)
+  }
+
+  pub(crate) fn brc20s_tick_holder_count(&self, tick_id: &brc20s::TickId) -> Result<usize> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let (_, holder_count) = brc20s_db.get_tick_holders(tick_id, 0, Some(0))?;
+    Ok(holder_count)
+  }
+
   pub(crate) fn brc20s_stake_info(
     &self,
     address: &bitcoin::Address,
@@ -1538,6 +1907,15 @@ impl Index {
     Ok(info)
   }
 
+  pub(crate) fn brc20s_all_stake_info(
+    &self,
+    address: &bitcoin::Address,
+  ) -> Result<Vec<brc20s::StakeInfo>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(brc20s_db.get_all_user_stakeinfo(&ScriptKey::from_address(address.clone()))?)
+  }
+
   pub(crate) fn brc20s_all_pool_info(
     &self,
     start: usize,
@@ -1559,6 +1937,48 @@ impl Index {
     Ok(all_pool)
   }
 
+  pub(crate) fn brc20s_pools_by_stake(
+    &self,
+    pledged: &PledgedTick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<brc20s::PoolInfo>, usize)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let (pids, total) = brc20s_db.get_stake_to_all_pid(pledged, start, limit)?;
+    let mut pools = Vec::new();
+    for pid in pids {
+      if let Some(pool) = brc20s_db.get_pid_to_poolinfo(&pid)? {
+        pools.push(pool);
+      }
+    }
+    Ok((pools, total))
+  }
+
+  pub(crate) fn brc20s_pids_by_stake(&self, pledged: &PledgedTick) -> Result<Vec<brc20s::Pid>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(brc20s_db.get_stake_to_all_pid(pledged, 0, None)?.0)
+  }
+
+  pub(crate) fn brc20s_pools_by_earn(
+    &self,
+    earn: &brc20s::Tick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<brc20s::PoolInfo>, usize)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let (pids, total) = brc20s_db.get_earn_to_all_pid(earn, start, limit)?;
+    let mut pools = Vec::new();
+    for pid in pids {
+      if let Some(pool) = brc20s_db.get_pid_to_poolinfo(&pid)? {
+        pools.push(pool);
+      }
+    }
+    Ok((pools, total))
+  }
+
   pub(crate) fn brc20s_user_info(
     &self,
     pid: &brc20s::Pid,
@@ -1570,6 +1990,99 @@ impl Index {
     Ok(info)
   }
 
+  fn brc20s_stake_decimal(
+    brc20s_db: &brc20s_db::DataStoreReader,
+    brc20_db: &brc20_db::DataStoreReader,
+    stake: &PledgedTick,
+  ) -> u8 {
+    match stake {
+      PledgedTick::Native => NATIVE_TOKEN_DECIMAL,
+      PledgedTick::BRC20STick(tickid) => brc20s_db.get_tick_info(tickid).unwrap().unwrap().decimal,
+      PledgedTick::BRC20Tick(tick) => brc20_db.get_token_info(tick).unwrap().unwrap().decimal,
+      PledgedTick::Unknown => 0_u8,
+    }
+  }
+
+  pub(crate) fn brc20s_preview_user_reward(
+    &self,
+    user_info: &brc20s::UserInfo,
+    block: u64,
+  ) -> Result<u128> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+
+    let pool_info = brc20s_db
+      .get_pid_to_poolinfo(&user_info.pid)?
+      .ok_or(anyhow!("pool info not found from state!"))?;
+    let dec = Self::brc20s_stake_decimal(&brc20s_db, &brc20_db, &pool_info.stake);
+
+    Ok(reward::preview_user_reward(
+      user_info, &pool_info, block, dec,
+    )?)
+  }
+
+  pub(crate) fn brc20s_user_rewards(
+    &self,
+    address: &bitcoin::Address,
+  ) -> Result<(Vec<(brc20s::UserInfo, u128)>, u64)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    let block = self.height()?.map(|height| height.n()).unwrap_or_default();
+
+    let mut rewards = Vec::new();
+    for user_info in brc20s_db.get_user_infos(&ScriptKey::from_address(address.clone()))? {
+      let pool_info = brc20s_db
+        .get_pid_to_poolinfo(&user_info.pid)?
+        .ok_or(anyhow!("pool info not found from state!"))?;
+      let dec = Self::brc20s_stake_decimal(&brc20s_db, &brc20_db, &pool_info.stake);
+      let unrealized = reward::preview_user_reward(&user_info, &pool_info, block, dec)?;
+      rewards.push((user_info, unrealized));
+    }
+
+    Ok((rewards, block))
+  }
+
+  pub(crate) fn brc20s_user_pools(
+    &self,
+    address: &bitcoin::Address,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(brc20s::UserInfo, u128)>, usize, u64)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    let block = self.height()?.map(|height| height.n()).unwrap_or_default();
+
+    let (user_infos, total) =
+      brc20s_db.get_user_pools(&ScriptKey::from_address(address.clone()), start, limit)?;
+    let mut pools = Vec::new();
+    for user_info in user_infos {
+      let pool_info = brc20s_db
+        .get_pid_to_poolinfo(&user_info.pid)?
+        .ok_or(anyhow!("pool info not found from state!"))?;
+      let dec = Self::brc20s_stake_decimal(&brc20s_db, &brc20_db, &pool_info.stake);
+      let pending_reward = reward::preview_user_reward(&user_info, &pool_info, block, dec)?;
+      pools.push((user_info, pending_reward));
+    }
+
+    Ok((pools, total, block))
+  }
+
+  pub(crate) fn brc20s_pool_blocks_remaining(
+    &self,
+    pool_info: &brc20s::PoolInfo,
+  ) -> Result<Option<u64>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+
+    let dec = Self::brc20s_stake_decimal(&brc20s_db, &brc20_db, &pool_info.stake);
+
+    Ok(reward::blocks_remaining(pool_info, dec)?)
+  }
+
   pub(crate) fn brc20s_user_pending_reward(
     &self,
     pid: &brc20s::Pid,
@@ -1586,12 +2099,7 @@ impl Index {
       .get_pid_to_poolinfo(pid)?
       .ok_or(anyhow!("pool info not found from state!"))?;
 
-    let dec = match pool_info.clone().stake {
-      PledgedTick::Native => NATIVE_TOKEN_DECIMAL,
-      PledgedTick::BRC20STick(tickid) => brc20s_db.get_tick_info(&tickid).unwrap().unwrap().decimal,
-      PledgedTick::BRC20Tick(tick) => brc20_db.get_token_info(&tick).unwrap().unwrap().decimal,
-      PledgedTick::Unknown => 0_u8,
-    };
+    let dec = Self::brc20s_stake_decimal(&brc20s_db, &brc20_db, &pool_info.stake);
 
     let block = self.height().unwrap().unwrap_or(Height(0)).n();
 
@@ -1616,6 +2124,19 @@ impl Index {
     Ok(info)
   }
 
+  pub(crate) fn brc20s_balance_at(
+    &self,
+    tick_id: &brc20s::TickId,
+    address: &bitcoin::Address,
+    height: u64,
+  ) -> Result<Option<brc20s::Balance>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let info =
+      brc20s_db.get_balance_at(&ScriptKey::from_address(address.clone()), tick_id, height)?;
+    Ok(info)
+  }
+
   pub(crate) fn brc20s_all_balance(
     &self,
     address: &bitcoin::Address,
@@ -1626,6 +2147,20 @@ impl Index {
     Ok(all_balance)
   }
 
+  pub(crate) fn get_address_tokens(
+    &self,
+    address: &bitcoin::Address,
+  ) -> Result<(Vec<brc20::Balance>, Vec<(brc20s::TickId, brc20s::Balance)>)> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    let script_key = ScriptKey::from_address(address.clone());
+    Ok((
+      brc20_db.get_balances(&script_key)?,
+      brc20s_db.get_balances(&script_key)?,
+    ))
+  }
+
   pub(crate) fn brc20s_tickid_transferable(
     &self,
     tick_id: &brc20s::TickId,
@@ -1639,6 +2174,21 @@ impl Index {
     Ok(result)
   }
 
+  pub(crate) fn brc20s_transferable_by_id(
+    &self,
+    owner: &Script,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<brc20s::TransferableAsset>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+
+    let result = brc20s_db.get_transferable_by_id(
+      &ScriptKey::from_script(owner, self.get_chain_network()),
+      inscription_id,
+    )?;
+    Ok(result)
+  }
+
   pub(crate) fn brc20s_all_transferable(
     &self,
     address: &bitcoin::Address,
@@ -1649,6 +2199,24 @@ impl Index {
     Ok(info)
   }
 
+  pub(crate) fn brc20s_pending_transfers(
+    &self,
+    address: &bitcoin::Address,
+  ) -> Result<Vec<(InscriptionId, brc20s::TransferInfo)>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+
+    // the inscribe transfer table is keyed by inscription id only, so look up
+    // the inscriptions held by the address first.
+    let mut pending = Vec::new();
+    for asset in brc20s_db.get_transferable(&ScriptKey::from_address(address.clone()))? {
+      if let Some(info) = brc20s_db.get_inscribe_transfer_inscription(asset.inscription_id)? {
+        pending.push((asset.inscription_id, info));
+      }
+    }
+    Ok(pending)
+  }
+
   pub(crate) fn brc20s_txid_receipts(&self, txid: &Txid) -> Result<Option<Vec<brc20s::Receipt>>> {
     let rtx = self.database.begin_read().unwrap();
     let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
@@ -1670,6 +2238,88 @@ impl Index {
     Ok(Some(res))
   }
 
+  /// Parses the brc20s operation of an inscription the way a new inscription is resolved, or
+  /// returns `None` if the inscription is not indexed or is not a brc20s operation. The operations
+  /// of recently queried inscriptions are served from a cache.
+  pub(crate) fn brc20s_inscription_operation(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<brc20s_proto::Operation>> {
+    // an inscription which is not indexed yet isn't cached, it may still be indexed later.
+    if self.get_inscription_entry(inscription_id)?.is_none() {
+      return Ok(None);
+    }
+    self
+      .brc20s_operation_cache
+      .get_or_parse(inscription_id, || {
+        let Some(inscription) = self.get_inscription_by_id(inscription_id)? else {
+          return Ok(None);
+        };
+        let action = ord::Action::New {
+          cursed: false,
+          unbound: false,
+          inscription: inscription.clone(),
+        };
+        Ok(
+          brc20s_proto::deserialize_brc20s_operation(
+            &inscription,
+            &action,
+            self.protocol_json_limits(),
+          )
+          .ok(),
+        )
+      })
+  }
+
+  #[cfg(test)]
+  pub(crate) fn brc20s_operation_cache_stats(&self) -> (u64, u64) {
+    self.brc20s_operation_cache.stats()
+  }
+
+  /// Lists the balance and pool changes applied by the brc20s operations of the block at `height`
+  /// in the order they were applied, or returns `None` if the block is not indexed yet. Only the
+  /// blocks a reorg can still roll back keep their changes.
+  pub(crate) fn brc20s_block_mutations(
+    &self,
+    height: u64,
+  ) -> Result<Option<Vec<brc20s::Mutation>>> {
+    if self.block_hash(Some(height))?.is_none() {
+      return Ok(None);
+    }
+    let rtx = self.database.begin_read()?;
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(Some(brc20s_db.get_block_mutations(height)?))
+  }
+
+  /// Counts the receipts of every operation type in the blocks from `from_height` to `to_height`,
+  /// or returns `None` if the protocol operations of a block of the range are not indexed yet.
+  pub(crate) fn brc20s_operation_counts(
+    &self,
+    from_height: u64,
+    to_height: u64,
+  ) -> Result<Option<brc20s::OperationCounts>> {
+    if self
+      .protocol_block_height()?
+      .map_or(true, |height| height < to_height)
+    {
+      return Ok(None);
+    }
+    let rtx = self.database.begin_read()?;
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(Some(
+      brc20s_db.get_operation_counts(from_height, to_height)?,
+    ))
+  }
+
+  pub(crate) fn brc20s_txid_inscription_operations(
+    &self,
+    txid: &Txid,
+  ) -> Result<Vec<brc20s::InscriptionOperation>> {
+    let rtx = self.database.begin_read().unwrap();
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+    Ok(brc20s_db.get_txid_to_inscription_receipts(txid)?)
+  }
+
   pub(crate) fn brc20s_txs_receipts(
     &self,
     txs: &Vec<Txid>,
@@ -1687,13 +2337,58 @@ impl Index {
     Ok(result)
   }
 
+  pub(crate) fn ord_receipt_refs(
+    &self,
+    address: &bitcoin::Address,
+    before: Option<(u64, Txid)>,
+    limit: usize,
+  ) -> Result<Vec<ord::ReceiptRef>> {
+    let rtx = self.database.begin_read().unwrap();
+    let ord_db = ord::OrdDbReader::new(&rtx);
+    Ok(ord_db.get_receipt_refs(&ScriptKey::from_address(address.clone()), before, limit)?)
+  }
+
+  pub(crate) fn receipts_of_ref(
+    &self,
+    receipt_ref: &ord::ReceiptRef,
+  ) -> Result<(
+    Vec<ord::InscriptionOp>,
+    Vec<brc20::Receipt>,
+    Vec<brc20s::Receipt>,
+  )> {
+    let rtx = self.database.begin_read().unwrap();
+    let txid = &receipt_ref.txid;
+    let mut receipts = (Vec::new(), Vec::new(), Vec::new());
+    for protocol in &receipt_ref.protocols {
+      match protocol {
+        ord::Protocol::Ord => {
+          receipts.0 = ord::OrdDbReader::new(&rtx).get_transaction_operations(txid)?
+        }
+        ord::Protocol::BRC20 => {
+          receipts.1 = brc20_db::DataStoreReader::new(&rtx).get_transaction_receipts(txid)?
+        }
+        ord::Protocol::BRC20S => {
+          receipts.2 = brc20s_db::DataStoreReader::new(&rtx).get_txid_to_receipts(txid)?
+        }
+      }
+    }
+    Ok(receipts)
+  }
+
   pub(crate) fn ord_txid_inscriptions(
     &self,
     txid: &Txid,
   ) -> Result<Option<Vec<ord::InscriptionOp>>> {
     let rtx = self.database.begin_read().unwrap();
     let ord_db = ord::OrdDbReader::new(&rtx);
-    let res = ord_db.get_transaction_operations(txid)?;
+    let mut res = ord_db.get_transaction_operations(txid)?;
+
+    // fill in the number of an operation stored without one from its inscription entry.
+    for op in res.iter_mut().filter(|op| op.inscription_number.is_none()) {
+      op.inscription_number = self
+        .get_inscription_entry(op.inscription_id)?
+        .map(|entry| entry.number);
+    }
 
     if res.is_empty() {
       let tx = self.client.get_raw_transaction_info(txid, None)?;
@@ -1728,6 +2423,14 @@ impl Index {
   }
 }
 
+fn csv_escape(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use {
@@ -1856,6 +2559,47 @@ mod tests {
     }
   }
 
+  #[test]
+  fn empty_blocks_advance_height_without_changing_statistics() {
+    let context = Context::builder()
+      .args([
+        "--enable-index-brc20",
+        "--enable-index-brc20s",
+        "--enable-save-ord-receipts",
+        "--enable-index-bitmap",
+      ])
+      .build();
+    context.mine_blocks(1);
+
+    let lost_sats = context.index.statistic(Statistic::LostSats);
+    let unbound_inscriptions = context.index.statistic(Statistic::UnboundInscriptions);
+    let block_count = context.index.block_count().unwrap();
+
+    let blocks = context.mine_blocks(5);
+
+    assert_eq!(context.index.block_count().unwrap(), block_count + 5);
+    assert_eq!(
+      context.index.block_height().unwrap(),
+      Some(Height(block_count + 4))
+    );
+    for (i, block) in blocks.iter().enumerate() {
+      assert_eq!(
+        context
+          .index
+          .block_hash(Some(block_count + i as u64))
+          .unwrap(),
+        Some(block.block_hash())
+      );
+    }
+    assert_eq!(context.index.statistic(Statistic::LostSats), lost_sats);
+    assert_eq!(
+      context.index.statistic(Statistic::UnboundInscriptions),
+      unbound_inscriptions
+    );
+    assert!(context.index.brc20_get_all_tick_info().unwrap().is_empty());
+    assert_eq!(context.index.brc20s_all_tick_info(0, None).unwrap().1, 0);
+  }
+
   #[test]
   fn inscriptions_below_first_inscription_height_are_skipped() {
     let inscription = inscription("text/plain;charset=utf-8", "hello");
@@ -2894,6 +3638,50 @@ mod tests {
       format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of ord, consider updating ord: index schema {}, ord schema {SCHEMA_VERSION}", path.display(), u64::MAX));
   }
 
+  #[test]
+  fn changed_protocol_options_give_correct_error() {
+    let burn_a = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let burn_b = "bcrt1qvd26a8c26d4mu5fzyh74pvcp9ykgutxt9fktqf";
+
+    let tempdir = Context::builder()
+      .args(["--burn-address", burn_a, "--burn-address", burn_b])
+      .build()
+      .tempdir;
+
+    // the same burn addresses in another order open the index.
+    let tempdir = Context::builder()
+      .args(["--burn-address", burn_b, "--burn-address", burn_a])
+      .tempdir(tempdir)
+      .build()
+      .tempdir;
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+    let error = |tempdir: &TempDir, option: &str| {
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` was built with a different {option} setting, which changes the indexed BRC20 and BRC20S state, consider passing the same setting or deleting and rebuilding the index", tempdir.path().display())
+    };
+
+    let expected = error(&tempdir, "--burn-address");
+    let Err(err) = Context::builder()
+      .args(["--burn-address", burn_a])
+      .tempdir(tempdir)
+      .try_build()
+    else {
+      panic!("opened an index with other burn addresses");
+    };
+    assert_eq!(err.to_string(), expected);
+
+    let tempdir = Context::builder().build().tempdir;
+    let expected = error(&tempdir, "--lenient-content-type");
+    let Err(err) = Context::builder()
+      .arg("--lenient-content-type")
+      .tempdir(tempdir)
+      .try_build()
+    else {
+      panic!("opened an index with another content type setting");
+    };
+    assert_eq!(err.to_string(), expected);
+  }
+
   #[test]
   fn inscriptions_on_output() {
     for context in Context::configurations() {
@@ -4474,4 +5262,353 @@ mod tests {
         .is_none());
     }
   }
+
+  #[test]
+  fn export_receipts_writes_one_line_per_receipt() {
+    let context = Context::builder()
+      .args(["--enable-index-brc20", "--enable-index-brc20s"])
+      .build();
+    context.mine_blocks(3);
+    // the commit transactions, the sender of an operation is the owner of its input.
+    for block in 1..=3 {
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(block, 0, 0, Default::default())],
+        ..Default::default()
+      });
+    }
+    context.mine_blocks(1);
+
+    let operations = [
+      r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"1000","lim":"10","dec":"0"}"#,
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+      r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"}"#,
+    ];
+    let mut txids = Vec::new();
+    for (i, operation) in operations.iter().enumerate() {
+      txids.push(context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          4,
+          i + 1,
+          0,
+          inscription("text/plain;charset=utf-8", operation).to_witness(),
+        )],
+        ..Default::default()
+      }));
+      context.mine_blocks(1);
+    }
+
+    let export = |protocol, from_height, to_height| {
+      let mut ndjson = Vec::new();
+      let count = context
+        .index
+        .export_receipts(protocol, from_height, to_height, &mut ndjson)
+        .unwrap();
+      let lines = String::from_utf8(ndjson)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+      assert_eq!(count, u64::try_from(lines.len()).unwrap());
+      lines
+    };
+
+    let lines = export(ord::Protocol::BRC20, 4, 7);
+    assert_eq!(lines.len(), 2);
+    for (line, (txid, height, operation)) in lines
+      .iter()
+      .zip([(txids[0], 5, "Deploy"), (txids[1], 6, "Mint")])
+    {
+      assert_eq!(line["protocol"], "brc20");
+      assert_eq!(line["txid"], txid.to_string());
+      assert_eq!(line["height"], height);
+      assert_eq!(line["inscription_id"], format!("{txid}i0"));
+      assert_eq!(line["operation"], operation);
+      assert!(line["result"]["Ok"].is_object());
+    }
+    assert_eq!(export(ord::Protocol::BRC20, 6, 6).len(), 1);
+
+    // the deposit into a pool that does not exist is recorded with its error.
+    let lines = export(ord::Protocol::BRC20S, 0, 7);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["protocol"], "brc20s");
+    assert_eq!(lines[0]["txid"], txids[2].to_string());
+    assert_eq!(lines[0]["height"], 7);
+    assert_eq!(lines[0]["operation"], "Stake");
+    assert!(lines[0]["result"]["Err"].is_object());
+
+    assert!(context
+      .index
+      .export_receipts(ord::Protocol::BRC20, 0, 8, &mut Vec::new())
+      .is_err());
+    assert!(context
+      .index
+      .export_receipts(ord::Protocol::Ord, 0, 7, &mut Vec::new())
+      .is_err());
+  }
+
+  #[test]
+  fn protocol_confirmations_delay_protocol_execution() {
+    let context = Context::builder()
+      .args(["--enable-index-brc20", "--protocol-confirmations", "2"])
+      .build();
+    let minter = Address::p2wsh(Script::from_bytes(&[0x51]), Network::Regtest);
+    context.mine_blocks(2);
+    // the commit transactions, the sender of an operation is the owner of its input.
+    for block in 1..=2 {
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(block, 0, 0, Default::default())],
+        ..Default::default()
+      });
+    }
+    context.mine_blocks(1);
+
+    // the deploy is inscribed in block 4 and the mint in block 5.
+    let mut txids = Vec::new();
+    for (i, operation) in [
+      r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"1000","lim":"10","dec":"0"}"#,
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+    ]
+    .iter()
+    .enumerate()
+    {
+      txids.push(context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          3,
+          i + 1,
+          0,
+          inscription("text/plain;charset=utf-8", operation).to_witness(),
+        )],
+        recipient: Some(minter.clone()),
+        ..Default::default()
+      }));
+      context.mine_blocks(1);
+    }
+
+    let tick = brc20::Tick::from_str("ordi").unwrap();
+    let balance = || {
+      context
+        .index
+        .brc20_get_balance_by_address(&tick, &minter)
+        .unwrap()
+        .map(|balance| balance.overall_balance)
+    };
+
+    // the ord index is at the tip, the operations of the last 2 blocks are not executed yet.
+    assert!(context
+      .index
+      .get_inscription_entry(InscriptionId {
+        txid: txids[1],
+        index: 0
+      })
+      .unwrap()
+      .is_some());
+    assert!(context.index.brc20_get_tick_info(&tick).unwrap().is_none());
+    assert_eq!(balance(), None);
+
+    context.mine_blocks(1);
+    assert!(context.index.brc20_get_tick_info(&tick).unwrap().is_some());
+    assert_eq!(balance(), None);
+
+    context.mine_blocks(1);
+    assert_eq!(
+      context
+        .index
+        .brc20_get_tick_info(&tick)
+        .unwrap()
+        .unwrap()
+        .minted,
+      10
+    );
+    assert_eq!(balance(), Some(10));
+  }
+
+  #[test]
+  fn brc20s_transfer_credits_the_output_the_sat_moves_to() {
+    use crate::okx::datastore::brc20s::DataStoreReadWrite as _;
+
+    let context = Context::builder()
+      .args(["--enable-index-brc20", "--enable-index-brc20s"])
+      .build();
+    let [owner, change, receiver] = [0x51, 0x52, 0x53]
+      .map(|opcode| Address::p2wsh(Script::from_bytes(&[opcode]), Network::Regtest));
+    context.mine_blocks(2);
+    // the commit transaction, the sender of an operation is the owner of its input.
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Default::default())],
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+
+    let tick_id = brc20s::TickId::from_str("f7c515d6b7").unwrap();
+    let owner_key = ScriptKey::from_address(owner.clone());
+    context
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_tick_info(
+            &tick_id,
+            &brc20s::TickInfo::new(
+              tick_id,
+              &brc20s::Tick::from_str("ordi1").unwrap(),
+              &InscriptionId {
+                txid: Txid::all_zeros(),
+                index: 0,
+              },
+              1000,
+              0,
+              100,
+              1000,
+              &owner_key,
+              1,
+              0,
+              1,
+              Vec::new(),
+            ),
+          )
+          .unwrap();
+        store
+          .set_token_balance(
+            &owner_key,
+            &tick_id,
+            brc20s::Balance {
+              tick_id,
+              overall_balance: 100,
+              transferable_balance: 0,
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        1,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc20-s","op":"transfer","tid":"f7c515d6b7","tick":"ordi1","amt":"60"}"#,
+        )
+        .to_witness(),
+      )],
+      recipient: Some(owner.clone()),
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    let inscription_id = InscriptionId { txid, index: 0 };
+    assert_eq!(
+      context
+        .index
+        .brc20s_tickid_transferable(&tick_id, &owner)
+        .unwrap()
+        .iter()
+        .map(|asset| asset.inscription_id)
+        .collect::<Vec<_>>(),
+      vec![inscription_id]
+    );
+
+    // the first input covers the first output, so the inscription sat moves to the second one.
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, Default::default()), (4, 1, 0, Default::default())],
+      outputs: 2,
+      recipients: &[change.clone(), receiver.clone()],
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    assert_eq!(
+      context
+        .index
+        .get_inscription_satpoint_by_id(inscription_id)
+        .unwrap(),
+      Some(SatPoint {
+        outpoint: OutPoint { txid, vout: 1 },
+        offset: 0,
+      })
+    );
+
+    let balance = |address: &Address| {
+      context
+        .index
+        .brc20s_balance(&tick_id, address)
+        .unwrap()
+        .map(|balance| (balance.overall_balance, balance.transferable_balance))
+    };
+    assert_eq!(balance(&owner), Some((40, 0)));
+    assert_eq!(balance(&change), None);
+    assert_eq!(balance(&receiver), Some((60, 0)));
+    assert!(context
+      .index
+      .brc20s_tickid_transferable(&tick_id, &owner)
+      .unwrap()
+      .is_empty());
+  }
+
+  #[test]
+  fn brc20s_operation_counts_of_block_range() {
+    let context = Context::builder()
+      .args(["--enable-index-brc20", "--enable-index-brc20s"])
+      .build();
+    context.mine_blocks(4);
+    // the commit transactions, the sender of an operation is the owner of its input.
+    for block in 1..=4 {
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(block, 0, 0, Default::default())],
+        ..Default::default()
+      });
+    }
+    context.mine_blocks(1);
+
+    // operations of pools and ticks that don't exist still leave a receipt.
+    let blocks = [
+      vec![r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"}"#],
+      vec![
+        r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"}"#,
+        r#"{"p":"brc20-s","op":"mint","tick":"ordi","pid":"a3668daeaa#01","amt":"10"}"#,
+      ],
+      vec![r#"{"p":"brc20-s","op":"withdraw","pid":"a3668daeaa#01","amt":"10"}"#],
+    ];
+    let mut commit = 0;
+    for operations in blocks {
+      for operation in operations {
+        commit += 1;
+        context.rpc_server.broadcast_tx(TransactionTemplate {
+          inputs: &[(
+            5,
+            commit,
+            0,
+            inscription("text/plain;charset=utf-8", operation).to_witness(),
+          )],
+          ..Default::default()
+        });
+      }
+      context.mine_blocks(1);
+    }
+
+    let counts = |from_height, to_height| {
+      context
+        .index
+        .brc20s_operation_counts(from_height, to_height)
+        .unwrap()
+    };
+
+    assert_eq!(counts(0, 5), Some(brc20s::OperationCounts::default()));
+    assert_eq!(
+      counts(6, 7),
+      Some(brc20s::OperationCounts {
+        mint: 1,
+        stake: 2,
+        ..Default::default()
+      })
+    );
+    let expected = brc20s::OperationCounts {
+      mint: 1,
+      stake: 2,
+      unstake: 1,
+      ..Default::default()
+    };
+    assert_eq!(counts(0, 8), Some(expected));
+    // the second query is answered from the cached block counts.
+    assert_eq!(counts(0, 8), Some(expected));
+    assert_eq!(counts(8, 9), None);
+  }
 }
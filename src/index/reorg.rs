@@ -23,6 +23,9 @@ const MAX_SAVEPOINTS: usize = 2;
 const SAVEPOINT_INTERVAL: u64 = 10;
 const CHAIN_TIP_DISTANCE: u64 = 21;
 
+// the deepest reorg the savepoints can roll back, the blocks below it are final.
+pub(crate) const MAX_REORG_DEPTH: u64 = MAX_SAVEPOINTS as u64 * SAVEPOINT_INTERVAL;
+
 pub(crate) struct Reorg {}
 
 impl Reorg {
@@ -2,7 +2,7 @@ use {
   self::inscription_updater::InscriptionUpdater,
   super::{fetcher::Fetcher, *},
   crate::okx::{
-    datastore::StateReadWrite,
+    datastore::{ord::InscriptionOp, StateReadWrite},
     protocol::{BlockContext, ProtocolConfig, ProtocolManager},
   },
   futures::future::try_join_all,
@@ -33,6 +33,16 @@ impl From<Block> for BlockData {
   }
 }
 
+// the inscription operations of a block waiting for `--protocol-confirmations`, along with the
+// time and the transactions of the block they need to be executed.
+#[derive(Serialize, Deserialize)]
+struct PendingBlock {
+  time: u32,
+  // the transactions carrying the operations, in block order.
+  txdata: Vec<(Transaction, Txid)>,
+  operations: HashMap<Txid, Vec<InscriptionOp>>,
+}
+
 pub(crate) struct Updater<'index> {
   range_cache: HashMap<OutPointValue, Vec<u8>>,
   height: u64,
@@ -59,6 +69,11 @@ impl<'index> Updater<'_> {
   }
 
   pub(crate) fn update_index(&mut self) -> Result {
+    // deploys of a failed update are executed again, drop what they left behind.
+    if let Some(webhook) = &self.index.deploy_webhook {
+      webhook.discard();
+    }
+
     let mut wtx = self.index.begin_write()?;
     let starting_height = self.index.client.get_block_count()? + 1;
 
@@ -322,6 +337,8 @@ impl<'index> Updater<'_> {
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
+    log_mdc::insert("height", self.height.to_string());
+
     let start = Instant::now();
     let mut sat_ranges_written = 0;
     let mut outputs_in_block = 0;
@@ -539,17 +556,7 @@ impl<'index> Updater<'_> {
     std::mem::drop(inscription_id_to_inscription_entry);
     std::mem::drop(outpoint_to_entry);
 
-    // Create a protocol manager to index the block of brc20, brc20s data.
-    let config = ProtocolConfig::new_with_options(&index.options);
-    ProtocolManager::new(&index.client, &StateReadWrite::new(wtx), &config).index_block(
-      BlockContext {
-        network: index.get_chain_network(),
-        blockheight: self.height,
-        blocktime: block.header.time,
-      },
-      &block,
-      operations,
-    )?;
+    self.index_protocol(index, wtx, &block, operations)?;
 
     statistic_to_count.insert(&Statistic::LostSats.key(), &lost_sats)?;
 
@@ -568,6 +575,83 @@ impl<'index> Updater<'_> {
     Ok(())
   }
 
+  // Executes the brc20 and brc20s operations of the blocks which are `--protocol-confirmations`
+  // blocks below the current one. The operations of the current block wait in
+  // `HEIGHT_TO_PENDING_OPERATIONS` until then, stored with the block time and the transactions
+  // they were inscribed in, so that the block is not fetched again.
+  // Reorgs no deeper than the savepoints are already rolled back along with the rest of the index,
+  // the delay only keeps the protocol state of the last blocks out of the api until they are deep.
+  fn index_protocol(
+    &self,
+    index: &Index,
+    wtx: &WriteTransaction,
+    block: &BlockData,
+    mut operations: HashMap<Txid, Vec<InscriptionOp>>,
+  ) -> Result {
+    let confirmations = index.options.protocol_confirmations;
+
+    let mut confirmed = Vec::new();
+    {
+      let mut pending_blocks = wtx.open_table(HEIGHT_TO_PENDING_OPERATIONS)?;
+      if confirmations > 0 && !operations.is_empty() {
+        let pending = PendingBlock {
+          time: block.header.time,
+          txdata: block
+            .txdata
+            .iter()
+            .filter(|(_, txid)| operations.contains_key(txid))
+            .cloned()
+            .collect(),
+          operations: std::mem::take(&mut operations),
+        };
+        pending_blocks.insert(&self.height, bincode::serialize(&pending)?.as_slice())?;
+      }
+      // a lowered confirmation count releases every pending block which is deep enough now.
+      if let Some(height) = self.height.checked_sub(confirmations) {
+        for entry in pending_blocks.range(..=height)? {
+          let (height, pending) = entry?;
+          confirmed.push((
+            height.value(),
+            bincode::deserialize::<PendingBlock>(pending.value())?,
+          ));
+        }
+        for (height, _) in &confirmed {
+          pending_blocks.remove(height)?;
+        }
+      }
+    }
+
+    // Create a protocol manager to index the block of brc20, brc20s data.
+    let config = ProtocolConfig::new_with_options(&index.options, index.deploy_webhook.clone())?;
+    let state_store = if index.options.enable_block_mutations {
+      StateReadWrite::new_with_block_mutations(wtx)
+    } else {
+      StateReadWrite::new(wtx)
+    };
+    let protocol_manager = ProtocolManager::new(&index.client, &state_store, &config);
+    let context = |blockheight, blocktime| BlockContext {
+      network: index.get_chain_network(),
+      blockheight,
+      blocktime,
+    };
+    for (height, pending) in confirmed {
+      protocol_manager.index_block(
+        context(height, pending.time),
+        &pending.txdata,
+        pending.operations,
+      )?;
+    }
+    if confirmations == 0 {
+      protocol_manager.index_block(
+        context(self.height, block.header.time),
+        &block.txdata,
+        operations,
+      )?;
+    }
+
+    Ok(())
+  }
+
   fn index_transaction_sats(
     &mut self,
     tx: &Transaction,
@@ -689,6 +773,10 @@ impl<'index> Updater<'_> {
 
     wtx.commit()?;
 
+    if let Some(webhook) = &self.index.deploy_webhook {
+      webhook.flush();
+    }
+
     Reorg::update_savepoints(self.index, self.height)?;
 
     Ok(())
@@ -163,7 +163,7 @@ pub fn main() {
     Ok(d) => d,
     Err(e) => panic!("get log file error: {}", e),
   };
-  if let Err(e) = logger::init(args.options.log_level(), log_dir) {
+  if let Err(e) = logger::init(args.options.log_level(), args.options.log_format, log_dir) {
     panic!("initialize logger error: {}", e);
   }
 
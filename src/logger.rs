@@ -10,20 +10,40 @@ use log4rs::{
     },
   },
   config::{Appender, Logger, Root},
-  encode::pattern::PatternEncoder,
+  encode::{json::JsonEncoder, pattern::PatternEncoder, Encode},
   Config,
 };
 use std::fs;
 use std::path::Path;
 
-pub fn init<P: AsRef<Path>>(level: log::LevelFilter, log_dir: P) -> anyhow::Result<log4rs::Handle> {
+#[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub(crate) enum LogFormat {
+  #[default]
+  Human,
+  Json,
+}
+
+impl LogFormat {
+  // json lines carry the level, timestamp, target, message and the mdc, which
+  // holds indexing context such as the block height being indexed.
+  fn encoder(self) -> Box<dyn Encode> {
+    match self {
+      Self::Human => Box::new(PatternEncoder::default()),
+      Self::Json => Box::new(JsonEncoder::new()),
+    }
+  }
+}
+
+pub(crate) fn init<P: AsRef<Path>>(
+  level: log::LevelFilter,
+  format: LogFormat,
+  log_dir: P,
+) -> anyhow::Result<log4rs::Handle> {
   fs::create_dir_all(&log_dir)?;
   let log_file = log_dir.as_ref().join("ord.log");
 
-  let stdout = ConsoleAppender::builder().build();
+  let stdout = ConsoleAppender::builder().encoder(format.encoder()).build();
 
-  // using default encoder for now, change it as needed.
-  let encoder = PatternEncoder::default();
   let trigger = SizeTrigger::new(1024 * 1024 * 20);
   let roller = FixedWindowRoller::builder()
     .build(
@@ -38,7 +58,7 @@ pub fn init<P: AsRef<Path>>(level: log::LevelFilter, log_dir: P) -> anyhow::Resu
   let policy = CompoundPolicy::new(Box::new(trigger), Box::new(roller));
   let rfile = RollingFileAppender::builder()
     .append(true)
-    .encoder(Box::new(encoder))
+    .encoder(format.encoder())
     .build(&log_file, Box::new(policy))
     .with_context(|| format!("Failed to create rolling file {}", log_file.display()))?;
 
@@ -56,3 +76,38 @@ pub fn init<P: AsRef<Path>>(level: log::LevelFilter, log_dir: P) -> anyhow::Resu
 
   log4rs::init_config(cfg).context("log4rs init config error")
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use log4rs::encode::writer::simple::SimpleWriter;
+
+  #[test]
+  fn json_log_line() {
+    log_mdc::insert("height", "840000");
+
+    let mut writer = SimpleWriter(Vec::new());
+    LogFormat::Json
+      .encoder()
+      .encode(
+        &mut writer,
+        &log::Record::builder()
+          .level(log::Level::Info)
+          .target("ord::index::updater")
+          .args(format_args!("indexed block"))
+          .build(),
+      )
+      .unwrap();
+
+    log_mdc::remove("height");
+
+    let line = String::from_utf8(writer.0).unwrap();
+    assert_eq!(line.lines().count(), 1);
+    let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(json["level"], "INFO");
+    assert_eq!(json["target"], "ord::index::updater");
+    assert_eq!(json["message"], "indexed block");
+    assert_eq!(json["mdc"]["height"], "840000");
+    assert!(json["time"].is_string());
+  }
+}
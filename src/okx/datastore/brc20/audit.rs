@@ -0,0 +1,109 @@
+use super::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferableMismatch {
+  pub script: ScriptKey,
+  pub tick: Tick,
+  pub overall_balance: u128,
+  pub transferable_balance: u128,
+  pub transferable_total: u128,
+}
+
+// every transferable inscription must be reserved from the balance of its owner, so per
+// address and tick the transferable logs must sum up to the transferable balance and never
+// exceed the overall balance. available balance is overall - transferable.
+pub fn audit_transferable<T: DataStoreReadOnly>(
+  store: &T,
+) -> Result<Vec<TransferableMismatch>, T::Error> {
+  let mut mismatches = Vec::new();
+  store.get_all_acc_balance(|script, balance| {
+    let transferable_total = store
+      .get_transferable_by_tick(script, &balance.tick)?
+      .iter()
+      .fold(0u128, |total, log| total.saturating_add(log.amount));
+
+    if transferable_total != balance.transferable_balance
+      || balance.transferable_balance > balance.overall_balance
+    {
+      mismatches.push(TransferableMismatch {
+        script: script.clone(),
+        tick: balance.tick.clone(),
+        overall_balance: balance.overall_balance,
+        transferable_balance: balance.transferable_balance,
+        transferable_total,
+      });
+    }
+    Ok(())
+  })?;
+  Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::brc20::redb::DataStore;
+  use ::redb::Database;
+  use bitcoin::Address;
+  use std::str::FromStr;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_audit_transferable() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick = Tick::from_str("abcd").unwrap();
+    let mut transferable = TransferableLog {
+      inscription_id: InscriptionId::from_str(
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      )
+      .unwrap(),
+      inscription_number: 1,
+      amount: 30,
+      tick: tick.clone(),
+      owner: script.clone(),
+    };
+
+    brc20db
+      .update_token_balance(
+        &script,
+        Balance {
+          tick: tick.clone(),
+          overall_balance: 100,
+          transferable_balance: 30,
+        },
+      )
+      .unwrap();
+    brc20db
+      .insert_transferable(&script, &tick, transferable.clone())
+      .unwrap();
+    assert_eq!(audit_transferable(&brc20db).unwrap(), Vec::new());
+
+    // corrupt the transferable so it exceeds the overall balance
+    brc20db
+      .remove_transferable(&script, &tick, transferable.inscription_id)
+      .unwrap();
+    transferable.amount = 130;
+    brc20db
+      .insert_transferable(&script, &tick, transferable)
+      .unwrap();
+    assert_eq!(
+      audit_transferable(&brc20db).unwrap(),
+      vec![TransferableMismatch {
+        script,
+        tick,
+        overall_balance: 100,
+        transferable_balance: 30,
+        transferable_total: 130,
+      }]
+    );
+  }
+}
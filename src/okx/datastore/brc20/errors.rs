@@ -1,4 +1,4 @@
-use crate::InscriptionId;
+use crate::{okx::protocol::brc20::params::MAX_DECIMAL_WIDTH, InscriptionId};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error, Deserialize, Serialize)]
@@ -18,6 +18,7 @@ pub enum BRC20Error {
   #[error("illegal tick length '{0}'")]
   InvalidTickLen(String),
 
+  // superseded by `InvalidDecimals`, kept so that saved receipts still deserialize.
   #[error("decimals {0} too large")]
   DecimalsTooLarge(u8),
 
@@ -33,7 +34,7 @@ pub enum BRC20Error {
   #[error("amount overflow: {0}")]
   AmountOverflow(String),
 
-  #[error("insufficient balance: {0} {1}")]
+  #[error("insufficient balance: available {0}, requested {1}")]
   InsufficientBalance(String, String),
 
   #[error("amount exceed limit: {0}")]
@@ -63,4 +64,7 @@ pub enum BRC20Error {
 
   #[error("invalid integer {0}")]
   InvalidInteger(String),
+
+  #[error("decimals {0} must be an integer from 0 to {max}", max = MAX_DECIMAL_WIDTH)]
+  InvalidDecimals(String),
 }
@@ -1,5 +1,6 @@
 use super::*;
 use crate::{InscriptionId, SatPoint};
+use bitcoin::Txid;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -21,12 +22,34 @@ pub struct Receipt {
   pub result: Result<Event, BRC20Error>,
 }
 
+impl Receipt {
+  /// Returns the ticker of the receipt, `None` if the execution has failed.
+  pub fn tick(&self) -> Option<&Tick> {
+    match self.result.as_ref().ok()? {
+      Event::Deploy(event) => Some(&event.tick),
+      Event::Mint(event) => Some(&event.tick),
+      Event::InscribeTransfer(event) => Some(&event.tick),
+      Event::Transfer(event) => Some(&event.tick),
+      Event::Burn(event) => Some(&event.tick),
+    }
+  }
+}
+
+/// A successful receipt of a ticker, with the transaction and block it was executed in.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TickReceipt {
+  pub height: u64,
+  pub txid: Txid,
+  pub receipt: Receipt,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Event {
   Deploy(DeployEvent),
   Mint(MintEvent),
   InscribeTransfer(InscripbeTransferEvent),
   Transfer(TransferEvent),
+  Burn(BurnEvent),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -57,6 +80,12 @@ pub struct TransferEvent {
   pub msg: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BurnEvent {
+  pub tick: Tick,
+  pub amount: u128,
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
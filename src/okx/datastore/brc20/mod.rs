@@ -1,3 +1,4 @@
+pub(super) mod audit;
 pub(super) mod balance;
 pub(super) mod errors;
 pub(super) mod events;
@@ -8,8 +9,15 @@ pub(super) mod transfer;
 pub(super) mod transferable_log;
 
 pub use self::{
-  balance::Balance, errors::BRC20Error, events::Receipt, events::*, tick::*, token_info::TokenInfo,
-  transfer::TransferInfo, transferable_log::TransferableLog,
+  audit::{audit_transferable, TransferableMismatch},
+  balance::Balance,
+  errors::BRC20Error,
+  events::Receipt,
+  events::*,
+  tick::*,
+  token_info::TokenInfo,
+  transfer::TransferInfo,
+  transferable_log::TransferableLog,
 };
 use super::ScriptKey;
 use crate::{InscriptionId, Result};
@@ -25,11 +33,21 @@ pub trait DataStoreReadOnly {
     script_key: &ScriptKey,
     tick: &Tick,
   ) -> Result<Option<Balance>, Self::Error>;
+  fn get_all_acc_balance<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>;
 
   fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error>;
   fn get_tokens_info(&self) -> Result<Vec<TokenInfo>, Self::Error>;
 
   fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error>;
+  fn get_tick_receipts(
+    &self,
+    tick: &Tick,
+    op: Option<OperationType>,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickReceipt>, usize), Self::Error>;
 
   fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableLog>, Self::Error>;
   fn get_transferable_by_tick(
@@ -63,13 +81,18 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     tick: &Tick,
     minted_amt: u128,
     minted_block_number: u64,
+    minted_block_time: u32,
   ) -> Result<(), Self::Error>;
 
+  fn update_burned_token_info(&self, tick: &Tick, burned_amt: u128) -> Result<(), Self::Error>;
+
   fn save_transaction_receipts(&self, txid: &Txid, receipts: &[Receipt])
     -> Result<(), Self::Error>;
 
   fn add_transaction_receipt(&self, txid: &Txid, receipt: &Receipt) -> Result<(), Self::Error>;
 
+  fn add_tick_receipt(&self, tick: &Tick, receipt: &TickReceipt) -> Result<(), Self::Error>;
+
   fn insert_transferable(
     &self,
     script: &ScriptKey,
@@ -1,7 +1,7 @@
 mod read_only;
 mod read_write;
 
-use super::{LowerTick, ScriptKey, Tick};
+use super::{LowerTick, OperationType, ScriptKey, Tick};
 use crate::{InscriptionId, Result};
 
 use bitcoin::Txid;
@@ -16,6 +16,11 @@ const BRC20_TRANSFERABLELOG: TableDefinition<&str, &[u8]> =
   TableDefinition::new("BRC20_TRANSFERABLELOG");
 const BRC20_INSCRIBE_TRANSFER: TableDefinition<&[u8; 36], &[u8]> =
   TableDefinition::new("BRC20_INSCRIBE_TRANSFER");
+const BRC20_TICK_EVENTS: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_TICK_EVENTS");
+const BRC20_TICK_OP_EVENTS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20_TICK_OP_EVENTS");
+const BRC20_TICK_EVENT_COUNTS: TableDefinition<&str, u64> =
+  TableDefinition::new("BRC20_TICK_EVENT_COUNTS");
 
 fn script_tick_key(script: &ScriptKey, tick: &Tick) -> String {
   format!("{}_{}", script, tick.to_lowercase().hex())
@@ -28,3 +33,58 @@ fn min_script_tick_key(script: &ScriptKey) -> String {
 fn max_script_tick_key(script: &ScriptKey) -> String {
   format!("{}_{}", script, LowerTick::max_hex())
 }
+
+// the block height is fixed width so that the events of a ticker are ordered by height, then txid.
+fn tick_event_key(tick: &Tick, height: u64, txid: &Txid, inscription_id: &InscriptionId) -> String {
+  format!(
+    "{}_{:016x}_{}_{}",
+    tick.to_lowercase().hex(),
+    height,
+    txid,
+    inscription_id
+  )
+}
+
+fn min_tick_event_key(tick: &Tick) -> String {
+  format!("{}_", tick.to_lowercase().hex())
+}
+
+fn max_tick_event_key(tick: &Tick) -> String {
+  format!("{}_~", tick.to_lowercase().hex())
+}
+
+// the events of a ticker are indexed again by operation type, so a page of one type is read
+// without decoding the events of the other types.
+fn tick_op_prefix(tick: &Tick, op: &OperationType) -> String {
+  let op = match op {
+    OperationType::Deploy => "d",
+    OperationType::Mint => "m",
+    OperationType::InscribeTransfer => "i",
+    OperationType::Transfer => "t",
+  };
+  format!("{}_{}", tick.to_lowercase().hex(), op)
+}
+
+fn tick_op_event_key(
+  tick: &Tick,
+  op: &OperationType,
+  height: u64,
+  txid: &Txid,
+  inscription_id: &InscriptionId,
+) -> String {
+  format!(
+    "{}_{:016x}_{}_{}",
+    tick_op_prefix(tick, op),
+    height,
+    txid,
+    inscription_id
+  )
+}
+
+fn min_tick_op_event_key(tick: &Tick, op: &OperationType) -> String {
+  format!("{}_", tick_op_prefix(tick, op))
+}
+
+fn max_tick_op_event_key(tick: &Tick, op: &OperationType) -> String {
+  format!("{}_~", tick_op_prefix(tick, op))
+}
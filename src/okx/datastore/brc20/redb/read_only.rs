@@ -1,6 +1,7 @@
 use super::*;
 use crate::okx::datastore::brc20::{
-  Balance, DataStoreReadOnly, Receipt, Tick, TokenInfo, TransferInfo, TransferableLog,
+  Balance, DataStoreReadOnly, OperationType, Receipt, Tick, TickReceipt, TokenInfo, TransferInfo,
+  TransferableLog,
 };
 use redb::{
   AccessGuard, Range, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue,
@@ -8,6 +9,7 @@ use redb::{
 };
 use std::borrow::Borrow;
 use std::ops::RangeBounds;
+use std::str::FromStr;
 
 pub fn try_init_tables<'db, 'a>(
   wtx: &'a WriteTransaction<'db>,
@@ -19,6 +21,9 @@ pub fn try_init_tables<'db, 'a>(
     wtx.open_table(BRC20_EVENTS)?;
     wtx.open_table(BRC20_TRANSFERABLELOG)?;
     wtx.open_table(BRC20_INSCRIBE_TRANSFER)?;
+    wtx.open_table(BRC20_TICK_EVENTS)?;
+    wtx.open_table(BRC20_TICK_OP_EVENTS)?;
+    wtx.open_table(BRC20_TICK_EVENT_COUNTS)?;
   }
 
   Ok(true)
@@ -123,6 +128,25 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_all_acc_balance<F>(&self, mut f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>,
+  {
+    for result in self.wrapper.open_table(BRC20_BALANCES)?.range::<&str>(..)? {
+      let (key, data) = result?;
+      let script = key
+        .value()
+        .rsplit_once('_')
+        .and_then(|(script, _)| ScriptKey::from_str(script).ok())
+        .unwrap();
+      f(
+        &script,
+        bincode::deserialize::<Balance>(data.value()).unwrap(),
+      )?;
+    }
+    Ok(())
+  }
+
   fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error> {
     Ok(
       self
@@ -158,6 +182,47 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_tick_receipts(
+    &self,
+    tick: &Tick,
+    op: Option<OperationType>,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickReceipt>, usize), Self::Error> {
+    let (table, min, max, count_key) = match op.as_ref() {
+      Some(op) => (
+        BRC20_TICK_OP_EVENTS,
+        min_tick_op_event_key(tick, op),
+        max_tick_op_event_key(tick, op),
+        tick_op_prefix(tick, op),
+      ),
+      None => (
+        BRC20_TICK_EVENTS,
+        min_tick_event_key(tick),
+        max_tick_event_key(tick),
+        tick.to_lowercase().hex(),
+      ),
+    };
+    // the latest events first, only the events of the page are decoded.
+    let receipts = self
+      .wrapper
+      .open_table(table)?
+      .range(min.as_str()..max.as_str())?
+      .rev()
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| bincode::deserialize::<TickReceipt>(data.value()).unwrap())
+      })
+      .collect();
+    let total = self
+      .wrapper
+      .open_table(BRC20_TICK_EVENT_COUNTS)?
+      .get(count_key.as_str())?
+      .map_or(0, |count| count.value());
+    Ok((receipts, usize::try_from(total).unwrap()))
+  }
+
   fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableLog>, Self::Error> {
     Ok(
       self
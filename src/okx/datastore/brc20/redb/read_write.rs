@@ -1,14 +1,14 @@
 use crate::{
   okx::datastore::brc20::{
-    Balance, DataStoreReadOnly, DataStoreReadWrite, Receipt, Tick, TokenInfo, TransferInfo,
-    TransferableLog,
+    Balance, DataStoreReadOnly, DataStoreReadWrite, OperationType, Receipt, Tick, TickReceipt,
+    TokenInfo, TransferInfo, TransferableLog,
   },
   InscriptionId,
 };
 
 use super::*;
 use bitcoin::Txid;
-use redb::WriteTransaction;
+use redb::{ReadableTable, WriteTransaction};
 
 pub struct DataStore<'db, 'a> {
   wtx: &'a WriteTransaction<'db>,
@@ -35,6 +35,13 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_balance(script_key, tick)
   }
 
+  fn get_all_acc_balance<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>,
+  {
+    read_only::new_with_wtx(self.wtx).get_all_acc_balance(f)
+  }
+
   fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_token_info(tick)
   }
@@ -47,6 +54,16 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_transaction_receipts(txid)
   }
 
+  fn get_tick_receipts(
+    &self,
+    tick: &Tick,
+    op: Option<OperationType>,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickReceipt>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_tick_receipts(tick, op, start, limit)
+  }
+
   fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableLog>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_transferable(script)
   }
@@ -101,6 +118,7 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tick: &Tick,
     minted_amt: u128,
     minted_block_number: u64,
+    minted_block_time: u32,
   ) -> Result<(), Self::Error> {
     let mut info = self
       .get_token_info(tick)?
@@ -108,6 +126,24 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
     info.minted = minted_amt;
     info.latest_mint_number = minted_block_number;
+    if info.completed_block_height.is_none() && info.minted >= info.supply {
+      info.completed_block_height = Some(minted_block_number);
+      info.completed_block_time = Some(minted_block_time);
+    }
+
+    self.wtx.open_table(BRC20_TOKEN)?.insert(
+      tick.to_lowercase().hex().as_str(),
+      bincode::serialize(&info).unwrap().as_slice(),
+    )?;
+    Ok(())
+  }
+
+  fn update_burned_token_info(&self, tick: &Tick, burned_amt: u128) -> Result<(), Self::Error> {
+    let mut info = self
+      .get_token_info(tick)?
+      .unwrap_or_else(|| panic!("token {} not exist", tick.as_str()));
+
+    info.burned_supply = burned_amt;
 
     self.wtx.open_table(BRC20_TOKEN)?.insert(
       tick.to_lowercase().hex().as_str(),
@@ -134,6 +170,41 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     self.save_transaction_receipts(txid, &receipts)
   }
 
+  fn add_tick_receipt(&self, tick: &Tick, receipt: &TickReceipt) -> Result<(), Self::Error> {
+    let value = bincode::serialize(receipt).unwrap();
+    self.wtx.open_table(BRC20_TICK_EVENTS)?.insert(
+      tick_event_key(
+        tick,
+        receipt.height,
+        &receipt.txid,
+        &receipt.receipt.inscription_id,
+      )
+      .as_str(),
+      value.as_slice(),
+    )?;
+    self.wtx.open_table(BRC20_TICK_OP_EVENTS)?.insert(
+      tick_op_event_key(
+        tick,
+        &receipt.receipt.op,
+        receipt.height,
+        &receipt.txid,
+        &receipt.receipt.inscription_id,
+      )
+      .as_str(),
+      value.as_slice(),
+    )?;
+
+    let mut counts = self.wtx.open_table(BRC20_TICK_EVENT_COUNTS)?;
+    for key in [
+      tick.to_lowercase().hex(),
+      tick_op_prefix(tick, &receipt.receipt.op),
+    ] {
+      let count = counts.get(key.as_str())?.map_or(0, |count| count.value());
+      counts.insert(key.as_str(), count + 1)?;
+    }
+    Ok(())
+  }
+
   fn insert_transferable(
     &self,
     script: &ScriptKey,
@@ -214,8 +285,9 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 #[cfg(test)]
 mod tests {
   use crate::okx::datastore::brc20::{
-    BRC20Error, Balance, DataStoreReadOnly, DataStoreReadWrite, Event, MintEvent, OperationType,
-    Receipt, Tick, TokenInfo, TransferEvent, TransferableLog,
+    BRC20Error, Balance, BurnEvent, DataStoreReadOnly, DataStoreReadWrite, DeployEvent, Event,
+    InscripbeTransferEvent, MintEvent, OperationType, Receipt, Tick, TickReceipt, TokenInfo,
+    TransferEvent, TransferableLog,
   };
 
   use super::*;
@@ -298,6 +370,62 @@ mod tests {
     assert_eq!(all_balances, expect_balances);
   }
 
+  #[test]
+  fn test_get_all_acc_balance() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20db = DataStore::new(&wtx);
+
+    let script1 = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let script2 = ScriptKey::from_address(
+      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+        .unwrap()
+        .assume_checked(),
+    );
+    let balance1 = Balance {
+      tick: Tick::from_str("abcd").unwrap(),
+      overall_balance: 100,
+      transferable_balance: 30,
+    };
+    let balance2 = Balance {
+      tick: Tick::from_str("1234").unwrap(),
+      overall_balance: 10,
+      transferable_balance: 0,
+    };
+
+    brc20db
+      .update_token_balance(&script1, balance1.clone())
+      .unwrap();
+    brc20db
+      .update_token_balance(&script1, balance2.clone())
+      .unwrap();
+    brc20db
+      .update_token_balance(&script2, balance1.clone())
+      .unwrap();
+
+    let mut all_balances = Vec::new();
+    brc20db
+      .get_all_acc_balance(|script, balance| {
+        all_balances.push((script.to_string(), balance));
+        Ok(())
+      })
+      .unwrap();
+
+    assert_eq!(
+      all_balances,
+      vec![
+        (script2.to_string(), balance1.clone()),
+        (script1.to_string(), balance2),
+        (script1.to_string(), balance1),
+      ]
+    );
+  }
+
   #[test]
   fn test_set_get_balance() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -364,6 +492,9 @@ mod tests {
       deployed_number: 99,
       deployed_timestamp: 11222,
       latest_mint_number: 101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
 
     brc20db.insert_token_info(&upper_tick, &expect).unwrap();
@@ -401,6 +532,9 @@ mod tests {
       deployed_number: 99,
       deployed_timestamp: 11222,
       latest_mint_number: 101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     let expect2 = TokenInfo {
       tick: Tick::from_str("1234").unwrap(),
@@ -421,6 +555,9 @@ mod tests {
       deployed_number: 299,
       deployed_timestamp: 33222,
       latest_mint_number: 2101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     let expect3 = TokenInfo {
       tick: Tick::from_str("xyzm").unwrap(),
@@ -441,6 +578,9 @@ mod tests {
       deployed_number: 399,
       deployed_timestamp: 33222,
       latest_mint_number: 3101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
 
     let expect4 = TokenInfo {
@@ -462,6 +602,9 @@ mod tests {
       deployed_number: 499,
       deployed_timestamp: 44222,
       latest_mint_number: 4101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
 
     brc20db.insert_token_info(&expect1.tick, &expect1).unwrap();
@@ -503,13 +646,16 @@ mod tests {
       deployed_number: 99,
       deployed_timestamp: 33222,
       latest_mint_number: 101,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
 
     brc20db.insert_token_info(&tick, &org_info).unwrap();
     let mint_amount = 30;
     let mint_block = 222;
     brc20db
-      .update_mint_token_info(&tick, org_info.minted + mint_amount, mint_block)
+      .update_mint_token_info(&tick, org_info.minted + mint_amount, mint_block, 1687245485)
       .unwrap();
 
     let upper_tick = Tick::from_str("ABcD").unwrap();
@@ -518,6 +664,9 @@ mod tests {
       Some(TokenInfo {
         minted: org_info.minted + mint_amount,
         latest_mint_number: mint_block,
+        burned_supply: 0,
+        completed_block_height: None,
+        completed_block_time: None,
         ..org_info.clone()
       })
     );
@@ -526,6 +675,9 @@ mod tests {
       Some(TokenInfo {
         minted: org_info.minted + mint_amount,
         latest_mint_number: mint_block,
+        burned_supply: 0,
+        completed_block_height: None,
+        completed_block_time: None,
         ..org_info
       })
     );
@@ -637,6 +789,168 @@ mod tests {
     assert_eq!(brc20db.get_transaction_receipts(&txid).unwrap(), receipts);
   }
 
+  #[test]
+  fn test_get_tick_receipts() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick = Tick::from_str("abcd").unwrap();
+    let tick_receipt = |height: u64, index: u32, op: OperationType, event: Event| TickReceipt {
+      height,
+      txid: Txid::from_str(&format!("{index}{}", "1".repeat(63))).unwrap(),
+      receipt: Receipt {
+        inscription_id: InscriptionId::from_str(&format!("{index}{}i0", "1".repeat(63))).unwrap(),
+        inscription_number: index.into(),
+        old_satpoint: SatPoint::from_str(&format!("{index}{}:0:0", "1".repeat(63))).unwrap(),
+        new_satpoint: SatPoint::from_str(&format!("{index}{}:0:0", "1".repeat(63))).unwrap(),
+        op,
+        from: script.clone(),
+        to: script.clone(),
+        result: Ok(event),
+      },
+    };
+
+    let deploy = tick_receipt(
+      1,
+      1,
+      OperationType::Deploy,
+      Event::Deploy(DeployEvent {
+        supply: 100,
+        limit_per_mint: 10,
+        decimal: 18,
+        tick: tick.clone(),
+      }),
+    );
+    let mint = tick_receipt(
+      2,
+      2,
+      OperationType::Mint,
+      Event::Mint(MintEvent {
+        tick: tick.clone(),
+        amount: 10,
+        msg: None,
+      }),
+    );
+    let inscribe_transfer = tick_receipt(
+      2,
+      3,
+      OperationType::InscribeTransfer,
+      Event::InscribeTransfer(InscripbeTransferEvent {
+        tick: tick.clone(),
+        amount: 5,
+      }),
+    );
+    let transfer = tick_receipt(
+      3,
+      4,
+      OperationType::Transfer,
+      Event::Transfer(TransferEvent {
+        tick: tick.clone(),
+        amount: 5,
+        msg: None,
+      }),
+    );
+    let burn = tick_receipt(
+      4,
+      5,
+      OperationType::Transfer,
+      Event::Burn(BurnEvent {
+        tick: tick.clone(),
+        amount: 5,
+      }),
+    );
+    let other_tick = Tick::from_str("abce").unwrap();
+    let other = tick_receipt(
+      5,
+      6,
+      OperationType::Mint,
+      Event::Mint(MintEvent {
+        tick: other_tick.clone(),
+        amount: 10,
+        msg: None,
+      }),
+    );
+
+    for receipt in [&deploy, &mint, &inscribe_transfer, &transfer, &burn] {
+      brc20db.add_tick_receipt(&tick, receipt).unwrap();
+    }
+    brc20db.add_tick_receipt(&other_tick, &other).unwrap();
+
+    // the latest block first, then the greater txid.
+    let upper_tick = Tick::from_str("ABCD").unwrap();
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&upper_tick, None, 0, None)
+        .unwrap(),
+      (
+        vec![
+          burn.clone(),
+          transfer.clone(),
+          inscribe_transfer.clone(),
+          mint.clone(),
+          deploy.clone()
+        ],
+        5
+      )
+    );
+    assert_eq!(
+      brc20db.get_tick_receipts(&tick, None, 1, Some(2)).unwrap(),
+      (vec![transfer.clone(), inscribe_transfer.clone()], 5)
+    );
+
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&tick, Some(OperationType::Deploy), 0, None)
+        .unwrap(),
+      (vec![deploy], 1)
+    );
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&tick, Some(OperationType::Mint), 0, None)
+        .unwrap(),
+      (vec![mint], 1)
+    );
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&tick, Some(OperationType::InscribeTransfer), 0, None)
+        .unwrap(),
+      (vec![inscribe_transfer], 1)
+    );
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&tick, Some(OperationType::Transfer), 0, None)
+        .unwrap(),
+      (vec![burn.clone(), transfer], 2)
+    );
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&tick, Some(OperationType::Transfer), 1, Some(1))
+        .unwrap()
+        .1,
+      2
+    );
+
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&other_tick, None, 0, None)
+        .unwrap(),
+      (vec![other], 1)
+    );
+    assert_eq!(
+      brc20db
+        .get_tick_receipts(&Tick::from_str("none").unwrap(), None, 0, None)
+        .unwrap(),
+      (Vec::new(), 0)
+    );
+  }
+
   #[test]
   fn test_get_transferable_by_tick() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -14,4 +14,8 @@ pub struct TokenInfo {
   pub deployed_number: u64,
   pub deployed_timestamp: u32,
   pub latest_mint_number: u64,
+  pub burned_supply: u128,
+  // the block of the mint that reached the supply, `None` while the token is still mintable.
+  pub completed_block_height: Option<u64>,
+  pub completed_block_time: Option<u32>,
 }
@@ -0,0 +1,250 @@
+use super::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SupplyMismatch {
+  pub tick_id: TickId,
+  // `None` if the balances belong to a tick that was never deployed.
+  pub circulation: Option<u128>,
+  pub balance_total: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransferableMismatch {
+  pub script: ScriptKey,
+  pub tick_id: TickId,
+  pub overall_balance: u128,
+  pub transferable_balance: u128,
+  pub transferable_total: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct VerifyReport {
+  pub supply: Vec<SupplyMismatch>,
+  pub transferable: Vec<TransferableMismatch>,
+}
+
+// every claimed reward is credited to the balance of its owner, so per tick the balances must sum
+// up to the circulation. transferable assets are reserved from the balance of their owner, so per
+// address and tick they must sum up to the transferable balance and never exceed the overall
+// balance, an asset without any balance is backed by nothing.
+pub fn verify<T: DataStoreReadOnly>(store: &T) -> Result<VerifyReport, T::Error> {
+  let mut transferable_totals = BTreeMap::new();
+  store.get_all_transferable(|asset| {
+    let total = transferable_totals
+      .entry((asset.owner.to_string(), asset.tick_id.hex()))
+      .or_insert((asset.owner.clone(), asset.tick_id, 0u128));
+    total.2 = total.2.saturating_add(asset.amount);
+    Ok(())
+  })?;
+
+  let mut report = VerifyReport::default();
+  let mut balance_totals = BTreeMap::new();
+  store.get_all_acc_balance(|script, balance| {
+    let total = balance_totals
+      .entry(balance.tick_id.hex())
+      .or_insert((balance.tick_id, 0u128));
+    total.1 = total.1.saturating_add(balance.overall_balance);
+
+    let transferable_total = transferable_totals
+      .remove(&(script.to_string(), balance.tick_id.hex()))
+      .map_or(0, |(_, _, total)| total);
+    if transferable_total != balance.transferable_balance
+      || balance.transferable_balance > balance.overall_balance
+    {
+      report.transferable.push(TransferableMismatch {
+        script: script.clone(),
+        tick_id: balance.tick_id,
+        overall_balance: balance.overall_balance,
+        transferable_balance: balance.transferable_balance,
+        transferable_total,
+      });
+    }
+    Ok(())
+  })?;
+
+  for (script, tick_id, transferable_total) in transferable_totals.into_values() {
+    report.transferable.push(TransferableMismatch {
+      script,
+      tick_id,
+      overall_balance: 0,
+      transferable_balance: 0,
+      transferable_total,
+    });
+  }
+
+  let (tick_infos, _) = store.get_all_tick_info(0, None)?;
+  for tick_info in tick_infos {
+    let balance_total = balance_totals
+      .remove(&tick_info.tick_id.hex())
+      .map_or(0, |(_, total)| total);
+    if balance_total != tick_info.circulation {
+      report.supply.push(SupplyMismatch {
+        tick_id: tick_info.tick_id,
+        circulation: Some(tick_info.circulation),
+        balance_total,
+      });
+    }
+  }
+  for (tick_id, balance_total) in balance_totals.into_values() {
+    report.supply.push(SupplyMismatch {
+      tick_id,
+      circulation: None,
+      balance_total,
+    });
+  }
+
+  Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::brc20s::redb::DataStore;
+  use ::redb::Database;
+  use bitcoin::Address;
+  use std::str::FromStr;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_verify() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let script2 = ScriptKey::from_address(
+      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id = TickId::from_str("a3668daeaa").unwrap();
+    let inscription_id =
+      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    brc20s_db
+      .set_tick_info(
+        &tick_id,
+        &TickInfo::new(
+          tick_id,
+          &Tick::from_str("abcdef").unwrap(),
+          &inscription_id,
+          1000,
+          18,
+          100,
+          1000,
+          &script,
+          0,
+          0,
+          0,
+          Vec::new(),
+        ),
+      )
+      .unwrap();
+    brc20s_db
+      .set_token_balance(
+        &script,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 70,
+          transferable_balance: 20,
+        },
+      )
+      .unwrap();
+    brc20s_db
+      .set_token_balance(
+        &script2,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 30,
+          transferable_balance: 0,
+        },
+      )
+      .unwrap();
+    let asset = TransferableAsset {
+      inscription_id,
+      amount: 20,
+      tick_id,
+      owner: script.clone(),
+    };
+    brc20s_db
+      .set_transferable_assets(&script, &tick_id, &inscription_id, &asset)
+      .unwrap();
+    assert_eq!(verify(&brc20s_db).unwrap(), VerifyReport::default());
+
+    // a balance credited without minting, a transferable asset without a backing balance and a
+    // balance of a tick that was never deployed.
+    brc20s_db
+      .set_token_balance(
+        &script2,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 40,
+          transferable_balance: 0,
+        },
+      )
+      .unwrap();
+    let unbacked_id =
+      InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    brc20s_db
+      .set_transferable_assets(
+        &script2,
+        &tick_id,
+        &unbacked_id,
+        &TransferableAsset {
+          inscription_id: unbacked_id,
+          amount: 5,
+          tick_id,
+          owner: script2.clone(),
+        },
+      )
+      .unwrap();
+    let unknown_tick_id = TickId::from_str("b3668daeaa").unwrap();
+    brc20s_db
+      .set_token_balance(
+        &script,
+        &unknown_tick_id,
+        Balance {
+          tick_id: unknown_tick_id,
+          overall_balance: 10,
+          transferable_balance: 0,
+        },
+      )
+      .unwrap();
+
+    assert_eq!(
+      verify(&brc20s_db).unwrap(),
+      VerifyReport {
+        supply: vec![
+          SupplyMismatch {
+            tick_id,
+            circulation: Some(100),
+            balance_total: 110,
+          },
+          SupplyMismatch {
+            tick_id: unknown_tick_id,
+            circulation: None,
+            balance_total: 10,
+          },
+        ],
+        transferable: vec![TransferableMismatch {
+          script: script2,
+          tick_id,
+          overall_balance: 40,
+          transferable_balance: 0,
+          transferable_total: 5,
+        }],
+      }
+    );
+  }
+}
@@ -37,6 +37,7 @@ pub enum Event {
   Mint(MintEvent),
   InscribeTransfer(InscribeTransferEvent),
   Transfer(TransferEvent),
+  Burn(BurnEvent),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -62,6 +63,8 @@ pub struct DepositEvent {
   pub pid: Pid,
   pub amt: u128,
   pub period_settlement_reward: u128,
+  // the total staked in the pool once the operation is applied.
+  pub pool_total_after: u128,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -69,12 +72,16 @@ pub struct WithdrawEvent {
   pub pid: Pid,
   pub amt: u128,
   pub period_settlement_reward: u128,
+  // the total staked in the pool once the operation is applied.
+  pub pool_total_after: u128,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PassiveWithdrawEvent {
   pub pid: Pid,
   pub amt: u128,
+  // the total staked in the pool once the operation is applied.
+  pub pool_total_after: u128,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -96,6 +103,12 @@ pub struct TransferEvent {
   pub msg: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BurnEvent {
+  pub tick_id: TickId,
+  pub amt: u128,
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,15 +1,28 @@
+mod audit;
 mod balance;
 mod event;
+mod mutation;
 pub mod pool_info;
 pub mod redb;
 mod stake_info;
+mod stats;
 mod temp;
 mod tick;
 mod transfer;
 mod user_info;
 
 pub use self::{
-  balance::*, event::*, pool_info::*, stake_info::*, temp::*, tick::*, transfer::*, user_info::*,
+  audit::{verify, VerifyReport},
+  balance::*,
+  event::*,
+  mutation::*,
+  pool_info::*,
+  stake_info::*,
+  stats::*,
+  temp::*,
+  tick::*,
+  transfer::*,
+  user_info::*,
 };
 use crate::okx::datastore::ScriptKey;
 use crate::InscriptionId;
@@ -34,6 +47,12 @@ pub trait DataStoreReadOnly {
     limit: Option<usize>,
   ) -> Result<(Vec<TickInfo>, usize), Self::Error>;
 
+  fn get_all_tick_info_after(
+    &self,
+    after: Option<&TickId>,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error>;
+
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error>;
 
@@ -52,6 +71,8 @@ pub trait DataStoreReadOnly {
     pledged_tick: &PledgedTick,
   ) -> Result<Option<StakeInfo>, Self::Error>;
 
+  fn get_all_user_stakeinfo(&self, script_key: &ScriptKey) -> Result<Vec<StakeInfo>, Self::Error>;
+
   // BRC20S_PID_TO_USERINFO
   fn get_pid_to_use_info(
     &self,
@@ -59,6 +80,23 @@ pub trait DataStoreReadOnly {
     pid: &Pid,
   ) -> Result<Option<UserInfo>, Self::Error>;
 
+  fn get_user_infos(&self, script_key: &ScriptKey) -> Result<Vec<UserInfo>, Self::Error>;
+
+  fn get_user_pools(
+    &self,
+    script_key: &ScriptKey,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<UserInfo>, usize), Self::Error>;
+
+  // BRC20S_PID_TO_STAKERS
+  fn get_pool_stakers(
+    &self,
+    pid: &Pid,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, UserInfo)>, usize), Self::Error>;
+
   // BRC20S_STAKE_TICKID_TO_PID
   fn get_tickid_stake_to_pid(
     &self,
@@ -70,7 +108,29 @@ pub trait DataStoreReadOnly {
   fn get_tickid_to_all_pid(&self, tick_id: &TickId) -> Result<Vec<Pid>, Self::Error>;
 
   // get_stake_to_all_pid
-  fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error>;
+  fn get_stake_to_all_pid(
+    &self,
+    pledged: &PledgedTick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error>;
+
+  // BRC20S_EARN_TO_PID
+  fn get_earn_to_all_pid(
+    &self,
+    earn: &Tick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error>;
+
+  // BRC20S_DEPLOY_HEIGHT_TO_TICKID
+  fn get_tick_info_by_deploy_height(
+    &self,
+    from: u64,
+    to: u64,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error>;
 
   // BRC20S_BALANCE
   fn get_balance(
@@ -81,6 +141,26 @@ pub trait DataStoreReadOnly {
 
   fn get_balances(&self, script_key: &ScriptKey) -> Result<Vec<(TickId, Balance)>, Self::Error>;
 
+  fn get_all_acc_balance<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>;
+
+  // BRC20S_TICKID_TO_SCRIPT
+  fn get_tick_holders(
+    &self,
+    tick_id: &TickId,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, Balance)>, usize), Self::Error>;
+
+  // BRC20S_BALANCE_HISTORY, the latest balance recorded at or before the height.
+  fn get_balance_at(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    height: u64,
+  ) -> Result<Option<Balance>, Self::Error>;
+
   // BRC20S_TRANSFERABLE_ASSETS
   fn get_transferable_asset(
     &self,
@@ -91,6 +171,10 @@ pub trait DataStoreReadOnly {
 
   fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableAsset>, Self::Error>;
 
+  fn get_all_transferable<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(TransferableAsset) -> Result<(), Self::Error>;
+
   fn get_transferable_by_tickid(
     &self,
     script: &ScriptKey,
@@ -111,6 +195,12 @@ pub trait DataStoreReadOnly {
     &self,
     inscription_id: InscriptionId,
   ) -> Result<Option<TransferInfo>, Self::Error>;
+
+  // BRC20S_BLOCK_MUTATIONS, the balance and pool changes applied by the block at the height.
+  fn get_block_mutations(&self, height: u64) -> Result<Vec<Mutation>, Self::Error>;
+
+  // BRC20S_BLOCK_OPERATION_COUNTS, the summed operation counts of the blocks from `from` to `to`.
+  fn get_operation_counts(&self, from: u64, to: u64) -> Result<OperationCounts, Self::Error>;
 }
 
 pub trait DataStoreReadWrite: DataStoreReadOnly {
@@ -151,6 +241,12 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     pid: &Pid,
   ) -> Result<(), Self::Error>;
 
+  // BRC20S_EARN_TO_PID
+  fn set_earn_to_pid(&self, earn: &Tick, pid: &Pid) -> Result<(), Self::Error>;
+
+  // BRC20S_DEPLOY_HEIGHT_TO_TICKID
+  fn set_deploy_height_to_tickid(&self, height: u64, tick_id: &TickId) -> Result<(), Self::Error>;
+
   // BRC20S_BALANCE
   fn set_token_balance(
     &self,
@@ -159,6 +255,15 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     balance: Balance,
   ) -> Result<(), Self::Error>;
 
+  // BRC20S_BALANCE_HISTORY
+  fn set_balance_history(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    height: u64,
+    balance: &Balance,
+  ) -> Result<(), Self::Error>;
+
   // BRC20S_TRANSFERABLE_ASSETS
   fn set_transferable_assets(
     &self,
@@ -190,4 +295,26 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     &self,
     inscription_id: InscriptionId,
   ) -> Result<(), Self::Error>;
+
+  // BRC20S_BLOCK_MUTATIONS, moves the balance and pool changes recorded since the last save to the
+  // block at the height and drops the changes of the blocks deeper than a reorg can roll back.
+  // Does nothing when the store doesn't record changes.
+  fn save_block_mutations(&self, height: u64) -> Result<(), Self::Error>;
+
+  // BRC20S_BLOCK_OPERATION_COUNTS, the operation counts of the block at the height.
+  fn set_block_operation_counts(
+    &self,
+    height: u64,
+    counts: &OperationCounts,
+  ) -> Result<(), Self::Error>;
+
+  // sets the point `rollback_to_savepoint` undoes the writes back to, replacing a previous one.
+  // The inscribe-transfer records and block mutations saved since are not undone.
+  fn savepoint(&self) -> Result<(), Self::Error>;
+
+  // keeps the writes since the savepoint.
+  fn release_savepoint(&self) -> Result<(), Self::Error>;
+
+  // undoes the writes since the savepoint and releases it, nothing is undone without a savepoint.
+  fn rollback_to_savepoint(&self) -> Result<(), Self::Error>;
 }
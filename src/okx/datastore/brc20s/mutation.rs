@@ -0,0 +1,21 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// A balance or pool change applied while executing a block. The changes of a block are kept in
+/// the order they were applied with the state before and after, so the balances and pools of the
+/// block can be audited or undone by applying them backwards. The other brc20s tables, such as
+/// tick infos, user infos and receipts, are not logged.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Mutation {
+  Balance {
+    script_key: ScriptKey,
+    tick_id: TickId,
+    old: Option<Balance>,
+    new: Balance,
+  },
+  Pool {
+    pid: Pid,
+    old: Option<Box<PoolInfo>>,
+    new: Box<PoolInfo>,
+  },
+}
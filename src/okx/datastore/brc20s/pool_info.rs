@@ -93,6 +93,13 @@ pub struct PoolInfo {
   pub only: bool,
   pub deploy_block: u64,
   pub deploy_block_time: u32,
+  // the erate halves every this many blocks since the deploy block, `None` for a constant erate.
+  pub halving_interval: Option<u64>,
+  // the least amount a single stake into the pool must have, `None` for no minimum.
+  pub min_stake: Option<u128>,
+  // rewards are weighted by the age of a stake until it is this many blocks old, `None` to weight
+  // by amount only.
+  pub maturity: Option<u64>,
 }
 
 impl PoolInfo {
@@ -110,6 +117,9 @@ impl PoolInfo {
     only: bool,
     deploy_block: u64,
     deploy_block_time: u32,
+    halving_interval: Option<u64>,
+    min_stake: Option<u128>,
+    maturity: Option<u64>,
   ) -> Self {
     Self {
       pid: pid.clone(),
@@ -125,6 +135,9 @@ impl PoolInfo {
       only,
       deploy_block,
       deploy_block_time,
+      halving_interval,
+      min_stake,
+      maturity,
     }
   }
 }
@@ -1,13 +1,17 @@
 use super::*;
 use crate::okx::datastore::brc20s::{
-  Balance, DataStoreReadOnly, InscriptionOperation, Pid, PledgedTick, PoolInfo, Receipt, StakeInfo,
-  TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+  Balance, DataStoreReadOnly, InscriptionOperation, Mutation, OperationCounts, Pid, PledgedTick,
+  PoolInfo, Receipt, StakeInfo, Tick, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
 };
 use redb::{
   AccessGuard, Range, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue,
   StorageError, Table, TableDefinition, WriteTransaction,
 };
-use std::{borrow::Borrow, ops::RangeBounds};
+use std::{
+  borrow::Borrow,
+  ops::{Bound, RangeBounds},
+  str::FromStr,
+};
 
 pub fn try_init_tables<'db, 'a>(
   wtx: &'a WriteTransaction<'db>,
@@ -18,12 +22,19 @@ pub fn try_init_tables<'db, 'a>(
     wtx.open_table(BRC20S_PID_TO_POOLINFO)?;
     wtx.open_table(BRC20S_USER_STAKEINFO)?;
     wtx.open_table(BRC20S_PID_TO_USERINFO)?;
+    wtx.open_table(BRC20S_PID_TO_STAKERS)?;
     wtx.open_table(BRC20S_STAKE_TICKID_TO_PID)?;
     wtx.open_table(BRC20S_TICKID_STAKE_TO_PID)?;
+    wtx.open_table(BRC20S_EARN_TO_PID)?;
+    wtx.open_table(BRC20S_DEPLOY_HEIGHT_TO_TICKID)?;
     wtx.open_table(BRC20S_BALANCES)?;
+    wtx.open_table(BRC20S_TICKID_TO_SCRIPT)?;
+    wtx.open_table(BRC20S_BALANCE_HISTORY)?;
     wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?;
     wtx.open_table(BRC20S_TXID_TO_RECEIPTS)?;
     wtx.open_table(BRC20S_INSCRIBE_TRANSFER)?;
+    wtx.open_table(BRC20S_BLOCK_MUTATIONS)?;
+    wtx.open_table(BRC20S_BLOCK_OPERATION_COUNTS)?;
   }
 
   Ok(true)
@@ -158,6 +169,30 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     Ok((tickinfos, usize::try_from(total).unwrap()))
   }
 
+  fn get_all_tick_info_after(
+    &self,
+    after: Option<&TickId>,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
+    let table = self.wrapper.open_table(BRC20S_TICKINFO)?;
+    let total = table.len()?;
+    let min_key = TickId::min_hex();
+    let max_key = TickId::max_hex();
+    let after_key = after.map(|tick_id| tick_id.hex());
+    let lower = match after_key.as_deref() {
+      Some(key) => Bound::Excluded(key),
+      None => Bound::Included(min_key.as_str()),
+    };
+    let tickinfos = table
+      .range::<&str>((lower, Bound::Excluded(max_key.as_str())))?
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| bincode::deserialize::<TickInfo>(data.value()).unwrap())
+      })
+      .collect();
+    Ok((tickinfos, usize::try_from(total).unwrap()))
+  }
+
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
     Ok(
@@ -221,6 +256,21 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_all_user_stakeinfo(&self, script_key: &ScriptKey) -> Result<Vec<StakeInfo>, Self::Error> {
+    Ok(
+      self
+        .wrapper
+        .open_table(BRC20S_USER_STAKEINFO)?
+        .range(
+          min_script_pledged_key(script_key).as_str()..max_script_pledged_key(script_key).as_str(),
+        )?
+        .flat_map(|result| {
+          result.map(|(_, data)| bincode::deserialize::<StakeInfo>(data.value()).unwrap())
+        })
+        .collect(),
+    )
+  }
+
   // BRC20S_PID_TO_USERINFO
   fn get_pid_to_use_info(
     &self,
@@ -236,6 +286,70 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_user_infos(&self, script_key: &ScriptKey) -> Result<Vec<UserInfo>, Self::Error> {
+    Ok(
+      self
+        .wrapper
+        .open_table(BRC20S_PID_TO_USERINFO)?
+        .range(min_script_pid_key(script_key).as_str()..max_script_pid_key(script_key).as_str())?
+        .flat_map(|result| {
+          result.map(|(_, data)| bincode::deserialize::<UserInfo>(data.value()).unwrap())
+        })
+        .collect(),
+    )
+  }
+
+  fn get_user_pools(
+    &self,
+    script_key: &ScriptKey,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<UserInfo>, usize), Self::Error> {
+    let min = min_script_pid_key(script_key);
+    let max = max_script_pid_key(script_key);
+    let table = self.wrapper.open_table(BRC20S_PID_TO_USERINFO)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    // keys are ordered by pid after the script key prefix.
+    let user_infos = table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| bincode::deserialize::<UserInfo>(data.value()).unwrap())
+      })
+      .collect();
+    Ok((user_infos, total))
+  }
+
+  // BRC20S_PID_TO_STAKERS
+  fn get_pool_stakers(
+    &self,
+    pid: &Pid,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, UserInfo)>, usize), Self::Error> {
+    let min = min_pid_script_key(pid);
+    let max = max_pid_script_key(pid);
+    let table = self.wrapper.open_table(BRC20S_PID_TO_STAKERS)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    let scripts = table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| bincode::deserialize::<ScriptKey>(data.value()).unwrap())
+      })
+      .collect::<Vec<ScriptKey>>();
+
+    let mut stakers = Vec::new();
+    for script in scripts {
+      if let Some(user_info) = self.get_pid_to_use_info(&script, pid)? {
+        stakers.push((script, user_info));
+      }
+    }
+    Ok((stakers, total))
+  }
+
   // BRC20S_STAKE_TICKID_TO_PID
   fn get_tickid_stake_to_pid(
     &self,
@@ -271,20 +385,81 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
   }
 
   // get_stake_to_all_pid
-  fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error> {
-    Ok(
-      self
-        .wrapper
-        .open_table(BRC20S_STAKE_TICKID_TO_PID)?
-        .range(min_stake_tickid_key(pledged).as_str()..max_stake_tickid_key(pledged).as_str())?
-        .flat_map(|result| {
-          result.map(|(_, data)| {
-            let pid = bincode::deserialize::<Pid>(data.value()).unwrap();
-            pid
-          })
+  fn get_stake_to_all_pid(
+    &self,
+    pledged: &PledgedTick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error> {
+    let min = min_stake_tickid_key(pledged);
+    let max = max_stake_tickid_key(pledged);
+    let table = self.wrapper.open_table(BRC20S_STAKE_TICKID_TO_PID)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    // keys are ordered by tick id, which is the prefix of the pid.
+    let pids = table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| {
+          let pid = bincode::deserialize::<Pid>(data.value()).unwrap();
+          pid
         })
-        .collect(),
-    )
+      })
+      .collect();
+    Ok((pids, total))
+  }
+
+  // BRC20S_EARN_TO_PID
+  fn get_earn_to_all_pid(
+    &self,
+    earn: &Tick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error> {
+    let min = min_earn_pid_key(earn);
+    let max = max_earn_pid_key(earn);
+    let table = self.wrapper.open_table(BRC20S_EARN_TO_PID)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    let pids = table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| {
+          let pid = bincode::deserialize::<Pid>(data.value()).unwrap();
+          pid
+        })
+      })
+      .collect();
+    Ok((pids, total))
+  }
+
+  // BRC20S_DEPLOY_HEIGHT_TO_TICKID
+  fn get_tick_info_by_deploy_height(
+    &self,
+    from: u64,
+    to: u64,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
+    let min = min_deploy_height_tickid_key(from);
+    let max = max_deploy_height_tickid_key(to);
+    let table = self.wrapper.open_table(BRC20S_DEPLOY_HEIGHT_TO_TICKID)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    let mut tickinfos = Vec::new();
+    for result in table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+    {
+      let (_, data) = result?;
+      let tick_id = bincode::deserialize::<TickId>(data.value()).unwrap();
+      if let Some(tick_info) = self.get_tick_info(&tick_id)? {
+        tickinfos.push(tick_info);
+      }
+    }
+    Ok((tickinfos, total))
   }
 
   // BRC20S_BALANCE
@@ -324,6 +499,78 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_all_acc_balance<F>(&self, mut f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>,
+  {
+    for result in self
+      .wrapper
+      .open_table(BRC20S_BALANCES)?
+      .range::<&str>(..)?
+    {
+      let (key, data) = result?;
+      let script = key
+        .value()
+        .rsplit_once('_')
+        .and_then(|(script, _)| ScriptKey::from_str(script).ok())
+        .unwrap();
+      f(
+        &script,
+        bincode::deserialize::<Balance>(data.value()).unwrap(),
+      )?;
+    }
+    Ok(())
+  }
+
+  // BRC20S_TICKID_TO_SCRIPT
+  fn get_tick_holders(
+    &self,
+    tick_id: &TickId,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, Balance)>, usize), Self::Error> {
+    let min = min_tickid_script_key(tick_id);
+    let max = max_tickid_script_key(tick_id);
+    let table = self.wrapper.open_table(BRC20S_TICKID_TO_SCRIPT)?;
+    let total = table.range(min.as_str()..max.as_str())?.count();
+    let scripts = table
+      .range(min.as_str()..max.as_str())?
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .flat_map(|result| {
+        result.map(|(_, data)| bincode::deserialize::<ScriptKey>(data.value()).unwrap())
+      })
+      .collect::<Vec<ScriptKey>>();
+
+    let mut holders = Vec::new();
+    for script in scripts {
+      if let Some(balance) = self.get_balance(&script, tick_id)? {
+        holders.push((script, balance));
+      }
+    }
+    Ok((holders, total))
+  }
+
+  // BRC20S_BALANCE_HISTORY
+  fn get_balance_at(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    height: u64,
+  ) -> Result<Option<Balance>, Self::Error> {
+    let min = script_tickid_height_key(script_key, tick_id, 0);
+    let max = script_tickid_height_key(script_key, tick_id, height);
+    Ok(
+      self
+        .wrapper
+        .open_table(BRC20S_BALANCE_HISTORY)?
+        .range(min.as_str()..=max.as_str())?
+        .next_back()
+        .transpose()?
+        .map(|(_, data)| bincode::deserialize::<Balance>(data.value()).unwrap()),
+    )
+  }
+
   // BRC20S_TRANSFERABLE_ASSETS
   fn get_transferable_asset(
     &self,
@@ -353,6 +600,21 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     )
   }
 
+  fn get_all_transferable<F>(&self, mut f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(TransferableAsset) -> Result<(), Self::Error>,
+  {
+    for result in self
+      .wrapper
+      .open_table(BRC20S_TRANSFERABLE_ASSETS)?
+      .range::<&str>(..)?
+    {
+      let (_, data) = result?;
+      f(bincode::deserialize::<TransferableAsset>(data.value()).unwrap())?;
+    }
+    Ok(())
+  }
+
   fn get_transferable_by_tickid(
     &self,
     script: &ScriptKey,
@@ -423,4 +685,31 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
         .map(|v| bincode::deserialize::<TransferInfo>(v.value()).unwrap()),
     )
   }
+
+  // BRC20S_BLOCK_MUTATIONS
+  fn get_block_mutations(&self, height: u64) -> Result<Vec<Mutation>, Self::Error> {
+    Ok(
+      self
+        .wrapper
+        .open_table(BRC20S_BLOCK_MUTATIONS)?
+        .get(height)?
+        .map_or(Vec::new(), |v| {
+          bincode::deserialize::<Vec<Mutation>>(v.value()).unwrap()
+        }),
+    )
+  }
+
+  // BRC20S_BLOCK_OPERATION_COUNTS
+  fn get_operation_counts(&self, from: u64, to: u64) -> Result<OperationCounts, Self::Error> {
+    let mut counts = OperationCounts::default();
+    for result in self
+      .wrapper
+      .open_table(BRC20S_BLOCK_OPERATION_COUNTS)?
+      .range(from..=to)?
+    {
+      let (_, data) = result?;
+      counts.merge(&bincode::deserialize::<OperationCounts>(data.value()).unwrap());
+    }
+    Ok(counts)
+  }
 }
@@ -1,21 +1,100 @@
 use super::*;
 use crate::{
+  index::MAX_REORG_DEPTH,
   okx::datastore::brc20s::{
-    Balance, DataStoreReadOnly, DataStoreReadWrite, InscriptionOperation, Pid, PoolInfo, Receipt,
-    StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+    Balance, DataStoreReadOnly, DataStoreReadWrite, InscriptionOperation, Mutation,
+    OperationCounts, Pid, PoolInfo, Receipt, StakeInfo, Tick, TickId, TickInfo, TransferInfo,
+    TransferableAsset, UserInfo,
   },
   InscriptionId,
 };
 use bitcoin::Txid;
-use redb::WriteTransaction;
+use redb::{ReadableTable, TableDefinition, WriteTransaction};
+use std::cell::RefCell;
 
 pub struct DataStore<'db, 'a> {
   wtx: &'a WriteTransaction<'db>,
+  // the balance and pool changes applied since the mutations were last saved to a block, `None`
+  // when the store doesn't record them.
+  mutations: Option<RefCell<Vec<Mutation>>>,
+  savepoint: RefCell<Option<Savepoint>>,
+}
+
+// the previous values of the entries written since the savepoint was set, in the order they were
+// written, and the number of mutations recorded before it.
+struct Savepoint {
+  mutations: usize,
+  undo: Vec<(
+    TableDefinition<'static, &'static str, &'static [u8]>,
+    String,
+    Option<Vec<u8>>,
+  )>,
 }
 
 impl<'db, 'a> DataStore<'db, 'a> {
   pub fn new(wtx: &'a WriteTransaction<'db>) -> Self {
-    Self { wtx }
+    Self {
+      wtx,
+      mutations: None,
+      savepoint: RefCell::new(None),
+    }
+  }
+
+  // a store which also records the balance and pool changes to be saved as block mutations.
+  pub fn new_with_mutations(wtx: &'a WriteTransaction<'db>) -> Self {
+    Self {
+      wtx,
+      mutations: Some(RefCell::new(Vec::new())),
+      savepoint: RefCell::new(None),
+    }
+  }
+
+  // writes to the tables keyed by string go through `insert` and `remove`, so they can be undone
+  // while a savepoint is set. That covers every table written by deploy, mint, deposit and withdraw.
+  fn insert(
+    &self,
+    table: TableDefinition<'static, &'static str, &'static [u8]>,
+    key: &str,
+    value: &[u8],
+  ) -> Result<Option<Vec<u8>>, redb::Error> {
+    let old = self
+      .wtx
+      .open_table(table)?
+      .insert(key, value)?
+      .map(|old| old.value().to_vec());
+    self.journal(table, key, old.clone());
+    Ok(old)
+  }
+
+  fn remove(
+    &self,
+    table: TableDefinition<'static, &'static str, &'static [u8]>,
+    key: &str,
+  ) -> Result<(), redb::Error> {
+    let old = self
+      .wtx
+      .open_table(table)?
+      .remove(key)?
+      .map(|old| old.value().to_vec());
+    self.journal(table, key, old);
+    Ok(())
+  }
+
+  fn record(&self, mutation: impl FnOnce() -> Option<Mutation>) {
+    if let Some(mutations) = self.mutations.as_ref() {
+      mutations.borrow_mut().extend(mutation());
+    }
+  }
+
+  fn journal(
+    &self,
+    table: TableDefinition<'static, &'static str, &'static [u8]>,
+    key: &str,
+    old: Option<Vec<u8>>,
+  ) {
+    if let Some(savepoint) = self.savepoint.borrow_mut().as_mut() {
+      savepoint.undo.push((table, key.to_string(), old));
+    }
   }
 }
 
@@ -43,6 +122,14 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_all_tick_info(start, limit)
   }
 
+  fn get_all_tick_info_after(
+    &self,
+    after: Option<&TickId>,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_all_tick_info_after(after, limit)
+  }
+
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_pid_to_poolinfo(pid)
@@ -69,6 +156,10 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_user_stakeinfo(script_key, pledged_tick)
   }
 
+  fn get_all_user_stakeinfo(&self, script_key: &ScriptKey) -> Result<Vec<StakeInfo>, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_all_user_stakeinfo(script_key)
+  }
+
   // BRC20S_PID_TO_USERINFO
   fn get_pid_to_use_info(
     &self,
@@ -78,6 +169,29 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_pid_to_use_info(script_key, pid)
   }
 
+  fn get_user_infos(&self, script_key: &ScriptKey) -> Result<Vec<UserInfo>, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_user_infos(script_key)
+  }
+
+  fn get_user_pools(
+    &self,
+    script_key: &ScriptKey,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<UserInfo>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_user_pools(script_key, start, limit)
+  }
+
+  // BRC20S_PID_TO_STAKERS
+  fn get_pool_stakers(
+    &self,
+    pid: &Pid,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, UserInfo)>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_pool_stakers(pid, start, limit)
+  }
+
   // BRC20S_STAKE_TICKID_TO_PID
   fn get_tickid_stake_to_pid(
     &self,
@@ -93,8 +207,34 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
   }
 
   // get_stake_to_all_pid
-  fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error> {
-    read_only::new_with_wtx(self.wtx).get_stake_to_all_pid(pledged)
+  fn get_stake_to_all_pid(
+    &self,
+    pledged: &PledgedTick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_stake_to_all_pid(pledged, start, limit)
+  }
+
+  // BRC20S_EARN_TO_PID
+  fn get_earn_to_all_pid(
+    &self,
+    earn: &Tick,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<Pid>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_earn_to_all_pid(earn, start, limit)
+  }
+
+  // BRC20S_DEPLOY_HEIGHT_TO_TICKID
+  fn get_tick_info_by_deploy_height(
+    &self,
+    from: u64,
+    to: u64,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_tick_info_by_deploy_height(from, to, start, limit)
   }
 
   // BRC20S_BALANCE
@@ -110,6 +250,33 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_balances(script_key)
   }
 
+  fn get_all_acc_balance<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(&ScriptKey, Balance) -> Result<(), Self::Error>,
+  {
+    read_only::new_with_wtx(self.wtx).get_all_acc_balance(f)
+  }
+
+  // BRC20S_TICKID_TO_SCRIPT
+  fn get_tick_holders(
+    &self,
+    tick_id: &TickId,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<(ScriptKey, Balance)>, usize), Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_tick_holders(tick_id, start, limit)
+  }
+
+  // BRC20S_BALANCE_HISTORY
+  fn get_balance_at(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    height: u64,
+  ) -> Result<Option<Balance>, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_balance_at(script_key, tick_id, height)
+  }
+
   // BRC20S_TRANSFERABLE_ASSETS
   fn get_transferable_asset(
     &self,
@@ -124,6 +291,13 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_transferable(script)
   }
 
+  fn get_all_transferable<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: FnMut(TransferableAsset) -> Result<(), Self::Error>,
+  {
+    read_only::new_with_wtx(self.wtx).get_all_transferable(f)
+  }
+
   fn get_transferable_by_tickid(
     &self,
     script: &ScriptKey,
@@ -155,6 +329,16 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
   ) -> Result<Option<TransferInfo>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_inscribe_transfer_inscription(inscription_id)
   }
+
+  // BRC20S_BLOCK_MUTATIONS
+  fn get_block_mutations(&self, height: u64) -> Result<Vec<Mutation>, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_block_mutations(height)
+  }
+
+  // BRC20S_BLOCK_OPERATION_COUNTS
+  fn get_operation_counts(&self, from: u64, to: u64) -> Result<OperationCounts, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_operation_counts(from, to)
+  }
 }
 
 impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
@@ -164,7 +348,8 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tx_id: &Txid,
     inscription_operations: &[InscriptionOperation],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(TXID_TO_INSCRIPTION_RECEIPTS)?.insert(
+    self.insert(
+      TXID_TO_INSCRIPTION_RECEIPTS,
       tx_id.to_string().as_str(),
       bincode::serialize(inscription_operations)
         .unwrap()
@@ -175,7 +360,8 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
   // BRC20S_TICKINFO
   fn set_tick_info(&self, tick_id: &TickId, tick_info: &TickInfo) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TICKINFO)?.insert(
+    self.insert(
+      BRC20S_TICKINFO,
       tick_id.hex().as_str(),
       bincode::serialize(tick_info).unwrap().as_slice(),
     )?;
@@ -184,10 +370,19 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
   // BRC20S_PID_TO_POOLINFO
   fn set_pid_to_poolinfo(&self, pid: &Pid, pool_info: &PoolInfo) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_PID_TO_POOLINFO)?.insert(
+    let old = self.insert(
+      BRC20S_PID_TO_POOLINFO,
       pid.hex().as_str(),
       bincode::serialize(pool_info).unwrap().as_slice(),
     )?;
+    self.record(|| {
+      let old = old.map(|old| bincode::deserialize::<PoolInfo>(&old).unwrap());
+      (old.as_ref() != Some(pool_info)).then(|| Mutation::Pool {
+        pid: pid.clone(),
+        old: old.map(Box::new),
+        new: Box::new(pool_info.clone()),
+      })
+    });
     Ok(())
   }
 
@@ -198,24 +393,40 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     pledged_tick: &PledgedTick,
     stake_info: &StakeInfo,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_USER_STAKEINFO)?.insert(
+    self.insert(
+      BRC20S_USER_STAKEINFO,
       script_pledged_key(script_key, pledged_tick).as_str(),
       bincode::serialize(stake_info).unwrap().as_slice(),
     )?;
     Ok(())
   }
 
-  // BRC20S_PID_TO_USERINFO
+  // BRC20S_PID_TO_USERINFO, BRC20S_PID_TO_STAKERS
   fn set_pid_to_use_info(
     &self,
     script_key: &ScriptKey,
     pid: &Pid,
     user_info: &UserInfo,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_PID_TO_USERINFO)?.insert(
+    self.insert(
+      BRC20S_PID_TO_USERINFO,
       script_pid_key(script_key, pid).as_str(),
       bincode::serialize(user_info).unwrap().as_slice(),
     )?;
+
+    // only users with a stake left are listed as stakers of the pool.
+    if user_info.staked > 0 {
+      self.insert(
+        BRC20S_PID_TO_STAKERS,
+        pid_script_key(pid, script_key).as_str(),
+        bincode::serialize(script_key).unwrap().as_slice(),
+      )?;
+    } else {
+      self.remove(
+        BRC20S_PID_TO_STAKERS,
+        pid_script_key(pid, script_key).as_str(),
+      )?;
+    }
     Ok(())
   }
 
@@ -226,29 +437,91 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     pledged: &PledgedTick,
     pid: &Pid,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_STAKE_TICKID_TO_PID)?.insert(
+    self.insert(
+      BRC20S_STAKE_TICKID_TO_PID,
       stake_tickid_key(pledged, tick_id).as_str(),
       bincode::serialize(pid).unwrap().as_slice(),
     )?;
 
-    self.wtx.open_table(BRC20S_TICKID_STAKE_TO_PID)?.insert(
+    self.insert(
+      BRC20S_TICKID_STAKE_TO_PID,
       tickid_stake_key(pledged, tick_id).as_str(),
       bincode::serialize(pid).unwrap().as_slice(),
     )?;
     Ok(())
   }
 
-  // BRC20S_BALANCE
+  // BRC20S_EARN_TO_PID
+  fn set_earn_to_pid(&self, earn: &Tick, pid: &Pid) -> Result<(), Self::Error> {
+    self.insert(
+      BRC20S_EARN_TO_PID,
+      earn_pid_key(earn, pid).as_str(),
+      bincode::serialize(pid).unwrap().as_slice(),
+    )?;
+    Ok(())
+  }
+
+  // BRC20S_DEPLOY_HEIGHT_TO_TICKID
+  fn set_deploy_height_to_tickid(&self, height: u64, tick_id: &TickId) -> Result<(), Self::Error> {
+    self.insert(
+      BRC20S_DEPLOY_HEIGHT_TO_TICKID,
+      deploy_height_tickid_key(height, tick_id).as_str(),
+      bincode::serialize(tick_id).unwrap().as_slice(),
+    )?;
+    Ok(())
+  }
+
+  // BRC20S_BALANCE, BRC20S_TICKID_TO_SCRIPT
   fn set_token_balance(
     &self,
     script_key: &ScriptKey,
     tick_id: &TickId,
     balance: Balance,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_BALANCES)?.insert(
+    let old = self.insert(
+      BRC20S_BALANCES,
       script_tickid_key(script_key, tick_id).as_str(),
       bincode::serialize(&balance).unwrap().as_slice(),
     )?;
+
+    // only scripts with a balance left are listed as holders of the tick.
+    if balance.overall_balance > 0 {
+      self.insert(
+        BRC20S_TICKID_TO_SCRIPT,
+        tickid_script_key(tick_id, script_key).as_str(),
+        bincode::serialize(script_key).unwrap().as_slice(),
+      )?;
+    } else {
+      self.remove(
+        BRC20S_TICKID_TO_SCRIPT,
+        tickid_script_key(tick_id, script_key).as_str(),
+      )?;
+    }
+    self.record(|| {
+      let old = old.map(|old| bincode::deserialize::<Balance>(&old).unwrap());
+      (old != Some(balance)).then(|| Mutation::Balance {
+        script_key: script_key.clone(),
+        tick_id: *tick_id,
+        old,
+        new: balance,
+      })
+    });
+    Ok(())
+  }
+
+  // BRC20S_BALANCE_HISTORY
+  fn set_balance_history(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    height: u64,
+    balance: &Balance,
+  ) -> Result<(), Self::Error> {
+    self.insert(
+      BRC20S_BALANCE_HISTORY,
+      script_tickid_height_key(script_key, tick_id, height).as_str(),
+      bincode::serialize(balance).unwrap().as_slice(),
+    )?;
     Ok(())
   }
 
@@ -260,7 +533,8 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     inscription_id: &InscriptionId,
     transferable_asset: &TransferableAsset,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?.insert(
+    self.insert(
+      BRC20S_TRANSFERABLE_ASSETS,
       script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str(),
       bincode::serialize(transferable_asset).unwrap().as_slice(),
     )?;
@@ -279,7 +553,8 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tx_id: &Txid,
     receipts: &[Receipt],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TXID_TO_RECEIPTS)?.insert(
+    self.insert(
+      BRC20S_TXID_TO_RECEIPTS,
       tx_id.to_string().as_str(),
       bincode::serialize(receipts).unwrap().as_slice(),
     )?;
@@ -292,10 +567,10 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tick_id: &TickId,
     inscription_id: &InscriptionId,
   ) -> Result<(), Self::Error> {
-    self
-      .wtx
-      .open_table(BRC20S_TRANSFERABLE_ASSETS)?
-      .remove(script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str())?;
+    self.remove(
+      BRC20S_TRANSFERABLE_ASSETS,
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str(),
+    )?;
     Ok(())
   }
 
@@ -331,6 +606,77 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
       .remove(&value)?;
     Ok(())
   }
+
+  // BRC20S_BLOCK_OPERATION_COUNTS
+  fn set_block_operation_counts(
+    &self,
+    height: u64,
+    counts: &OperationCounts,
+  ) -> Result<(), Self::Error> {
+    self
+      .wtx
+      .open_table(BRC20S_BLOCK_OPERATION_COUNTS)?
+      .insert(height, bincode::serialize(counts).unwrap().as_slice())?;
+    Ok(())
+  }
+
+  fn savepoint(&self) -> Result<(), Self::Error> {
+    self.savepoint.replace(Some(Savepoint {
+      mutations: self
+        .mutations
+        .as_ref()
+        .map_or(0, |mutations| mutations.borrow().len()),
+      undo: Vec::new(),
+    }));
+    Ok(())
+  }
+
+  fn release_savepoint(&self) -> Result<(), Self::Error> {
+    self.savepoint.take();
+    Ok(())
+  }
+
+  fn rollback_to_savepoint(&self) -> Result<(), Self::Error> {
+    let Some(savepoint) = self.savepoint.take() else {
+      return Ok(());
+    };
+    for (table, key, old) in savepoint.undo.into_iter().rev() {
+      let mut table = self.wtx.open_table(table)?;
+      match old {
+        Some(old) => table.insert(key.as_str(), old.as_slice())?,
+        None => table.remove(key.as_str())?,
+      };
+    }
+    if let Some(mutations) = self.mutations.as_ref() {
+      mutations.borrow_mut().truncate(savepoint.mutations);
+    }
+    Ok(())
+  }
+
+  // BRC20S_BLOCK_MUTATIONS
+  fn save_block_mutations(&self, height: u64) -> Result<(), Self::Error> {
+    let Some(mutations) = self.mutations.as_ref() else {
+      return Ok(());
+    };
+    let mutations = mutations.take();
+    let mut table = self.wtx.open_table(BRC20S_BLOCK_MUTATIONS)?;
+    if !mutations.is_empty() {
+      table.insert(height, bincode::serialize(&mutations).unwrap().as_slice())?;
+    }
+
+    // the blocks deeper than a reorg can roll back are final, their mutations are dropped.
+    let Some(prune_to) = height.checked_sub(MAX_REORG_DEPTH) else {
+      return Ok(());
+    };
+    let pruned = table
+      .range(..=prune_to)?
+      .map(|entry| entry.map(|(height, _)| height.value()))
+      .collect::<Result<Vec<u64>, _>>()?;
+    for height in pruned {
+      table.remove(height)?;
+    }
+    Ok(())
+  }
 }
 
 #[cfg(test)]
@@ -417,6 +763,76 @@ mod tests {
     assert_eq!(all_balances, expect);
   }
 
+  #[test]
+  fn test_balance_history() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let other_script = ScriptKey::from_address(
+      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id = TickId::from_str("f7c515d6b1").unwrap();
+    let other_tick_id = TickId::from_str("f7c515d6b2").unwrap();
+    let balance = |overall_balance, transferable_balance| Balance {
+      tick_id,
+      overall_balance,
+      transferable_balance,
+    };
+
+    // the balance changes at blocks 100, 101 and 105.
+    for (height, balance) in [
+      (100, balance(10, 0)),
+      (101, balance(30, 5)),
+      (105, balance(25, 0)),
+    ] {
+      brc20s_db
+        .set_balance_history(&script, &tick_id, height, &balance)
+        .unwrap();
+    }
+    brc20s_db
+      .set_balance_history(&script, &other_tick_id, 102, &balance(1000, 0))
+      .unwrap();
+    brc20s_db
+      .set_balance_history(&other_script, &tick_id, 103, &balance(2000, 0))
+      .unwrap();
+
+    assert_eq!(
+      brc20s_db.get_balance_at(&script, &tick_id, 99).unwrap(),
+      None
+    );
+    assert_eq!(
+      brc20s_db.get_balance_at(&script, &tick_id, 100).unwrap(),
+      Some(balance(10, 0))
+    );
+    assert_eq!(
+      brc20s_db.get_balance_at(&script, &tick_id, 101).unwrap(),
+      Some(balance(30, 5))
+    );
+    assert_eq!(
+      brc20s_db.get_balance_at(&script, &tick_id, 104).unwrap(),
+      Some(balance(30, 5))
+    );
+    assert_eq!(
+      brc20s_db.get_balance_at(&script, &tick_id, 105).unwrap(),
+      Some(balance(25, 0))
+    );
+    assert_eq!(
+      brc20s_db
+        .get_balance_at(&script, &tick_id, u64::MAX)
+        .unwrap(),
+      Some(balance(25, 0))
+    );
+  }
+
   #[test]
   fn test_txid_to_inscription_receipts() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -429,16 +845,20 @@ mod tests {
 
     let op_vec = vec![
       InscriptionOperation {
-        txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
-          .unwrap(),
-      },
-      InscriptionOperation {
-        txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
-          .unwrap(),
+        txid,
+        inscription_id: InscriptionId { txid, index: 0 },
+        inscription_number: Some(1),
+        inscribe: true,
+        old_satpoint: SatPoint::from_str(&format!("{txid}:0:0")).unwrap(),
+        new_satpoint: Some(SatPoint::from_str(&format!("{txid}:0:0")).unwrap()),
       },
       InscriptionOperation {
-        txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
-          .unwrap(),
+        txid,
+        inscription_id: InscriptionId { txid, index: 1 },
+        inscription_number: None,
+        inscribe: false,
+        old_satpoint: SatPoint::from_str(&format!("{txid}:1:0")).unwrap(),
+        new_satpoint: None,
       },
     ];
 
@@ -485,6 +905,9 @@ mod tests {
       only: true,
       deploy_block: 100,
       deploy_block_time: 10000,
+      halving_interval: None,
+      min_stake: None,
+      maturity: None,
     };
     let mut pool_info_2 = pool_info_1.clone();
     pool_info_2.pid = pid_2.clone();
@@ -729,6 +1152,7 @@ mod tests {
       allocated: 100,
       decimal: 1,
       circulation: 100,
+      burned: 0,
       supply: 100,
       deployer: ScriptKey::from_address(
         Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
@@ -800,9 +1224,9 @@ mod tests {
       brc20s_db.get_all_tick_info(0, Some(9)).unwrap(),
       (
         vec![
-          tick_info1,
-          tick_info2,
-          tick_info3,
+          tick_info1.clone(),
+          tick_info2.clone(),
+          tick_info3.clone(),
           tick_info4.clone(),
           tick_info5.clone()
         ],
@@ -817,13 +1241,115 @@ mod tests {
 
     assert_eq!(
       brc20s_db.get_all_tick_info(3, Some(9)).unwrap(),
-      (vec![tick_info4, tick_info5], 5)
+      (vec![tick_info4.clone(), tick_info5.clone()], 5)
     );
 
     assert_eq!(
       brc20s_db.get_all_tick_info(5, Some(9)).unwrap(),
       (vec![], 5)
     );
+
+    assert_eq!(
+      brc20s_db.get_all_tick_info_after(None, Some(2)).unwrap(),
+      (vec![tick_info1, tick_info2], 5)
+    );
+
+    assert_eq!(
+      brc20s_db
+        .get_all_tick_info_after(Some(&tick_id_2), Some(2))
+        .unwrap(),
+      (vec![tick_info3, tick_info4], 5)
+    );
+
+    assert_eq!(
+      brc20s_db
+        .get_all_tick_info_after(Some(&tick_id_4), None)
+        .unwrap(),
+      (vec![tick_info5], 5)
+    );
+
+    assert_eq!(
+      brc20s_db
+        .get_all_tick_info_after(Some(&tick_id_5), Some(2))
+        .unwrap(),
+      (vec![], 5)
+    );
+  }
+
+  #[test]
+  fn test_tick_info_by_deploy_height() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let inscription_id =
+      InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    let tick_info = TickInfo {
+      tick_id: TickId::from_str("f7c515d6b1").unwrap(),
+      name: Tick::from_str("aBc1ab").unwrap(),
+      inscription_id,
+      allocated: 100,
+      decimal: 1,
+      circulation: 100,
+      burned: 0,
+      supply: 100,
+      deployer: ScriptKey::from_address(
+        Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+          .unwrap()
+          .assume_checked(),
+      ),
+      deploy_block: 0,
+      deploy_block_time: 100000,
+      latest_mint_block: 0,
+      pids: vec![],
+    };
+
+    // deploy heights 100, 100, 255, 256 and 4096, inserted in reverse order of the expected result.
+    let mut ticks = Vec::new();
+    for (tick_id, height) in [
+      ("f7c515d6b5", 4096),
+      ("f7c515d6b4", 256),
+      ("f7c515d6b3", 255),
+      ("f7c515d6b2", 100),
+      ("f7c515d6b1", 100),
+    ] {
+      let mut tick = tick_info.clone();
+      tick.tick_id = TickId::from_str(tick_id).unwrap();
+      tick.deploy_block = height;
+      brc20s_db.set_tick_info(&tick.tick_id, &tick).unwrap();
+      brc20s_db
+        .set_deploy_height_to_tickid(height, &tick.tick_id)
+        .unwrap();
+      ticks.push(tick);
+    }
+    ticks.reverse();
+
+    assert_eq!(
+      brc20s_db
+        .get_tick_info_by_deploy_height(0, u64::MAX, 0, None)
+        .unwrap(),
+      (ticks.clone(), 5)
+    );
+    assert_eq!(
+      brc20s_db
+        .get_tick_info_by_deploy_height(100, 256, 0, None)
+        .unwrap(),
+      (ticks[..4].to_vec(), 4)
+    );
+    assert_eq!(
+      brc20s_db
+        .get_tick_info_by_deploy_height(101, 4096, 1, Some(1))
+        .unwrap(),
+      (ticks[3..4].to_vec(), 3)
+    );
+    assert_eq!(
+      brc20s_db
+        .get_tick_info_by_deploy_height(257, 4095, 0, None)
+        .unwrap(),
+      (vec![], 0)
+    );
   }
 
   #[test]
@@ -841,6 +1367,7 @@ mod tests {
       pending_reward: 0,
       reward_debt: 0,
       latest_updated_block: 0,
+      stake_entry_block: 0,
     };
     let script_key = ScriptKey::from_address(
       Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
@@ -859,29 +1386,458 @@ mod tests {
         .unwrap(),
       user_info
     );
+
+    let pid2 = Pid::from_str("1234567890#02").unwrap();
+    let user_info2 = UserInfo {
+      pid: pid2.clone(),
+      staked: 10,
+      ..user_info.clone()
+    };
+    brc20s_db
+      .set_pid_to_use_info(&script_key, &pid2, &user_info2)
+      .unwrap();
+    let other_script_key = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    brc20s_db
+      .set_pid_to_use_info(&other_script_key, &pid, &user_info)
+      .unwrap();
+
+    assert_eq!(
+      brc20s_db.get_user_infos(&script_key).unwrap(),
+      vec![user_info.clone(), user_info2]
+    );
+    assert_eq!(
+      brc20s_db.get_user_infos(&other_script_key).unwrap(),
+      vec![user_info]
+    );
   }
 
   #[test]
-  fn test_transferable_assets() {
+  fn test_savepoint() {
     let dbfile = NamedTempFile::new().unwrap();
     let db = Database::create(dbfile.path()).unwrap();
     let wtx = db.begin_write().unwrap();
-    let brc20s_db = DataStore::new(&wtx);
+    let brc20s_db = DataStore::new_with_mutations(&wtx);
 
     let script_key = ScriptKey::from_address(
-      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
         .unwrap()
         .assume_checked(),
     );
     let tick_id = TickId::from_str("f7c515d6b7").unwrap();
-    let inscription_id =
-      InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
-        .unwrap();
-    let transferable_asset = TransferableAsset {
-      inscription_id,
-      amount: 100,
+    let balance = |overall_balance| Balance {
       tick_id,
-      owner: script_key.clone(),
+      overall_balance,
+      transferable_balance: 0,
+    };
+    let pid = Pid::from_str("1234567890#01").unwrap();
+    let user_info = UserInfo {
+      staked: 10,
+      ..UserInfo::default(&pid)
+    };
+
+    brc20s_db
+      .set_token_balance(&script_key, &tick_id, balance(10))
+      .unwrap();
+
+    // an overwritten entry gets its previous value back, a new one is removed.
+    brc20s_db.savepoint().unwrap();
+    brc20s_db
+      .set_token_balance(&script_key, &tick_id, balance(20))
+      .unwrap();
+    brc20s_db
+      .set_pid_to_use_info(&script_key, &pid, &user_info)
+      .unwrap();
+    brc20s_db.rollback_to_savepoint().unwrap();
+
+    assert_eq!(
+      brc20s_db.get_balance(&script_key, &tick_id).unwrap(),
+      Some(balance(10))
+    );
+    assert_eq!(
+      brc20s_db.get_pid_to_use_info(&script_key, &pid).unwrap(),
+      None
+    );
+    assert_eq!(brc20s_db.get_user_infos(&script_key).unwrap(), vec![]);
+
+    // the writes since a released savepoint are kept, and aren't undone by a later rollback.
+    brc20s_db.savepoint().unwrap();
+    brc20s_db
+      .set_token_balance(&script_key, &tick_id, balance(30))
+      .unwrap();
+    brc20s_db.release_savepoint().unwrap();
+    brc20s_db.rollback_to_savepoint().unwrap();
+
+    assert_eq!(
+      brc20s_db.get_balance(&script_key, &tick_id).unwrap(),
+      Some(balance(30))
+    );
+
+    // the rolled back changes are not recorded as mutations of the block.
+    brc20s_db.save_block_mutations(1).unwrap();
+    assert_eq!(
+      brc20s_db.get_block_mutations(1).unwrap(),
+      vec![
+        Mutation::Balance {
+          script_key: script_key.clone(),
+          tick_id,
+          old: None,
+          new: balance(10),
+        },
+        Mutation::Balance {
+          script_key: script_key.clone(),
+          tick_id,
+          old: Some(balance(10)),
+          new: balance(30),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_operation_counts_of_range() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    for (height, mint) in [(1, 1), (3, 2), (5, 4)] {
+      brc20s_db
+        .set_block_operation_counts(
+          height,
+          &OperationCounts {
+            mint,
+            ..Default::default()
+          },
+        )
+        .unwrap();
+    }
+
+    assert_eq!(brc20s_db.get_operation_counts(0, 5).unwrap().mint, 7);
+    assert_eq!(brc20s_db.get_operation_counts(2, 4).unwrap().mint, 2);
+    assert_eq!(brc20s_db.get_operation_counts(6, 9).unwrap().total(), 0);
+  }
+
+  #[test]
+  fn test_block_mutations_pruned_beyond_reorg_depth() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new_with_mutations(&wtx);
+
+    let script_key = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id = TickId::from_str("f7c515d6b7").unwrap();
+    let balance = |overall_balance| Balance {
+      tick_id,
+      overall_balance,
+      transferable_balance: 0,
+    };
+
+    for height in 1..=MAX_REORG_DEPTH + 2 {
+      brc20s_db
+        .set_token_balance(&script_key, &tick_id, balance(height.into()))
+        .unwrap();
+      brc20s_db.save_block_mutations(height).unwrap();
+    }
+
+    assert!(brc20s_db.get_block_mutations(1).unwrap().is_empty());
+    assert!(brc20s_db.get_block_mutations(2).unwrap().is_empty());
+    assert_eq!(brc20s_db.get_block_mutations(3).unwrap().len(), 1);
+    assert_eq!(
+      brc20s_db.get_block_mutations(MAX_REORG_DEPTH + 2).unwrap(),
+      vec![Mutation::Balance {
+        script_key: script_key.clone(),
+        tick_id,
+        old: Some(balance((MAX_REORG_DEPTH + 1).into())),
+        new: balance((MAX_REORG_DEPTH + 2).into()),
+      }]
+    );
+
+    // a store which doesn't record mutations saves none.
+    let brc20s_db = DataStore::new(&wtx);
+    brc20s_db
+      .set_token_balance(&script_key, &tick_id, balance(100))
+      .unwrap();
+    brc20s_db.save_block_mutations(100).unwrap();
+    assert!(brc20s_db.get_block_mutations(100).unwrap().is_empty());
+    assert_eq!(
+      brc20s_db
+        .get_block_mutations(MAX_REORG_DEPTH + 2)
+        .unwrap()
+        .len(),
+      1
+    );
+  }
+
+  #[test]
+  fn test_user_pools() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let script_key = ScriptKey::from_address(
+      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+        .unwrap()
+        .assume_checked(),
+    );
+    let other_script_key = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    let pid1 = Pid::from_str("1234567890#01").unwrap();
+    let pid2 = Pid::from_str("1234567890#02").unwrap();
+    let user_info1 = UserInfo {
+      pid: pid1.clone(),
+      staked: 100,
+      minted: 0,
+      pending_reward: 5,
+      reward_debt: 0,
+      latest_updated_block: 0,
+      stake_entry_block: 0,
+    };
+    let user_info2 = UserInfo {
+      pid: pid2.clone(),
+      staked: 200,
+      pending_reward: 7,
+      ..user_info1.clone()
+    };
+
+    // inserted out of order, returned ordered by pid.
+    brc20s_db
+      .set_pid_to_use_info(&script_key, &pid2, &user_info2)
+      .unwrap();
+    brc20s_db
+      .set_pid_to_use_info(&script_key, &pid1, &user_info1)
+      .unwrap();
+
+    assert_eq!(
+      brc20s_db.get_user_pools(&script_key, 0, None).unwrap(),
+      (vec![user_info1.clone(), user_info2.clone()], 2)
+    );
+    assert_eq!(
+      brc20s_db.get_user_pools(&script_key, 0, Some(1)).unwrap(),
+      (vec![user_info1], 2)
+    );
+    assert_eq!(
+      brc20s_db.get_user_pools(&script_key, 1, Some(1)).unwrap(),
+      (vec![user_info2], 2)
+    );
+    assert_eq!(
+      brc20s_db
+        .get_user_pools(&other_script_key, 0, None)
+        .unwrap(),
+      (vec![], 0)
+    );
+  }
+
+  #[test]
+  fn test_pool_stakers() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let pid = Pid::from_str("1234567890#01").unwrap();
+    let other_pid = Pid::from_str("1234567890#02").unwrap();
+    let user_info = UserInfo {
+      pid: pid.clone(),
+      staked: 0,
+      minted: 0,
+      pending_reward: 0,
+      reward_debt: 0,
+      latest_updated_block: 0,
+      stake_entry_block: 0,
+    };
+    let scripts = [
+      "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+      "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4",
+      "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e",
+    ]
+    .iter()
+    .map(|addr| ScriptKey::from_address(Address::from_str(addr).unwrap().assume_checked()))
+    .collect::<Vec<_>>();
+
+    for (i, script) in scripts.iter().enumerate() {
+      brc20s_db
+        .set_pid_to_use_info(
+          script,
+          &pid,
+          &UserInfo {
+            staked: 10 * (i as u128 + 1),
+            ..user_info.clone()
+          },
+        )
+        .unwrap();
+    }
+    // a stake in another pool of the same tick is not counted.
+    brc20s_db
+      .set_pid_to_use_info(
+        &scripts[0],
+        &other_pid,
+        &UserInfo {
+          pid: other_pid.clone(),
+          staked: 100,
+          ..user_info.clone()
+        },
+      )
+      .unwrap();
+
+    let (stakers, staker_count) = brc20s_db.get_pool_stakers(&pid, 0, None).unwrap();
+    assert_eq!(staker_count, 3);
+    assert_eq!(
+      stakers
+        .iter()
+        .map(|(_, user_info)| user_info.staked)
+        .sum::<u128>(),
+      60
+    );
+    assert_eq!(
+      brc20s_db
+        .get_pool_stakers(&pid, 1, Some(1))
+        .unwrap()
+        .0
+        .len(),
+      1
+    );
+
+    // fully unstaked users are no longer stakers of the pool.
+    brc20s_db
+      .set_pid_to_use_info(&scripts[1], &pid, &user_info)
+      .unwrap();
+    let (stakers, staker_count) = brc20s_db.get_pool_stakers(&pid, 0, None).unwrap();
+    assert_eq!(staker_count, 2);
+    assert_eq!(
+      stakers
+        .iter()
+        .map(|(_, user_info)| user_info.staked)
+        .sum::<u128>(),
+      40
+    );
+  }
+
+  #[test]
+  fn test_tick_holders() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let tick_id = TickId::from_str("f7c515d6b7").unwrap();
+    let other_tick_id = TickId::from_str("f7c515d6b8").unwrap();
+    let balance = |overall_balance: u128| Balance {
+      tick_id,
+      overall_balance,
+      transferable_balance: 0,
+    };
+    let holders = |tick_id: &TickId| {
+      brc20s_db
+        .get_tick_holders(tick_id, 0, None)
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|(script, balance)| (script, balance.overall_balance))
+        .collect::<Vec<_>>()
+    };
+    let scripts = [
+      "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+      "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4",
+      "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e",
+    ]
+    .iter()
+    .map(|addr| ScriptKey::from_address(Address::from_str(addr).unwrap().assume_checked()))
+    .collect::<Vec<_>>();
+
+    brc20s_db
+      .set_token_balance(&scripts[0], &tick_id, balance(100))
+      .unwrap();
+    brc20s_db
+      .set_token_balance(&scripts[1], &tick_id, balance(50))
+      .unwrap();
+    // a holder of another tick is not listed.
+    brc20s_db
+      .set_token_balance(
+        &scripts[2],
+        &other_tick_id,
+        Balance {
+          tick_id: other_tick_id,
+          ..balance(10)
+        },
+      )
+      .unwrap();
+    assert_eq!(
+      holders(&tick_id),
+      vec![(scripts[0].clone(), 100), (scripts[1].clone(), 50)]
+    );
+    assert_eq!(
+      brc20s_db
+        .get_tick_holders(&tick_id, 1, Some(1))
+        .unwrap()
+        .0
+        .len(),
+      1
+    );
+
+    // a transfer of the whole balance of the first script to the third.
+    brc20s_db
+      .set_token_balance(&scripts[0], &tick_id, balance(0))
+      .unwrap();
+    brc20s_db
+      .set_token_balance(&scripts[2], &tick_id, balance(100))
+      .unwrap();
+    assert_eq!(
+      holders(&tick_id),
+      vec![(scripts[2].clone(), 100), (scripts[1].clone(), 50)]
+    );
+    assert_eq!(brc20s_db.get_tick_holders(&tick_id, 0, None).unwrap().1, 2);
+
+    // the same transfer rolled back restores the previous holders.
+    brc20s_db.savepoint().unwrap();
+    brc20s_db
+      .set_token_balance(&scripts[1], &tick_id, balance(0))
+      .unwrap();
+    brc20s_db
+      .set_token_balance(&scripts[2], &tick_id, balance(150))
+      .unwrap();
+    assert_eq!(holders(&tick_id), vec![(scripts[2].clone(), 150)]);
+    brc20s_db.rollback_to_savepoint().unwrap();
+    assert_eq!(
+      holders(&tick_id),
+      vec![(scripts[2].clone(), 100), (scripts[1].clone(), 50)]
+    );
+    assert_eq!(holders(&other_tick_id), vec![(scripts[2].clone(), 10)]);
+  }
+
+  #[test]
+  fn test_transferable_assets() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let script_key = ScriptKey::from_address(
+      Address::from_str("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id = TickId::from_str("f7c515d6b7").unwrap();
+    let inscription_id =
+      InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    let transferable_asset = TransferableAsset {
+      inscription_id,
+      amount: 100,
+      tick_id,
+      owner: script_key.clone(),
     };
 
     brc20s_db
@@ -1144,25 +2100,86 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_stake_to_all_pid(&pledged_tick_30).unwrap(),
-      vec![pid_30.clone(), pid_30.clone(), pid_30]
+      brc20s_db
+        .get_stake_to_all_pid(&pledged_tick_30, 0, None)
+        .unwrap(),
+      (vec![pid_30.clone(), pid_30.clone(), pid_30], 3)
     );
 
     assert_eq!(
-      brc20s_db.get_stake_to_all_pid(&pledged_tick_btc).unwrap(),
-      vec![pid_btc]
+      brc20s_db
+        .get_stake_to_all_pid(&pledged_tick_btc, 0, None)
+        .unwrap(),
+      (vec![pid_btc], 1)
     );
 
     assert_eq!(
-      brc20s_db.get_stake_to_all_pid(&pledged_tick_20).unwrap(),
-      vec![pid_20.clone(), pid_20.clone(), pid_20]
+      brc20s_db
+        .get_stake_to_all_pid(&pledged_tick_20, 0, None)
+        .unwrap(),
+      (vec![pid_20.clone(), pid_20.clone(), pid_20], 3)
     );
 
     assert_eq!(
       brc20s_db
-        .get_stake_to_all_pid(&pledged_tick_unknown)
+        .get_stake_to_all_pid(&pledged_tick_unknown, 0, None)
         .unwrap(),
-      vec![pid_unknown]
+      (vec![pid_unknown], 1)
+    );
+  }
+
+  #[test]
+  fn test_stake_to_all_pid_pagination() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let pledged = PledgedTick::BRC20Tick(brc20::Tick::from_str("ordi").unwrap());
+    let other = PledgedTick::Native;
+
+    // insert in reverse order to make sure the result is sorted by pid.
+    let mut pids = Vec::new();
+    for i in (0..50u32).rev() {
+      let tick_id = TickId::from_str(&format!("{:010x}", i)).unwrap();
+      let pid = Pid::from_str(&format!("{}#01", tick_id.hex())).unwrap();
+      brc20s_db
+        .set_tickid_stake_to_pid(&tick_id, &pledged, &pid)
+        .unwrap();
+      pids.push(pid);
+    }
+    pids.reverse();
+
+    let tick_id = TickId::from_str("f7c515d6b1").unwrap();
+    brc20s_db
+      .set_tickid_stake_to_pid(&tick_id, &other, &Pid::from_str("f7c515d6b1#01").unwrap())
+      .unwrap();
+
+    let mut paged = Vec::new();
+    let mut start = 0;
+    loop {
+      let (page, total) = brc20s_db
+        .get_stake_to_all_pid(&pledged, start, Some(15))
+        .unwrap();
+      assert_eq!(total, 50);
+      if page.is_empty() {
+        break;
+      }
+      assert!(page.len() <= 15);
+      start += page.len();
+      paged.extend(page);
+    }
+    assert_eq!(paged, pids);
+
+    assert_eq!(
+      brc20s_db
+        .get_stake_to_all_pid(&pledged, 45, Some(10))
+        .unwrap(),
+      (pids[45..].to_vec(), 50)
+    );
+    assert_eq!(
+      brc20s_db.get_stake_to_all_pid(&pledged, 60, None).unwrap(),
+      (vec![], 50)
     );
   }
 
@@ -1239,4 +2256,95 @@ mod tests {
       vec![transferable_asset1, transferable_asset2]
     );
   }
+
+  #[test]
+  fn test_get_transferable_by_tickid_and_id() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20s_db = DataStore::new(&wtx);
+
+    let script_key1 = ScriptKey::from_address(
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
+        .unwrap()
+        .assume_checked(),
+    );
+    let script_key2 = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id1 = TickId::from_str("17c515d6b7").unwrap();
+    let tick_id2 = TickId::from_str("f7c515d6b7").unwrap();
+    let inscription_id = |n: u32| {
+      InscriptionId::from_str(&format!(
+        "1111111111111111111111111111111111111111111111111111111111111111i{n}"
+      ))
+      .unwrap()
+    };
+
+    // the first script holds two assets of tick 1 and one of tick 2, the second one of tick 1.
+    let assets = [
+      (&script_key1, tick_id1, 1),
+      (&script_key1, tick_id1, 2),
+      (&script_key1, tick_id2, 3),
+      (&script_key2, tick_id1, 4),
+    ]
+    .map(|(script_key, tick_id, n)| TransferableAsset {
+      inscription_id: inscription_id(n),
+      amount: u128::from(n) * 100,
+      tick_id,
+      owner: script_key.clone(),
+    });
+    for asset in &assets {
+      brc20s_db
+        .set_transferable_assets(&asset.owner, &asset.tick_id, &asset.inscription_id, asset)
+        .unwrap();
+    }
+
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_tickid(&script_key1, &tick_id1)
+        .unwrap(),
+      vec![assets[0].clone(), assets[1].clone()]
+    );
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_tickid(&script_key1, &tick_id2)
+        .unwrap(),
+      vec![assets[2].clone()]
+    );
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_tickid(&script_key2, &tick_id2)
+        .unwrap(),
+      vec![]
+    );
+
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_id(&script_key1, &inscription_id(3))
+        .unwrap(),
+      Some(assets[2].clone())
+    );
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_id(&script_key2, &inscription_id(4))
+        .unwrap(),
+      Some(assets[3].clone())
+    );
+    // an asset is only found under its owner.
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_id(&script_key2, &inscription_id(1))
+        .unwrap(),
+      None
+    );
+    assert_eq!(
+      brc20s_db
+        .get_transferable_by_id(&script_key1, &inscription_id(5))
+        .unwrap(),
+      None
+    );
+  }
 }
@@ -0,0 +1,134 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct OperationCounts {
+  pub deploy: u64,
+  pub mint: u64,
+  pub stake: u64,
+  pub unstake: u64,
+  pub passive_unstake: u64,
+  pub inscribe_transfer: u64,
+  pub transfer: u64,
+}
+
+impl OperationCounts {
+  // counts every receipt, whether or not the operation succeeded.
+  pub fn from_receipts<'a>(receipts: impl IntoIterator<Item = &'a Receipt>) -> Self {
+    let mut counts = Self::default();
+    for receipt in receipts {
+      let count = match receipt.op {
+        OperationType::Deploy => &mut counts.deploy,
+        OperationType::Mint => &mut counts.mint,
+        OperationType::Stake => &mut counts.stake,
+        OperationType::UnStake => &mut counts.unstake,
+        OperationType::PassiveUnStake => &mut counts.passive_unstake,
+        OperationType::InscribeTransfer => &mut counts.inscribe_transfer,
+        OperationType::Transfer => &mut counts.transfer,
+      };
+      *count += 1;
+    }
+    counts
+  }
+
+  pub fn merge(&mut self, other: &Self) {
+    self.deploy += other.deploy;
+    self.mint += other.mint;
+    self.stake += other.stake;
+    self.unstake += other.unstake;
+    self.passive_unstake += other.passive_unstake;
+    self.inscribe_transfer += other.inscribe_transfer;
+    self.transfer += other.transfer;
+  }
+
+  pub fn total(&self) -> u64 {
+    self.deploy
+      + self.mint
+      + self.stake
+      + self.unstake
+      + self.passive_unstake
+      + self.inscribe_transfer
+      + self.transfer
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::protocol::brc20s::BRC20SError;
+  use crate::{InscriptionId, SatPoint};
+  use bitcoin::Address;
+  use std::str::FromStr;
+
+  fn mock_receipt(op: OperationType, valid: bool) -> Receipt {
+    let addr =
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
+    Receipt {
+      inscription_id: InscriptionId::from_str(
+        "9991111111111111111111111111111111111111111111111111111111111111i1",
+      )
+      .unwrap(),
+      inscription_number: 0,
+      old_satpoint: SatPoint {
+        outpoint: Default::default(),
+        offset: 0,
+      },
+      new_satpoint: SatPoint {
+        outpoint: Default::default(),
+        offset: 0,
+      },
+      op,
+      from: ScriptKey::Address(addr.clone()),
+      to: ScriptKey::Address(addr),
+      result: if valid {
+        Ok(Vec::new())
+      } else {
+        Err(BRC20SError::InvalidTickLen("abcde".to_string()))
+      },
+    }
+  }
+
+  #[test]
+  fn test_operation_counts() {
+    let block_a = vec![
+      mock_receipt(OperationType::Deploy, true),
+      mock_receipt(OperationType::Deploy, false),
+      mock_receipt(OperationType::Stake, true),
+      mock_receipt(OperationType::Transfer, true),
+    ];
+    let block_b = vec![
+      mock_receipt(OperationType::Mint, true),
+      mock_receipt(OperationType::UnStake, true),
+      mock_receipt(OperationType::PassiveUnStake, true),
+      mock_receipt(OperationType::InscribeTransfer, false),
+      mock_receipt(OperationType::Stake, true),
+    ];
+
+    let mut counts = OperationCounts::from_receipts(&block_a);
+    assert_eq!(
+      counts,
+      OperationCounts {
+        deploy: 2,
+        stake: 1,
+        transfer: 1,
+        ..Default::default()
+      }
+    );
+
+    counts.merge(&OperationCounts::from_receipts(&block_b));
+    assert_eq!(
+      counts,
+      OperationCounts {
+        deploy: 2,
+        mint: 1,
+        stake: 2,
+        unstake: 1,
+        passive_unstake: 1,
+        inscribe_transfer: 1,
+        transfer: 1,
+      }
+    );
+    assert_eq!(counts.total(), 9);
+    assert_eq!(OperationCounts::from_receipts(&[]).total(), 0);
+  }
+}
@@ -1,8 +1,32 @@
+use crate::{
+  okx::datastore::ord::{Action, InscriptionOp},
+  InscriptionId, SatPoint,
+};
 use bitcoin::Txid;
 use serde::{Deserialize, Serialize};
 
-// TODO - this is a temporary solution to the problem of
+/// An inscription operation of a transaction which was resolved to a BRC20S message, saved before
+/// the message is executed.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub struct InscriptionOperation {
   pub txid: Txid,
+  pub inscription_id: InscriptionId,
+  pub inscription_number: Option<i64>,
+  // true if the inscription is inscribed by the transaction, false if it is transferred.
+  pub inscribe: bool,
+  pub old_satpoint: SatPoint,
+  pub new_satpoint: Option<SatPoint>,
+}
+
+impl From<&InscriptionOp> for InscriptionOperation {
+  fn from(op: &InscriptionOp) -> Self {
+    Self {
+      txid: op.txid,
+      inscription_id: op.inscription_id,
+      inscription_number: op.inscription_number,
+      inscribe: matches!(op.action, Action::New { .. }),
+      old_satpoint: op.old_satpoint,
+      new_satpoint: op.new_satpoint,
+    }
+  }
 }
@@ -175,6 +175,17 @@ impl PledgedTick {
     }
   }
 
+  /// The fragment identifying the pledged tick in redb keys.
+  /// Stored keys depend on it, so the encoding of every variant must not change.
+  pub fn redb_key_fragment(&self) -> String {
+    match self {
+      PledgedTick::Native => hex::encode(NATIVE_TOKEN),
+      PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
+      PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
+      PledgedTick::Unknown => hex::encode("!@#$%"),
+    }
+  }
+
   pub fn is_brc20(&self) -> bool {
     matches!(self, PledgedTick::BRC20Tick(_))
   }
@@ -210,6 +221,8 @@ pub struct TickInfo {
   pub allocated: u128,
   pub decimal: u8,
   pub circulation: u128,
+  // the amount burned out of the circulation, the minted amount is the circulation plus it.
+  pub burned: u128,
   pub supply: u128,
   pub deployer: ScriptKey,
   pub deploy_block: u64,
@@ -240,6 +253,7 @@ impl TickInfo {
       allocated,
       decimal,
       circulation: minted,
+      burned: 0,
       supply,
       deployer: deployer.clone(),
       deploy_block,
@@ -342,6 +356,20 @@ mod tests {
     assert!(PledgedTick::from_str("aBc1").is_brc20());
   }
 
+  #[test]
+  fn test_pledged_tick_redb_key_fragment() {
+    assert_eq!(PledgedTick::Native.redb_key_fragment(), "627463");
+    assert_eq!(
+      PledgedTick::BRC20Tick(brc20::Tick::from_str("aBc1").unwrap()).redb_key_fragment(),
+      "61626331000000000000000000000000"
+    );
+    assert_eq!(
+      PledgedTick::BRC20STick(TickId::from_str("A012345679").unwrap()).redb_key_fragment(),
+      "a012345679"
+    );
+    assert_eq!(PledgedTick::Unknown.redb_key_fragment(), "2140232425");
+  }
+
   #[test]
   fn test_tick_str() {
     assert_eq!(
@@ -10,6 +10,9 @@ pub struct UserInfo {
   pub pending_reward: u128,
   pub reward_debt: u128,
   pub latest_updated_block: u64,
+  // the stake-weighted average block the staked amount entered the pool, only tracked by pools
+  // with a maturity.
+  pub stake_entry_block: u64,
 }
 
 impl UserInfo {
@@ -21,6 +24,7 @@ impl UserInfo {
       pending_reward: 0,
       reward_debt: 0,
       latest_updated_block: 0,
+      stake_entry_block: 0,
     }
   }
 }
@@ -29,13 +33,14 @@ impl std::fmt::Display for UserInfo {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
       f,
-      "UserInfo {{ pid: {}, staked: {}, minted: {}, pending_reward: {},reward_debt: {},latest_updated_block: {}}}",
+      "UserInfo {{ pid: {}, staked: {}, minted: {}, pending_reward: {},reward_debt: {},latest_updated_block: {},stake_entry_block: {}}}",
       self.pid.as_str(),
       self.staked,
       self.minted,
       self.pending_reward,
       self.reward_debt,
       self.latest_updated_block,
+      self.stake_entry_block,
     )
   }
 }
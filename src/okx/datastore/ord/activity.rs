@@ -0,0 +1,21 @@
+use {
+  bitcoin::Txid,
+  serde::{Deserialize, Serialize},
+};
+
+/// Protocol of the receipts a transaction left for an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Protocol {
+  Ord,
+  BRC20,
+  BRC20S,
+}
+
+/// Points at the receipts of a transaction that involve an address. The receipts themselves stay
+/// in the txid keyed tables of each protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptRef {
+  pub height: u64,
+  pub txid: Txid,
+  pub protocols: Vec<Protocol>,
+}
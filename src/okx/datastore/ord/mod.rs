@@ -1,14 +1,16 @@
 pub use self::{
+  activity::{Protocol, ReceiptRef},
   operation::{Action, InscriptionOp},
   redb::{OrdDbReadWriter, OrdDbReader},
 };
 
 use {
-  crate::{InscriptionId, Result},
+  crate::{okx::datastore::ScriptKey, InscriptionId, Result},
   bitcoin::{OutPoint, TxOut, Txid},
   collections::CollectionKind,
   std::fmt::{Debug, Display},
 };
+pub mod activity;
 pub mod bitmap;
 pub mod collections;
 pub mod operation;
@@ -34,6 +36,15 @@ pub trait DataStoreReadOnly {
     &self,
     collection_key: &str,
   ) -> Result<Option<InscriptionId>, Self::Error>;
+
+  /// Returns at most `limit` receipt refs of the script key from the newest block down, starting
+  /// below the `(height, txid)` of the last ref of the previous page.
+  fn get_receipt_refs(
+    &self,
+    script_key: &ScriptKey,
+    before: Option<(u64, Txid)>,
+    limit: usize,
+  ) -> Result<Vec<ReceiptRef>, Self::Error>;
 }
 
 pub trait DataStoreReadWrite: DataStoreReadOnly {
@@ -56,4 +67,12 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     inscription_id: InscriptionId,
     kind: &[CollectionKind],
   ) -> Result<(), Self::Error>;
+
+  fn add_receipt_ref(
+    &self,
+    script_key: &ScriptKey,
+    height: u64,
+    txid: &Txid,
+    protocol: Protocol,
+  ) -> Result<(), Self::Error>;
 }
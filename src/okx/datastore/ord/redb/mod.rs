@@ -5,7 +5,9 @@ pub use self::{
   read_only::OrdDbReader,
   read_write::{try_init_tables, OrdDbReadWriter},
 };
-use {super::CollectionKind, redb::TableDefinition};
+use {
+  super::CollectionKind, crate::okx::datastore::ScriptKey, bitcoin::Txid, redb::TableDefinition,
+};
 
 const ORD_TX_TO_OPERATIONS: TableDefinition<&str, &[u8]> =
   TableDefinition::new("ORD_TX_TO_OPERATIONS");
@@ -13,3 +15,19 @@ const COLLECTIONS_KEY_TO_INSCRIPTION_ID: TableDefinition<&str, &[u8; 36]> =
   TableDefinition::new("COLLECTIONS_KEY_TO_INSCRIPTION_ID");
 const COLLECTIONS_INSCRIPTION_ID_TO_KINDS: TableDefinition<&[u8; 36], &[u8]> =
   TableDefinition::new("COLLECTIONS_INSCRIPTION_ID_TO_KINDS");
+const ADDRESS_TO_RECEIPT_REFS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("ADDRESS_TO_RECEIPT_REFS");
+
+// heights are fixed width so that the receipt refs of a script key are ordered by block.
+fn script_height_txid_key(script: &ScriptKey, height: u64, txid: &Txid) -> String {
+  format!("{}_{:016x}_{}", script, height, txid)
+}
+
+fn min_script_key(script: &ScriptKey) -> String {
+  format!("{}_", script)
+}
+
+// script keys are addresses or hex script hashes, so every key of a script sorts below `~`.
+fn max_script_key(script: &ScriptKey) -> String {
+  format!("{}_~", script)
+}
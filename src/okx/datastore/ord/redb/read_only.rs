@@ -2,7 +2,10 @@ use {
   super::*,
   crate::{
     index::{INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, OUTPOINT_TO_ENTRY},
-    okx::datastore::ord::{DataStoreReadOnly, InscriptionOp},
+    okx::datastore::{
+      ord::{DataStoreReadOnly, InscriptionOp, ReceiptRef},
+      ScriptKey,
+    },
     Hash, InscriptionId, Result,
   },
   bitcoin::{
@@ -10,10 +13,14 @@ use {
     OutPoint, TxOut, Txid,
   },
   redb::{
-    AccessGuard, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue, StorageError,
-    Table, TableDefinition, WriteTransaction,
+    AccessGuard, Range, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue,
+    StorageError, Table, TableDefinition, WriteTransaction,
+  },
+  std::{
+    borrow::Borrow,
+    io,
+    ops::{Bound, RangeBounds},
   },
-  std::{borrow::Borrow, io},
 };
 
 pub struct OrdDbReader<'db, 'a> {
@@ -70,6 +77,20 @@ impl<'db, 'txn, K: RedbKey + 'static, V: RedbValue + 'static> TableWrapper<'db,
       Self::WtxTable(wtx_table) => wtx_table.get(key),
     }
   }
+
+  fn range<'a: 'b, 'b, KR>(
+    &'a self,
+    range: impl RangeBounds<KR> + 'b,
+  ) -> Result<Range<'a, K, V>, StorageError>
+  where
+    K: 'a,
+    KR: Borrow<K::SelfType<'b>> + 'b,
+  {
+    match self {
+      Self::RtxTable(rtx_table) => rtx_table.range(range),
+      Self::WtxTable(wtx_table) => wtx_table.range(range),
+    }
+  }
 }
 
 impl<'db, 'a> DataStoreReadOnly for OrdDbReader<'db, 'a> {
@@ -149,4 +170,32 @@ impl<'db, 'a> DataStoreReadOnly for OrdDbReader<'db, 'a> {
         }),
     )
   }
+
+  fn get_receipt_refs(
+    &self,
+    script_key: &ScriptKey,
+    before: Option<(u64, Txid)>,
+    limit: usize,
+  ) -> Result<Vec<ReceiptRef>, Self::Error> {
+    let min_key = min_script_key(script_key);
+    let upper_key = match before {
+      Some((height, txid)) => script_height_txid_key(script_key, height, &txid),
+      None => max_script_key(script_key),
+    };
+    Ok(
+      self
+        .wrapper
+        .open_table(ADDRESS_TO_RECEIPT_REFS)?
+        .range::<&str>((
+          Bound::Included(min_key.as_str()),
+          Bound::Excluded(upper_key.as_str()),
+        ))?
+        .rev()
+        .take(limit)
+        .flat_map(|result| {
+          result.map(|(_, data)| bincode::deserialize::<ReceiptRef>(data.value()).unwrap())
+        })
+        .collect(),
+    )
+  }
 }
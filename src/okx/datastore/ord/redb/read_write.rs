@@ -2,11 +2,14 @@ use {
   super::*,
   crate::{
     index::OUTPOINT_TO_ENTRY,
-    okx::datastore::ord::{DataStoreReadOnly, DataStoreReadWrite, InscriptionOp},
+    okx::datastore::{
+      ord::{DataStoreReadOnly, DataStoreReadWrite, InscriptionOp, Protocol, ReceiptRef},
+      ScriptKey,
+    },
     InscriptionId, Result,
   },
   bitcoin::{consensus::Encodable, OutPoint, TxOut, Txid},
-  redb::{ReadTransaction, WriteTransaction},
+  redb::{ReadTransaction, ReadableTable, WriteTransaction},
 };
 
 pub fn try_init_tables<'db, 'a>(
@@ -17,6 +20,7 @@ pub fn try_init_tables<'db, 'a>(
     wtx.open_table(ORD_TX_TO_OPERATIONS)?;
     wtx.open_table(COLLECTIONS_KEY_TO_INSCRIPTION_ID)?;
     wtx.open_table(COLLECTIONS_INSCRIPTION_ID_TO_KINDS)?;
+    wtx.open_table(ADDRESS_TO_RECEIPT_REFS)?;
   }
   Ok(true)
 }
@@ -63,6 +67,15 @@ impl<'db, 'a> DataStoreReadOnly for OrdDbReadWriter<'db, 'a> {
   ) -> Result<Option<Vec<CollectionKind>>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_collections_of_inscription(inscription_id)
   }
+
+  fn get_receipt_refs(
+    &self,
+    script_key: &ScriptKey,
+    before: Option<(u64, Txid)>,
+    limit: usize,
+  ) -> Result<Vec<ReceiptRef>, Self::Error> {
+    read_only::new_with_wtx(self.wtx).get_receipt_refs(script_key, before, limit)
+  }
 }
 
 impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
@@ -125,6 +138,34 @@ impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
       .insert(&key, bincode::serialize(&kind).unwrap().as_slice())?;
     Ok(())
   }
+
+  // ADDRESS_TO_RECEIPT_REFS
+  fn add_receipt_ref(
+    &self,
+    script_key: &ScriptKey,
+    height: u64,
+    txid: &Txid,
+    protocol: Protocol,
+  ) -> Result<(), Self::Error> {
+    let key = script_height_txid_key(script_key, height, txid);
+    let mut table = self.wtx.open_table(ADDRESS_TO_RECEIPT_REFS)?;
+    let mut receipt_ref = table
+      .get(key.as_str())?
+      .map(|v| bincode::deserialize::<ReceiptRef>(v.value()).unwrap())
+      .unwrap_or(ReceiptRef {
+        height,
+        txid: *txid,
+        protocols: Vec::new(),
+      });
+    if !receipt_ref.protocols.contains(&protocol) {
+      receipt_ref.protocols.push(protocol);
+      table.insert(
+        key.as_str(),
+        bincode::serialize(&receipt_ref).unwrap().as_slice(),
+      )?;
+    }
+    Ok(())
+  }
 }
 
 #[cfg(test)]
@@ -195,4 +236,74 @@ mod tests {
       vec![operation]
     );
   }
+
+  #[test]
+  fn test_receipt_refs() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let ord_db = OrdDbReadWriter::new(&wtx);
+    let script = ScriptKey::from_address(
+      bitcoin::Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let other = ScriptKey::from_address(
+      bitcoin::Address::from_str("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa")
+        .unwrap()
+        .assume_checked(),
+    );
+    let txid1 =
+      Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735").unwrap();
+    let txid2 =
+      Txid::from_str("1111111111111111111111111111111111111111111111111111111111111111").unwrap();
+
+    // height 0x10 sorts before 0x9 without the fixed width key.
+    ord_db
+      .add_receipt_ref(&script, 9, &txid1, Protocol::Ord)
+      .unwrap();
+    ord_db
+      .add_receipt_ref(&script, 16, &txid2, Protocol::BRC20)
+      .unwrap();
+    ord_db
+      .add_receipt_ref(&script, 16, &txid2, Protocol::Ord)
+      .unwrap();
+    ord_db
+      .add_receipt_ref(&script, 16, &txid2, Protocol::BRC20)
+      .unwrap();
+    ord_db
+      .add_receipt_ref(&other, 20, &txid1, Protocol::BRC20S)
+      .unwrap();
+
+    let newest = ReceiptRef {
+      height: 16,
+      txid: txid2,
+      protocols: vec![Protocol::BRC20, Protocol::Ord],
+    };
+    let oldest = ReceiptRef {
+      height: 9,
+      txid: txid1,
+      protocols: vec![Protocol::Ord],
+    };
+    assert_eq!(
+      ord_db.get_receipt_refs(&script, None, 10).unwrap(),
+      vec![newest.clone(), oldest.clone()]
+    );
+    assert_eq!(
+      ord_db.get_receipt_refs(&script, None, 1).unwrap(),
+      vec![newest]
+    );
+    assert_eq!(
+      ord_db
+        .get_receipt_refs(&script, Some((16, txid2)), 10)
+        .unwrap(),
+      vec![oldest]
+    );
+    assert_eq!(
+      ord_db
+        .get_receipt_refs(&script, Some((9, txid1)), 10)
+        .unwrap(),
+      Vec::new()
+    );
+  }
 }
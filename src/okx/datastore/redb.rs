@@ -59,6 +59,16 @@ impl<'db, 'a> StateReadWrite<'db, 'a> {
       brc20s: BRC20SStateRW::new(wtx),
     }
   }
+
+  // a state store which also records the brc20s changes of every block, see
+  // `save_block_mutations`.
+  pub fn new_with_block_mutations(wtx: &'a WriteTransaction<'db>) -> Self {
+    Self {
+      ord: OrdStateRW::new(wtx),
+      brc20: BRC20StateRW::new(wtx),
+      brc20s: BRC20SStateRW::new_with_mutations(wtx),
+    }
+  }
 }
 
 impl<'db, 'a> StateRWriter for StateReadWrite<'db, 'a> {
@@ -1,6 +1,7 @@
 use bitcoin::{address, Address, Network, Script, ScriptHash};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum ScriptKey {
@@ -12,6 +13,7 @@ impl ScriptKey {
   pub fn from_address(address: Address) -> Self {
     ScriptKey::Address(Address::new(address.network, address.payload))
   }
+  // scripts without an address, like bare multisig or OP_RETURN, are keyed by their hash.
   pub fn from_script(script: &Script, network: Network) -> Self {
     match Address::from_script(script, network) {
       Ok(address) => ScriptKey::Address(Address::new(address.network, address.payload)),
@@ -32,6 +34,17 @@ impl Display for ScriptKey {
     )
   }
 }
+impl FromStr for ScriptKey {
+  type Err = bitcoin::hashes::hex::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match Address::from_str(s) {
+      Ok(address) => Ok(ScriptKey::Address(address)),
+      Err(_) => Ok(ScriptKey::ScriptHash(ScriptHash::from_str(s)?)),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -71,6 +84,53 @@ mod tests {
       )
     );
   }
+  #[test]
+  fn test_script_key_from_taproot_and_non_address_scripts() {
+    use bitcoin::blockdata::{opcodes::all::*, script::Builder};
+
+    let taproot = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let script = Address::from_str(taproot).unwrap().payload.script_pubkey();
+    let taproot_key = ScriptKey::from_script(&script, Network::Bitcoin);
+    assert_eq!(
+      taproot_key,
+      ScriptKey::Address(Address::from_str(taproot).unwrap())
+    );
+    assert_eq!(taproot_key.to_string(), taproot);
+
+    let multisig = Builder::new()
+      .push_opcode(OP_PUSHNUM_1)
+      .push_slice([0x02; 33])
+      .push_slice([0x03; 33])
+      .push_opcode(OP_PUSHNUM_2)
+      .push_opcode(OP_CHECKMULTISIG)
+      .into_script();
+    let multisig_key = ScriptKey::from_script(&multisig, Network::Bitcoin);
+    assert_eq!(multisig_key, ScriptKey::ScriptHash(multisig.script_hash()));
+
+    let op_return = Builder::new()
+      .push_opcode(OP_RETURN)
+      .push_slice(b"brc20")
+      .into_script();
+    let op_return_key = ScriptKey::from_script(&op_return, Network::Bitcoin);
+    assert_eq!(
+      op_return_key,
+      ScriptKey::ScriptHash(op_return.script_hash())
+    );
+
+    // the keys are distinct, and stable across calls and networks for non-address scripts.
+    assert_ne!(taproot_key, multisig_key);
+    assert_ne!(taproot_key, op_return_key);
+    assert_ne!(multisig_key, op_return_key);
+    assert_eq!(
+      ScriptKey::from_script(&multisig, Network::Testnet),
+      multisig_key
+    );
+    assert_eq!(
+      ScriptKey::from_str(&op_return_key.to_string()).unwrap(),
+      op_return_key
+    );
+  }
+
   #[test]
   fn test_script_key_serialize() {
     let script_key =
@@ -110,4 +170,22 @@ mod tests {
       script_key
     );
   }
+
+  #[test]
+  fn test_script_key_from_str() {
+    let script_key =
+      ScriptKey::Address(Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4").unwrap());
+    assert_eq!(
+      ScriptKey::from_str(&script_key.to_string()).unwrap(),
+      script_key
+    );
+    let script_key = ScriptKey::ScriptHash(
+      ScriptHash::from_str("df65c8a338dce7900824e7bd18c336656ca19e57").unwrap(),
+    );
+    assert_eq!(
+      ScriptKey::from_str(&script_key.to_string()).unwrap(),
+      script_key
+    );
+    assert!(ScriptKey::from_str("not a script key").is_err());
+  }
 }
@@ -12,7 +12,8 @@ mod msg_executor;
 mod msg_resolver;
 mod num;
 mod operation;
-mod params;
+pub(crate) mod params;
+mod version;
 
 use self::error::Error;
 pub(crate) use self::{
@@ -20,6 +21,7 @@ pub(crate) use self::{
   msg_executor::{execute, ExecutionMessage},
   num::Num,
   operation::{deserialize_brc20_operation, Deploy, Mint, Operation, Transfer},
+  version::get_config_by_network,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,6 +1,6 @@
 use super::{
   params::{BIGDECIMAL_TEN, MAXIMUM_SUPPLY, MAX_DECIMAL_WIDTH},
-  *,
+  version, *,
 };
 
 use crate::okx::datastore::brc20 as brc20_store;
@@ -9,8 +9,8 @@ use crate::okx::datastore::ord as ord_store;
 use crate::{
   okx::{
     datastore::brc20::{
-      BRC20Error, Balance, DeployEvent, Event, InscripbeTransferEvent, MintEvent, Receipt, Tick,
-      TokenInfo, TransferEvent, TransferInfo, TransferableLog,
+      BRC20Error, Balance, BurnEvent, DeployEvent, Event, InscripbeTransferEvent, MintEvent,
+      Receipt, Tick, TickReceipt, TokenInfo, TransferEvent, TransferInfo, TransferableLog,
     },
     protocol::{
       brc20::{Message, Mint, Operation},
@@ -22,6 +22,7 @@ use crate::{
 use anyhow::anyhow;
 use bigdecimal::num_bigint::Sign;
 use bitcoin::Network;
+use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,6 +34,10 @@ pub struct ExecutionMessage {
   pub(self) new_satpoint: SatPoint,
   pub(self) from: ScriptKey,
   pub(self) to: Option<ScriptKey>,
+  // the inscription is moved to an OP_RETURN output, a burn once `allow_op_return_burn` is active.
+  pub(self) to_op_return: bool,
+  // the inscription is moved to one of the configured burn addresses.
+  pub(self) to_burn_address: bool,
   pub(self) op: Operation,
 }
 
@@ -41,7 +46,16 @@ impl ExecutionMessage {
     ord_store: &O,
     msg: &Message,
     network: Network,
+    burn_addresses: &HashSet<ScriptKey>,
   ) -> Result<Self> {
+    let to_script = if msg.sat_in_outputs {
+      Some(utils::get_script_pubkey_on_satpoint(
+        msg.new_satpoint.unwrap(),
+        ord_store,
+      )?)
+    } else {
+      None
+    };
     Ok(Self {
       txid: msg.txid,
       inscription_id: msg.inscription_id,
@@ -51,15 +65,15 @@ impl ExecutionMessage {
         .new_satpoint
         .ok_or(anyhow!("new satpoint cannot be None"))?,
       from: utils::get_script_key_on_satpoint(msg.old_satpoint, ord_store, network)?,
-      to: if msg.sat_in_outputs {
-        Some(utils::get_script_key_on_satpoint(
-          msg.new_satpoint.unwrap(),
-          ord_store,
-          network,
-        )?)
-      } else {
-        None
-      },
+      to: to_script
+        .as_ref()
+        .map(|script| ScriptKey::from_script(script, network)),
+      to_op_return: to_script
+        .as_ref()
+        .map_or(false, |script| script.is_op_return()),
+      to_burn_address: to_script.map_or(false, |script| {
+        burn_addresses.contains(&ScriptKey::from_script(&script, network))
+      }),
       op: msg.op.clone(),
     })
   }
@@ -67,6 +81,7 @@ impl ExecutionMessage {
 
 pub fn execute<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreReadWrite>(
   context: BlockContext,
+  config: version::Config,
   ord_store: &'a O,
   brc20_store: &'a N,
   msg: &ExecutionMessage,
@@ -80,7 +95,7 @@ pub fn execute<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreRea
     Operation::InscribeTransfer(transfer) => {
       process_inscribe_transfer(context, ord_store, brc20_store, msg, transfer.clone())
     }
-    Operation::Transfer(_) => process_transfer(context, ord_store, brc20_store, msg),
+    Operation::Transfer(_) => process_transfer(context, &config, ord_store, brc20_store, msg),
   };
 
   let receipt = Receipt {
@@ -104,6 +119,19 @@ pub fn execute<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreRea
     .add_transaction_receipt(&msg.txid, &receipt)
     .map_err(|e| anyhow!("failed to add transaction receipt to state! error: {e}"))?;
 
+  if let Some(tick) = receipt.tick() {
+    brc20_store
+      .add_tick_receipt(
+        tick,
+        &TickReceipt {
+          height: context.blockheight,
+          txid: msg.txid,
+          receipt: receipt.clone(),
+        },
+      )
+      .map_err(|e| anyhow!("failed to add tick receipt to state! error: {e}"))?;
+  }
+
   Ok(Some(receipt))
 }
 
@@ -128,11 +156,7 @@ fn process_deploy<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStore
     )));
   }
 
-  let dec = Num::from_str(&deploy.decimals.map_or(MAX_DECIMAL_WIDTH.to_string(), |v| v))?
-    .checked_to_u8()?;
-  if dec > MAX_DECIMAL_WIDTH {
-    return Err(Error::BRC20Error(BRC20Error::DecimalsTooLarge(dec)));
-  }
+  let dec = resolve_decimals(deploy.decimals.as_deref())?;
   let base = BIGDECIMAL_TEN.checked_powu(u64::from(dec))?;
 
   let supply = Num::from_str(&deploy.max_supply)?;
@@ -173,6 +197,9 @@ fn process_deploy<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStore
     deployed_number: context.blockheight,
     latest_mint_number: context.blockheight,
     deployed_timestamp: context.blocktime,
+    burned_supply: 0,
+    completed_block_height: None,
+    completed_block_time: None,
   };
   brc20_store
     .insert_token_info(&tick, &new_info)
@@ -186,6 +213,19 @@ fn process_deploy<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStore
   }))
 }
 
+// The decimals of a token, 18 if the deploy omits them. Amounts of the token are always scaled
+// by the decimals stored at deploy time.
+fn resolve_decimals(decimals: Option<&str>) -> Result<u8, BRC20Error> {
+  let Some(decimals) = decimals else {
+    return Ok(MAX_DECIMAL_WIDTH);
+  };
+  Num::from_str(decimals)
+    .and_then(|dec| dec.checked_to_u8())
+    .ok()
+    .filter(|dec| *dec <= MAX_DECIMAL_WIDTH)
+    .ok_or(BRC20Error::InvalidDecimals(decimals.to_string()))
+}
+
 fn process_mint<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreReadWrite>(
   context: BlockContext,
   _ord_store: &'a O,
@@ -244,11 +284,12 @@ fn process_mint<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreRe
     amt
   };
 
-  // get or initialize user balance.
+  // get or initialize user balance. ticks are case insensitive, new balances take the case of the
+  // deployed ticker so that `ORDI` and `ordi` share the same balance.
   let mut balance = brc20_store
-    .get_balance(&to_script_key, &tick)
+    .get_balance(&to_script_key, &token_info.tick)
     .map_err(|e| Error::LedgerError(e))?
-    .map_or(Balance::new(&tick), |v| v);
+    .map_or(Balance::new(&token_info.tick), |v| v);
 
   // add amount to available balance.
   balance.overall_balance = Into::<Num>::into(balance.overall_balance)
@@ -263,7 +304,12 @@ fn process_mint<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreRe
   // update token minted.
   let minted = minted.checked_add(&amt)?.checked_to_u128()?;
   brc20_store
-    .update_mint_token_info(&tick, minted, context.blockheight)
+    .update_mint_token_info(
+      &token_info.tick,
+      minted,
+      context.blockheight,
+      context.blocktime,
+    )
     .map_err(|e| Error::LedgerError(e))?;
 
   Ok(Event::Mint(MintEvent {
@@ -305,16 +351,19 @@ fn process_inscribe_transfer<
   }
 
   amt = amt.checked_mul(&base)?;
-  if amt.sign() == Sign::NoSign || amt > Into::<Num>::into(token_info.supply) {
+  if amt.sign() == Sign::NoSign {
+    return Err(Error::BRC20Error(BRC20Error::InvalidZeroAmount));
+  }
+  if amt > Into::<Num>::into(token_info.supply) {
     return Err(Error::BRC20Error(BRC20Error::AmountOverflow(
       amt.to_string(),
     )));
   }
 
   let mut balance = brc20_store
-    .get_balance(&to_script_key, &tick)
+    .get_balance(&to_script_key, &token_info.tick)
     .map_err(|e| Error::LedgerError(e))?
-    .map_or(Balance::new(&tick), |v| v);
+    .map_or(Balance::new(&token_info.tick), |v| v);
 
   let overall = Into::<Num>::into(balance.overall_balance);
   let transferable = Into::<Num>::into(balance.transferable_balance);
@@ -341,7 +390,7 @@ fn process_inscribe_transfer<
     owner: to_script_key,
   };
   brc20_store
-    .insert_transferable(&inscription.owner, &tick, inscription.clone())
+    .insert_transferable(&inscription.owner, &inscription.tick, inscription.clone())
     .map_err(|e| Error::LedgerError(e))?;
 
   brc20_store
@@ -362,6 +411,7 @@ fn process_inscribe_transfer<
 
 fn process_transfer<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataStoreReadWrite>(
   _context: BlockContext,
+  config: &version::Config,
   _ord_store: &'a O,
   brc20_store: &'a N,
   msg: &ExecutionMessage,
@@ -379,12 +429,11 @@ fn process_transfer<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataSto
     )));
   }
 
-  let tick = transferable.tick;
-
   let token_info = brc20_store
-    .get_token_info(&tick)
+    .get_token_info(&transferable.tick)
     .map_err(|e| Error::LedgerError(e))?
-    .ok_or(BRC20Error::TickNotFound(tick.to_string()))?;
+    .ok_or(BRC20Error::TickNotFound(transferable.tick.to_string()))?;
+  let tick = token_info.tick.clone();
 
   // update from key balance.
   let mut from_balance = brc20_store
@@ -405,6 +454,29 @@ fn process_transfer<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataSto
     .update_token_balance(&msg.from, from_balance)
     .map_err(|e| Error::LedgerError(e))?;
 
+  brc20_store
+    .remove_transferable(&msg.from, &tick, msg.inscription_id)
+    .map_err(|e| Error::LedgerError(e))?;
+
+  brc20_store
+    .remove_inscribe_transfer_inscription(msg.inscription_id)
+    .map_err(|e| Error::LedgerError(e))?;
+
+  // burn the amount if transfer to an OP_RETURN output or a burn address.
+  if (msg.to_op_return && config.allow_op_return_burn) || msg.to_burn_address {
+    let burned_supply = Into::<Num>::into(token_info.burned_supply)
+      .checked_add(&amt)?
+      .checked_to_u128()?;
+    brc20_store
+      .update_burned_token_info(&tick, burned_supply)
+      .map_err(|e| Error::LedgerError(e))?;
+
+    return Ok(Event::Burn(BurnEvent {
+      tick: token_info.tick,
+      amount: amt.checked_to_u128()?,
+    }));
+  }
+
   // redirect receiver to sender if transfer to conibase.
   let mut out_msg = None;
 
@@ -429,17 +501,665 @@ fn process_transfer<'a, O: ord_store::DataStoreReadOnly, N: brc20_store::DataSto
     .update_token_balance(&to_script_key, to_balance)
     .map_err(|e| Error::LedgerError(e))?;
 
-  brc20_store
-    .remove_transferable(&msg.from, &tick, msg.inscription_id)
-    .map_err(|e| Error::LedgerError(e))?;
-
-  brc20_store
-    .remove_inscribe_transfer_inscription(msg.inscription_id)
-    .map_err(|e| Error::LedgerError(e))?;
-
   Ok(Event::Transfer(TransferEvent {
     msg: out_msg,
     tick: token_info.tick,
     amount: amt.checked_to_u128()?,
   }))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::index::INSCRIPTION_ID_TO_INSCRIPTION_ENTRY;
+  use crate::okx::datastore::{
+    brc20::{redb::DataStore, DataStoreReadOnly},
+    ord::{DataStoreReadWrite as _, OrdDbReadWriter},
+  };
+  use crate::okx::protocol::brc20::{Deploy, Mint, Transfer};
+  use bitcoin::{blockdata::opcodes, Address, OutPoint, ScriptBuf, TxOut};
+  use redb::{Database, WriteTransaction};
+  use tempfile::NamedTempFile;
+
+  const ADDRESS: &str = "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4";
+
+  // Runs `test` against the stores of a fresh database.
+  fn run(test: impl FnOnce(&WriteTransaction, &OrdDbReadWriter, &DataStore)) {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    test(&wtx, &OrdDbReadWriter::new(&wtx), &DataStore::new(&wtx));
+  }
+
+  fn context(blockheight: u64) -> BlockContext {
+    BlockContext {
+      network: Network::Bitcoin,
+      blockheight,
+      blocktime: 1687245000 + u32::try_from(blockheight).unwrap(),
+    }
+  }
+
+  fn script_key() -> ScriptKey {
+    ScriptKey::from_address(Address::from_str(ADDRESS).unwrap().assume_checked())
+  }
+
+  fn mock_execution_message(
+    index: u32,
+    from: &ScriptKey,
+    to: Option<ScriptKey>,
+    op: Operation,
+  ) -> ExecutionMessage {
+    let txid =
+      Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735").unwrap();
+    ExecutionMessage {
+      txid,
+      inscription_id: InscriptionId { txid, index },
+      inscription_number: i64::from(index),
+      old_satpoint: SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      new_satpoint: SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      from: from.clone(),
+      to,
+      to_op_return: false,
+      to_burn_address: false,
+      op,
+    }
+  }
+
+  // Executes a message of `script_key()` to itself and returns its result.
+  fn execute_op(
+    ord_store: &OrdDbReadWriter,
+    brc20_store: &DataStore,
+    index: u32,
+    op: Operation,
+  ) -> Result<Event, BRC20Error> {
+    let msg = mock_execution_message(index, &script_key(), Some(script_key()), op);
+    execute(context(1), version::genesis(), ord_store, brc20_store, &msg)
+      .unwrap()
+      .unwrap()
+      .result
+  }
+
+  // Deploys `tick` with a supply of 1000 and no decimals, then mints `amount` of it to
+  // `script_key()` with the messages of index 0 and 1.
+  fn deploy_and_mint(
+    ord_store: &OrdDbReadWriter,
+    brc20_store: &DataStore,
+    tick: &str,
+    amount: &str,
+  ) {
+    let deploy = Operation::Deploy(Deploy {
+      tick: tick.to_string(),
+      max_supply: "1000".to_string(),
+      mint_limit: None,
+      decimals: Some("0".to_string()),
+    });
+    assert!(execute_op(ord_store, brc20_store, 0, deploy).is_ok());
+    let mint = Operation::Mint(Mint {
+      tick: tick.to_string(),
+      amount: amount.to_string(),
+    });
+    assert!(execute_op(ord_store, brc20_store, 1, mint).is_ok());
+  }
+
+  #[test]
+  fn test_deploy_records_block() {
+    run(|_, ord_store, brc20_store| {
+      let deploy = mock_execution_message(
+        0,
+        &script_key(),
+        Some(script_key()),
+        Operation::Deploy(Deploy {
+          tick: "abcd".to_string(),
+          max_supply: "1000".to_string(),
+          mint_limit: None,
+          decimals: None,
+        }),
+      );
+      let receipt = execute(
+        context(779832),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &deploy,
+      )
+      .unwrap()
+      .unwrap();
+      assert!(receipt.result.is_ok());
+
+      let token_info = brc20_store
+        .get_token_info(&Tick::from_str("abcd").unwrap())
+        .unwrap()
+        .unwrap();
+      assert_eq!(token_info.deployed_number, 779832);
+      assert_eq!(token_info.deployed_timestamp, 1688024832);
+      assert_eq!(token_info.deploy_by, script_key());
+    });
+  }
+
+  #[test]
+  fn test_deploy_decimals() {
+    run(|_, ord_store, brc20_store| {
+      let script = script_key();
+      let mut index = 0;
+      let mut execute_next = |op: Operation| {
+        index += 1;
+        execute_op(ord_store, brc20_store, index, op)
+      };
+
+      // omitted decimals default to 18.
+      let result = execute_next(Operation::Deploy(Deploy {
+        tick: "abcd".to_string(),
+        max_supply: "1000".to_string(),
+        mint_limit: None,
+        decimals: None,
+      }));
+      assert!(result.is_ok());
+      let result = execute_next(Operation::Mint(Mint {
+        tick: "abcd".to_string(),
+        amount: "1.5".to_string(),
+      }));
+      assert!(result.is_ok());
+      let tick = Tick::from_str("abcd").unwrap();
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.decimal, 18);
+      assert_eq!(token_info.supply, 1000 * 10_u128.pow(18));
+      assert_eq!(
+        brc20_store
+          .get_balance(&script, &tick)
+          .unwrap()
+          .unwrap()
+          .overall_balance,
+        15 * 10_u128.pow(17)
+      );
+
+      // no fractional amounts for a token without decimals.
+      let result = execute_next(Operation::Deploy(Deploy {
+        tick: "efgh".to_string(),
+        max_supply: "1000".to_string(),
+        mint_limit: None,
+        decimals: Some("0".to_string()),
+      }));
+      assert!(result.is_ok());
+      let result = execute_next(Operation::Mint(Mint {
+        tick: "efgh".to_string(),
+        amount: "1.5".to_string(),
+      }));
+      assert_eq!(
+        result.unwrap_err(),
+        BRC20Error::AmountOverflow("1.5".to_string())
+      );
+      let result = execute_next(Operation::Mint(Mint {
+        tick: "efgh".to_string(),
+        amount: "2".to_string(),
+      }));
+      assert!(result.is_ok());
+      let tick = Tick::from_str("efgh").unwrap();
+      assert_eq!(
+        brc20_store.get_token_info(&tick).unwrap().unwrap().supply,
+        1000
+      );
+      assert_eq!(
+        brc20_store
+          .get_balance(&script, &tick)
+          .unwrap()
+          .unwrap()
+          .overall_balance,
+        2
+      );
+
+      for decimals in ["19", "1.5", "-1", "256"] {
+        let result = execute_next(Operation::Deploy(Deploy {
+          tick: "ijkl".to_string(),
+          max_supply: "1000".to_string(),
+          mint_limit: None,
+          decimals: Some(decimals.to_string()),
+        }));
+        assert_eq!(
+          result.unwrap_err(),
+          BRC20Error::InvalidDecimals(decimals.to_string())
+        );
+      }
+      assert_eq!(
+        brc20_store
+          .get_token_info(&Tick::from_str("ijkl").unwrap())
+          .unwrap(),
+        None
+      );
+    });
+  }
+
+  // Inscribes a transfer of `amount` abcd and resolves the message moving it from the first
+  // output of its transaction to a second one locked by `script_pubkey`.
+  fn move_transfer(
+    wtx: &WriteTransaction,
+    ord_store: &OrdDbReadWriter,
+    brc20_store: &DataStore,
+    amount: &str,
+    script_pubkey: ScriptBuf,
+    burn_addresses: &HashSet<ScriptKey>,
+  ) -> ExecutionMessage {
+    let transfer = Transfer {
+      tick: "abcd".to_string(),
+      amount: amount.to_string(),
+    };
+    let inscribe_transfer = mock_execution_message(
+      2,
+      &script_key(),
+      Some(script_key()),
+      Operation::InscribeTransfer(transfer.clone()),
+    );
+    let receipt = execute(
+      context(1),
+      version::genesis(),
+      ord_store,
+      brc20_store,
+      &inscribe_transfer,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(receipt.result.is_ok());
+
+    let txid = inscribe_transfer.txid;
+    let address = Address::from_str(ADDRESS).unwrap().assume_checked();
+    for (vout, script_pubkey) in [(0, address.script_pubkey()), (1, script_pubkey)] {
+      ord_store
+        .set_outpoint_to_txout(
+          OutPoint { txid, vout },
+          &TxOut {
+            value: 10_000,
+            script_pubkey,
+          },
+        )
+        .unwrap();
+    }
+    let mut key = [0; 36];
+    key[..32].copy_from_slice(txid.as_ref());
+    key[32..].copy_from_slice(&2u32.to_be_bytes());
+    wtx
+      .open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)
+      .unwrap()
+      .insert(&key, (0, 1, 2, (0, 0, 0), u64::MAX, 0))
+      .unwrap();
+
+    ExecutionMessage::from_message(
+      ord_store,
+      &Message {
+        txid,
+        inscription_id: inscribe_transfer.inscription_id,
+        old_satpoint: inscribe_transfer.old_satpoint,
+        new_satpoint: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 1 },
+          offset: 0,
+        }),
+        op: Operation::Transfer(transfer),
+        sat_in_outputs: true,
+      },
+      Network::Bitcoin,
+      burn_addresses,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_transfer_to_op_return_burns() {
+    run(|wtx, ord_store, brc20_store| {
+      let script = script_key();
+      let tick = Tick::from_str("abcd").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "abcd", "100");
+
+      let op_return = ScriptBuf::builder()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .into_script();
+      let transfer = move_transfer(
+        wtx,
+        ord_store,
+        brc20_store,
+        "40",
+        op_return,
+        &HashSet::new(),
+      );
+      assert!(transfer.to_op_return);
+      assert!(!transfer.to_burn_address);
+
+      let receipt = execute(
+        context(1),
+        version::ember(),
+        ord_store,
+        brc20_store,
+        &transfer,
+      )
+      .unwrap()
+      .unwrap();
+      assert_eq!(
+        receipt.result,
+        Ok(Event::Burn(BurnEvent {
+          tick: tick.clone(),
+          amount: 40,
+        }))
+      );
+
+      let balance = brc20_store.get_balance(&script, &tick).unwrap().unwrap();
+      assert_eq!(balance.overall_balance, 60);
+      assert_eq!(balance.transferable_balance, 0);
+      assert!(brc20_store
+        .get_transferable_by_tick(&script, &tick)
+        .unwrap()
+        .is_empty());
+
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.minted, 100);
+      assert_eq!(token_info.burned_supply, 40);
+    });
+  }
+
+  #[test]
+  fn test_transfer_to_op_return_before_activation() {
+    run(|wtx, ord_store, brc20_store| {
+      let tick = Tick::from_str("abcd").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "abcd", "100");
+
+      let op_return = ScriptBuf::builder()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .into_script();
+      let op_return_script = ScriptKey::from_script(&op_return, Network::Bitcoin);
+      let transfer = move_transfer(
+        wtx,
+        ord_store,
+        brc20_store,
+        "40",
+        op_return,
+        &HashSet::new(),
+      );
+
+      // the OP_RETURN script is credited like any other receiver.
+      let receipt = execute(
+        context(1),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &transfer,
+      )
+      .unwrap()
+      .unwrap();
+      assert!(matches!(receipt.result, Ok(Event::Transfer(_))));
+      assert_eq!(
+        brc20_store
+          .get_balance(&op_return_script, &tick)
+          .unwrap()
+          .unwrap()
+          .overall_balance,
+        40
+      );
+      assert_eq!(
+        brc20_store
+          .get_token_info(&tick)
+          .unwrap()
+          .unwrap()
+          .burned_supply,
+        0
+      );
+    });
+  }
+
+  #[test]
+  fn test_transfer_to_burn_address_burns() {
+    run(|wtx, ord_store, brc20_store| {
+      let burn_address = Address::from_str("1BitcoinEaterAddressDontSendf59kuE")
+        .unwrap()
+        .assume_checked();
+      let script = script_key();
+      let burn_script = ScriptKey::from_address(burn_address.clone());
+      let tick = Tick::from_str("abcd").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "abcd", "100");
+
+      let transfer = move_transfer(
+        wtx,
+        ord_store,
+        brc20_store,
+        "40",
+        burn_address.script_pubkey(),
+        &HashSet::from([burn_script.clone()]),
+      );
+      assert_eq!(transfer.to, Some(burn_script.clone()));
+      assert!(transfer.to_burn_address);
+
+      let receipt = execute(
+        context(1),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &transfer,
+      )
+      .unwrap()
+      .unwrap();
+      assert_eq!(
+        receipt.result,
+        Ok(Event::Burn(BurnEvent {
+          tick: tick.clone(),
+          amount: 40,
+        }))
+      );
+
+      let balance = brc20_store.get_balance(&script, &tick).unwrap().unwrap();
+      assert_eq!(balance.overall_balance, 60);
+      assert_eq!(balance.transferable_balance, 0);
+      assert_eq!(brc20_store.get_balance(&burn_script, &tick).unwrap(), None);
+
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.minted, 100);
+      assert_eq!(token_info.burned_supply, 40);
+    });
+  }
+
+  #[test]
+  fn test_inscribe_transfer_over_balance() {
+    run(|_, ord_store, brc20_store| {
+      let script = script_key();
+      let tick = Tick::from_str("abcd").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "abcd", "100");
+
+      let result = execute_op(
+        ord_store,
+        brc20_store,
+        2,
+        Operation::InscribeTransfer(Transfer {
+          tick: "abcd".to_string(),
+          amount: "150".to_string(),
+        }),
+      );
+      assert_eq!(
+        result,
+        Err(BRC20Error::InsufficientBalance(
+          "100".to_string(),
+          "150".to_string()
+        ))
+      );
+      assert_eq!(
+        result.unwrap_err().to_string(),
+        "insufficient balance: available 100, requested 150"
+      );
+
+      let balance = brc20_store.get_balance(&script, &tick).unwrap().unwrap();
+      assert_eq!(balance.overall_balance, 100);
+      assert_eq!(balance.transferable_balance, 0);
+      assert!(brc20_store
+        .get_transferable_by_tick(&script, &tick)
+        .unwrap()
+        .is_empty());
+    });
+  }
+
+  #[test]
+  fn test_zero_and_negative_amounts() {
+    run(|_, ord_store, brc20_store| {
+      let script = script_key();
+      let tick = Tick::from_str("abcd").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "abcd", "100");
+
+      for (amount, error) in [
+        ("0", BRC20Error::InvalidZeroAmount),
+        ("-5", BRC20Error::InvalidNum("-5".to_string())),
+      ] {
+        let operations = [
+          Operation::Mint(Mint {
+            tick: "abcd".to_string(),
+            amount: amount.to_string(),
+          }),
+          Operation::InscribeTransfer(Transfer {
+            tick: "abcd".to_string(),
+            amount: amount.to_string(),
+          }),
+        ];
+        for operation in operations {
+          let op = format!("{operation:?}");
+          let result = execute_op(ord_store, brc20_store, 2, operation);
+          assert_eq!(result, Err(error.clone()), "{amount} {op}");
+        }
+      }
+
+      let balance = brc20_store.get_balance(&script, &tick).unwrap().unwrap();
+      assert_eq!(balance.overall_balance, 100);
+      assert_eq!(balance.transferable_balance, 0);
+      assert!(brc20_store
+        .get_transferable_by_tick(&script, &tick)
+        .unwrap()
+        .is_empty());
+    });
+  }
+
+  #[test]
+  fn test_mint_tick_case_insensitive() {
+    run(|_, ord_store, brc20_store| {
+      let script = script_key();
+      let tick = Tick::from_str("ordi").unwrap();
+      deploy_and_mint(ord_store, brc20_store, "ordi", "50");
+
+      let upper_mint = Operation::Mint(Mint {
+        tick: "ORDI".to_string(),
+        amount: "100".to_string(),
+      });
+      assert!(execute_op(ord_store, brc20_store, 2, upper_mint).is_ok());
+      let inscribe_transfer = Operation::InscribeTransfer(Transfer {
+        tick: "OrDi".to_string(),
+        amount: "30".to_string(),
+      });
+      assert!(execute_op(ord_store, brc20_store, 3, inscribe_transfer).is_ok());
+
+      // the mint against `ORDI` credits the balance row of `ordi`.
+      assert_eq!(
+        brc20_store.get_balances(&script).unwrap(),
+        vec![Balance {
+          tick: tick.clone(),
+          overall_balance: 150,
+          transferable_balance: 30,
+        }]
+      );
+      assert_eq!(
+        brc20_store
+          .get_balance(&script, &Tick::from_str("ORDI").unwrap())
+          .unwrap()
+          .unwrap()
+          .tick,
+        tick
+      );
+      let transferable = brc20_store.get_transferable(&script).unwrap();
+      assert_eq!(transferable.len(), 1);
+      assert_eq!(transferable[0].tick, tick);
+      assert_eq!(
+        brc20_store.get_token_info(&tick).unwrap().unwrap().minted,
+        150
+      );
+    });
+  }
+
+  #[test]
+  fn test_mint_to_completion() {
+    run(|_, ord_store, brc20_store| {
+      let script = script_key();
+      let tick = Tick::from_str("ordi").unwrap();
+      let mint = |n| {
+        mock_execution_message(
+          n,
+          &script,
+          Some(script.clone()),
+          Operation::Mint(Mint {
+            tick: "ordi".to_string(),
+            amount: "60".to_string(),
+          }),
+        )
+      };
+
+      let deploy = mock_execution_message(
+        0,
+        &script,
+        Some(script.clone()),
+        Operation::Deploy(Deploy {
+          tick: "ordi".to_string(),
+          max_supply: "100".to_string(),
+          mint_limit: None,
+          decimals: Some("0".to_string()),
+        }),
+      );
+      execute(
+        context(1),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &deploy,
+      )
+      .unwrap()
+      .unwrap();
+
+      // still mintable
+      execute(
+        context(2),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &mint(1),
+      )
+      .unwrap()
+      .unwrap();
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.minted, 60);
+      assert_eq!(token_info.completed_block_height, None);
+      assert_eq!(token_info.completed_block_time, None);
+
+      // the second mint is cut to the remaining 40 and completes the supply
+      execute(
+        context(3),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &mint(2),
+      )
+      .unwrap()
+      .unwrap();
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.minted, 100);
+      assert_eq!(token_info.completed_block_height, Some(3));
+      assert_eq!(token_info.completed_block_time, Some(1687245003));
+
+      // later mints fail and leave the completion block alone
+      let receipt = execute(
+        context(4),
+        version::genesis(),
+        ord_store,
+        brc20_store,
+        &mint(3),
+      )
+      .unwrap()
+      .unwrap();
+      assert!(receipt.result.is_err());
+      let token_info = brc20_store.get_token_info(&tick).unwrap().unwrap();
+      assert_eq!(token_info.completed_block_height, Some(3));
+      assert_eq!(token_info.completed_block_time, Some(1687245003));
+    });
+  }
+}
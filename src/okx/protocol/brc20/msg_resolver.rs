@@ -6,7 +6,10 @@ use crate::{
       brc20::DataStoreReadOnly,
       ord::{Action, InscriptionOp},
     },
-    protocol::brc20::{deserialize_brc20_operation, Operation},
+    protocol::{
+      brc20::{deserialize_brc20_operation, Operation},
+      JsonLimits,
+    },
   },
   Result,
 };
@@ -17,6 +20,8 @@ impl Message {
     brc20_store: &N,
     new_inscriptions: &[Inscription],
     op: &InscriptionOp,
+    lenient_content_type: bool,
+    json_limits: JsonLimits,
   ) -> Result<Option<Message>> {
     log::debug!("BRC20 resolving the message from {:?}", op);
     let sat_in_outputs = op
@@ -36,6 +41,8 @@ impl Message {
             .get(usize::try_from(op.inscription_id.index).unwrap())
             .unwrap(),
           &op.action,
+          lenient_content_type,
+          json_limits,
         ) {
           Ok(brc20_operation) => brc20_operation,
           _ => return Ok(None),
@@ -154,7 +161,16 @@ mod tests {
     let (inscriptions, op) = create_inscribe_operation(
       r#"{ "p": "brc-20s","op": "deploy", "tick": "ordi", "max": "1000", "lim": "10" }"#,
     );
-    assert_matches!(Message::resolve(&brc20_store, &inscriptions, &op), Ok(None));
+    assert_matches!(
+      Message::resolve(
+        &brc20_store,
+        &inscriptions,
+        &op,
+        false,
+        JsonLimits::default()
+      ),
+      Ok(None)
+    );
   }
 
   #[test]
@@ -175,7 +191,16 @@ mod tests {
       },
       ..op
     };
-    assert_matches!(Message::resolve(&brc20_store, &inscriptions, &op), Ok(None));
+    assert_matches!(
+      Message::resolve(
+        &brc20_store,
+        &inscriptions,
+        &op,
+        false,
+        JsonLimits::default()
+      ),
+      Ok(None)
+    );
 
     let op2 = InscriptionOp {
       action: Action::New {
@@ -186,7 +211,13 @@ mod tests {
       ..op
     };
     assert_matches!(
-      Message::resolve(&brc20_store, &inscriptions, &op2),
+      Message::resolve(
+        &brc20_store,
+        &inscriptions,
+        &op2,
+        false,
+        JsonLimits::default()
+      ),
       Ok(None)
     );
     let op3 = InscriptionOp {
@@ -198,7 +229,13 @@ mod tests {
       ..op
     };
     assert_matches!(
-      Message::resolve(&brc20_store, &inscriptions, &op3),
+      Message::resolve(
+        &brc20_store,
+        &inscriptions,
+        &op3,
+        false,
+        JsonLimits::default()
+      ),
       Ok(None)
     );
   }
@@ -227,7 +264,13 @@ mod tests {
       sat_in_outputs: true,
     };
     assert_matches!(
-      Message::resolve(&brc20_store, &inscriptions, &op),
+      Message::resolve(
+        &brc20_store,
+        &inscriptions,
+        &op,
+        false,
+        JsonLimits::default()
+      ),
       Ok(Some(_result_msg))
     );
   }
@@ -241,7 +284,10 @@ mod tests {
 
     // inscribe transfer not found
     let op = create_transfer_operation();
-    assert_matches!(Message::resolve(&brc20_store, &[], &op), Ok(None));
+    assert_matches!(
+      Message::resolve(&brc20_store, &[], &op, false, JsonLimits::default()),
+      Ok(None)
+    );
 
     // non-first transfer operations.
     let op1 = InscriptionOp {
@@ -255,7 +301,10 @@ mod tests {
       },
       ..op
     };
-    assert_matches!(Message::resolve(&brc20_store, &[], &op1), Ok(None));
+    assert_matches!(
+      Message::resolve(&brc20_store, &[], &op1, false, JsonLimits::default()),
+      Ok(None)
+    );
   }
 
   #[test]
@@ -289,6 +338,9 @@ mod tests {
       sat_in_outputs: true,
     };
 
-    assert_matches!(Message::resolve(&brc20_store, &[], &op), Ok(Some(_msg)));
+    assert_matches!(
+      Message::resolve(&brc20_store, &[], &op, false, JsonLimits::default()),
+      Ok(Some(_msg))
+    );
   }
 }
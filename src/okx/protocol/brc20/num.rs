@@ -70,6 +70,15 @@ impl Num {
     scale
   }
 
+  /// Formats an amount scaled by `10^decimals` as a human readable decimal string,
+  /// e.g. `1500` with 3 decimals is formatted as `1.5`.
+  pub fn format_scaled(&self, decimals: u8) -> String {
+    let (int_val, scale) = self.0.as_bigint_and_exponent();
+    BigDecimal::new(int_val, scale + i64::from(decimals))
+      .normalized()
+      .to_string()
+  }
+
   pub fn checked_to_u128(&self) -> Result<u128, BRC20Error> {
     if !self.0.is_integer() {
       return Err(BRC20Error::InvalidInteger(self.clone().to_string()));
@@ -102,6 +111,9 @@ impl From<u128> for Num {
   }
 }
 
+/// Leading zeros and trailing fractional zeros have always been accepted in brc-20 amounts.
+/// `"007"` is `7`, but `"1.00"` keeps a scale of 2 and is rejected as the `max`, `lim` or `amt`
+/// of a token with fewer decimals.
 impl FromStr for Num {
   type Err = BRC20Error;
   fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -141,9 +153,9 @@ impl<'de> Deserialize<'de> for Num {
     D: Deserializer<'de>,
   {
     let s = String::deserialize(deserializer)?;
-    Ok(Self(
-      BigDecimal::from_str(&s).map_err(serde::de::Error::custom)?,
-    ))
+    Ok(Self(BigDecimal::from_str(&s).map_err(|_| {
+      serde::de::Error::custom(BRC20Error::InvalidNum(s.clone()))
+    })?))
   }
 }
 
@@ -234,6 +246,21 @@ mod tests {
     assert!(Num::from_str("1.0000000000000000001").is_err());
   }
 
+  #[test]
+  fn test_non_canonical_num() {
+    assert_eq!(Num::from_str("007").unwrap(), Num::from(7_u64));
+    assert_eq!(Num::from_str("007").unwrap().scale(), 0);
+
+    assert_eq!(Num::from_str("1.00").unwrap(), Num::from(1_u64));
+    assert_eq!(Num::from_str("1.00").unwrap().scale(), 2);
+
+    assert_eq!(
+      Num::from_str("01.5").unwrap(),
+      Num(BigDecimal::new(BigInt::from(15), 1))
+    );
+    assert_eq!(Num::from_str("01.5").unwrap().scale(), 1);
+  }
+
   #[test]
   fn test_invalid_num() {
     assert!(Num::from_str("").is_err());
@@ -268,6 +295,29 @@ mod tests {
     assert_eq!(Num::from_str("1.11").unwrap(), num);
   }
 
+  #[test]
+  fn test_num_errors_show_input() {
+    for input in [
+      "abc",
+      ".1",
+      "1.",
+      "1e5",
+      "-1",
+      "123.456.789",
+      "0.1234567890123456789",
+    ] {
+      let err = Num::from_str(input).unwrap_err().to_string();
+      assert!(err.contains(input), "{err}");
+    }
+
+    for input in ["abc", "123.456.789"] {
+      let err = serde_json::from_str::<Num>(&format!("\"{input}\""))
+        .unwrap_err()
+        .to_string();
+      assert!(err.contains(input), "{err}");
+    }
+  }
+
   #[test]
   fn test_num_checked_add() {
     assert_eq!(
@@ -432,4 +482,28 @@ mod tests {
       BRC20Error::InvalidNum("100E2".to_string())
     );
   }
+
+  #[test]
+  fn test_format_scaled() {
+    // 18 decimals
+    assert_eq!(Num::from(0u128).format_scaled(18), "0");
+    assert_eq!(Num::from(1u128).format_scaled(18), "0.000000000000000001");
+    assert_eq!(
+      Num::from(1_000_000_000_000_000_000u128).format_scaled(18),
+      "1"
+    );
+    assert_eq!(
+      Num::from(1_500_000_000_000_000_000u128).format_scaled(18),
+      "1.5"
+    );
+    assert_eq!(
+      Num::from(u128::MAX).format_scaled(18),
+      "340282366920938463463.374607431768211455"
+    );
+
+    // 0 decimals
+    assert_eq!(Num::from(0u128).format_scaled(0), "0");
+    assert_eq!(Num::from(1000u128).format_scaled(0), "1000");
+    assert_eq!(Num::from(u128::MAX).format_scaled(0), u128::MAX.to_string());
+  }
 }
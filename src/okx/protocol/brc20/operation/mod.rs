@@ -3,7 +3,13 @@ mod mint;
 mod transfer;
 
 use super::{params::*, *};
-use crate::{okx::datastore::ord::Action, Inscription};
+use crate::{
+  okx::{
+    datastore::ord::Action,
+    protocol::utils::{from_str_bounded, JsonLimits},
+  },
+  Inscription,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -39,28 +45,36 @@ enum RawOperation {
   Transfer(Transfer),
 }
 
+/// Deserialize the brc20 operation of an inscription.
+///
+/// With `lenient_content_type`, an inscription without a content type is still parsed
+/// as long as its body is a brc20 json. A json beyond `json_limits` is invalid.
 pub(crate) fn deserialize_brc20_operation(
   inscription: &Inscription,
   action: &Action,
+  lenient_content_type: bool,
+  json_limits: JsonLimits,
 ) -> Result<Operation> {
   let content_body = std::str::from_utf8(inscription.body().ok_or(JSONError::InvalidJson)?)?;
-  if content_body.len() < 40 {
+  if content_body.len() < MIN_OPERATION_BODY_LEN {
     return Err(JSONError::NotBRC20Json.into());
   }
 
-  let content_type = inscription
-    .content_type()
-    .ok_or(JSONError::InvalidContentType)?;
-
-  if content_type != "text/plain"
-    && content_type != "text/plain;charset=utf-8"
-    && content_type != "text/plain;charset=UTF-8"
-    && content_type != "application/json"
-    && !content_type.starts_with("text/plain;")
-  {
-    return Err(JSONError::UnSupportContentType.into());
+  match inscription.content_type() {
+    Some(content_type) => {
+      if content_type != "text/plain"
+        && content_type != "text/plain;charset=utf-8"
+        && content_type != "text/plain;charset=UTF-8"
+        && content_type != "application/json"
+        && !content_type.starts_with("text/plain;")
+      {
+        return Err(JSONError::UnSupportContentType.into());
+      }
+    }
+    None if lenient_content_type => {}
+    None => return Err(JSONError::InvalidContentType.into()),
   }
-  let raw_operation = match deserialize_brc20(content_body) {
+  let raw_operation = match deserialize_brc20_bounded(content_body, json_limits) {
     Ok(op) => op,
     Err(e) => {
       return Err(e.into());
@@ -80,8 +94,13 @@ pub(crate) fn deserialize_brc20_operation(
   }
 }
 
+#[cfg(test)]
 fn deserialize_brc20(s: &str) -> Result<RawOperation, JSONError> {
-  let value: Value = serde_json::from_str(s).map_err(|_| JSONError::InvalidJson)?;
+  deserialize_brc20_bounded(s, JsonLimits::default())
+}
+
+fn deserialize_brc20_bounded(s: &str, json_limits: JsonLimits) -> Result<RawOperation, JSONError> {
+  let value: Value = from_str_bounded(s, json_limits).ok_or(JSONError::InvalidJson)?;
   if value.get("p") != Some(&json!(PROTOCOL_LITERAL)) {
     return Err(JSONError::NotBRC20Json);
   }
@@ -181,6 +200,36 @@ mod tests {
     assert!(deserialize_brc20(json_str).is_err())
   }
 
+  #[test]
+  fn test_json_too_deep() {
+    let json_str = format!(
+      r#"{{"p":"brc-20","op":"mint","tick":"smol","amt":"33","x":{}1{}}}"#,
+      "[".repeat(100),
+      "]".repeat(100)
+    );
+    assert!(deserialize_brc20(&json_str).is_ok());
+    assert_eq!(
+      deserialize_brc20_bounded(
+        &json_str,
+        JsonLimits {
+          max_size: None,
+          max_depth: Some(32),
+        }
+      ),
+      Err(JSONError::InvalidJson)
+    );
+    assert_eq!(
+      deserialize_brc20_bounded(
+        &json_str,
+        JsonLimits {
+          max_size: Some(128),
+          max_depth: None,
+        }
+      ),
+      Err(JSONError::InvalidJson)
+    );
+  }
+
   #[test]
   fn test_deserialize_case_insensitive() {
     let max_supply = "21000000".to_string();
@@ -198,6 +247,79 @@ mod tests {
 
     assert_eq!(deserialize_brc20(&json_str), Err(JSONError::NotBRC20Json));
   }
+  #[test]
+  fn test_min_operation_body_len() {
+    let action = Action::New {
+      cursed: false,
+      unbound: false,
+      inscription: crate::inscription("text/plain;charset=utf-8", ""),
+    };
+    let content_type = "text/plain;charset=utf-8";
+
+    let mint = r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1"}"#;
+    assert!(mint.len() > MIN_OPERATION_BODY_LEN);
+    assert_eq!(
+      deserialize_brc20_operation(
+        &crate::inscription(content_type, mint),
+        &action,
+        false,
+        JsonLimits::default()
+      )
+      .unwrap(),
+      Operation::Mint(Mint {
+        tick: "ordi".to_string(),
+        amount: "1".to_string(),
+      })
+    );
+
+    let transfer = r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"1"}"#;
+    assert!(transfer.len() > MIN_OPERATION_BODY_LEN);
+    assert_eq!(
+      deserialize_brc20_operation(
+        &crate::inscription(content_type, transfer),
+        &action,
+        false,
+        JsonLimits::default()
+      )
+      .unwrap(),
+      Operation::InscribeTransfer(Transfer {
+        tick: "ordi".to_string(),
+        amount: "1".to_string(),
+      })
+    );
+
+    // shorter than any valid operation, but still parsed and failed on execution.
+    let invalid_tick = r#"{"p":"brc-20","op":"mint","tick":"ord","amt":"1"}"#;
+    assert_eq!(
+      deserialize_brc20_operation(
+        &crate::inscription(content_type, invalid_tick),
+        &action,
+        false,
+        JsonLimits::default()
+      )
+      .unwrap(),
+      Operation::Mint(Mint {
+        tick: "ord".to_string(),
+        amount: "1".to_string(),
+      })
+    );
+
+    let short = r#"{"p":"brc-20","op":"mint","tick":"ord"}"#;
+    assert_eq!(short.len(), MIN_OPERATION_BODY_LEN - 1);
+    assert_eq!(
+      deserialize_brc20_operation(
+        &crate::inscription(content_type, short),
+        &action,
+        false,
+        JsonLimits::default()
+      )
+      .unwrap_err()
+      .downcast::<JSONError>()
+      .unwrap(),
+      JSONError::NotBRC20Json
+    );
+  }
+
   #[test]
   fn test_ignore_non_transfer_brc20() {
     let content_type = "text/plain;charset=utf-8";
@@ -213,6 +335,8 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        false,
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Deploy(Deploy {
@@ -235,6 +359,8 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        false,
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Mint(Mint {
@@ -255,6 +381,8 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        false,
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::InscribeTransfer(Transfer {
@@ -267,23 +395,88 @@ mod tests {
       content_type,
       r#"{"p":"brc-20","op":"deploy","tick":"abcd","max":"12000","lim":"12","dec":"11"}"#,
     );
-    assert!(deserialize_brc20_operation(&inscription, &Action::Transfer).is_err());
+    assert!(deserialize_brc20_operation(
+      &inscription,
+      &Action::Transfer,
+      false,
+      JsonLimits::default()
+    )
+    .is_err());
 
     let inscription = crate::inscription(
       content_type,
       r#"{"p":"brc-20","op":"mint","tick":"abcd","amt":"12000"}"#,
     );
-    assert!(deserialize_brc20_operation(&inscription, &Action::Transfer).is_err());
+    assert!(deserialize_brc20_operation(
+      &inscription,
+      &Action::Transfer,
+      false,
+      JsonLimits::default()
+    )
+    .is_err());
     let inscription = crate::inscription(
       content_type,
       r#"{"p":"brc-20","op":"transfer","tick":"abcd","amt":"12000"}"#,
     );
     assert_eq!(
-      deserialize_brc20_operation(&inscription, &Action::Transfer).unwrap(),
+      deserialize_brc20_operation(
+        &inscription,
+        &Action::Transfer,
+        false,
+        JsonLimits::default()
+      )
+      .unwrap(),
       Operation::Transfer(Transfer {
         tick: "abcd".to_string(),
         amount: "12000".to_string()
       })
     );
   }
+
+  #[test]
+  fn test_deploy_without_content_type() {
+    let inscription = Inscription::new(
+      None,
+      Some(
+        r#"{"p":"brc-20","op":"deploy","tick":"abcd","max":"12000","lim":"12","dec":"11"}"#
+          .as_bytes()
+          .to_vec(),
+      ),
+    );
+    let action = Action::New {
+      cursed: false,
+      unbound: false,
+      inscription: inscription.clone(),
+    };
+
+    assert_eq!(
+      deserialize_brc20_operation(&inscription, &action, false, JsonLimits::default())
+        .unwrap_err()
+        .downcast::<JSONError>()
+        .unwrap(),
+      JSONError::InvalidContentType
+    );
+    assert_eq!(
+      deserialize_brc20_operation(&inscription, &action, true, JsonLimits::default()).unwrap(),
+      Operation::Deploy(Deploy {
+        tick: "abcd".to_string(),
+        max_supply: "12000".to_string(),
+        mint_limit: Some("12".to_string()),
+        decimals: Some("11".to_string()),
+      })
+    );
+
+    // the body must still be a brc20 json.
+    let inscription = Inscription::new(
+      None,
+      Some(
+        r#"{"p":"brc-21","op":"deploy","tick":"abcd","max":"12000","lim":"12","dec":"11"}"#
+          .as_bytes()
+          .to_vec(),
+      ),
+    );
+    assert!(
+      deserialize_brc20_operation(&inscription, &action, true, JsonLimits::default()).is_err()
+    );
+  }
 }
@@ -4,6 +4,11 @@ use once_cell::sync::Lazy;
 pub const PROTOCOL_LITERAL: &str = "brc-20";
 pub const MAX_DECIMAL_WIDTH: u8 = 18;
 
+// Bodies shorter than this are not parsed as brc20 operations. The shortest valid operation,
+// `{"p":"brc-20","op":"mint","tick":"ordi","amt":"1"}`, is longer, but the bodies in between have
+// always been parsed and can leave a failed receipt, so raising it would change a reindex.
+pub const MIN_OPERATION_BODY_LEN: usize = 40;
+
 pub static MAXIMUM_SUPPLY: Lazy<Num> = Lazy::new(|| Num::from(u64::MAX));
 
 pub static BIGDECIMAL_TEN: Lazy<Num> = Lazy::new(|| Num::from(10u64));
@@ -0,0 +1,31 @@
+use bitcoin::Network;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+  /// Whether a transfer inscription sent to an OP_RETURN output burns its amount instead of
+  /// crediting it to the OP_RETURN script.
+  pub allow_op_return_burn: bool,
+}
+
+// the rules brc20 is indexed with since its first block.
+pub const fn genesis() -> Config {
+  Config {
+    allow_op_return_burn: false,
+  }
+}
+
+// op return burn, a transfer inscription sent to an OP_RETURN output is burned.
+// regtest only until an activation height is scheduled for the public networks.
+pub const fn ember() -> Config {
+  Config {
+    allow_op_return_burn: true,
+  }
+}
+
+pub fn get_config_by_network(network: Network, _blockheight: u64) -> Config {
+  match network {
+    Network::Bitcoin | Network::Testnet | Network::Signet => genesis(),
+    Network::Regtest => ember(),
+    _ => panic!("not support network"),
+  }
+}
@@ -0,0 +1,202 @@
+use {
+  super::Operation,
+  crate::InscriptionId,
+  std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+  },
+};
+
+/// Least recently used cache of the BRC20S operations parsed from inscriptions on the query paths,
+/// so a queried inscription is only read and parsed once while it stays in the cache. Indexing
+/// doesn't go through it, it parses every new inscription exactly once. Clones share the same
+/// cache.
+#[derive(Debug, Clone)]
+pub struct OperationCache {
+  inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+  capacity: usize,
+  // the last use of every cached inscription, `None` if it is not a BRC20S operation.
+  entries: HashMap<InscriptionId, (u64, Option<Operation>)>,
+  // the cached inscriptions ordered by their last use, the least recently used first.
+  uses: BTreeMap<u64, InscriptionId>,
+  clock: u64,
+  hits: u64,
+  misses: u64,
+}
+
+impl OperationCache {
+  /// Creates a cache of at most `capacity` inscriptions. A cache of capacity 0 parses every time.
+  pub(crate) fn new(capacity: usize) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(Inner {
+        capacity,
+        ..Default::default()
+      })),
+    }
+  }
+
+  /// Returns the cached operation of the inscription, or parses it with `parse` and caches the
+  /// result unless parsing fails. Inscriptions are immutable, so the parsed operation never goes
+  /// stale.
+  pub(crate) fn get_or_parse<E>(
+    &self,
+    inscription_id: InscriptionId,
+    parse: impl FnOnce() -> Result<Option<Operation>, E>,
+  ) -> Result<Option<Operation>, E> {
+    {
+      let mut inner = self.inner.lock().unwrap();
+      if let Some(operation) = inner.touch(inscription_id) {
+        inner.hits += 1;
+        return Ok(operation);
+      }
+      inner.misses += 1;
+    }
+
+    // parse without holding the lock, a concurrent parse of the same inscription is harmless.
+    let operation = parse()?;
+    self
+      .inner
+      .lock()
+      .unwrap()
+      .insert(inscription_id, operation.clone());
+    Ok(operation)
+  }
+
+  /// Returns the number of cache hits and misses so far.
+  #[cfg(test)]
+  pub(crate) fn stats(&self) -> (u64, u64) {
+    let inner = self.inner.lock().unwrap();
+    (inner.hits, inner.misses)
+  }
+}
+
+impl Inner {
+  fn touch(&mut self, inscription_id: InscriptionId) -> Option<Option<Operation>> {
+    self.clock += 1;
+    let clock = self.clock;
+    let (last_use, operation) = self.entries.get_mut(&inscription_id)?;
+    self.uses.remove(last_use);
+    self.uses.insert(clock, inscription_id);
+    *last_use = clock;
+    Some(operation.clone())
+  }
+
+  fn insert(&mut self, inscription_id: InscriptionId, operation: Option<Operation>) {
+    if self.capacity == 0 {
+      return;
+    }
+    self.clock += 1;
+    if let Some((last_use, _)) = self.entries.insert(inscription_id, (self.clock, operation)) {
+      self.uses.remove(&last_use);
+    }
+    self.uses.insert(self.clock, inscription_id);
+
+    while self.entries.len() > self.capacity {
+      let Some((_, evicted)) = self.uses.pop_first() else {
+        break;
+      };
+      self.entries.remove(&evicted);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::okx::protocol::brc20s::Stake, std::str::FromStr};
+
+  fn inscription_id(index: u32) -> InscriptionId {
+    InscriptionId::from_str(&format!(
+      "1111111111111111111111111111111111111111111111111111111111111111i{index}"
+    ))
+    .unwrap()
+  }
+
+  fn stake() -> Operation {
+    Operation::Stake(Stake {
+      pool_id: "fea607ea9e#0f".to_string(),
+      amount: "10".to_string(),
+    })
+  }
+
+  fn get_or_parse(
+    cache: &OperationCache,
+    index: u32,
+    parse: impl FnOnce() -> Option<Operation>,
+  ) -> Option<Operation> {
+    cache
+      .get_or_parse(inscription_id(index), || Ok::<_, ()>(parse()))
+      .unwrap()
+  }
+
+  #[test]
+  fn second_parse_hits_cache() {
+    let cache = OperationCache::new(2);
+    assert_eq!(get_or_parse(&cache, 0, || Some(stake())), Some(stake()));
+    assert_eq!(
+      get_or_parse(&cache, 0, || panic!("parsed twice")),
+      Some(stake())
+    );
+    assert_eq!(get_or_parse(&cache, 1, || None), None);
+    assert_eq!(get_or_parse(&cache, 1, || panic!("parsed twice")), None);
+    assert_eq!(cache.stats(), (2, 2));
+  }
+
+  #[test]
+  fn failed_parse_is_not_cached() {
+    let cache = OperationCache::new(2);
+    assert_eq!(
+      cache.get_or_parse(inscription_id(0), || Err("unavailable")),
+      Err("unavailable")
+    );
+    assert_eq!(get_or_parse(&cache, 0, || Some(stake())), Some(stake()));
+    assert_eq!(cache.stats(), (0, 2));
+  }
+
+  #[test]
+  fn evicts_least_recently_used() {
+    let cache = OperationCache::new(2);
+    get_or_parse(&cache, 0, || Some(stake()));
+    get_or_parse(&cache, 1, || None);
+    // using inscription 0 again makes inscription 1 the least recently used.
+    get_or_parse(&cache, 0, || panic!("parsed twice"));
+    get_or_parse(&cache, 2, || None);
+
+    get_or_parse(&cache, 0, || panic!("parsed twice"));
+    get_or_parse(&cache, 2, || panic!("parsed twice"));
+    assert_eq!(get_or_parse(&cache, 1, || Some(stake())), Some(stake()));
+    assert_eq!(cache.stats(), (3, 4));
+  }
+
+  #[test]
+  fn zero_capacity_always_parses() {
+    let cache = OperationCache::new(0);
+    get_or_parse(&cache, 0, || None);
+    assert_eq!(get_or_parse(&cache, 0, || Some(stake())), Some(stake()));
+    assert_eq!(cache.stats(), (0, 2));
+  }
+
+  #[test]
+  fn shared_between_threads() {
+    let cache = OperationCache::new(8);
+    std::thread::scope(|scope| {
+      for _ in 0..4 {
+        let cache = cache.clone();
+        scope.spawn(move || {
+          for index in 0..8 {
+            get_or_parse(&cache, index, || Some(stake()));
+          }
+        });
+      }
+    });
+    let (hits, misses) = cache.stats();
+    assert_eq!(hits + misses, 32);
+    assert!(misses >= 8);
+    for index in 0..8 {
+      get_or_parse(&cache, index, || panic!("parsed twice"));
+    }
+  }
+}
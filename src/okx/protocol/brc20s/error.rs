@@ -97,7 +97,7 @@ pub enum BRC20SError {
   #[error("amount overflow: {0}")]
   AmountOverflow(String),
 
-  #[error("insufficient balance: {0} {1}")]
+  #[error("insufficient balance: available {0}, requested {1}")]
   InsufficientBalance(String, String),
 
   #[error("dmax:{0} must be less than totoalsupply:{1}")]
@@ -167,6 +167,27 @@ pub enum BRC20SError {
 
   #[error("staked:{0} can not equal to earn:{1}")]
   StakeEqualEarn(String, String),
+
+  #[error("invalid tick name '{0}'")]
+  InvalidTickName(String),
+
+  #[error("tick id {0} has been taken by another tick")]
+  DuplicateTickId(String),
+
+  #[error("pool {0} is exhausted, remaining distributable: {1}")]
+  PoolExhausted(String, String),
+
+  #[error("tick {0} has reached the limit of {1} pools")]
+  TooManyPools(String, u64),
+
+  #[error("stake amount {0} is below the minimum stake {1} of pool {2}")]
+  BelowMinimumStake(String, String, String),
+
+  #[error("amount {0} has more than {1} decimal places")]
+  InvalidAmountPrecision(String, u8),
+
+  #[error("{0} is already staked in the only pool {1}")]
+  OnlyPoolViolation(String, String),
 }
 
 impl<L: DataStoreReadOnly> From<BRC20SError> for Error<L> {
@@ -2,6 +2,7 @@ use crate::inscription_id::InscriptionId;
 use crate::SatPoint;
 use bitcoin::Txid;
 
+pub mod cache;
 pub mod error;
 pub mod hash;
 pub mod msg_executor;
@@ -17,6 +18,7 @@ mod version;
 
 pub(crate) use self::operation::deserialize_brc20s_operation;
 pub use self::{
+  cache::OperationCache,
   error::{BRC20SError, Error},
   msg_executor::{execute, ExecutionMessage},
   num::Num,
@@ -6,9 +6,10 @@ use crate::okx::{
       get_stake_dec, get_user_common_balance, stake_is_exist,
     },
     brc20s::{
-      Balance, DeployPoolEvent, DeployTickEvent, DepositEvent, Event, InscribeTransferEvent,
-      MintEvent, PassiveWithdrawEvent, Pid, PoolInfo, Receipt, StakeInfo, Tick, TickId, TickInfo,
-      TransferEvent, TransferInfo, TransferableAsset, UserInfo, WithdrawEvent,
+      Balance, BurnEvent, DeployPoolEvent, DeployTickEvent, DepositEvent, Event,
+      InscribeTransferEvent, MintEvent, OperationType, PassiveWithdrawEvent, Pid, PoolInfo,
+      Receipt, StakeInfo, Tick, TickId, TickInfo, TransferEvent, TransferInfo, TransferableAsset,
+      UserInfo, WithdrawEvent,
     },
     ScriptKey,
   },
@@ -17,6 +18,7 @@ use crate::okx::{
       hash::caculate_tick_id,
       operation::Operation,
       params::{BIGDECIMAL_TEN, MAX_DECIMAL_WIDTH},
+      util::validate_tick_name,
       version, BRC20SError, Deploy, Error, Message, Mint, Num, PassiveUnStake, Stake, Transfer,
       UnStake,
     },
@@ -32,8 +34,9 @@ use crate::okx::datastore::ord;
 use crate::{InscriptionId, Result, SatPoint};
 use anyhow::anyhow;
 use bigdecimal::num_bigint::Sign;
-use bitcoin::{Network, Txid};
+use bitcoin::{Network, OutPoint, Txid};
 use std::cmp;
+use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,10 +46,13 @@ pub struct ExecutionMessage {
   pub(crate) inscription_number: i64,
   pub(crate) commit_input_satpoint: Option<SatPoint>,
   pub(crate) old_satpoint: SatPoint,
-  pub(crate) new_satpoint: SatPoint,
+  // none while the transaction is unconfirmed and the sat is not bound to its outputs yet.
+  pub(crate) new_satpoint: Option<SatPoint>,
   pub(crate) commit_from: Option<ScriptKey>,
   pub(crate) from: ScriptKey,
   pub(crate) to: Option<ScriptKey>,
+  // the inscription is moved to one of the configured burn addresses.
+  pub(crate) to_burn_address: bool,
   pub(crate) op: Operation,
 }
 
@@ -55,42 +61,62 @@ impl ExecutionMessage {
     ord_store: &O,
     msg: &Message,
     network: Network,
+    burn_addresses: &HashSet<ScriptKey>,
   ) -> Result<Self> {
+    let to = match msg.new_satpoint {
+      Some(new_satpoint) if msg.sat_in_outputs => Some(utils::get_script_key_on_satpoint(
+        new_satpoint,
+        ord_store,
+        network,
+      )?),
+      _ => None,
+    };
     Ok(Self {
       txid: msg.txid,
       inscription_id: msg.inscription_id,
       inscription_number: utils::get_inscription_number_by_id(msg.inscription_id, ord_store)?,
       commit_input_satpoint: msg.commit_input_satpoint,
       old_satpoint: msg.old_satpoint,
-      new_satpoint: msg
-        .new_satpoint
-        .ok_or(anyhow!("new satpoint cannot be None"))?,
+      new_satpoint: msg.new_satpoint,
       commit_from: msg
         .commit_input_satpoint
         .map(|satpoint| utils::get_script_key_on_satpoint(satpoint, ord_store, network))
         .transpose()?,
       from: utils::get_script_key_on_satpoint(msg.old_satpoint, ord_store, network)?,
-      to: if msg.sat_in_outputs {
-        Some(utils::get_script_key_on_satpoint(
-          msg.new_satpoint.unwrap(),
-          ord_store,
-          network,
-        )?)
-      } else {
-        None
-      },
+      to_burn_address: to.as_ref().map_or(false, |to| burn_addresses.contains(to)),
+      to,
       op: msg.op.clone(),
     })
   }
 }
 
+/// Executes a message against the brc20s state and saves its receipts.
+///
+/// A message without a new satpoint has no known destination yet. A transfer of such a message is
+/// skipped without a receipt: nothing is credited and the inscribed transfer stays spendable, so it
+/// is executed once the sat is bound. Every other operation doesn't need a destination and is
+/// executed as usual, its receipt records the null outpoint as new satpoint.
 pub fn execute<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
   context: BlockContext,
+  first_brc20s_height: Option<u64>,
   config: version::Config,
   brc20_store: &'a M,
   brc20s_store: &'a N,
   msg: &ExecutionMessage,
-) -> Result<Option<Receipt>> {
+) -> Result<Vec<Receipt>> {
+  // messages are only resolved after activation, but guard here as well so that nothing
+  // reaching the executor early can touch the state.
+  if !first_brc20s_height
+    .map(|height| context.blockheight >= height)
+    .unwrap_or(false)
+  {
+    log::debug!(
+      "BRC20S skip message at height {} before activation: {:?}",
+      context.blockheight,
+      msg
+    );
+    return Ok(Vec::new());
+  }
   log::debug!("BRC20S execute message: {:?}", msg);
   let mut is_save_receipt = true;
   let event = match &msg.op {
@@ -142,15 +168,36 @@ pub fn execute<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
       };
       events
     }
-    Operation::Mint(mint) => process_mint(
-      context,
-      config.clone(),
-      brc20_store,
-      brc20s_store,
-      msg,
-      mint.clone(),
-    )
-    .map(|event| vec![event]),
+    // every pool of a batch mint is executed on its own and gets its own receipt.
+    Operation::Mint(mint) => {
+      if mint.pools.is_some() && !config.allow_multi_pool_mint {
+        log::debug!("BRC20S skip multi pool mint before activation: {:?}", msg);
+        return Ok(Vec::new());
+      }
+      return mint
+        .entries()
+        .into_iter()
+        .map(|mint| {
+          let event = process_mint(
+            context,
+            config.clone(),
+            brc20_store,
+            brc20s_store,
+            msg,
+            mint,
+          )
+          .map(|event| vec![event]);
+          save_receipt(brc20s_store, msg, OperationType::Mint, event)
+        })
+        .collect();
+    }
+    Operation::Batch(operations) => {
+      if !config.allow_batch_operations {
+        log::debug!("BRC20S skip batch operations before activation: {:?}", msg);
+        return Ok(Vec::new());
+      }
+      return execute_batch(context, config, brc20_store, brc20s_store, msg, operations);
+    }
     Operation::InscribeTransfer(transfer) => process_inscribe_transfer(
       context,
       config.clone(),
@@ -161,22 +208,148 @@ pub fn execute<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
     )
     .map(|event| vec![event]),
     Operation::Transfer(_) => {
+      if msg.new_satpoint.is_none() {
+        log::debug!(
+          "BRC20S skip transfer of inscription {} without new satpoint",
+          msg.inscription_id
+        );
+        return Ok(Vec::new());
+      }
+      // the inscribe-transfer record is taken by the first spend, a replayed spend of the same
+      // inscription finds nothing and moves no balance.
+      if brc20s_store
+        .get_inscribe_transfer_inscription(msg.inscription_id)
+        .map_err(|e| {
+          anyhow!(
+            "failed to get inscribe transfer inscription for {}! error: {e}",
+            msg.inscription_id
+          )
+        })?
+        .is_none()
+      {
+        log::debug!(
+          "BRC20S skip transfer of spent inscription {}",
+          msg.inscription_id
+        );
+        return Ok(Vec::new());
+      }
       process_transfer(context, config, brc20_store, brc20s_store, msg).map(|event| vec![event])
     }
   };
 
   if !is_save_receipt {
-    return Ok(None);
+    return Ok(Vec::new());
+  }
+
+  Ok(vec![save_receipt(
+    brc20s_store,
+    msg,
+    msg.op.op_type(),
+    event,
+  )?])
+}
+
+// the operations of a batch are executed in order on a savepoint of the state, each deposit,
+// withdrawal and single pool mint gets its own receipt. Once one of them fails, the ones before
+// are rolled back and only the failed one gets a receipt.
+fn execute_batch<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
+  context: BlockContext,
+  config: version::Config,
+  brc20_store: &'a M,
+  brc20s_store: &'a N,
+  msg: &ExecutionMessage,
+  operations: &[Operation],
+) -> Result<Vec<Receipt>> {
+  brc20s_store
+    .savepoint()
+    .map_err(|e| anyhow!("failed to set savepoint to state! error: {e}"))?;
+
+  let mut executed = Vec::new();
+  for operation in operations {
+    let entries = match operation {
+      Operation::Mint(mint) if mint.pools.is_some() && !config.allow_multi_pool_mint => {
+        vec![operation.clone()]
+      }
+      Operation::Mint(mint) => mint.entries().into_iter().map(Operation::Mint).collect(),
+      operation => vec![operation.clone()],
+    };
+    for entry in entries {
+      let event = match &entry {
+        Operation::Mint(mint) if mint.pools.is_some() => Err(Error::BRC20SError(
+          BRC20SError::InternalError("multi pool mint is not activated".to_string()),
+        )),
+        Operation::Mint(mint) => process_mint(
+          context,
+          config.clone(),
+          brc20_store,
+          brc20s_store,
+          msg,
+          mint.clone(),
+        ),
+        Operation::Stake(stake) => process_stake(
+          context,
+          config.clone(),
+          brc20_store,
+          brc20s_store,
+          msg,
+          stake.clone(),
+        ),
+        Operation::UnStake(unstake) => process_unstake(
+          context,
+          config.clone(),
+          brc20_store,
+          brc20s_store,
+          msg,
+          unstake.clone(),
+        ),
+        _ => Err(Error::BRC20SError(BRC20SError::InternalError(format!(
+          "{:?} can not be batched",
+          entry.op_type()
+        )))),
+      };
+      match event {
+        Ok(event) => executed.push((entry.op_type(), vec![event])),
+        Err(e) => {
+          brc20s_store
+            .rollback_to_savepoint()
+            .map_err(|e| anyhow!("failed to roll back state to savepoint! error: {e}"))?;
+          return Ok(vec![save_receipt(
+            brc20s_store,
+            msg,
+            entry.op_type(),
+            Err(e),
+          )?]);
+        }
+      }
+    }
   }
 
+  brc20s_store
+    .release_savepoint()
+    .map_err(|e| anyhow!("failed to release savepoint of state! error: {e}"))?;
+  executed
+    .into_iter()
+    .map(|(op, events)| save_receipt(brc20s_store, msg, op, Ok(events)))
+    .collect()
+}
+
+fn save_receipt<N: brc20s::DataStoreReadWrite>(
+  brc20s_store: &N,
+  msg: &ExecutionMessage,
+  op: OperationType,
+  event: Result<Vec<Event>, Error<N>>,
+) -> Result<Receipt> {
   let receipt = Receipt {
     inscription_id: msg.inscription_id,
     inscription_number: msg.inscription_number,
     old_satpoint: msg.old_satpoint,
-    new_satpoint: msg.new_satpoint,
+    new_satpoint: msg.new_satpoint.unwrap_or(SatPoint {
+      outpoint: OutPoint::null(),
+      offset: 0,
+    }),
     from: msg.from.clone(),
     to: msg.to.clone().map_or(msg.from.clone(), |v| v),
-    op: msg.op.op_type(),
+    op,
     result: match event {
       Ok(event) => Ok(event),
       Err(Error::BRC20SError(e)) => Err(e),
@@ -188,7 +361,7 @@ pub fn execute<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
   brc20s_store
     .add_transaction_receipt(&msg.txid, &receipt)
     .map_err(|e| anyhow!("failed to set transaction receipts to state! error: {e}"))?;
-  Ok(Some(receipt))
+  Ok(receipt)
 }
 
 pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
@@ -202,10 +375,20 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
   // ignore inscribe inscription to coinbase.
   let to_script_key = msg.to.clone().ok_or(BRC20SError::InscribeToCoinbase)?;
   let mut events = Vec::new();
+  // the keys of the pool extensions are ignored like any unknown key before their activation.
+  let deploy = Deploy {
+    halving: deploy.halving.filter(|_| config.allow_halving),
+    min_stake: deploy.min_stake.filter(|_| config.allow_min_stake),
+    maturity: deploy.maturity.filter(|_| config.allow_maturity),
+    ..deploy
+  };
   // inscription message basic availability check
   if let Some(iserr) = deploy.validate_basic().err() {
     return Err(Error::BRC20SError(iserr));
   }
+  if config.strict_tick_names {
+    validate_tick_name(deploy.earn.as_str())?;
+  }
 
   let from_script_key = match msg.commit_from.clone() {
     Some(script) => script,
@@ -220,6 +403,8 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
   let pid = deploy.get_pool_id();
   let ptype = deploy.get_pool_type();
   let only = deploy.get_only();
+  let halving_interval = deploy.get_halving_interval();
+  let maturity = deploy.get_maturity();
   let mut stake = deploy.get_stake_id();
   // temp disable
   // brc20-s can not be staked
@@ -266,6 +451,15 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
     )));
   }
 
+  // the minimum stake is kept in the decimal of the staked tick, as the amounts of stakes are.
+  let min_stake = match deploy.min_stake.as_ref() {
+    Some(min_stake) => Some(
+      convert_pledged_tick_with_decimal(&stake, min_stake.as_str(), brc20s_store, brc20_store)?
+        .checked_to_u128()?,
+    ),
+    None => None,
+  };
+
   let erate: u128;
 
   let earn_tick = deploy.get_earn_tick();
@@ -277,6 +471,18 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
     .get_tick_info(&tick_id)
     .map_err(|e| Error::LedgerError(e))?
   {
+    if is_tick_id_collision::<N>(&deploy, &stored_tick, &from_script_key, &to_script_key) {
+      log::warn!(
+        "BRC20S tick id {} of deploy {} collides with the tick deployed by {}",
+        tick_id.hex(),
+        msg.inscription_id,
+        stored_tick.inscription_id
+      );
+      return Err(Error::BRC20SError(BRC20SError::DuplicateTickId(
+        tick_id.hex(),
+      )));
+    }
+
     if stored_tick.name != earn_tick {
       return Err(Error::BRC20SError(BRC20SError::TickNameNotMatch(
         deploy.earn.clone(),
@@ -310,6 +516,18 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
       )));
     }
 
+    if let Some(max_tick_pool_num) = config.max_tick_pool_num {
+      let pools = brc20s_store
+        .get_tickid_to_all_pid(&tick_id)
+        .map_err(|e| Error::LedgerError(e))?;
+      if u64::try_from(pools.len()).unwrap() >= max_tick_pool_num {
+        return Err(Error::BRC20SError(BRC20SError::TooManyPools(
+          tick_id.hex(),
+          max_tick_pool_num,
+        )));
+      }
+    }
+
     dmax = convert_amount_with_decimal(dmax_str, stored_tick.decimal)?.checked_to_u128()?;
     // check dmax
     if stored_tick.supply - stored_tick.allocated < dmax {
@@ -352,6 +570,31 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
       )));
     }
 
+    // ticks of the same name are distinguished by tick id, but a name which only differs
+    // in case from an existing tick is rejected. The pools are indexed by lowercase earn name.
+    if config.strict_tick_names {
+      let (pids, _) = brc20s_store
+        .get_earn_to_all_pid(&earn_tick, 0, None)
+        .map_err(|e| Error::LedgerError(e))?;
+      // the pools of a tick are adjacent, their ids start with the tick id.
+      let mut tick_ids = pids
+        .iter()
+        .filter_map(|pid| TickId::from_str(pid.as_str().split('#').next()?).ok())
+        .collect::<Vec<_>>();
+      tick_ids.dedup();
+      for tick_id in tick_ids {
+        let name = brc20s_store
+          .get_tick_info(&tick_id)
+          .map_err(|e| Error::LedgerError(e))?
+          .map(|tick| tick.name);
+        if name.map_or(false, |name| name.as_str() != earn_tick.as_str()) {
+          return Err(Error::BRC20SError(BRC20SError::DuplicateTick(
+            deploy.earn.clone(),
+          )));
+        }
+      }
+    }
+
     let pids = vec![pid.clone()];
     dmax = convert_amount_with_decimal(dmax_str, decimal)?.checked_to_u128()?;
     let tick = TickInfo::new(
@@ -371,10 +614,13 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
     brc20s_store
       .set_tick_info(&tick_id, &tick)
       .map_err(|e| Error::LedgerError(e))?;
+    brc20s_store
+      .set_deploy_height_to_tickid(context.blockheight, &tick_id)
+      .map_err(|e| Error::LedgerError(e))?;
 
     events.push(Event::DeployTick(DeployTickEvent {
       tick_id,
-      name: earn_tick,
+      name: earn_tick.clone(),
       supply: tick.supply,
       decimal: tick.decimal,
     }));
@@ -394,6 +640,9 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
     only,
     context.blockheight,
     context.blocktime,
+    halving_interval,
+    min_stake,
+    maturity,
   );
 
   brc20s_store
@@ -402,6 +651,9 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
   brc20s_store
     .set_tickid_stake_to_pid(&tick_id, &stake, &pid)
     .map_err(|e| Error::LedgerError(e))?;
+  brc20s_store
+    .set_earn_to_pid(&earn_tick, &pid)
+    .map_err(|e| Error::LedgerError(e))?;
 
   events.push(Event::DeployPool(DeployPoolEvent {
     pid,
@@ -414,6 +666,49 @@ pub fn process_deploy<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreRead
   Ok(events)
 }
 
+// The tick id is a truncated hash of the tick, so the tick described by a deploy can derive
+// the id of another stored tick. Such a deploy must not add a pool to the stored tick.
+fn is_tick_id_collision<N: brc20s::DataStoreReadWrite>(
+  deploy: &Deploy,
+  stored_tick: &TickInfo,
+  from: &ScriptKey,
+  to: &ScriptKey,
+) -> bool {
+  let Some(supply_str) = deploy.total_supply.as_ref() else {
+    return false;
+  };
+  let Some(decimal) = Num::from_str(
+    deploy
+      .decimals
+      .as_deref()
+      .unwrap_or(&MAX_DECIMAL_WIDTH.to_string()),
+  )
+  .and_then(|decimal| decimal.checked_to_u8())
+  .ok() else {
+    return false;
+  };
+  let Some(supply) = convert_amount_with_decimal::<N>(supply_str, decimal)
+    .ok()
+    .and_then(|supply| supply.checked_to_u128().ok())
+  else {
+    return false;
+  };
+  let earn_tick = deploy.get_earn_tick();
+  if stored_tick.name.as_str() == earn_tick.as_str()
+    && stored_tick.supply == supply
+    && stored_tick.decimal == decimal
+  {
+    return false;
+  }
+
+  convert_amount_without_decimal::<N>(supply, decimal)
+    .ok()
+    .and_then(|supply| supply.checked_to_u128().ok())
+    .map_or(false, |supply| {
+      caculate_tick_id(earn_tick.as_str(), supply, decimal, from, to) == stored_tick.tick_id
+    })
+}
+
 fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>(
   context: BlockContext,
   config: version::Config,
@@ -459,6 +754,17 @@ fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite
     brc20_store,
   )?;
 
+  if let Some(min_stake) = pool.min_stake {
+    if amount < Num::from(min_stake) {
+      return Err(Error::BRC20SError(BRC20SError::BelowMinimumStake(
+        stake_msg.amount.clone(),
+        convert_pledged_tick_without_decimal(&stake_tick, min_stake, brc20s_store, brc20_store)?
+          .to_string(),
+        pool_id.as_str().to_string(),
+      )));
+    }
+  }
+
   // check user balance of stake is more than ammount to staked
   let stake_balance =
     get_user_common_balance(&to_script_key, &stake_tick, brc20s_store, brc20_store);
@@ -480,6 +786,21 @@ fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite
     .map_err(|e| Error::LedgerError(e))?
     .map_or(StakeInfo::new(vec![], &stake_tick, 0, 0), |v| v);
 
+  // amounts staked into an `only` pool can't be staked anywhere else. Once single only pool is
+  // active, they also have to be withdrawn from it before another `only` pool of the tick is used.
+  if config.single_only_pool && pool.only {
+    if let Some((staked_pid, _, _)) = user_stakeinfo
+      .pool_stakes
+      .iter()
+      .find(|(pid, only, _)| *only && *pid != pool_id)
+    {
+      return Err(Error::BRC20SError(BRC20SError::OnlyPoolViolation(
+        stake_tick.to_string(),
+        staked_pid.as_str().to_string(),
+      )));
+    }
+  }
+
   // Verifying weather more than max_staked_pool_num at there is a bug which user deposit up to max_staked_pool_num use can not staked any pool.
   // So we disable follow code after update max_staked_pool_num
 
@@ -506,8 +827,8 @@ fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite
   };
   if can_stake_balance.lt(&amount) {
     return Err(Error::BRC20SError(BRC20SError::InsufficientBalance(
-      amount.truncate_to_str().unwrap(),
       can_stake_balance.to_string(),
+      amount.truncate_to_str().unwrap(),
     )));
   }
 
@@ -515,8 +836,9 @@ fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite
   reward::update_pool(&mut pool, context.blockheight, dec)?;
   let mut reward = 0_u128;
   if !is_first_stake {
-    reward = reward::withdraw_user_reward(&mut userinfo, &pool, dec)?;
+    reward = reward::withdraw_user_reward(&mut userinfo, &mut pool, dec)?;
   }
+  reward::update_stake_entry_block(&mut userinfo, &pool, &amount)?;
   // updated user balance of stakedhehe =
   userinfo.staked = has_staked.checked_add(&amount)?.checked_to_u128()?;
   reward::update_user_stake(&mut userinfo, &pool, dec)?;
@@ -565,6 +887,7 @@ fn process_stake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite
     pid: pool_id,
     amt: amount.checked_to_u128()?,
     period_settlement_reward: reward,
+    pool_total_after: pool.staked,
   }))
 }
 
@@ -626,8 +949,7 @@ fn process_unstake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWri
   }
 
   let dec = get_stake_dec(&stake_tick, brc20s_store, brc20_store);
-  reward::update_pool(&mut pool, context.blockheight, dec)?;
-  let reward = reward::withdraw_user_reward(&mut userinfo, &pool, dec)?;
+  let reward = reward::accrue_user_reward(&mut userinfo, &mut pool, context.blockheight, dec)?;
   userinfo.staked = has_staked.checked_sub(&amount)?.checked_to_u128()?;
   pool.staked = Num::from(pool.staked)
     .checked_sub(&amount)?
@@ -638,8 +960,8 @@ fn process_unstake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWri
     .get_user_stakeinfo(&to_script_key, &stake_tick)
     .map_err(|e| Error::LedgerError(e))?
     .ok_or(Error::BRC20SError(BRC20SError::InsufficientBalance(
-      amount.truncate_to_str().unwrap(),
       0_u128.to_string(),
+      amount.truncate_to_str().unwrap(),
     )))?;
 
   //update pool_stakes
@@ -678,6 +1000,7 @@ fn process_unstake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWri
     pid: pool_id,
     amt: amount.checked_to_u128()?,
     period_settlement_reward: reward,
+    pool_total_after: pool.staked,
   }))
 }
 
@@ -732,17 +1055,21 @@ fn process_passive_unstake<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStor
       convert_pledged_tick_without_decimal(&stake_tick, *stake, brc20s_store, brc20_store)?;
     let stake_msg = UnStake::new(pid.as_str(), withdraw_stake.to_string().as_str());
     passive_msg.op = Operation::UnStake(stake_msg.clone());
-    process_unstake(
+    let Event::Withdraw(withdraw) = process_unstake(
       context,
       config.clone(),
       brc20_store,
       brc20s_store,
       &passive_msg,
       stake_msg,
-    )?;
+    )?
+    else {
+      unreachable!("an unstake only emits a withdraw event");
+    };
     events.push(Event::PassiveWithdraw(PassiveWithdrawEvent {
       pid: pid.clone(),
       amt: *stake,
+      pool_total_after: withdraw.pool_total_after,
     }));
   }
 
@@ -792,7 +1119,10 @@ fn process_mint<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>
   // check amount
   let mut amt = Num::from_str(&mint.amount)?;
   if amt.scale() > i64::from(tick_info.decimal) {
-    return Err(Error::BRC20SError(BRC20SError::AmountOverflow(mint.amount)));
+    return Err(Error::BRC20SError(BRC20SError::InvalidAmountPrecision(
+      mint.amount,
+      tick_info.decimal,
+    )));
   }
   let base = BIGDECIMAL_TEN.checked_powu(u64::from(tick_info.decimal))?;
   amt = amt.checked_mul(&base)?;
@@ -817,10 +1147,17 @@ fn process_mint<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWrite>
     user_info.pending_reward -= amt.checked_to_u128()?;
     user_info.minted += amt.checked_to_u128()?;
   } else {
-    reward::update_pool(&mut pool_info, context.blockheight, dec)?;
-    reward::withdraw_user_reward(&mut user_info, &pool_info, dec)?;
+    reward::accrue_user_reward(&mut user_info, &mut pool_info, context.blockheight, dec)?;
     reward::update_user_stake(&mut user_info, &pool_info, dec)?;
     if amt > user_info.pending_reward.into() {
+      // nothing more will be distributed, only the pending reward is left to mint.
+      let remaining = pool_info.dmax.saturating_sub(pool_info.minted);
+      if remaining == 0 {
+        return Err(Error::BRC20SError(BRC20SError::PoolExhausted(
+          pool_id.as_str().to_string(),
+          remaining.to_string(),
+        )));
+      }
       return Err(Error::BRC20SError(BRC20SError::AmountExceedLimit(
         amt.truncate_to_str().unwrap(),
       )));
@@ -992,7 +1329,7 @@ fn process_transfer<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWr
     msg.to.clone().unwrap()
   };
 
-  brc20s_store
+  let mut tick_info = brc20s_store
     .get_tick_info(&transferable.tick_id)
     .map_err(|e| Error::LedgerError(e))?
     .ok_or(BRC20SError::TickNotFound(transferable.tick_id.hex()))?;
@@ -1015,6 +1352,32 @@ fn process_transfer<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWr
   brc20s_store
     .set_token_balance(&from_script_key, &transferable.tick_id, from_balance)
     .map_err(|e| Error::LedgerError(e))?;
+
+  brc20s_store
+    .remove_transferable(&from_script_key, &transferable.tick_id, &msg.inscription_id)
+    .map_err(|e| Error::LedgerError(e))?;
+
+  brc20s_store
+    .remove_inscribe_transfer_inscription(msg.inscription_id)
+    .map_err(|e| Error::LedgerError(e))?;
+
+  // burn the amount out of the circulation if transfer to a burn address.
+  if msg.to_burn_address {
+    tick_info.circulation = Into::<Num>::into(tick_info.circulation)
+      .checked_sub(&amt)?
+      .checked_to_u128()?;
+    tick_info.burned = Into::<Num>::into(tick_info.burned)
+      .checked_add(&amt)?
+      .checked_to_u128()?;
+    brc20s_store
+      .set_tick_info(&transferable.tick_id, &tick_info)
+      .map_err(|e| Error::LedgerError(e))?;
+
+    return Ok(Event::Burn(BurnEvent {
+      tick_id: transferable.tick_id,
+      amt: amt.checked_to_u128()?,
+    }));
+  }
   // redirect receiver to sender if transfer to conibase.
   // let to_script_key = if let None = to_script_key.clone() {
   //   from_script_key.clone()
@@ -1035,14 +1398,6 @@ fn process_transfer<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWr
     .set_token_balance(&to_script_key, &transferable.tick_id, to_balance)
     .map_err(|e| Error::LedgerError(e))?;
 
-  brc20s_store
-    .remove_transferable(&from_script_key, &transferable.tick_id, &msg.inscription_id)
-    .map_err(|e| Error::LedgerError(e))?;
-
-  brc20s_store
-    .remove_inscribe_transfer_inscription(msg.inscription_id)
-    .map_err(|e| Error::LedgerError(e))?;
-
   Ok(Event::Transfer(TransferEvent {
     tick_id: transferable.tick_id,
     amt: amt.checked_to_u128()?,
@@ -1055,6 +1410,7 @@ fn process_transfer<'a, M: brc20::DataStoreReadWrite, N: brc20s::DataStoreReadWr
 mod tests {
   use std::ops::Sub;
 
+  use super::super::operation::mint::MintPool;
   use super::super::*;
   use super::*;
   use crate::index::INSCRIPTION_ID_TO_INSCRIPTION_ENTRY;
@@ -1065,7 +1421,7 @@ mod tests {
   use crate::okx::datastore::brc20s::DataStoreReadOnly;
   use crate::okx::datastore::brc20s::DataStoreReadWrite as BRC20SDataStoreReadWrite;
   use crate::okx::datastore::brc20s::Event::PassiveWithdraw;
-  use crate::okx::datastore::brc20s::PledgedTick;
+  use crate::okx::datastore::brc20s::{Mutation, PledgedTick};
   use crate::okx::protocol::brc20s::params::NATIVE_TOKEN;
   use crate::okx::protocol::brc20s::test::{
     mock_create_brc20s_message, mock_deploy_msg, mock_passive_unstake_msg, mock_stake_msg,
@@ -1141,6 +1497,7 @@ mod tests {
           Err(e) => Err(e),
         }
       }
+      Operation::Batch(_) => unreachable!("batches only run through `execute`"),
     };
 
     match result {
@@ -1173,6 +1530,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_store.insert_token_info(&token, &token_info);
 
@@ -1232,6 +1592,88 @@ mod tests {
     assert_eq!(serde_json::to_string(&user_info).unwrap(), expect_user_info);
   }
 
+  #[test]
+  fn test_execute_before_activation_height() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let deploy = Deploy {
+      pool_type: "pool".to_string(),
+      pool_id: "13395c5283#1f".to_string(),
+      stake: "btc1".to_string(),
+      earn: "ordi1".to_string(),
+      earn_rate: "10".to_string(),
+      distribution_max: "12000000".to_string(),
+      decimals: Some("18".to_string()),
+      total_supply: Some("21000000".to_string()),
+      only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
+    };
+    let addr1 =
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
+    let script = ScriptKey::from_address(addr1.assume_checked());
+    let msg = mock_create_brc20s_message(script.clone(), script, Operation::Deploy(deploy));
+    let result = set_brc20_token_user(&brc20_data_store, "btc1", &msg.from, 200_u128, 18_u8).err();
+    assert_eq!(None, result);
+
+    let tick_id = TickId::from_str("13395c5283").unwrap();
+    let first_brc20s_height = Some(800000);
+    let context = BlockContext {
+      blockheight: 799999,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
+    };
+    let receipts = execute(
+      context,
+      first_brc20s_height,
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert!(receipts.is_empty());
+    assert_eq!(None, brc20s_data_store.get_tick_info(&tick_id).unwrap());
+
+    // brc20s indexing disabled.
+    let receipts = execute(
+      BlockContext {
+        blockheight: 800000,
+        ..context
+      },
+      None,
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert!(receipts.is_empty());
+    assert_eq!(None, brc20s_data_store.get_tick_info(&tick_id).unwrap());
+
+    let receipt = execute(
+      BlockContext {
+        blockheight: 800000,
+        ..context
+      },
+      first_brc20s_height,
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap()
+    .remove(0);
+    assert!(receipt.result.is_ok());
+    assert!(brc20s_data_store.get_tick_info(&tick_id).unwrap().is_some());
+  }
+
   #[test]
   fn test_process_deploy() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -1251,6 +1693,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
 
     let addr1 =
@@ -1303,8 +1748,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"13395c5283#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"13395c5283#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -1352,6 +1797,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
 
@@ -1360,9 +1808,10 @@ mod tests {
     second_deply.stake = "orea".to_string();
     second_deply.distribution_max = "9000000".to_string();
     second_deply.earn_rate = "0.1".to_string();
+    second_deply.halving = Some("210000".to_string());
     let msg = mock_create_brc20s_message(
       script.clone(),
-      script,
+      script.clone(),
       Operation::Deploy(second_deply.clone()),
     );
     let context = BlockContext {
@@ -1370,7 +1819,10 @@ mod tests {
       blocktime: 1687245485,
       network: Network::Bitcoin,
     };
-    let config = version::get_config_by_network(context.network, context.blockheight);
+    let config = version::Config {
+      allow_halving: true,
+      ..version::get_config_by_network(context.network, context.blockheight)
+    };
     let result = process_deploy(
       context,
       config.clone(),
@@ -1395,8 +1847,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":21000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#11"]}"#;
-    let expect_pool_info = r#"{"pid":"13395c5283#11","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000000000000000,"minted":0,"staked":0,"dmax":9000000000000000000000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":20,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":21000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#11"]}"#;
+    let expect_pool_info = r#"{"pid":"13395c5283#11","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000000000000000,"minted":0,"staked":0,"dmax":9000000000000000000000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":20,"deploy_block_time":1687245485,"halving_interval":210000,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
   }
@@ -1420,6 +1872,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -1473,8 +1928,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"13395c5283#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"13395c5283#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
     //add brc20 tokeninfo
@@ -1492,6 +1947,9 @@ mod tests {
         deployed_number: 0,
         deployed_timestamp: 0,
         latest_mint_number: 0,
+        burned_supply: 0,
+        completed_block_height: None,
+        completed_block_time: None,
       };
       brc20_data_store.insert_token_info(&token, &token_info);
 
@@ -1508,6 +1966,9 @@ mod tests {
         deployed_number: 0,
         deployed_timestamp: 0,
         latest_mint_number: 0,
+        burned_supply: 0,
+        completed_block_height: None,
+        completed_block_time: None,
       };
       brc20_data_store.insert_token_info(&token, &token_info);
 
@@ -1524,6 +1985,9 @@ mod tests {
         deployed_number: 0,
         deployed_timestamp: 0,
         latest_mint_number: 0,
+        burned_supply: 0,
+        completed_block_height: None,
+        completed_block_time: None,
       };
       brc20_data_store.insert_token_info(&token, &token_info);
     }
@@ -1600,8 +2064,8 @@ mod tests {
         .unwrap()
         .unwrap();
 
-      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01"]}"#;
-      let expect_pool_info = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":8000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01"]}"#;
+      let expect_pool_info = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":8000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
       assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
     }
@@ -1647,8 +2111,8 @@ mod tests {
         .unwrap()
         .unwrap();
 
-      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20100000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01","13395c5283#02"]}"#;
-      let expect_pool_info = r#"{"pid":"13395c5283#02","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore2"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":100000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20100000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01","13395c5283#02"]}"#;
+      let expect_pool_info = r#"{"pid":"13395c5283#02","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore2"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":100000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
       assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
     }
@@ -1814,8 +2278,8 @@ mod tests {
         .unwrap()
         .unwrap();
 
-      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20200000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01","13395c5283#02","13395c5283#03"]}"#;
-      let expect_pool_info = r#"{"pid":"13395c5283#03","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore3"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":100000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+      let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":20200000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#1f","13395c5283#01","13395c5283#02","13395c5283#03"]}"#;
+      let expect_pool_info = r#"{"pid":"13395c5283#03","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"ore3"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":100000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
       assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
     }
@@ -2033,6 +2497,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -2828,6 +3295,37 @@ mod tests {
         Err(e) => Err(BRC20SError::InternalError(e.to_string())),
       };
       assert_eq!(Err(BRC20SError::InvalidNum("1l".to_string())), result);
+
+      for zero in ["0", "0.000"] {
+        let mut err_erate = deploy.clone();
+        err_erate.earn_rate = zero.to_string();
+        let msg = mock_create_brc20s_message(
+          script.clone(),
+          script.clone(),
+          Operation::Deploy(err_erate.clone()),
+        );
+        let context = BlockContext {
+          blockheight: 0,
+          blocktime: 1687245485,
+          network: Network::Bitcoin,
+        };
+        let config = version::get_config_by_network(context.network, context.blockheight);
+        let result = process_deploy(
+          context,
+          config,
+          &brc20_data_store,
+          &brc20s_data_store,
+          &msg,
+          err_erate,
+        );
+
+        let result: Result<Vec<Event>, BRC20SError> = match result {
+          Ok(event) => Ok(event),
+          Err(Error::BRC20SError(e)) => Err(e),
+          Err(e) => Err(BRC20SError::InternalError(e.to_string())),
+        };
+        assert_eq!(Err(BRC20SError::InvalidErate(zero.to_string())), result);
+      }
     }
 
     //err dmax
@@ -3008,6 +3506,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -3029,6 +3530,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -3083,8 +3587,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"burned":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -3137,8 +3641,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20}"#;
-    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_poolinfo, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_stakeinfo, serde_json::to_string(&stakeinfo).unwrap());
@@ -3193,8 +3697,8 @@ mod tests {
         .unwrap();
       let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
       let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,2000000000]],"max_share":0,"total_only":2000000000}"#;
-      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":2000000000,"minted":0,"pending_reward":1000000,"reward_debt":2000000,"latest_updated_block":30}"#;
-      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":2000000000,"minted":0,"pending_reward":1000000,"reward_debt":2000000,"latest_updated_block":30,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       println!(
         "expect_poolinfo:{}",
         serde_json::to_string(&pool_info).unwrap()
@@ -3403,8 +3907,8 @@ mod tests {
         .unwrap();
       let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
       let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,2000000000]],"max_share":0,"total_only":2000000000}"#;
-      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":2000000000,"minted":0,"pending_reward":1000000,"reward_debt":2000000,"latest_updated_block":30}"#;
-      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":2000000000,"minted":0,"pending_reward":1000000,"reward_debt":2000000,"latest_updated_block":30,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       println!(
         "expect_poolinfo:{}",
         serde_json::to_string(&pool_info).unwrap()
@@ -3435,6 +3939,9 @@ mod tests {
         decimals: Some("2".to_string()),
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       };
       let addr1 =
         Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
@@ -3623,7 +4130,8 @@ mod tests {
         Ok(Event::Deposit(DepositEvent {
           pid,
           amt: 1000,
-          period_settlement_reward: 0
+          period_settlement_reward: 0,
+          pool_total_after: 1000,
         })),
         result
       );
@@ -3648,6 +4156,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -3669,6 +4180,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -3723,8 +4237,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"burned":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -3777,8 +4291,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20}"#;
-    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_poolinfo, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_stakeinfo, serde_json::to_string(&stakeinfo).unwrap());
@@ -3834,8 +4348,8 @@ mod tests {
       let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
       let expect_stakeinfo =
         r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[],"max_share":0,"total_only":0}"#;
-      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":0,"minted":0,"pending_reward":1000000,"reward_debt":0,"latest_updated_block":30}"#;
-      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":0,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":0,"minted":0,"pending_reward":1000000,"reward_debt":0,"latest_updated_block":30,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":0,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       println!(
         "expect_poolinfo:{}",
         serde_json::to_string(&pool_info).unwrap()
@@ -3873,6 +4387,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -3894,6 +4411,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -3948,8 +4468,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["fea607ea9e#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"burned":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["fea607ea9e#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -4002,8 +4522,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_poolinfo, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_stakeinfo, serde_json::to_string(&stakeinfo).unwrap());
@@ -4159,6 +4679,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -4180,6 +4703,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -4234,8 +4760,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"burned":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":10,"deploy_block_time":1687245485,"latest_mint_block":10,"pids":["fea607ea9e#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":10,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -4288,8 +4814,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20}"#;
-    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_poolinfo, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_stakeinfo, serde_json::to_string(&stakeinfo).unwrap());
@@ -4354,8 +4880,8 @@ mod tests {
       let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
       let expect_stakeinfo =
         r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[],"max_share":0,"total_only":0}"#;
-      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":0,"minted":0,"pending_reward":1000000,"reward_debt":0,"latest_updated_block":30}"#;
-      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":0,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":0,"minted":0,"pending_reward":1000000,"reward_debt":0,"latest_updated_block":30,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":1000000,"staked":0,"dmax":1200000000,"acc_reward_per_share":"1000000000000000","last_update_block":30,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       println!(
         "expect_poolinfo:{}",
         serde_json::to_string(&pool_info).unwrap()
@@ -4393,6 +4919,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let addr1 =
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
@@ -4414,6 +4943,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -4468,8 +5000,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["fea607ea9e#1f"]}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"fea607ea9e","name":"ordi","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":1200000000,"decimal":2,"circulation":0,"burned":0,"supply":2100000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["fea607ea9e#1f"]}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":0,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -4522,8 +5054,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_poolinfo, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_stakeinfo, serde_json::to_string(&stakeinfo).unwrap());
@@ -4665,6 +5197,67 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_deploy_indexes_pools_by_earn() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (deploy1, msg1) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, false, addr, addr,
+    );
+    let (deploy2, msg2) = mock_deploy_msg(
+      "pool", "02", "orea", "ordi1", "10", "9000000", "21000000", 18, false, addr, addr,
+    );
+    let (deploy3, msg3) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi2", "10", "12000000", "21000000", 18, false, addr, addr,
+    );
+    for tick in ["btc1", "orea"] {
+      let result = set_brc20_token_user(&brc20_data_store, tick, &msg1.from, 200_u128, 18_u8).err();
+      assert_eq!(None, result);
+    }
+    for msg in [&msg1, &msg2, &msg3] {
+      let result = execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        msg,
+        0,
+        version::zebra(),
+      );
+      assert_eq!(None, result.err());
+    }
+
+    assert_eq!(
+      brc20s_data_store
+        .get_earn_to_all_pid(&Tick::from_str("ordi1").unwrap(), 0, None)
+        .unwrap(),
+      (vec![deploy1.get_pool_id(), deploy2.get_pool_id()], 2)
+    );
+    // earn tick names are case insensitive.
+    assert_eq!(
+      brc20s_data_store
+        .get_earn_to_all_pid(&Tick::from_str("ORDI1").unwrap(), 1, Some(1))
+        .unwrap(),
+      (vec![deploy2.get_pool_id()], 2)
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_earn_to_all_pid(&Tick::from_str("ordi2").unwrap(), 0, None)
+        .unwrap(),
+      (vec![deploy3.get_pool_id()], 1)
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_earn_to_all_pid(&Tick::from_str("ordi3").unwrap(), 0, None)
+        .unwrap(),
+      (vec![], 0)
+    );
+  }
+
   #[test]
   fn test_process_deploy_most() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -4697,8 +5290,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#01"]}"#;
-    let expect_pool_info = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":12000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#01"]}"#;
+    let expect_pool_info = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
 
@@ -4826,8 +5419,8 @@ mod tests {
       .unwrap()
       .unwrap();
 
-    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":21000000000000000000000000,"decimal":18,"circulation":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#01","13395c5283#02"]}"#;
-    let expect_pool_info = r#"{"pid":"13395c5283#02","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000000000000000,"minted":0,"staked":0,"dmax":9000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_tick_info = r#"{"tick_id":"13395c5283","name":"ordi1","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","allocated":21000000000000000000000000,"decimal":18,"circulation":0,"burned":0,"supply":21000000000000000000000000,"deployer":{"Address":"bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e"},"deploy_block":0,"deploy_block_time":1687245485,"latest_mint_block":0,"pids":["13395c5283#01","13395c5283#02"]}"#;
+    let expect_pool_info = r#"{"pid":"13395c5283#02","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000000000000000,"minted":0,"staked":0,"dmax":9000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(expect_tick_info, serde_json::to_string(&tick_info).unwrap());
   }
@@ -4863,6 +5456,9 @@ mod tests {
       deployed_number: 0,
       deployed_timestamp: 0,
       latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
     };
     let _ = brc20_data_store.insert_token_info(&token, &token_info);
     let balance = BRC20Balance {
@@ -4883,6 +5479,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let msg = mock_create_brc20s_message(
       script.clone(),
@@ -4971,8 +5570,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stake_info = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20,"stake_entry_block":0}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":1000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(
@@ -4986,6 +5585,7 @@ mod tests {
       tick: "ordi".to_string(),
       pool_id: pid.as_str().to_string(),
       amount: "1.1".to_string(),
+      pools: None,
     };
 
     let msg = mock_create_brc20s_message(
@@ -5097,7 +5697,10 @@ mod tests {
         println!("success:{}", serde_json::to_string_pretty(&event).unwrap());
       }
       Err(Error::BRC20SError(e)) => {
-        assert_eq!("amount overflow: 11.0111111111", e.to_string())
+        assert_eq!(
+          "amount 11.0111111111 has more than 2 decimal places",
+          e.to_string()
+        )
       }
       _ => {
         panic!("")
@@ -5185,7 +5788,7 @@ mod tests {
         println!("success:{}", serde_json::to_string_pretty(&event).unwrap());
       }
       Err(Error::BRC20SError(e)) => {
-        assert_eq!("invalid number: 0", e.to_string())
+        assert_eq!("zero amount not allowed", e.to_string())
       }
       _ => {
         panic!("")
@@ -5248,47 +5851,462 @@ mod tests {
   }
 
   #[test]
-  fn test_transfer() {
+  fn test_transfer_spent_twice() {
     let db_file = NamedTempFile::new().unwrap();
     let db = Database::create(db_file.path()).unwrap();
     let wtx = db.begin_write().unwrap();
     let brc20_data_store = brc20_db::DataStore::new(&wtx);
     let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
 
-    // deploy brc20
-    let script = ScriptKey::from_address(
+    let from = ScriptKey::from_address(
       Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
         .unwrap()
         .assume_checked(),
     );
-
-    let script1 = ScriptKey::from_address(
+    let to = ScriptKey::from_address(
       Address::from_str("bc1q9cv6smq87myk2ujs352c3lulwzvdfujd5059ny")
         .unwrap()
         .assume_checked(),
     );
 
-    let inscription_id =
-      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
-        .unwrap();
+    // an inscribed transfer of 60 out of a balance of 100.
+    let tick_id = TickId::from_str("13395c5283").unwrap();
+    let tick = Tick::from_str("ordi1").unwrap();
+    let msg = mock_create_brc20s_message(
+      from.clone(),
+      to.clone(),
+      Operation::Transfer(Transfer {
+        tick_id: tick_id.hex(),
+        tick: tick.as_str().to_string(),
+        amount: "60".to_string(),
+      }),
+    );
+    brc20s_data_store
+      .set_tick_info(
+        &tick_id,
+        &TickInfo::new(
+          tick_id,
+          &tick,
+          &msg.inscription_id,
+          1000,
+          0,
+          100,
+          1000,
+          &from,
+          1,
+          0,
+          1,
+          Vec::new(),
+        ),
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_token_balance(
+        &from,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 100,
+          transferable_balance: 60,
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_transferable_assets(
+        &from,
+        &tick_id,
+        &msg.inscription_id,
+        &TransferableAsset {
+          inscription_id: msg.inscription_id,
+          amount: 60,
+          tick_id,
+          owner: from.clone(),
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .insert_inscribe_transfer_inscription(
+        msg.inscription_id,
+        TransferInfo {
+          tick_id,
+          tick_name: tick,
+          amt: 60,
+        },
+      )
+      .unwrap();
 
-    let token = brc20::Tick::from_str("orea".to_string().as_str()).unwrap();
-    let token_info = TokenInfo {
-      tick: token.clone(),
-      inscription_id,
-      inscription_number: 0,
-      supply: 21000000000_u128,
-      minted: 2000000000_u128,
-      limit_per_mint: 0,
-      decimal: 3,
-      deploy_by: script.clone(),
-      deployed_number: 0,
-      deployed_timestamp: 0,
-      latest_mint_number: 0,
+    let context = BlockContext {
+      blockheight: 10,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
     };
-    let _ = brc20_data_store.insert_token_info(&token, &token_info);
-    let balance = BRC20Balance {
-      tick: token.clone(),
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert!(receipts[0].result.is_ok());
+
+    // the replayed spend is a no-op without a receipt.
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert!(receipts.is_empty());
+
+    let from_balance = brc20s_data_store
+      .get_balance(&from, &tick_id)
+      .unwrap()
+      .unwrap();
+    assert_eq!(from_balance.overall_balance, 40);
+    assert_eq!(from_balance.transferable_balance, 0);
+    let to_balance = brc20s_data_store
+      .get_balance(&to, &tick_id)
+      .unwrap()
+      .unwrap();
+    assert_eq!(to_balance.overall_balance, 60);
+    assert_eq!(
+      brc20s_data_store
+        .get_transaction_receipts(&msg.txid)
+        .unwrap()
+        .len(),
+      1
+    );
+  }
+
+  #[test]
+  fn test_transfer_without_new_satpoint() {
+    let db_file = NamedTempFile::new().unwrap();
+    let db = Database::create(db_file.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let from = ScriptKey::from_address(
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
+        .unwrap()
+        .assume_checked(),
+    );
+    let to = ScriptKey::from_address(
+      Address::from_str("bc1q9cv6smq87myk2ujs352c3lulwzvdfujd5059ny")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    // an inscribed transfer of 60 out of a balance of 100, spent by an unconfirmed transaction.
+    let tick_id = TickId::from_str("13395c5283").unwrap();
+    let tick = Tick::from_str("ordi1").unwrap();
+    let mut msg = mock_create_brc20s_message(
+      from.clone(),
+      to.clone(),
+      Operation::Transfer(Transfer {
+        tick_id: tick_id.hex(),
+        tick: tick.as_str().to_string(),
+        amount: "60".to_string(),
+      }),
+    );
+    msg.new_satpoint = None;
+    msg.to = None;
+    brc20s_data_store
+      .set_tick_info(
+        &tick_id,
+        &TickInfo::new(
+          tick_id,
+          &tick,
+          &msg.inscription_id,
+          1000,
+          0,
+          100,
+          1000,
+          &from,
+          1,
+          0,
+          1,
+          Vec::new(),
+        ),
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_token_balance(
+        &from,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 100,
+          transferable_balance: 60,
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_transferable_assets(
+        &from,
+        &tick_id,
+        &msg.inscription_id,
+        &TransferableAsset {
+          inscription_id: msg.inscription_id,
+          amount: 60,
+          tick_id,
+          owner: from.clone(),
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .insert_inscribe_transfer_inscription(
+        msg.inscription_id,
+        TransferInfo {
+          tick_id,
+          tick_name: tick,
+          amt: 60,
+        },
+      )
+      .unwrap();
+
+    let context = BlockContext {
+      blockheight: 10,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
+    };
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert!(receipts.is_empty());
+
+    // nothing moved and the transfer is still spendable.
+    assert_eq!(
+      brc20s_data_store.get_balance(&from, &tick_id).unwrap(),
+      Some(Balance {
+        tick_id,
+        overall_balance: 100,
+        transferable_balance: 60,
+      })
+    );
+    assert_eq!(brc20s_data_store.get_balance(&to, &tick_id).unwrap(), None);
+    assert!(brc20s_data_store
+      .get_transferable_by_id(&from, &msg.inscription_id)
+      .unwrap()
+      .is_some());
+    assert!(brc20s_data_store
+      .get_inscribe_transfer_inscription(msg.inscription_id)
+      .unwrap()
+      .is_some());
+    assert!(brc20s_data_store
+      .get_transaction_receipts(&msg.txid)
+      .unwrap()
+      .is_empty());
+
+    // operations which don't need a destination are executed.
+    let mut mint = mock_create_brc20s_message(
+      from.clone(),
+      from.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: "13395c5283#01".to_string(),
+        amount: "5".to_string(),
+        pools: None,
+      }),
+    );
+    mint.new_satpoint = None;
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &mint,
+    )
+    .unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].new_satpoint.outpoint, OutPoint::null());
+  }
+
+  #[test]
+  fn test_transfer_to_burn_address() {
+    let db_file = NamedTempFile::new().unwrap();
+    let db = Database::create(db_file.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let from = ScriptKey::from_address(
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
+        .unwrap()
+        .assume_checked(),
+    );
+    let burn = ScriptKey::from_address(
+      Address::from_str("1BitcoinEaterAddressDontSendf59kuE")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    // an inscribed transfer of 60 out of a minted balance of 100, sent to a burn address.
+    let tick_id = TickId::from_str("13395c5283").unwrap();
+    let tick = Tick::from_str("ordi1").unwrap();
+    let mut msg = mock_create_brc20s_message(
+      from.clone(),
+      burn.clone(),
+      Operation::Transfer(Transfer {
+        tick_id: tick_id.hex(),
+        tick: tick.as_str().to_string(),
+        amount: "60".to_string(),
+      }),
+    );
+    msg.to_burn_address = true;
+    brc20s_data_store
+      .set_tick_info(
+        &tick_id,
+        &TickInfo::new(
+          tick_id,
+          &tick,
+          &msg.inscription_id,
+          1000,
+          0,
+          100,
+          1000,
+          &from,
+          1,
+          0,
+          1,
+          Vec::new(),
+        ),
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_token_balance(
+        &from,
+        &tick_id,
+        Balance {
+          tick_id,
+          overall_balance: 100,
+          transferable_balance: 60,
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .set_transferable_assets(
+        &from,
+        &tick_id,
+        &msg.inscription_id,
+        &TransferableAsset {
+          inscription_id: msg.inscription_id,
+          amount: 60,
+          tick_id,
+          owner: from.clone(),
+        },
+      )
+      .unwrap();
+    brc20s_data_store
+      .insert_inscribe_transfer_inscription(
+        msg.inscription_id,
+        TransferInfo {
+          tick_id,
+          tick_name: tick,
+          amt: 60,
+        },
+      )
+      .unwrap();
+
+    let context = BlockContext {
+      blockheight: 10,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
+    };
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(
+      receipts[0].result,
+      Ok(vec![Event::Burn(BurnEvent { tick_id, amt: 60 })])
+    );
+
+    // the amount leaves the sender and the circulation, the burn address is not credited.
+    assert_eq!(
+      brc20s_data_store.get_balance(&from, &tick_id).unwrap(),
+      Some(Balance {
+        tick_id,
+        overall_balance: 40,
+        transferable_balance: 0,
+      })
+    );
+    assert_eq!(
+      brc20s_data_store.get_balance(&burn, &tick_id).unwrap(),
+      None
+    );
+    assert!(brc20s_data_store
+      .get_transferable_by_id(&from, &msg.inscription_id)
+      .unwrap()
+      .is_none());
+    let tick_info = brc20s_data_store.get_tick_info(&tick_id).unwrap().unwrap();
+    assert_eq!((tick_info.circulation, tick_info.burned), (40, 60));
+  }
+
+  #[test]
+  fn test_transfer() {
+    let db_file = NamedTempFile::new().unwrap();
+    let db = Database::create(db_file.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    // deploy brc20
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    let script1 = ScriptKey::from_address(
+      Address::from_str("bc1q9cv6smq87myk2ujs352c3lulwzvdfujd5059ny")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    let inscription_id =
+      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+
+    let token = brc20::Tick::from_str("orea".to_string().as_str()).unwrap();
+    let token_info = TokenInfo {
+      tick: token.clone(),
+      inscription_id,
+      inscription_number: 0,
+      supply: 21000000000_u128,
+      minted: 2000000000_u128,
+      limit_per_mint: 0,
+      decimal: 3,
+      deploy_by: script.clone(),
+      deployed_number: 0,
+      deployed_timestamp: 0,
+      latest_mint_number: 0,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
+    };
+    let _ = brc20_data_store.insert_token_info(&token, &token_info);
+    let balance = BRC20Balance {
+      tick: token.clone(),
       overall_balance: 2000000000_u128,
       transferable_balance: 0_u128,
     };
@@ -5306,6 +6324,9 @@ mod tests {
       decimals: Some("2".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     let msg = mock_create_brc20s_message(
       script.clone(),
@@ -5411,8 +6432,8 @@ mod tests {
       .unwrap();
     let pool_info = brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap();
     let expect_stake_info = r#"{"stake":{"BRC20Tick":"orea"},"pool_stakes":[["fea607ea9e#1f",true,1000000000]],"max_share":0,"total_only":1000000000}"#;
-    let expect_user_info = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20}"#;
-    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485}"#;
+    let expect_user_info = r#"{"pid":"fea607ea9e#1f","staked":1000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":20,"stake_entry_block":0}"#;
+    let expect_pool_info = r#"{"pid":"fea607ea9e#1f","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"orea"},"erate":100000,"minted":0,"staked":2000000000,"dmax":1200000000,"acc_reward_per_share":"0","last_update_block":20,"only":true,"deploy_block":10,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
 
     assert_eq!(expect_pool_info, serde_json::to_string(&pool_info).unwrap());
     assert_eq!(
@@ -5426,6 +6447,7 @@ mod tests {
       tick: "ordi".to_string(),
       pool_id: pid.as_str().to_string(),
       amount: "10.1".to_string(),
+      pools: None,
     };
 
     let msg = mock_create_brc20s_message(
@@ -5594,7 +6616,10 @@ mod tests {
       error_transfer_msg,
     ) {
       Err(Error::BRC20SError(e)) => {
-        assert_eq!("insufficient balance: 1010 1020", e.to_string())
+        assert_eq!(
+          "insufficient balance: available 1010, requested 1020",
+          e.to_string()
+        )
       }
       _ => {
         panic!("")
@@ -5711,7 +6736,7 @@ mod tests {
 
     // inscribe to coinbase, ok
     let mut error_msg = msg.clone();
-    error_msg.new_satpoint.outpoint.txid =
+    error_msg.new_satpoint.as_mut().unwrap().outpoint.txid =
       Txid::from_str("2111111111111111111111111111111111111111111111111111111111111111").unwrap();
     match process_transfer(
       context,
@@ -5740,7 +6765,7 @@ mod tests {
 
     // inscribe to coinbase, second
     let mut error_msg = msg.clone();
-    error_msg.new_satpoint.outpoint.txid =
+    error_msg.new_satpoint.as_mut().unwrap().outpoint.txid =
       Txid::from_str("2111111111111111111111111111111111111111111111111111111111111111").unwrap();
     match process_transfer(
       context,
@@ -6065,8 +7090,8 @@ mod tests {
       );
       assert_eq!(
         Err(BRC20SError::InsufficientBalance(
-          "300000000000000000000".to_string(),
           "200000000000000000000".to_string(),
+          "300000000000000000000".to_string(),
         )),
         result
       );
@@ -6083,8 +7108,8 @@ mod tests {
     assert_eq!(None, result.err());
 
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,50000000000000000000]],"max_share":0,"total_only":50000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only1,
@@ -6106,8 +7131,8 @@ mod tests {
     assert_eq!(None, result.err());
 
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,50000000000000000000],["fb641f54a2#01",false,49000000000000000000]],"max_share":49000000000000000000,"total_only":50000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":49000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":49000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -6139,8 +7164,8 @@ mod tests {
       assert_eq!(None, result.err());
 
       let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,50000000000000000000],["fb641f54a2#01",false,49000000000000000000],["7737ed558e#01",true,49000000000000000000],["b25c7ef626#01",false,50000000000000000000]],"max_share":50000000000000000000,"total_only":99000000000000000000}"#;
-      let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-      let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       assert_stake_info(
         &brc20s_data_store,
         pid_share2,
@@ -6162,8 +7187,8 @@ mod tests {
     );
     assert_eq!(
       Some(BRC20SError::InsufficientBalance(
-        "51100000000000000000".to_string(),
         "51000000000000000000".to_string(),
+        "51100000000000000000".to_string(),
       )),
       result.err()
     );
@@ -6178,8 +7203,8 @@ mod tests {
     );
     assert_eq!(
       Some(BRC20SError::InsufficientBalance(
-        "102000000000000000000".to_string(),
         "51000000000000000000".to_string(),
+        "102000000000000000000".to_string(),
       )),
       result.err()
     );
@@ -6194,8 +7219,8 @@ mod tests {
     );
     assert_eq!(None, result.err());
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,50000000000000000000],["fb641f54a2#01",false,49000000000000000000],["b25c7ef626#01",false,50000000000000000000],["7737ed558e#01",true,99000000000000000000]],"max_share":50000000000000000000,"total_only":149000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"7737ed558e#01","staked":99000000000000000000,"minted":0,"pending_reward":9999999999999999976,"reward_debt":20204081632653061176,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"7737ed558e#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":99000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"204081632653061224","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"7737ed558e#01","staked":99000000000000000000,"minted":0,"pending_reward":9999999999999999976,"reward_debt":20204081632653061176,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"7737ed558e#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":99000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"204081632653061224","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only2,
@@ -6216,8 +7241,8 @@ mod tests {
     );
     assert_eq!(None, result.err());
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,50000000000000000000],["b25c7ef626#01",false,50000000000000000000],["7737ed558e#01",true,99000000000000000000],["fb641f54a2#01",false,51000000000000000000]],"max_share":51000000000000000000,"total_only":149000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":51000000000000000000,"minted":0,"pending_reward":9999999999999999976,"reward_debt":10408163265306122424,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":51000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"204081632653061224","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":51000000000000000000,"minted":0,"pending_reward":9999999999999999976,"reward_debt":10408163265306122424,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":51000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"204081632653061224","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -6384,8 +7409,8 @@ mod tests {
       version::zebra(),
     );
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000]],"max_share":0,"total_only":49000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":49000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9800000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":49000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9800000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only1,
@@ -6416,8 +7441,8 @@ mod tests {
       version::zebra(),
     );
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000],["fb641f54a2#01",false,49000000000000000000]],"max_share":49000000000000000000,"total_only":49000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":49000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9800000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":49000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9800000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":49000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -6450,8 +7475,8 @@ mod tests {
       assert_eq!(None, result.err());
 
       let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000],["fb641f54a2#01",false,49000000000000000000],["7737ed558e#01",true,50000000000000000000],["b25c7ef626#01",false,50000000000000000000]],"max_share":50000000000000000000,"total_only":99000000000000000000}"#;
-      let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
-      let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+      let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
+      let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
       assert_stake_info(
         &brc20s_data_store,
         pid_share2,
@@ -6472,8 +7497,8 @@ mod tests {
       version::zebra(),
     );
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000],["fb641f54a2#01",false,49000000000000000000],["7737ed558e#01",true,48000000000000000000],["b25c7ef626#01",false,50000000000000000000]],"max_share":50000000000000000000,"total_only":97000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"7737ed558e#01","staked":48000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9600000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"7737ed558e#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":48000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"7737ed558e#01","staked":48000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9600000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"7737ed558e#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":48000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only2,
@@ -6493,8 +7518,8 @@ mod tests {
       version::zebra(),
     );
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000],["fb641f54a2#01",false,49000000000000000000],["7737ed558e#01",true,48000000000000000000],["b25c7ef626#01",false,48000000000000000000]],"max_share":49000000000000000000,"total_only":97000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":48000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9600000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":48000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"b25c7ef626#01","staked":48000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":9600000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"b25c7ef626#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":48000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share2,
@@ -6515,8 +7540,8 @@ mod tests {
       version::zebra(),
     );
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,49000000000000000000],["7737ed558e#01",true,48000000000000000000],["b25c7ef626#01",false,48000000000000000000]],"max_share":48000000000000000000,"total_only":97000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":0,"minted":0,"pending_reward":19999999999999999976,"reward_debt":0,"latest_updated_block":2}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"404081632653061224","last_update_block":2,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":0,"minted":0,"pending_reward":19999999999999999976,"reward_debt":0,"latest_updated_block":2,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"404081632653061224","last_update_block":2,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -6663,14 +7688,15 @@ mod tests {
     assert_eq!(
       Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
         pid: Pid::from_str(pid_only1).unwrap(),
-        amt: 40000000000000000000
+        amt: 40000000000000000000,
+        pool_total_after: 10000000000000000000,
       })]),
       result
     );
 
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["13395c5283#01",true,10000000000000000000]],"max_share":0,"total_only":10000000000000000000}"#;
-    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":10000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":2000000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":10000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":10000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":2000000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":10000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only1,
@@ -6695,15 +7721,16 @@ mod tests {
     assert_eq!(
       Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
         pid: Pid::from_str(pid_only1).unwrap(),
-        amt: 10000000000000000000
+        amt: 10000000000000000000,
+        pool_total_after: 0,
       })]),
       result
     );
 
     let expect_stakeinfo =
       r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[],"max_share":0,"total_only":0}"#;
-    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":0,"minted":0,"pending_reward":20000000000000000000,"reward_debt":0,"latest_updated_block":2}"#;
-    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"1200000000000000000","last_update_block":2,"only":true,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"13395c5283#01","staked":0,"minted":0,"pending_reward":20000000000000000000,"reward_debt":0,"latest_updated_block":2,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"13395c5283#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"1200000000000000000","last_update_block":2,"only":true,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_only1,
@@ -6758,14 +7785,15 @@ mod tests {
     assert_eq!(
       Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
         pid: Pid::from_str(pid_share1).unwrap(),
-        amt: 40000000000000000000
+        amt: 40000000000000000000,
+        pool_total_after: 10000000000000000000,
       })]),
       result
     );
 
     let expect_stakeinfo = r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[["fb641f54a2#01",false,10000000000000000000]],"max_share":10000000000000000000,"total_only":0}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":10000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":2000000000000000000,"latest_updated_block":1}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":10000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":10000000000000000000,"minted":0,"pending_reward":10000000000000000000,"reward_debt":2000000000000000000,"latest_updated_block":1,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":10000000000000000000,"staked":10000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"200000000000000000","last_update_block":1,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -6790,15 +7818,16 @@ mod tests {
     assert_eq!(
       Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
         pid: Pid::from_str(pid_share1).unwrap(),
-        amt: 10000000000000000000
+        amt: 10000000000000000000,
+        pool_total_after: 0,
       })]),
       result
     );
 
     let expect_stakeinfo =
       r#"{"stake":{"BRC20Tick":"btc1"},"pool_stakes":[],"max_share":0,"total_only":0}"#;
-    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":0,"minted":0,"pending_reward":20000000000000000000,"reward_debt":0,"latest_updated_block":2}"#;
-    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"1200000000000000000","last_update_block":2,"only":false,"deploy_block":0,"deploy_block_time":1687245485}"#;
+    let expect_userinfo = r#"{"pid":"fb641f54a2#01","staked":0,"minted":0,"pending_reward":20000000000000000000,"reward_debt":0,"latest_updated_block":2,"stake_entry_block":0}"#;
+    let expect_poolinfo = r#"{"pid":"fb641f54a2#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{"BRC20Tick":"btc1"},"erate":10000000000000000000,"minted":20000000000000000000,"staked":0,"dmax":12000000000000000000000000,"acc_reward_per_share":"1200000000000000000","last_update_block":2,"only":false,"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}"#;
     assert_stake_info(
       &brc20s_data_store,
       pid_share1,
@@ -7007,9 +8036,9 @@ mod tests {
       total_only
     );
     let expect_stakeinfo = temp.as_str();
-    let expect_userinfo = r#"{"pid":"a2c6a6a614#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
+    let expect_userinfo = r#"{"pid":"a2c6a6a614#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
     let temp = format!(
-      r##"{{"pid":"a2c6a6a614#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485}}"##,
+      r##"{{"pid":"a2c6a6a614#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}}"##,
       pool_only1.clone()
     );
     let expect_poolinfo = temp.as_str();
@@ -7023,9 +8052,9 @@ mod tests {
       expect_userinfo,
     );
 
-    let expect_userinfo = r#"{"pid":"83050baa2b#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
+    let expect_userinfo = r#"{"pid":"83050baa2b#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
     let temp = format!(
-      r##"{{"pid":"83050baa2b#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485}}"##,
+      r##"{{"pid":"83050baa2b#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}}"##,
       pool_only2.clone()
     );
     let expect_poolinfo = temp.as_str();
@@ -7039,9 +8068,9 @@ mod tests {
       expect_userinfo,
     );
 
-    let expect_userinfo = r#"{"pid":"934a4f7aff#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
+    let expect_userinfo = r#"{"pid":"934a4f7aff#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
     let temp = format!(
-      r##"{{"pid":"934a4f7aff#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485}}"##,
+      r##"{{"pid":"934a4f7aff#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}}"##,
       pool_only3.clone()
     );
     let expect_poolinfo = temp.as_str();
@@ -7055,9 +8084,9 @@ mod tests {
       expect_userinfo,
     );
 
-    let expect_userinfo = r#"{"pid":"92c3f0f4ab#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0}"#;
+    let expect_userinfo = r#"{"pid":"92c3f0f4ab#01","staked":50000000000000000000,"minted":0,"pending_reward":0,"reward_debt":0,"latest_updated_block":0,"stake_entry_block":0}"#;
     let temp = format!(
-      r##"{{"pid":"92c3f0f4ab#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485}}"##,
+      r##"{{"pid":"92c3f0f4ab#01","ptype":"Pool","inscription_id":"1111111111111111111111111111111111111111111111111111111111111111i1","stake":{{"BRC20Tick":"btc1"}},"erate":10000000000000000000,"minted":0,"staked":50000000000000000000,"dmax":12000000000000000000000000,"acc_reward_per_share":"0","last_update_block":0,"only":{},"deploy_block":0,"deploy_block_time":1687245485,"halving_interval":null,"min_stake":null,"maturity":null}}"##,
       pool_only4.clone()
     );
     let expect_poolinfo = temp.as_str();
@@ -7074,6 +8103,119 @@ mod tests {
     Ok((results, from_script))
   }
 
+  #[test]
+  fn test_passive_unstake_receipt() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let context = BlockContext {
+      blockheight: 1,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
+    };
+
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, false, addr, addr,
+    );
+    let pid_share1 = deploy.pool_id.clone();
+    set_brc20_token_user(&brc20_data_store, "btc1", &msg.from, 100_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    )
+    .unwrap();
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi2", "10", "12000000", "21000000", 18, false, addr, addr,
+    );
+    let pid_share2 = deploy.pool_id.clone();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    )
+    .unwrap();
+
+    for pid in [&pid_share1, &pid_share2] {
+      let (_, msg) = mock_stake_msg(pid, "80", addr, addr);
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        0,
+        version::zebra(),
+      )
+      .unwrap();
+    }
+
+    // balance still covers the staked amount, no receipt is saved
+    let (_, msg) = mock_passive_unstake_msg("btc1", "20", addr, addr);
+    let receipts = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+    assert!(receipts.is_empty());
+    assert!(brc20s_data_store
+      .get_transaction_receipts(&msg.txid)
+      .unwrap()
+      .is_empty());
+
+    // simulate a transfer that drops the staked balance below the staked amount
+    set_brc20_token_user(&brc20_data_store, "btc1", &msg.from, 30_u128, 18_u8).unwrap();
+    let (_, msg) = mock_passive_unstake_msg("btc1", "70", addr, addr);
+    let receipt = execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap()
+    .remove(0);
+
+    let expect_events = vec![
+      PassiveWithdraw(PassiveWithdrawEvent {
+        pid: Pid::from_str(&pid_share2).unwrap(),
+        amt: 50000000000000000000,
+        pool_total_after: 30000000000000000000,
+      }),
+      PassiveWithdraw(PassiveWithdrawEvent {
+        pid: Pid::from_str(&pid_share1).unwrap(),
+        amt: 50000000000000000000,
+        pool_total_after: 30000000000000000000,
+      }),
+    ];
+    assert_eq!(receipt.op, brc20s::OperationType::PassiveUnStake);
+    // the receipt carries the transfer inscription that triggered the passive withdraw.
+    assert_eq!(receipt.inscription_id, msg.inscription_id);
+    assert_eq!(receipt.old_satpoint, msg.old_satpoint);
+    assert_eq!(Some(receipt.new_satpoint), msg.new_satpoint);
+    assert_eq!(receipt.from, msg.from);
+    assert_eq!(receipt.to, msg.from);
+    assert_eq!(receipt.result, Ok(expect_events));
+    assert_eq!(
+      brc20s_data_store
+        .get_transaction_receipts(&msg.txid)
+        .unwrap(),
+      vec![receipt]
+    );
+  }
+
   #[test]
   fn test_process_passive_unstake_most() {
     // 1-only(50) 2-share(50) 3-only(50) 4-share(50) transfer 50 no passwithdraw
@@ -7163,11 +8305,13 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7218,15 +8362,18 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7277,19 +8424,23 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7382,7 +8533,8 @@ mod tests {
       assert_eq!(
         Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
           pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-          amt: 50000000000000000000
+          amt: 50000000000000000000,
+          pool_total_after: 0,
         }),]),
         result
       );
@@ -7432,15 +8584,18 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7491,19 +8646,23 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7597,11 +8756,13 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7652,15 +8813,18 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7711,19 +8875,23 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7773,7 +8941,8 @@ mod tests {
       assert_eq!(
         Ok(vec![PassiveWithdraw(PassiveWithdrawEvent {
           pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-          amt: 50000000000000000000
+          amt: 50000000000000000000,
+          pool_total_after: 0,
         })]),
         result
       );
@@ -7823,11 +8992,13 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7878,15 +9049,18 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -7937,19 +9111,23 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -8129,19 +9307,23 @@ mod tests {
         Ok(vec![
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("92c3f0f4ab#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("83050baa2b#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("934a4f7aff#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
           PassiveWithdraw(PassiveWithdrawEvent {
             pid: Pid::from_str("a2c6a6a614#01").unwrap(),
-            amt: 50000000000000000000
+            amt: 50000000000000000000,
+            pool_total_after: 0,
           }),
         ]),
         result
@@ -8234,4 +9416,1550 @@ mod tests {
     let now = Local::now().sub(old);
     print!("\nend:{}\n", now);
   }
+
+  #[test]
+  fn test_process_deploy_tick_name() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let strict = version::Config {
+      strict_tick_names: true,
+      ..version::zebra()
+    };
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (_, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let result = set_brc20_token_user(&brc20_data_store, "btc1", &msg.from, 200_u128, 18_u8).err();
+    assert_eq!(None, result);
+
+    // earn with a control character
+    let (_, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "or\ndi", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      strict.clone(),
+    );
+    assert_eq!(
+      Err(BRC20SError::InvalidTickName("or\ndi".to_string())),
+      result
+    );
+
+    let (_, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      strict.clone(),
+    );
+    assert_eq!(None, result.err());
+
+    // same name with a different tick id is still allowed
+    let (_, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "22000000", 18, true, addr, addr,
+    );
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      strict.clone(),
+    );
+    assert_eq!(None, result.err());
+
+    // name only differs in case from an existing tick
+    let (_, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ORDI1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      strict.clone(),
+    );
+    assert_eq!(Err(BRC20SError::DuplicateTick("ORDI1".to_string())), result);
+
+    // both names are accepted before the rules are activated
+    for earn in ["or\ndi", "ORDI1"] {
+      let (_, msg) = mock_deploy_msg(
+        "pool", "01", "btc1", earn, "10", "12000000", "21000000", 18, true, addr, addr,
+      );
+      let result = execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        0,
+        version::zebra(),
+      );
+      assert_eq!(None, result.err(), "{earn}");
+    }
+  }
+
+  #[test]
+  fn test_process_deploy_tick_id_collision() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (first_deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    set_brc20_token_user(&brc20_data_store, "btc1", &msg.from, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    )
+    .unwrap();
+
+    // store the first tick under the id derived by another tick, as if both ids collided.
+    let (second_deploy, msg) = mock_deploy_msg(
+      "pool", "02", "btc1", "ordi1", "10", "12000000", "22000000", 18, true, addr, addr,
+    );
+    let second_tick_id = second_deploy.get_tick_id();
+    let mut first_tick = brc20s_data_store
+      .get_tick_info(&first_deploy.get_tick_id())
+      .unwrap()
+      .unwrap();
+    first_tick.tick_id = second_tick_id;
+    brc20s_data_store
+      .set_tick_info(&second_tick_id, &first_tick)
+      .unwrap();
+
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    );
+    assert_eq!(
+      Err(BRC20SError::DuplicateTickId(second_tick_id.hex())),
+      result
+    );
+    assert_eq!(
+      None,
+      brc20s_data_store
+        .get_pid_to_poolinfo(&second_deploy.get_pool_id())
+        .unwrap()
+    );
+
+    // a deploy of the stored tick itself is not a collision.
+    let (_, msg) = mock_deploy_msg(
+      "pool", "02", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let result = execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    );
+    assert_eq!(
+      Err(BRC20SError::StakeAlreadyExist(
+        "btc1".to_string(),
+        first_deploy.get_tick_id().hex()
+      )),
+      result
+    );
+  }
+
+  #[test]
+  fn test_realized_and_unrealized_rewards() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    )
+    .unwrap();
+
+    // stake at block 1, 10 tokens per block are earned from then on
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Stake(Stake {
+        pool_id: pid.as_str().to_string(),
+        amount: "100".to_string(),
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+
+    // claim 5 of the 20 tokens earned at block 3
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: pid.as_str().to_string(),
+        amount: "5".to_string(),
+        pools: None,
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      3,
+      version::zebra(),
+    )
+    .unwrap();
+
+    let base = 10_u128.pow(18);
+    let user_infos = brc20s_data_store.get_user_infos(&script).unwrap();
+    assert_eq!(user_infos.len(), 1);
+    let user_info = &user_infos[0];
+    let pool_info = brc20s_data_store
+      .get_pid_to_poolinfo(&pid)
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(user_info.minted, 5 * base);
+    assert_eq!(
+      reward::preview_user_reward(user_info, &pool_info, 3, 18),
+      Ok(15 * base)
+    );
+    assert_eq!(
+      reward::preview_user_reward(user_info, &pool_info, 5, 18),
+      Ok(35 * base)
+    );
+  }
+
+  #[test]
+  fn test_batch_mint() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let mut pids = Vec::new();
+    for (pool_number, stake) in [("01", "btc1"), ("02", "btc2")] {
+      let (deploy, msg) = mock_deploy_msg(
+        "pool",
+        pool_number,
+        stake,
+        "ordi1",
+        "10",
+        "10000000",
+        "21000000",
+        18,
+        true,
+        addr,
+        addr,
+      );
+      set_brc20_token_user(&brc20_data_store, stake, &msg.from, 200_u128, 18_u8).unwrap();
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        0,
+        version::zebra(),
+      )
+      .unwrap();
+      pids.push(deploy.get_pool_id());
+    }
+    let script = ScriptKey::from_address(Address::from_str(addr).unwrap().assume_checked());
+    let tick_id = TickId::from_str(pids[0].as_str().split('#').next().unwrap()).unwrap();
+
+    // stake at block 1, 10 tokens per block are earned from each pool from then on
+    for pid in &pids {
+      let msg = mock_create_brc20s_message(
+        script.clone(),
+        script.clone(),
+        Operation::Stake(Stake {
+          pool_id: pid.as_str().to_string(),
+          amount: "100".to_string(),
+        }),
+      );
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::zebra(),
+      )
+      .unwrap();
+    }
+
+    // mint from both pools and from a pool that doesn't exist at block 3
+    let missing_pid = format!("{}#03", tick_id.hex());
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: "".to_string(),
+        amount: "".to_string(),
+        pools: Some(vec![
+          MintPool {
+            pool_id: pids[0].as_str().to_string(),
+            amount: "5".to_string(),
+          },
+          MintPool {
+            pool_id: missing_pid.clone(),
+            amount: "1".to_string(),
+          },
+          MintPool {
+            pool_id: pids[1].as_str().to_string(),
+            amount: "15".to_string(),
+          },
+        ]),
+      }),
+    );
+    // ignored before activation
+    let context = BlockContext {
+      blockheight: 3,
+      blocktime: 1687245485,
+      network: Network::Bitcoin,
+    };
+    assert!(execute(
+      context,
+      Some(0),
+      version::zebra(),
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap()
+    .is_empty());
+
+    let receipts = execute(
+      context,
+      Some(0),
+      version::Config {
+        allow_multi_pool_mint: true,
+        ..version::zebra()
+      },
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+    )
+    .unwrap();
+
+    let base = 10_u128.pow(18);
+    assert_eq!(receipts.len(), 3);
+    assert!(receipts
+      .iter()
+      .all(|receipt| receipt.op == brc20s::OperationType::Mint));
+    assert_eq!(
+      receipts[0].result,
+      Ok(vec![Event::Mint(MintEvent {
+        pid: pids[0].clone(),
+        amt: 5 * base,
+      })])
+    );
+    assert_eq!(
+      receipts[1].result,
+      Err(BRC20SError::PoolNotExist(missing_pid))
+    );
+    assert_eq!(
+      receipts[2].result,
+      Ok(vec![Event::Mint(MintEvent {
+        pid: pids[1].clone(),
+        amt: 15 * base,
+      })])
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_transaction_receipts(&msg.txid)
+        .unwrap(),
+      receipts
+    );
+
+    let balance = brc20s_data_store
+      .get_balance(&script, &tick_id)
+      .unwrap()
+      .unwrap();
+    assert_eq!(balance.overall_balance, 20 * base);
+  }
+
+  #[test]
+  fn test_deploy_pools_beyond_tick_limit() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let config = version::Config {
+      max_tick_pool_num: Some(2),
+      ..version::koala()
+    };
+
+    let mut results = Vec::new();
+    for (number, stake) in [("01", "btc1"), ("02", "btc2"), ("03", "btc3")] {
+      let (deploy, msg) = mock_deploy_msg(
+        "pool", number, stake, "ordi1", "10", "1000000", "21000000", 18, true, addr, addr,
+      );
+      set_brc20_token_user(&brc20_data_store, stake, &msg.from, 200_u128, 18_u8).unwrap();
+      results.push((
+        deploy.get_tick_id(),
+        execute_for_test(
+          &brc20_data_store,
+          &brc20s_data_store,
+          &msg,
+          0,
+          config.clone(),
+        ),
+      ));
+    }
+
+    let tick_id = results[0].0;
+    assert!(results[0].1.is_ok());
+    assert!(results[1].1.is_ok());
+    assert_eq!(
+      results[2].1,
+      Err(BRC20SError::TooManyPools(tick_id.hex(), 2))
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_tickid_to_all_pid(&tick_id)
+        .unwrap()
+        .len(),
+      2
+    );
+
+    // no limit unless configured.
+    let (_, msg) = mock_deploy_msg(
+      "pool", "03", "btc3", "ordi1", "10", "1000000", "21000000", 18, true, addr, addr,
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::koala(),
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn test_stake_with_minimum() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    let (mut deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+
+    deploy.min_stake = Some("50".to_string());
+    let msg = mock_create_brc20s_message(script.clone(), script.clone(), Operation::Deploy(deploy));
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::Config {
+        allow_min_stake: true,
+        ..version::zebra()
+      },
+    )
+    .unwrap();
+    let pool_info = brc20s_data_store
+      .get_pid_to_poolinfo(&pid)
+      .unwrap()
+      .unwrap();
+    assert_eq!(pool_info.min_stake, Some(50 * base));
+
+    let (_, msg) = mock_stake_msg(pid.as_str(), "49.9", addr, addr);
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        2,
+        version::zebra(),
+      ),
+      Err(BRC20SError::BelowMinimumStake(
+        "49.9".to_string(),
+        "50".to_string(),
+        pid.as_str().to_string(),
+      ))
+    );
+
+    for (amount, staked) in [("50", 50), ("51", 101)] {
+      let (_, msg) = mock_stake_msg(pid.as_str(), amount, addr, addr);
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        2,
+        version::zebra(),
+      )
+      .unwrap();
+      let user_info = brc20s_data_store
+        .get_pid_to_use_info(&script, &pid)
+        .unwrap()
+        .unwrap();
+      assert_eq!(user_info.staked, staked * base);
+    }
+  }
+
+  #[test]
+  fn test_pool_extensions_before_activation() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (mut deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+
+    // invalid values would fail the deploy if the keys weren't ignored.
+    deploy.halving = Some("0".to_string());
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Deploy(deploy.clone()),
+    );
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::Config {
+          allow_halving: true,
+          ..version::zebra()
+        },
+      ),
+      Err(BRC20SError::InvalidInteger("0".to_string()))
+    );
+
+    deploy.min_stake = Some("0".to_string());
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Deploy(deploy.clone()),
+    );
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::Config {
+          allow_min_stake: true,
+          ..version::zebra()
+        },
+      ),
+      Err(BRC20SError::InvalidZeroAmount)
+    );
+
+    deploy.maturity = Some("0".to_string());
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Deploy(deploy.clone()),
+    );
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::Config {
+          allow_maturity: true,
+          ..version::zebra()
+        },
+      ),
+      Err(BRC20SError::InvalidInteger("0".to_string()))
+    );
+
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+    let pool_info = brc20s_data_store
+      .get_pid_to_poolinfo(&pid)
+      .unwrap()
+      .unwrap();
+    assert_eq!(pool_info.halving_interval, None);
+    assert_eq!(pool_info.min_stake, None);
+    assert_eq!(pool_info.maturity, None);
+  }
+
+  #[test]
+  fn test_batch_operations() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::panda(),
+    )
+    .unwrap();
+    let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::panda(),
+    )
+    .unwrap();
+
+    let user_info = brc20s_data_store
+      .get_pid_to_use_info(&script, &pid)
+      .unwrap()
+      .unwrap();
+    let pool_info = brc20s_data_store
+      .get_pid_to_poolinfo(&pid)
+      .unwrap()
+      .unwrap();
+
+    let batch = |operations: Vec<Operation>, config: version::Config| {
+      execute(
+        BlockContext {
+          blockheight: 2,
+          blocktime: 1687245485,
+          network: Network::Bitcoin,
+        },
+        Some(0),
+        config,
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mock_create_brc20s_message(script.clone(), script.clone(), Operation::Batch(operations)),
+      )
+      .unwrap()
+    };
+    let withdraw = |amount: &str| {
+      Operation::UnStake(UnStake {
+        pool_id: pid.as_str().to_string(),
+        amount: amount.to_string(),
+      })
+    };
+    let deposit = |amount: &str| {
+      Operation::Stake(Stake {
+        pool_id: pid.as_str().to_string(),
+        amount: amount.to_string(),
+      })
+    };
+
+    // the second operation fails, the withdrawal before it is rolled back.
+    let receipts = batch(vec![withdraw("50"), deposit("1000")], version::panda());
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].op, OperationType::Stake);
+    assert_eq!(
+      receipts[0].result,
+      Err(BRC20SError::InsufficientBalance(
+        (150 * base).to_string(),
+        (1000 * base).to_string()
+      ))
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_pid_to_use_info(&script, &pid)
+        .unwrap(),
+      Some(user_info.clone())
+    );
+    assert_eq!(
+      brc20s_data_store.get_pid_to_poolinfo(&pid).unwrap(),
+      Some(pool_info.clone())
+    );
+
+    // batches are ignored until they are activated.
+    assert_eq!(
+      batch(vec![withdraw("50"), deposit("20")], version::koala()),
+      vec![]
+    );
+    assert_eq!(
+      brc20s_data_store
+        .get_pid_to_use_info(&script, &pid)
+        .unwrap(),
+      Some(user_info)
+    );
+
+    let receipts = batch(vec![withdraw("50"), deposit("20")], version::panda());
+    assert_eq!(
+      receipts
+        .iter()
+        .map(|receipt| (receipt.op.clone(), receipt.result.is_ok()))
+        .collect::<Vec<_>>(),
+      vec![(OperationType::UnStake, true), (OperationType::Stake, true)]
+    );
+    let user_info = brc20s_data_store
+      .get_pid_to_use_info(&script, &pid)
+      .unwrap()
+      .unwrap();
+    assert_eq!(user_info.staked, 70 * base);
+  }
+
+  #[test]
+  fn test_zero_and_negative_amounts() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    let tick_id = deploy.get_tick_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+    let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: pid.as_str().to_string(),
+        amount: "10".to_string(),
+        pools: None,
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      6,
+      version::zebra(),
+    )
+    .unwrap();
+
+    for (amount, error) in [
+      ("0", BRC20SError::InvalidZeroAmount),
+      ("-5", BRC20SError::InvalidNum("-5".to_string())),
+    ] {
+      let operations = [
+        Operation::Mint(Mint {
+          tick: "ordi1".to_string(),
+          pool_id: pid.as_str().to_string(),
+          amount: amount.to_string(),
+          pools: None,
+        }),
+        Operation::InscribeTransfer(Transfer {
+          tick: "ordi1".to_string(),
+          tick_id: tick_id.hex(),
+          amount: amount.to_string(),
+        }),
+        Operation::Stake(Stake {
+          pool_id: pid.as_str().to_string(),
+          amount: amount.to_string(),
+        }),
+        Operation::UnStake(UnStake {
+          pool_id: pid.as_str().to_string(),
+          amount: amount.to_string(),
+        }),
+      ];
+      for operation in operations {
+        let msg = mock_create_brc20s_message(script.clone(), script.clone(), operation);
+        assert_eq!(
+          execute_for_test(
+            &brc20_data_store,
+            &brc20s_data_store,
+            &msg,
+            7,
+            version::zebra(),
+          ),
+          Err(error.clone()),
+          "{amount} {:?}",
+          msg.op
+        );
+      }
+    }
+
+    let balance = brc20s_data_store
+      .get_balance(&script, &tick_id)
+      .unwrap()
+      .unwrap();
+    assert_eq!(balance.overall_balance, 10 * base);
+    assert_eq!(balance.transferable_balance, 0);
+    let user_info = brc20s_data_store
+      .get_pid_to_use_info(&script, &pid)
+      .unwrap()
+      .unwrap();
+    assert_eq!(user_info.staked, 100 * base);
+  }
+
+  #[test]
+  fn test_block_mutations_of_mint() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new_with_mutations(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    let tick_id = deploy.get_tick_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+    let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+    brc20s_data_store.save_block_mutations(1).unwrap();
+
+    // a block with a single mint of 10 ordi1.
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: pid.as_str().to_string(),
+        amount: "10".to_string(),
+        pools: None,
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      6,
+      version::zebra(),
+    )
+    .unwrap();
+    brc20s_data_store.save_block_mutations(6).unwrap();
+
+    let mutations = brc20s_data_store.get_block_mutations(6).unwrap();
+    let balance_mutations = mutations
+      .iter()
+      .filter(|mutation| matches!(mutation, Mutation::Balance { .. }))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      balance_mutations,
+      vec![&Mutation::Balance {
+        script_key: script,
+        tick_id,
+        old: None,
+        new: Balance {
+          tick_id,
+          overall_balance: 10 * base,
+          transferable_balance: 0,
+        },
+      }]
+    );
+    // the pool is updated to the mint block, the state after each change is the current state.
+    let Some(Mutation::Pool { new, .. }) = mutations
+      .iter()
+      .rev()
+      .find(|mutation| matches!(mutation, Mutation::Pool { .. }))
+    else {
+      panic!("no pool mutation in {mutations:?}");
+    };
+    assert_eq!(
+      Some(new.as_ref()),
+      brc20s_data_store
+        .get_pid_to_poolinfo(&pid)
+        .unwrap()
+        .as_ref()
+    );
+    assert_eq!(new.last_update_block, 6);
+
+    assert!(brc20s_data_store
+      .get_block_mutations(1)
+      .unwrap()
+      .iter()
+      .all(|mutation| matches!(mutation, Mutation::Pool { .. })));
+    assert!(brc20s_data_store.get_block_mutations(2).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_mint_from_two_pools_of_one_tick() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    // pools staking btc1 and btc2 both distribute ordi1.
+    let (deploy_a, msg_a) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let (deploy_b, msg_b) = mock_deploy_msg(
+      "pool", "02", "btc2", "ordi1", "10", "9000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg_a.from.clone();
+    let tick_id = deploy_a.get_tick_id();
+    assert_eq!(deploy_b.get_tick_id(), tick_id);
+    for (tick, msg) in [("btc1", &msg_a), ("btc2", &msg_b)] {
+      set_brc20_token_user(&brc20_data_store, tick, &script, 200_u128, 18_u8).unwrap();
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        msg,
+        0,
+        version::zebra(),
+      )
+      .unwrap();
+    }
+
+    for (pid, amount) in [
+      (deploy_a.get_pool_id(), "30"),
+      (deploy_b.get_pool_id(), "20"),
+    ] {
+      let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::zebra(),
+      )
+      .unwrap();
+
+      let msg = mock_create_brc20s_message(
+        script.clone(),
+        script.clone(),
+        Operation::Mint(Mint {
+          tick: "ordi1".to_string(),
+          pool_id: pid.as_str().to_string(),
+          amount: amount.to_string(),
+          pools: None,
+        }),
+      );
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        6,
+        version::zebra(),
+      )
+      .unwrap();
+    }
+
+    let tick_info = brc20s_data_store.get_tick_info(&tick_id).unwrap().unwrap();
+    assert_eq!(tick_info.supply, 21000000 * base);
+    assert_eq!(tick_info.allocated, 21000000 * base);
+    assert_eq!(tick_info.circulation, 50 * base);
+    assert_eq!(
+      brc20s_data_store
+        .get_balance(&script, &tick_id)
+        .unwrap()
+        .unwrap()
+        .overall_balance,
+      50 * base
+    );
+  }
+
+  #[test]
+  fn test_compound_brc20s_stake() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let base = 10_u128.pow(18);
+
+    // pool a stakes btc1 and earns ordi1
+    let (deploy_a, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid_a = deploy_a.get_pool_id();
+    let tick_a = TickId::from_str(pid_a.as_str().split('#').next().unwrap()).unwrap();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::panda(),
+    )
+    .unwrap();
+
+    let (_, msg) = mock_stake_msg(pid_a.as_str(), "100", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::panda(),
+    )
+    .unwrap();
+
+    // claim 50 ordi1 at block 6
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "ordi1".to_string(),
+        pool_id: pid_a.as_str().to_string(),
+        amount: "50".to_string(),
+        pools: None,
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      6,
+      version::panda(),
+    )
+    .unwrap();
+
+    // pool b stakes the earned ordi1 and earns sats1
+    let (deploy_b, msg) = mock_deploy_msg(
+      "pool",
+      "01",
+      tick_a.hex().as_str(),
+      "sats1",
+      "2",
+      "12000000",
+      "21000000",
+      18,
+      true,
+      addr,
+      addr,
+    );
+    let pid_b = deploy_b.get_pool_id();
+    let stake_a = PledgedTick::BRC20STick(tick_a);
+    assert_eq!(deploy_b.get_stake_id(), stake_a);
+
+    // the earned ticks can't be staked before brc20s staking is enabled
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        7,
+        version::koala(),
+      ),
+      Err(BRC20SError::StakeNoPermission(tick_a.hex()))
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      7,
+      version::panda(),
+    )
+    .unwrap();
+
+    let (_, msg) = mock_stake_msg(pid_b.as_str(), "25", addr, addr);
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        8,
+        version::panda(),
+      ),
+      Ok(vec![Event::Deposit(DepositEvent {
+        pid: pid_b.clone(),
+        amt: 25 * base,
+        period_settlement_reward: 0,
+        pool_total_after: 25 * base,
+      })])
+    );
+
+    let stake_info = brc20s_data_store
+      .get_user_stakeinfo(&script, &stake_a)
+      .unwrap()
+      .unwrap();
+    assert_eq!(stake_info.total_only, 25 * base);
+    assert_eq!(
+      stake_info.pool_stakes,
+      vec![(pid_b.clone(), true, 25 * base)]
+    );
+
+    // only the staked part of the balance is locked
+    let balance = brc20s_data_store
+      .get_balance(&script, &tick_a)
+      .unwrap()
+      .unwrap();
+    assert_eq!(balance.overall_balance, 50 * base);
+    let (_, msg) = mock_stake_msg(pid_b.as_str(), "26", addr, addr);
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        8,
+        version::panda(),
+      ),
+      Err(BRC20SError::InsufficientBalance(
+        (25 * base).to_string(),
+        (26 * base).to_string()
+      ))
+    );
+
+    // 2 sats1 per block are earned in pool b, while pool a keeps accruing ordi1
+    let pool_b = brc20s_data_store
+      .get_pid_to_poolinfo(&pid_b)
+      .unwrap()
+      .unwrap();
+    let user_b = brc20s_data_store
+      .get_pid_to_use_info(&script, &pid_b)
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      reward::preview_user_reward(&user_b, &pool_b, 13, 18),
+      Ok(10 * base)
+    );
+
+    let msg = mock_create_brc20s_message(
+      script.clone(),
+      script.clone(),
+      Operation::Mint(Mint {
+        tick: "sats1".to_string(),
+        pool_id: pid_b.as_str().to_string(),
+        amount: "10".to_string(),
+        pools: None,
+      }),
+    );
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      13,
+      version::panda(),
+    )
+    .unwrap();
+    let tick_b = TickId::from_str(pid_b.as_str().split('#').next().unwrap()).unwrap();
+    let balance = brc20s_data_store
+      .get_balance(&script, &tick_b)
+      .unwrap()
+      .unwrap();
+    assert_eq!(balance.overall_balance, 10 * base);
+
+    // withdrawing from pool b unlocks the ordi1 again
+    let (_, msg) = mock_unstake_msg(pid_b.as_str(), "25", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      14,
+      version::panda(),
+    )
+    .unwrap();
+    let stake_info = brc20s_data_store
+      .get_user_stakeinfo(&script, &stake_a)
+      .unwrap()
+      .unwrap();
+    assert_eq!(stake_info.total_only, 0);
+  }
+
+  #[test]
+  fn test_mint_exhausted_pool() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "100", "21000000", 18, true, addr, addr,
+    );
+    let script = msg.from.clone();
+    let pid = deploy.get_pool_id();
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      0,
+      version::zebra(),
+    )
+    .unwrap();
+
+    // stake at block 1, the dmax of 100 is distributed by block 11
+    let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+    execute_for_test(
+      &brc20_data_store,
+      &brc20s_data_store,
+      &msg,
+      1,
+      version::zebra(),
+    )
+    .unwrap();
+
+    let mint_msg = |amount: &str| {
+      mock_create_brc20s_message(
+        script.clone(),
+        script.clone(),
+        Operation::Mint(Mint {
+          tick: "ordi1".to_string(),
+          pool_id: pid.as_str().to_string(),
+          amount: amount.to_string(),
+          pools: None,
+        }),
+      )
+    };
+
+    // the pool still distributes, minting beyond the reward exceeds the limit
+    let base = 10_u128.pow(18);
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mint_msg("60"),
+        5,
+        version::zebra(),
+      ),
+      Err(BRC20SError::AmountExceedLimit((60 * base).to_string()))
+    );
+
+    // the pool is exhausted, but the pending reward can still be minted
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mint_msg("101"),
+        20,
+        version::zebra(),
+      ),
+      Err(BRC20SError::PoolExhausted(
+        pid.as_str().to_string(),
+        "0".to_string()
+      ))
+    );
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mint_msg("100"),
+        20,
+        version::zebra(),
+      ),
+      Ok(vec![Event::Mint(MintEvent {
+        pid: pid.clone(),
+        amt: 100 * base,
+      })])
+    );
+    assert_eq!(
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mint_msg("1"),
+        30,
+        version::zebra(),
+      ),
+      Err(BRC20SError::PoolExhausted(
+        pid.as_str().to_string(),
+        "0".to_string()
+      ))
+    );
+  }
+
+  #[test]
+  fn test_mint_amount_precision() {
+    let mint = |decimals: u8| {
+      let dbfile = NamedTempFile::new().unwrap();
+      let db = Database::create(dbfile.path()).unwrap();
+      let wtx = db.begin_write().unwrap();
+
+      let brc20_data_store = brc20_db::DataStore::new(&wtx);
+      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+      let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+      let (deploy, msg) = mock_deploy_msg(
+        "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", decimals, true, addr, addr,
+      );
+      let script = msg.from.clone();
+      let pid = deploy.get_pool_id();
+      set_brc20_token_user(&brc20_data_store, "btc1", &script, 200_u128, 18_u8).unwrap();
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        0,
+        version::zebra(),
+      )
+      .unwrap();
+      let (_, msg) = mock_stake_msg(pid.as_str(), "100", addr, addr);
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &msg,
+        1,
+        version::zebra(),
+      )
+      .unwrap();
+
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        &mock_create_brc20s_message(
+          script.clone(),
+          script,
+          Operation::Mint(Mint {
+            tick: "ordi1".to_string(),
+            pool_id: pid.as_str().to_string(),
+            amount: "1.123456789012345678".to_string(),
+            pools: None,
+          }),
+        ),
+        11,
+        version::zebra(),
+      )
+      .map(|events| (pid, events))
+    };
+
+    // the amount is rejected rather than truncated to the decimals of the tick.
+    assert_eq!(
+      mint(8),
+      Err(BRC20SError::InvalidAmountPrecision(
+        "1.123456789012345678".to_string(),
+        8
+      ))
+    );
+
+    let (pid, events) = mint(18).unwrap();
+    assert_eq!(
+      events,
+      vec![Event::Mint(MintEvent {
+        pid,
+        amt: 1123456789012345678,
+      })]
+    );
+  }
+
+  #[test]
+  fn test_only_pool_violation() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let run = |msg: &ExecutionMessage, config: version::Config| {
+      execute_for_test(&brc20_data_store, &brc20s_data_store, msg, 1, config).map(|_| ())
+    };
+
+    let script = ScriptKey::from_address(Address::from_str(addr).unwrap().assume_checked());
+    set_brc20_token_user(&brc20_data_store, "btc1", &script, 300_u128, 18_u8).unwrap();
+
+    // two only pools and a share pool, all staking btc1.
+    let pids = [("ordi1", true), ("ordi2", true), ("ordi3", false)].map(|(earn, only)| {
+      let (deploy, msg) = mock_deploy_msg(
+        "pool", "01", "btc1", earn, "10", "12000000", "21000000", 18, only, addr, addr,
+      );
+      run(&msg, version::panda()).unwrap();
+      deploy.get_pool_id()
+    });
+    let [only1, only2, share] = pids.map(|pid| pid.as_str().to_string());
+    let stake = |pid: &str| mock_stake_msg(pid, "100", addr, addr).1;
+
+    run(&stake(&only1), version::panda()).unwrap();
+    assert_eq!(
+      run(&stake(&only2), version::panda()),
+      Err(BRC20SError::OnlyPoolViolation(
+        "btc1".to_string(),
+        only1.clone()
+      ))
+    );
+    // adding to the only pool already staked in and staking into a share pool are unrestricted.
+    run(&stake(&only1), version::panda()).unwrap();
+    run(&stake(&share), version::panda()).unwrap();
+
+    // once the stake is withdrawn, another only pool can be used.
+    run(
+      &mock_unstake_msg(&only1, "200", addr, addr).1,
+      version::panda(),
+    )
+    .unwrap();
+    run(&stake(&only2), version::panda()).unwrap();
+
+    // before activation, amounts can be spread over several only pools.
+    run(&stake(&only1), version::koala()).unwrap();
+    assert_eq!(
+      brc20s_data_store
+        .get_user_stakeinfo(
+          &script,
+          &PledgedTick::BRC20Tick(brc20::Tick::from_str("btc1").unwrap())
+        )
+        .unwrap()
+        .unwrap()
+        .total_only,
+      200 * 10_u128.pow(18)
+    );
+  }
+
+  #[test]
+  fn test_pool_total_after() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20_data_store = brc20_db::DataStore::new(&wtx);
+    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+
+    let alice = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
+    let bob = "bc1q9cv6smq87myk2ujs352c3lulwzvdfujd5059ny";
+    let base = 10_u128.pow(18);
+    let run = |msg: &ExecutionMessage| {
+      execute_for_test(
+        &brc20_data_store,
+        &brc20s_data_store,
+        msg,
+        1,
+        version::panda(),
+      )
+    };
+
+    for addr in [alice, bob] {
+      let script = ScriptKey::from_address(Address::from_str(addr).unwrap().assume_checked());
+      set_brc20_token_user(&brc20_data_store, "btc1", &script, 100_u128, 18_u8).unwrap();
+    }
+    let (deploy, msg) = mock_deploy_msg(
+      "pool", "01", "btc1", "ordi1", "10", "12000000", "21000000", 18, false, alice, alice,
+    );
+    run(&msg).unwrap();
+    let pid = deploy.get_pool_id();
+
+    // every receipt carries the pool total once the operation is applied.
+    assert_eq!(
+      run(&mock_stake_msg(pid.as_str(), "30", alice, alice).1),
+      Ok(vec![Event::Deposit(DepositEvent {
+        pid: pid.clone(),
+        amt: 30 * base,
+        period_settlement_reward: 0,
+        pool_total_after: 30 * base,
+      })])
+    );
+    assert_eq!(
+      run(&mock_stake_msg(pid.as_str(), "50", bob, bob).1),
+      Ok(vec![Event::Deposit(DepositEvent {
+        pid: pid.clone(),
+        amt: 50 * base,
+        period_settlement_reward: 0,
+        pool_total_after: 80 * base,
+      })])
+    );
+    assert_eq!(
+      run(&mock_unstake_msg(pid.as_str(), "20", alice, alice).1),
+      Ok(vec![Event::Withdraw(WithdrawEvent {
+        pid,
+        amt: 20 * base,
+        period_settlement_reward: 0,
+        pool_total_after: 60 * base,
+      })])
+    );
+  }
 }
@@ -7,7 +7,10 @@ use {
         brc20s,
         ord::{self, Action, InscriptionOp},
       },
-      protocol::brc20s::{deserialize_brc20s_operation, operation::Transfer},
+      protocol::{
+        brc20s::{deserialize_brc20s_operation, operation::Transfer},
+        JsonLimits,
+      },
     },
     Index, Result,
   },
@@ -24,6 +27,7 @@ impl Message {
     brc20s_store: &'a M,
     new_inscriptions: &[Inscription],
     op: &InscriptionOp,
+    json_limits: JsonLimits,
     outpoint_to_txout_cache: &mut HashMap<OutPoint, TxOut>,
   ) -> Result<Option<Self>> {
     log::debug!("BRC20S resolving the message from {:?}", op);
@@ -43,6 +47,7 @@ impl Message {
             .get(usize::try_from(op.inscription_id.index).unwrap())
             .unwrap(),
           &op.action,
+          json_limits,
         ) {
           Ok(brc20s_operation) => brc20s_operation,
           _ => return Ok(None),
@@ -244,6 +249,7 @@ mod tests {
         &brc20s_store,
         &inscriptions,
         &op,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -279,6 +285,7 @@ mod tests {
         &brc20s_store,
         &inscriptions,
         &op,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -299,6 +306,7 @@ mod tests {
         &brc20s_store,
         &inscriptions,
         &op2,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -318,6 +326,7 @@ mod tests {
         &brc20s_store,
         &inscriptions,
         &op3,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -343,6 +352,7 @@ mod tests {
         &brc20s_store,
         &[],
         &op,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -367,6 +377,7 @@ mod tests {
         &brc20s_store,
         &[],
         &op1,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(None)
@@ -417,6 +428,7 @@ mod tests {
         &brc20s_store,
         &[],
         &op,
+        JsonLimits::default(),
         &mut outpoint_to_txout_cache,
       ),
       Ok(Some(_msg))
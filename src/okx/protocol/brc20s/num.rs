@@ -6,6 +6,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+// The integer part of a num is limited to 512 bits. It is far above every value the staking math
+// produces, even the intermediate products of u128 amounts and the per share multiplier.
+const MAX_NUM_BITS: u64 = 512;
+
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub struct Num(BigDecimal);
 
@@ -14,9 +18,8 @@ impl Num {
     Self(BigDecimal::zero())
   }
 
-  // TODO check overflow
   pub fn checked_add(&self, other: &Num) -> Result<Self, BRC20SError> {
-    Ok(Self(self.0.clone() + &other.0))
+    Self(self.0.clone() + &other.0).check_overflow("checked_add", self, other)
   }
 
   pub fn checked_sub(&self, other: &Num) -> Result<Self, BRC20SError> {
@@ -31,9 +34,20 @@ impl Num {
     Ok(Self(self.0.clone() - &other.0))
   }
 
-  // TODO check overflow
   pub fn checked_mul(&self, other: &Num) -> Result<Self, BRC20SError> {
-    Ok(Self(self.0.clone() * &other.0))
+    Self(self.0.clone() * &other.0).check_overflow("checked_mul", self, other)
+  }
+
+  fn check_overflow(self, op: &str, org: &Num, other: &Num) -> Result<Self, BRC20SError> {
+    let (integer, _) = self.0.with_scale(0).into_bigint_and_exponent();
+    if integer.bits() > MAX_NUM_BITS {
+      return Err(BRC20SError::Overflow {
+        op: String::from(op),
+        org: org.to_string(),
+        other: other.to_string(),
+      });
+    }
+    Ok(self)
   }
 
   pub fn checked_div(&self, other: &Num) -> Result<Self, BRC20SError> {
@@ -79,6 +93,15 @@ impl Num {
     scale
   }
 
+  /// Formats an amount scaled by `10^decimals` as a human readable decimal string,
+  /// e.g. `1500` with 3 decimals is formatted as `1.5`.
+  pub fn format_scaled(&self, decimals: u8) -> String {
+    let (int_val, scale) = self.0.as_bigint_and_exponent();
+    BigDecimal::new(int_val, scale + i64::from(decimals))
+      .normalized()
+      .to_string()
+  }
+
   pub fn checked_to_u128(&self) -> Result<u128, BRC20SError> {
     if !self.0.is_integer() {
       return Err(BRC20SError::InvalidInteger(self.clone().to_string()));
@@ -121,6 +144,15 @@ impl Num {
     Ok(result)
   }
 
+  pub fn ceil(&self) -> Self {
+    let integer = self.0.with_scale(0);
+    if self.0 > integer {
+      Self(integer + BigDecimal::one())
+    } else {
+      Self(integer)
+    }
+  }
+
   pub fn max(a: &Num, b: &Num) -> Self {
     if a.gt(b) {
       a.clone()
@@ -154,6 +186,9 @@ impl From<u128> for Num {
   }
 }
 
+/// Parsed like the brc-20 numbers. A mint or transfer `amt` is checked against the decimals
+/// of the tick by its scale, so `"1.00"` fails for a tick of a single decimal while `"01.0"`
+/// passes.
 impl FromStr for Num {
   type Err = BRC20SError;
   fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -193,9 +228,9 @@ impl<'de> Deserialize<'de> for Num {
     D: Deserializer<'de>,
   {
     let s = String::deserialize(deserializer)?;
-    Ok(Self(
-      BigDecimal::from_str(&s).map_err(serde::de::Error::custom)?,
-    ))
+    Ok(Self(BigDecimal::from_str(&s).map_err(|_| {
+      serde::de::Error::custom(BRC20SError::InvalidNum(s.clone()))
+    })?))
   }
 }
 
@@ -234,6 +269,21 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_ceil() {
+    assert_eq!(Num::from_str("0").unwrap().ceil(), Num::from(0u64));
+    assert_eq!(Num::from_str("3").unwrap().ceil(), Num::from(3u64));
+    assert_eq!(
+      Num::from_str("3.000000000000000001").unwrap().ceil(),
+      Num::from(4u64)
+    );
+    assert_eq!(Num::from_str("3.9").unwrap().ceil(), Num::from(4u64));
+    assert_eq!(
+      Num(BigDecimal::from_str("-3.5").unwrap()).ceil(),
+      Num(BigDecimal::from(-3))
+    );
+  }
+
   #[test]
   fn test_num_from_str() {
     assert!(Num::from_str(".1").is_err());
@@ -289,6 +339,21 @@ mod tests {
     assert!(Num::from_str("1.0000000000000000001").is_err());
   }
 
+  #[test]
+  fn test_non_canonical_num() {
+    assert_eq!(Num::from_str("007").unwrap(), Num::from(7_u64));
+    assert_eq!(Num::from_str("007").unwrap().scale(), 0);
+
+    assert_eq!(Num::from_str("1.00").unwrap(), Num::from(1_u64));
+    assert_eq!(Num::from_str("1.00").unwrap().scale(), 2);
+
+    assert_eq!(
+      Num::from_str("01.5").unwrap(),
+      Num(BigDecimal::new(BigInt::from(15), 1))
+    );
+    assert_eq!(Num::from_str("01.5").unwrap().scale(), 1);
+  }
+
   #[test]
   fn test_invalid_num() {
     assert!(Num::from_str("").is_err());
@@ -323,6 +388,29 @@ mod tests {
     assert_eq!(Num::from_str("1.11").unwrap(), num);
   }
 
+  #[test]
+  fn test_num_errors_show_input() {
+    for input in [
+      "abc",
+      ".1",
+      "1.",
+      "1e5",
+      "-1",
+      "123.456.789",
+      "0.1234567890123456789",
+    ] {
+      let err = Num::from_str(input).unwrap_err().to_string();
+      assert!(err.contains(input), "{err}");
+    }
+
+    for input in ["abc", "123.456.789"] {
+      let err = serde_json::from_str::<Num>(&format!("\"{input}\""))
+        .unwrap_err()
+        .to_string();
+      assert!(err.contains(input), "{err}");
+    }
+  }
+
   #[test]
   fn test_num_checked_add() {
     assert_eq!(
@@ -351,6 +439,46 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_num_checked_overflow() {
+    let near_max = Num(BigDecimal::from(BigInt::one() << (MAX_NUM_BITS - 1)));
+    let max = Num(BigDecimal::from((BigInt::one() << MAX_NUM_BITS) - 1));
+    assert_eq!(max.checked_add(&Num::zero()).unwrap(), max);
+
+    assert_eq!(
+      max.checked_add(&Num::from_str("1").unwrap()).unwrap_err(),
+      BRC20SError::Overflow {
+        op: String::from("checked_add"),
+        org: max.to_string(),
+        other: "1".to_string(),
+      }
+    );
+    assert_eq!(
+      near_max.checked_mul(&near_max).unwrap_err(),
+      BRC20SError::Overflow {
+        op: String::from("checked_mul"),
+        org: near_max.to_string(),
+        other: near_max.to_string(),
+      }
+    );
+    assert_eq!(
+      near_max
+        .checked_mul(&Num::from_str("2").unwrap())
+        .unwrap_err(),
+      BRC20SError::Overflow {
+        op: String::from("checked_mul"),
+        org: near_max.to_string(),
+        other: "2".to_string(),
+      }
+    );
+    assert_eq!(
+      near_max
+        .checked_mul(&Num::from_str("1.5").unwrap())
+        .unwrap(),
+      Num(BigDecimal::from(BigInt::from(3) << (MAX_NUM_BITS - 2)))
+    );
+  }
+
   #[test]
   fn test_num_checked_sub() {
     assert_eq!(
@@ -621,4 +749,28 @@ mod tests {
     let is_integer = amt.is_positive();
     println!("checked_mul {:?}, {}", amt, is_integer);
   }
+
+  #[test]
+  fn test_format_scaled() {
+    // 18 decimals
+    assert_eq!(Num::from(0u128).format_scaled(18), "0");
+    assert_eq!(Num::from(1u128).format_scaled(18), "0.000000000000000001");
+    assert_eq!(
+      Num::from(1_000_000_000_000_000_000u128).format_scaled(18),
+      "1"
+    );
+    assert_eq!(
+      Num::from(1_500_000_000_000_000_000u128).format_scaled(18),
+      "1.5"
+    );
+    assert_eq!(
+      Num::from(u128::MAX).format_scaled(18),
+      "340282366920938463463.374607431768211455"
+    );
+
+    // 0 decimals
+    assert_eq!(Num::from(0u128).format_scaled(0), "0");
+    assert_eq!(Num::from(1000u128).format_scaled(0), "1000");
+    assert_eq!(Num::from(u128::MAX).format_scaled(0), u128::MAX.to_string());
+  }
 }
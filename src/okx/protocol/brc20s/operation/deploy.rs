@@ -5,6 +5,7 @@ use crate::okx::protocol::brc20s::params::{
 };
 use crate::okx::protocol::brc20s::util::{validate_amount, validate_pool_str};
 use crate::okx::protocol::brc20s::{BRC20SError, Num};
+use bigdecimal::num_bigint::Sign;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -47,6 +48,34 @@ pub struct Deploy {
   // Assets only deposit this pool，must be yes
   #[serde(rename = "only")]
   pub only: Option<String>,
+
+  // Halving interval: the earn rate halves every this many blocks since deployment, default: never
+  #[serde(
+    rename = "halving",
+    default,
+    deserialize_with = "super::ignore_invalid",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub halving: Option<String>,
+
+  // Minimum stake: the least amount of a single deposit into the pool, default: no minimum
+  #[serde(
+    rename = "minstake",
+    default,
+    deserialize_with = "super::ignore_invalid",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub min_stake: Option<String>,
+
+  // Maturity: rewards are weighted by how long a stake has been held, in full after this many
+  // blocks, default: weighted by amount only
+  #[serde(
+    rename = "maturity",
+    default,
+    deserialize_with = "super::ignore_invalid",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub maturity: Option<String>,
 }
 
 impl Deploy {
@@ -96,6 +125,20 @@ impl Deploy {
     self.only == Some("1".to_string())
   }
 
+  pub fn get_halving_interval(&self) -> Option<u64> {
+    self
+      .halving
+      .as_ref()
+      .and_then(|interval| interval.parse::<u64>().ok())
+  }
+
+  pub fn get_maturity(&self) -> Option<u64> {
+    self
+      .maturity
+      .as_ref()
+      .and_then(|maturity| maturity.parse::<u64>().ok())
+  }
+
   pub fn get_tick_id(&self) -> TickId {
     let tick_str = self.pool_id.as_str().split('#').next().unwrap();
     TickId::from_str(tick_str).unwrap()
@@ -125,7 +168,10 @@ impl Deploy {
       return Err(iserr);
     }
 
-    // validate earn_rate
+    // validate earn_rate, a pool earning nothing per block can never be minted from.
+    if Num::from_str(self.earn_rate.as_str()).map_or(false, |erate| erate.sign() == Sign::NoSign) {
+      return Err(BRC20SError::InvalidErate(self.earn_rate.clone()));
+    }
     validate_amount(self.earn_rate.as_str())?;
     // validate distribution_max
     validate_amount(self.distribution_max.as_str())?;
@@ -161,6 +207,35 @@ impl Deploy {
       }
     }
 
+    // validate halving, an interval of 0 blocks would halve the earn rate to nothing at once.
+    if let Some(halving) = self.halving.as_ref() {
+      let interval = halving
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| halving.parse::<u64>().ok())
+        .flatten();
+      if interval.map_or(true, |interval| interval == 0) {
+        return Err(BRC20SError::InvalidInteger(halving.clone()));
+      }
+    }
+
+    // validate maturity, a stake would be mature at once with a maturity of 0 blocks.
+    if let Some(maturity) = self.maturity.as_ref() {
+      let blocks = maturity
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| maturity.parse::<u64>().ok())
+        .flatten();
+      if blocks.map_or(true, |blocks| blocks == 0) {
+        return Err(BRC20SError::InvalidInteger(maturity.clone()));
+      }
+    }
+
+    // validate min_stake
+    if let Some(min_stake) = self.min_stake.as_ref() {
+      validate_amount(min_stake.as_str())?;
+    }
+
     Ok(())
   }
 }
@@ -182,6 +257,9 @@ mod tests {
       total_supply: Some("12".to_string()),
       decimals: Some("11".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
 
     assert_eq!(
@@ -201,6 +279,42 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_min_stake() {
+    let deploy = |min_stake: &str| Deploy {
+      pool_type: "pool".to_string(),
+      pool_id: "a3668daeaa#1f".to_string(),
+      stake: "btc".to_string(),
+      earn: "ordi".to_string(),
+      earn_rate: "10".to_string(),
+      distribution_max: "12000000".to_string(),
+      total_supply: Some("21000000".to_string()),
+      decimals: Some("18".to_string()),
+      only: Some("1".to_string()),
+      halving: None,
+      min_stake: Some(min_stake.to_string()),
+      maturity: None,
+    };
+
+    assert_eq!(
+      deserialize_brc20s(
+        r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","erate":"10","dmax":"12000000","dec":"18","total":"21000000","only":"1","minstake":"0.5"}"#
+      )
+      .unwrap(),
+      RawOperation::Deploy(deploy("0.5"))
+    );
+
+    assert_eq!(deploy("0.5").validate_basic(), Ok(()));
+    assert_eq!(
+      deploy("0").validate_basic(),
+      Err(BRC20SError::InvalidZeroAmount)
+    );
+    assert_eq!(
+      deploy("a").validate_basic(),
+      Err(BRC20SError::InvalidNum("a".to_string()))
+    );
+  }
+
   #[test]
   fn test_deserialize() {
     assert_eq!(
@@ -218,6 +332,9 @@ mod tests {
         decimals: Some("18".to_string()),
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
   }
@@ -247,6 +364,9 @@ mod tests {
         decimals: Some("18".to_string()),
         total_supply: Some("21000000".to_string()),
         only: None,
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
 
@@ -264,6 +384,9 @@ mod tests {
         decimals: Some("18".to_string()),
         total_supply: None,
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
 
@@ -280,6 +403,31 @@ mod tests {
         decimals: None,
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
+      })
+    );
+  }
+
+  #[test]
+  fn test_ignore_invalid_extension_keys() {
+    assert_eq!(
+      deserialize_brc20s(r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","erate":"10","dmax":"12000000","dec":"18","total":"21000000","only":"1","halving":1,"minstake":{"amt":"1"},"maturity":null}"#)
+        .unwrap(),
+      RawOperation::Deploy(Deploy {
+        pool_type: "pool".to_string(),
+        pool_id: "a3668daeaa#1f".to_string(),
+        stake: "btc".to_string(),
+        earn: "ordi".to_string(),
+        earn_rate: "10".to_string(),
+        distribution_max: "12000000".to_string(),
+        decimals: Some("18".to_string()),
+        total_supply: Some("21000000".to_string()),
+        only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
   }
@@ -299,6 +447,9 @@ mod tests {
         decimals: Some("20".to_string()),
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
   }
@@ -315,6 +466,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy
       .validate_basic()
@@ -334,6 +488,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -347,6 +504,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -360,6 +520,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -373,6 +536,9 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -386,6 +552,9 @@ mod tests {
       decimals: Some("a".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -399,6 +568,9 @@ mod tests {
       decimals: Some("1".to_string()),
       total_supply: Some("abc".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
 
@@ -412,7 +584,58 @@ mod tests {
       decimals: Some("18".to_string()),
       total_supply: Some("21000000".to_string()),
       only: Some("1".to_string()),
+      halving: None,
+      min_stake: None,
+      maturity: None,
     };
     assert!(deploy.validate_basic().is_err());
   }
+
+  #[test]
+  fn test_validate_halving() {
+    let RawOperation::Deploy(mut deploy) = deserialize_brc20s(
+      r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","erate":"10","dmax":"12000000","dec":"18","total":"21000000","only":"1","halving":"210000"}"#,
+    )
+    .unwrap() else {
+      panic!("not a deploy");
+    };
+    assert_eq!(deploy.get_halving_interval(), Some(210000));
+    assert!(deploy.validate_basic().is_ok());
+
+    for halving in ["0", "-1", "+5", "1.5", "", "a", "18446744073709551616"] {
+      deploy.halving = Some(halving.to_string());
+      assert_eq!(
+        deploy.validate_basic(),
+        Err(BRC20SError::InvalidInteger(halving.to_string()))
+      );
+    }
+
+    deploy.halving = None;
+    assert_eq!(deploy.get_halving_interval(), None);
+    assert!(deploy.validate_basic().is_ok());
+  }
+
+  #[test]
+  fn test_validate_maturity() {
+    let RawOperation::Deploy(mut deploy) = deserialize_brc20s(
+      r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","erate":"10","dmax":"12000000","dec":"18","total":"21000000","only":"1","maturity":"144"}"#,
+    )
+    .unwrap() else {
+      panic!("not a deploy");
+    };
+    assert_eq!(deploy.get_maturity(), Some(144));
+    assert!(deploy.validate_basic().is_ok());
+
+    for maturity in ["0", "-1", "+5", "1.5", "", "a", "18446744073709551616"] {
+      deploy.maturity = Some(maturity.to_string());
+      assert_eq!(
+        deploy.validate_basic(),
+        Err(BRC20SError::InvalidInteger(maturity.to_string()))
+      );
+    }
+
+    deploy.maturity = None;
+    assert_eq!(deploy.get_maturity(), None);
+    assert!(deploy.validate_basic().is_ok());
+  }
 }
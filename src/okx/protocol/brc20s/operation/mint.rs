@@ -5,21 +5,100 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(try_from = "MintJson")]
 pub struct Mint {
   // Ticker: 4-6 letter identifier of the brc20-s
   #[serde(rename = "tick")]
   pub tick: String,
 
   // 10 letter identifier of the token id + "#" + 2 letter of pool number
-  #[serde(rename = "pid")]
+  // Empty in the batch form.
+  #[serde(rename = "pid", skip_serializing_if = "String::is_empty")]
   pub pool_id: String,
 
   // Amount to mint: States the amount of the brc20-s to mint. Has to be less than "lim" above if stated
+  // Empty in the batch form.
+  #[serde(rename = "amt", skip_serializing_if = "String::is_empty")]
+  pub amount: String,
+
+  // Batch form: mint from each of the listed pools instead of a single "pid" and "amt".
+  #[serde(rename = "pools", skip_serializing_if = "Option::is_none")]
+  pub pools: Option<Vec<MintPool>>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct MintPool {
+  #[serde(rename = "pid")]
+  pub pool_id: String,
+
   #[serde(rename = "amt")]
   pub amount: String,
 }
 
+#[derive(Deserialize)]
+struct MintJson {
+  tick: String,
+  pid: Option<String>,
+  amt: Option<String>,
+  // parsed only in the batch form, so that a stray value of any type is ignored by a single mint.
+  pools: Option<serde_json::Value>,
+}
+
+/// The most pools a single mint inscription can list.
+pub const MAX_MINT_POOLS: usize = 16;
+
+impl TryFrom<MintJson> for Mint {
+  type Error = String;
+
+  fn try_from(json: MintJson) -> Result<Self, Self::Error> {
+    // a mint carrying `pid` or `amt` is a single pool mint as it always was, a stray `pools` key
+    // is ignored just like any other unknown key.
+    match json.pools {
+      Some(pools) if json.pid.is_none() && json.amt.is_none() => {
+        let pools =
+          serde_json::from_value::<Vec<MintPool>>(pools).map_err(|error| error.to_string())?;
+        if pools.is_empty() {
+          return Err("`pools` cannot be empty".to_string());
+        }
+        if pools.len() > MAX_MINT_POOLS {
+          return Err(format!(
+            "`pools` cannot list more than {MAX_MINT_POOLS} pools"
+          ));
+        }
+        Ok(Self {
+          tick: json.tick,
+          pool_id: String::new(),
+          amount: String::new(),
+          pools: Some(pools),
+        })
+      }
+      _ => Ok(Self {
+        tick: json.tick,
+        pool_id: json.pid.ok_or("missing field `pid`")?,
+        amount: json.amt.ok_or("missing field `amt`")?,
+        pools: None,
+      }),
+    }
+  }
+}
+
 impl Mint {
+  /// Splits the mint into one single pool mint per entry, a single pool mint is returned as is.
+  pub fn entries(&self) -> Vec<Mint> {
+    match &self.pools {
+      None => vec![self.clone()],
+      Some(pools) => pools
+        .iter()
+        .map(|pool| Mint {
+          tick: self.tick.clone(),
+          pool_id: pool.pool_id.clone(),
+          amount: pool.amount.clone(),
+          pools: None,
+        })
+        .collect(),
+    }
+  }
+
   pub fn get_pool_id(&self) -> Result<Pid, BRC20SError> {
     Pid::from_str(self.pool_id.as_str())
   }
@@ -62,6 +141,7 @@ mod tests {
       tick: "tick".to_string(),
       pool_id: "pid".to_string(),
       amount: "amt".to_string(),
+      pools: None,
     };
 
     assert_eq!(
@@ -93,6 +173,7 @@ mod tests {
         tick: "tick".to_string(),
         pool_id: "tid".to_string(),
         amount: "amt".to_string(),
+        pools: None,
       })
     );
   }
@@ -129,7 +210,132 @@ mod tests {
         tick: "tick".to_string(),
         pool_id: "pid-2".to_string(),
         amount: "amt".to_string(),
+        pools: None,
+      })
+    );
+  }
+
+  #[test]
+  fn test_deserialize_batch() {
+    let json_str = r#"{
+        "p": "brc20-s",
+        "op": "mint",
+        "tick": "tick",
+        "pools": [
+          {"pid": "pid-1", "amt": "1"},
+          {"pid": "pid-2", "amt": "2.5"},
+          {"pid": "pid-3", "amt": "3"}
+        ]
+      }"#;
+
+    let mint = Mint {
+      tick: "tick".to_string(),
+      pool_id: "".to_string(),
+      amount: "".to_string(),
+      pools: Some(vec![
+        MintPool {
+          pool_id: "pid-1".to_string(),
+          amount: "1".to_string(),
+        },
+        MintPool {
+          pool_id: "pid-2".to_string(),
+          amount: "2.5".to_string(),
+        },
+        MintPool {
+          pool_id: "pid-3".to_string(),
+          amount: "3".to_string(),
+        },
+      ]),
+    };
+    assert_eq!(
+      deserialize_brc20s(json_str).unwrap(),
+      RawOperation::Mint(mint.clone())
+    );
+    assert_eq!(
+      serde_json::to_string(&mint).unwrap(),
+      r#"{"tick":"tick","pools":[{"pid":"pid-1","amt":"1"},{"pid":"pid-2","amt":"2.5"},{"pid":"pid-3","amt":"3"}]}"#
+    );
+
+    assert_eq!(
+      mint.entries(),
+      vec![
+        Mint {
+          tick: "tick".to_string(),
+          pool_id: "pid-1".to_string(),
+          amount: "1".to_string(),
+          pools: None,
+        },
+        Mint {
+          tick: "tick".to_string(),
+          pool_id: "pid-2".to_string(),
+          amount: "2.5".to_string(),
+          pools: None,
+        },
+        Mint {
+          tick: "tick".to_string(),
+          pool_id: "pid-3".to_string(),
+          amount: "3".to_string(),
+          pools: None,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_single_mint_ignores_pools() {
+    assert_eq!(
+      deserialize_brc20s(
+        r#"{"p":"brc20-s","op":"mint","tick":"tick","pid":"pid-1","amt":"1","pools":[{"pid":"pid-2","amt":"2"}]}"#
+      )
+      .unwrap(),
+      RawOperation::Mint(Mint {
+        tick: "tick".to_string(),
+        pool_id: "pid-1".to_string(),
+        amount: "1".to_string(),
+        pools: None,
       })
     );
+    assert_eq!(
+      deserialize_brc20s(
+        r#"{"p":"brc20-s","op":"mint","tick":"tick","pid":"pid-1","pools":[{"pid":"pid-2","amt":"2"}]}"#
+      )
+      .unwrap_err(),
+      JSONError::ParseOperationJsonError("missing field `amt`".to_string())
+    );
+    assert_eq!(
+      deserialize_brc20s(
+        r#"{"p":"brc20-s","op":"mint","tick":"tick","pid":"pid-1","amt":"1","pools":1}"#
+      )
+      .unwrap(),
+      RawOperation::Mint(Mint {
+        tick: "tick".to_string(),
+        pool_id: "pid-1".to_string(),
+        amount: "1".to_string(),
+        pools: None,
+      })
+    );
+  }
+
+  #[test]
+  fn test_deserialize_invalid_batch() {
+    assert_eq!(
+      deserialize_brc20s(r#"{"p":"brc20-s","op":"mint","tick":"tick","pools":[]}"#).unwrap_err(),
+      JSONError::ParseOperationJsonError("`pools` cannot be empty".to_string())
+    );
+    let pools = vec![r#"{"pid":"pid","amt":"1"}"#; MAX_MINT_POOLS + 1].join(",");
+    assert_eq!(
+      deserialize_brc20s(&format!(
+        r#"{{"p":"brc20-s","op":"mint","tick":"tick","pools":[{pools}]}}"#
+      ))
+      .unwrap_err(),
+      JSONError::ParseOperationJsonError(format!(
+        "`pools` cannot list more than {MAX_MINT_POOLS} pools"
+      ))
+    );
+    assert_eq!(
+      deserialize_brc20s(r#"{"p":"brc20-s","op":"mint","tick":"tick","pools":[{"pid":"pid-1"}]}"#)
+        .unwrap_err(),
+      JSONError::ParseOperationJsonError("missing field `amt`".to_string())
+    );
   }
 }
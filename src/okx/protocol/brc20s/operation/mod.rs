@@ -8,10 +8,13 @@ pub mod unstake;
 use super::error::JSONError;
 use super::params::*;
 use crate::{
-  okx::datastore::{brc20s::OperationType, ord::Action},
+  okx::{
+    datastore::{brc20s::OperationType, ord::Action},
+    protocol::utils::{from_str_bounded, JsonLimits},
+  },
   Inscription, Result,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::{json, Value};
 
 pub use self::{
@@ -20,6 +23,7 @@ pub use self::{
 };
 
 #[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Operation {
   Deploy(Deploy),
   Mint(Mint),
@@ -28,6 +32,8 @@ pub enum Operation {
   PassiveUnStake(PassiveUnStake),
   InscribeTransfer(Transfer),
   Transfer(Transfer),
+  /// Operations inscribed together as a json array, executed all or none.
+  Batch(Vec<Operation>),
 }
 
 impl Operation {
@@ -40,12 +46,15 @@ impl Operation {
       Operation::PassiveUnStake(_) => OperationType::PassiveUnStake,
       Operation::InscribeTransfer(_) => OperationType::InscribeTransfer,
       Operation::Transfer(_) => OperationType::Transfer,
+      // a batch is never empty.
+      Operation::Batch(operations) => operations[0].op_type(),
     }
   }
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(tag = "op")]
+#[allow(clippy::large_enum_variant)]
 pub enum RawOperation {
   #[serde(rename = "deploy")]
   Deploy(Deploy),
@@ -66,12 +75,14 @@ pub enum RawOperation {
   Transfer(Transfer),
 }
 
+/// Deserialize the brc20s operation of an inscription, a json beyond `json_limits` is invalid.
 pub(crate) fn deserialize_brc20s_operation(
   inscription: &Inscription,
   action: &Action,
+  json_limits: JsonLimits,
 ) -> Result<Operation> {
   let content_body = std::str::from_utf8(inscription.body().ok_or(JSONError::InvalidJson)?)?;
-  if content_body.len() < 40 {
+  if content_body.len() < MIN_OPERATION_BODY_LEN {
     return Err(JSONError::NotBRC20SJson.into());
   }
 
@@ -88,15 +99,24 @@ pub(crate) fn deserialize_brc20s_operation(
     return Err(JSONError::UnSupportContentType.into());
   }
 
-  let raw_operation = match deserialize_brc20s(content_body) {
-    Ok(op) => op,
-    Err(e) => {
-      return Err(e.into());
-    }
-  };
-
-  match action {
-    Action::New { .. } => match raw_operation {
+  let value: Value = from_str_bounded(content_body, json_limits).ok_or(JSONError::InvalidJson)?;
+
+  match (value, action) {
+    // only the operations that move the balances of the inscriber can be batched, deploys and
+    // transfers are still inscribed one by one.
+    (Value::Array(values), Action::New { .. }) if !values.is_empty() => Ok(Operation::Batch(
+      values
+        .into_iter()
+        .map(|value| match deserialize_brc20s_value(value)? {
+          RawOperation::Stake(stake) => Ok(Operation::Stake(stake)),
+          RawOperation::UnStake(unstake) => Ok(Operation::UnStake(unstake)),
+          RawOperation::Mint(mint) => Ok(Operation::Mint(mint)),
+          _ => Err(JSONError::NotBRC20SJson),
+        })
+        .collect::<Result<_, _>>()?,
+    )),
+    (Value::Array(_), _) => Err(JSONError::NotBRC20SJson.into()),
+    (value, Action::New { .. }) => match deserialize_brc20s_value(value)? {
       RawOperation::Deploy(deploy) => Ok(Operation::Deploy(deploy)),
       RawOperation::Stake(stake) => Ok(Operation::Stake(stake)),
       RawOperation::UnStake(unstake) => Ok(Operation::UnStake(unstake)),
@@ -104,15 +124,30 @@ pub(crate) fn deserialize_brc20s_operation(
       RawOperation::Transfer(transfer) => Ok(Operation::InscribeTransfer(transfer)),
       RawOperation::PassiveUnStake(_) => Err(JSONError::NotBRC20SJson.into()),
     },
-    Action::Transfer => match raw_operation {
+    (value, Action::Transfer) => match deserialize_brc20s_value(value)? {
       RawOperation::Transfer(transfer) => Ok(Operation::Transfer(transfer)),
       _ => Err(JSONError::NotBRC20SJson.into()),
     },
   }
 }
 
+/// Deserializes an optional key that only has a meaning once its extension is activated, a value of
+/// another type is ignored like an unknown key, so that it can't fail an operation which parsed
+/// before the key existed.
+fn ignore_invalid<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: DeserializeOwned,
+{
+  Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+#[cfg(test)]
 pub fn deserialize_brc20s(s: &str) -> Result<RawOperation, JSONError> {
-  let value: Value = serde_json::from_str(s).map_err(|_| JSONError::InvalidJson)?;
+  deserialize_brc20s_value(serde_json::from_str(s).map_err(|_| JSONError::InvalidJson)?)
+}
+
+fn deserialize_brc20s_value(value: Value) -> Result<RawOperation, JSONError> {
   if value.get("p") != Some(&json!(PROTOCOL_LITERAL)) {
     return Err(JSONError::NotBRC20SJson);
   }
@@ -146,6 +181,9 @@ mod tests {
         decimals: Some("18".to_string()),
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       })
     );
   }
@@ -192,6 +230,7 @@ mod tests {
         tick: "tick".to_string(),
         pool_id: "pid".to_string(),
         amount: "amt".to_string(),
+        pools: None,
       })
     );
   }
@@ -282,6 +321,36 @@ mod tests {
     assert_eq!(deserialize_brc20s(json_str), Err(JSONError::InvalidJson))
   }
 
+  #[test]
+  fn test_json_too_deep() {
+    let json_str = format!(
+      r#"{{"p":"brc20-s","op":"deposit","pid":"pid","amt":"amt","x":{}1{}}}"#,
+      "[".repeat(100),
+      "]".repeat(100)
+    );
+    let inscription = crate::inscription("text/plain;charset=utf-8", &json_str);
+    let action = Action::New {
+      cursed: false,
+      unbound: false,
+      inscription: inscription.clone(),
+    };
+    assert!(deserialize_brc20s_operation(&inscription, &action, JsonLimits::default()).is_ok());
+    assert_eq!(
+      deserialize_brc20s_operation(
+        &inscription,
+        &action,
+        JsonLimits {
+          max_size: None,
+          max_depth: Some(32),
+        }
+      )
+      .unwrap_err()
+      .downcast::<JSONError>()
+      .unwrap(),
+      JSONError::InvalidJson
+    );
+  }
+
   #[test]
   fn test_deserialize_case_insensitive() {
     let json_str = r#"{
@@ -310,6 +379,123 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_min_operation_body_len() {
+    let action = Action::New {
+      cursed: false,
+      unbound: false,
+      inscription: crate::inscription("text/plain;charset=utf-8", ""),
+    };
+    let content_type = "text/plain;charset=utf-8";
+
+    let deposit = r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"1"}"#;
+    assert!(deposit.len() > MIN_OPERATION_BODY_LEN);
+    assert_eq!(
+      deserialize_brc20s_operation(
+        &crate::inscription(content_type, deposit),
+        &action,
+        JsonLimits::default()
+      )
+      .unwrap(),
+      Operation::Stake(Stake {
+        pool_id: "a3668daeaa#01".to_string(),
+        amount: "1".to_string(),
+      })
+    );
+
+    // shorter than any valid operation, but still parsed and failed on execution.
+    let invalid_pid = r#"{"p":"brc20-s","op":"deposit","pid":"a3668daea#01","amt":"1"}"#;
+    assert_eq!(
+      deserialize_brc20s_operation(
+        &crate::inscription(content_type, invalid_pid),
+        &action,
+        JsonLimits::default()
+      )
+      .unwrap(),
+      Operation::Stake(Stake {
+        pool_id: "a3668daea#01".to_string(),
+        amount: "1".to_string(),
+      })
+    );
+
+    let short = r#"{"p":"brc20-s","op":"mint","tick":"or"}"#;
+    assert_eq!(short.len(), MIN_OPERATION_BODY_LEN - 1);
+    assert_eq!(
+      deserialize_brc20s_operation(
+        &crate::inscription(content_type, short),
+        &action,
+        JsonLimits::default()
+      )
+      .unwrap_err()
+      .downcast::<JSONError>()
+      .unwrap(),
+      JSONError::NotBRC20SJson
+    );
+  }
+
+  #[test]
+  fn test_batch_deserialize() {
+    let content_type = "text/plain;charset=utf-8";
+    let new = |body: &str| {
+      deserialize_brc20s_operation(
+        &crate::inscription(content_type, body),
+        &Action::New {
+          cursed: false,
+          unbound: false,
+          inscription: crate::inscription(content_type, body),
+        },
+        JsonLimits::default(),
+      )
+    };
+
+    assert_eq!(
+      new(
+        r#"[{"p":"brc20-s","op":"withdraw","pid":"a3668daeaa#01","amt":"10"},{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#02","amt":"20"}]"#
+      )
+      .unwrap(),
+      Operation::Batch(vec![
+        Operation::UnStake(UnStake {
+          pool_id: "a3668daeaa#01".to_string(),
+          amount: "10".to_string(),
+        }),
+        Operation::Stake(Stake {
+          pool_id: "a3668daeaa#02".to_string(),
+          amount: "20".to_string(),
+        }),
+      ])
+    );
+
+    for body in [
+      // every operation of a batch must be brc20-s.
+      r#"[{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"},{"p":"brc-20","op":"mint","tick":"abcd","amt":"1"}]"#,
+      // deploys and transfers can not be batched.
+      r#"[{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"},{"p":"brc20-s","op":"transfer","tid":"tick_id","tick":"abcd","amt":"1"}]"#,
+      // nor can batches.
+      r#"[{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"},[{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"}]]"#,
+      r#"[                                                                                    ]"#,
+    ] {
+      assert_eq!(
+        new(body).unwrap_err().downcast::<JSONError>().unwrap(),
+        JSONError::NotBRC20SJson
+      );
+    }
+
+    assert_eq!(
+      deserialize_brc20s_operation(
+        &crate::inscription(
+          content_type,
+          r#"[{"p":"brc20-s","op":"transfer","tid":"tick_id","tick":"abcd","amt":"12000"}]"#
+        ),
+        &Action::Transfer,
+        JsonLimits::default(),
+      )
+      .unwrap_err()
+      .downcast::<JSONError>()
+      .unwrap(),
+      JSONError::NotBRC20SJson
+    );
+  }
+
   #[test]
   fn test_ignore_non_transfer_brc20s() {
     let content_type = "text/plain;charset=utf-8";
@@ -325,6 +511,7 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Deploy(Deploy {
@@ -337,11 +524,14 @@ mod tests {
         decimals: Some("18".to_string()),
         total_supply: Some("21000000".to_string()),
         only: Some("1".to_string()),
+        halving: None,
+        min_stake: None,
+        maturity: None,
       }),
     );
     let inscription = crate::inscription(
       content_type,
-      r#"{"p":"brc20-s","op":"deposit","pid":"pool_id","amt":"12000"}"#,
+      r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"12000"}"#,
     );
 
     assert_eq!(
@@ -352,16 +542,17 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Stake(Stake {
-        pool_id: "pool_id".to_string(),
+        pool_id: "a3668daeaa#01".to_string(),
         amount: "12000".to_string()
       })
     );
     let inscription = crate::inscription(
       content_type,
-      r#"{"p":"brc20-s","op":"mint","tick":"tick","pid":"pool_id","amt":"12000"}"#,
+      r#"{"p":"brc20-s","op":"mint","tick":"tick","pid":"a3668daeaa#01","amt":"12000"}"#,
     );
 
     assert_eq!(
@@ -372,17 +563,19 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Mint(Mint {
         tick: "tick".to_string(),
-        pool_id: "pool_id".to_string(),
-        amount: "12000".to_string()
+        pool_id: "a3668daeaa#01".to_string(),
+        amount: "12000".to_string(),
+        pools: None
       })
     );
     let inscription = crate::inscription(
       content_type,
-      r#"{"p":"brc20-s","op":"withdraw","pid":"pool_id","amt":"12000"}"#,
+      r#"{"p":"brc20-s","op":"withdraw","pid":"a3668daeaa#01","amt":"12000"}"#,
     );
 
     assert_eq!(
@@ -393,10 +586,11 @@ mod tests {
           unbound: false,
           inscription: inscription.clone()
         },
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::UnStake(UnStake {
-        pool_id: "pool_id".to_string(),
+        pool_id: "a3668daeaa#01".to_string(),
         amount: "12000".to_string()
       })
     );
@@ -407,6 +601,7 @@ mod tests {
         r#"{"p":"brc-20","op":"deploy","tick":"abcd","max":"12000","lim":"12","dec":"11"}"#
       ),
       &Action::Transfer,
+      JsonLimits::default(),
     )
     .is_err());
 
@@ -416,6 +611,7 @@ mod tests {
         r#"{"p":"brc20-s","op":"mint","tick":"abcd","amt":"12000"}"#
       ),
       &Action::Transfer,
+      JsonLimits::default(),
     )
     .is_err());
 
@@ -426,6 +622,7 @@ mod tests {
           r#"{"p":"brc20-s","op":"transfer","tid":"tick_id","tick":"abcd","amt":"12000"}"#
         ),
         &Action::Transfer,
+        JsonLimits::default(),
       )
       .unwrap(),
       Operation::Transfer(Transfer {
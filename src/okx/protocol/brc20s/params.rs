@@ -16,5 +16,11 @@ pub const POOL_TYPE: &str = "pool";
 pub const FIXED_TYPE: &str = "fixed";
 pub const PID_BYTE_COUNT: usize = 13;
 
+// Bodies shorter than this are not parsed as brc20-s operations. The shortest valid operation,
+// `{"p":"brc20-s","op":"deposit","pid":"0123456789#01","amt":"1"}`, is longer, but the bodies in
+// between have always been parsed and can leave a failed receipt, so raising it would change a
+// reindex.
+pub const MIN_OPERATION_BODY_LEN: usize = 40;
+
 pub static BIGDECIMAL_TEN: Lazy<Num> = Lazy::new(|| Num::from(10u64));
 pub static ZERO_NUM: Lazy<Num> = Lazy::new(|| Num::from(0u64));
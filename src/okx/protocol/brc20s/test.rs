@@ -25,10 +25,11 @@ pub(crate) fn mock_create_brc20s_message(
     inscription_number: 0,
     commit_input_satpoint: None,
     old_satpoint,
-    new_satpoint,
+    new_satpoint: Some(new_satpoint),
     commit_from: Some(from.clone()),
     from,
     to: Some(to),
+    to_burn_address: false,
     op,
   }
 }
@@ -65,6 +66,9 @@ pub(crate) fn mock_deploy_msg(
     total_supply: Some(supply.to_string()),
     decimals: Some(dec.to_string()),
     only,
+    halving: None,
+    min_stake: None,
+    maturity: None,
   };
 
   let execute_msg = mock_create_brc20s_message(
@@ -1,8 +1,21 @@
-use crate::okx::protocol::brc20s::params::{PID_BYTE_COUNT, TICK_ID_BYTE_COUNT};
+use crate::okx::protocol::brc20s::params::{
+  PID_BYTE_COUNT, TICK_BYTE_MAX_COUNT, TICK_BYTE_MIN_COUNT, TICK_ID_BYTE_COUNT,
+};
 use crate::okx::protocol::brc20s::BRC20SError;
 use crate::okx::protocol::brc20s::Num;
+use bigdecimal::num_bigint::Sign;
 use std::str::FromStr;
 
+// tick name of brc20-s must be 4-6 bytes and can not contain control characters
+pub fn validate_tick_name(s: &str) -> Result<(), BRC20SError> {
+  if !(TICK_BYTE_MIN_COUNT..=TICK_BYTE_MAX_COUNT).contains(&s.len())
+    || s.chars().any(char::is_control)
+  {
+    return Err(BRC20SError::InvalidTickName(s.to_string()));
+  }
+  Ok(())
+}
+
 pub fn validate_pool_str(s: &str) -> Result<(), BRC20SError> {
   if s.len() != PID_BYTE_COUNT {
     return Err(BRC20SError::InvalidPoolId(
@@ -59,10 +72,14 @@ pub fn validate_pool_str(s: &str) -> Result<(), BRC20SError> {
 }
 
 // validate input amount by user
-// it's must be less than max of u64 and positive integer
+// it's must be less than max of u64 and positive integer, a zero amount is rejected on its own
+// and a negative one can't be parsed.
 // eg. 2.000001
 pub fn validate_amount(amount: &str) -> Result<(), BRC20SError> {
   let amt = Num::from_str(amount)?;
+  if amt.sign() == Sign::NoSign {
+    return Err(BRC20SError::InvalidZeroAmount);
+  }
   if !amt.is_less_than_max_u64() || !amt.is_positive() {
     return Err(BRC20SError::InvalidNum(amount.to_string()));
   }
@@ -104,15 +121,33 @@ mod tests {
     assert_eq!(Ok(()), validate_amount(amt));
 
     let amt = "0";
+    assert_eq!(Err(BRC20SError::InvalidZeroAmount), validate_amount(amt));
+
+    let amt = "0.000";
+    assert_eq!(Err(BRC20SError::InvalidZeroAmount), validate_amount(amt));
+
+    let amt = "-1";
     assert_eq!(
       Err(BRC20SError::InvalidNum(amt.to_string())),
       validate_amount(amt)
     );
+  }
 
-    let amt = "-1";
+  #[test]
+  fn test_validate_tick_name() {
+    assert_eq!(validate_tick_name("ordi"), Ok(()));
+    assert_eq!(validate_tick_name("ordi12"), Ok(()));
     assert_eq!(
-      Err(BRC20SError::InvalidNum(amt.to_string())),
-      validate_amount(amt)
+      validate_tick_name(""),
+      Err(BRC20SError::InvalidTickName("".to_string()))
+    );
+    assert_eq!(
+      validate_tick_name("abcdefg"),
+      Err(BRC20SError::InvalidTickName("abcdefg".to_string()))
+    );
+    assert_eq!(
+      validate_tick_name("or\ndi"),
+      Err(BRC20SError::InvalidTickName("or\ndi".to_string()))
     );
   }
 
@@ -8,6 +8,23 @@ pub struct Config {
   pub allow_brc20_staking: bool,
   pub allow_brc20s_staking: bool,
   pub max_staked_pool_num: u64,
+  /// The most pools a tick can be distributed by, unlimited if unset.
+  pub max_tick_pool_num: Option<u64>,
+  /// Whether an inscription can carry a json array of operations executed all or none.
+  pub allow_batch_operations: bool,
+  /// Whether an address can have a stake in at most one `only` pool of each staked tick.
+  pub single_only_pool: bool,
+  /// Whether a mint can list several pools in `pools` instead of a single `pid` and `amt`.
+  pub allow_multi_pool_mint: bool,
+  /// Whether a deploy can set a `halving` interval of its earn rate.
+  pub allow_halving: bool,
+  /// Whether a deploy can set the `minstake` of a single deposit.
+  pub allow_min_stake: bool,
+  /// Whether a deploy can weight its rewards by stake age with a `maturity`.
+  pub allow_maturity: bool,
+  /// Whether a new earn tick is rejected when its name contains control characters or only
+  /// differs in case from an existing tick.
+  pub strict_tick_names: bool,
 }
 
 // start at block 798108
@@ -18,6 +35,14 @@ pub const fn zebra() -> Config {
     allow_brc20_staking: true,
     allow_brc20s_staking: false,
     max_staked_pool_num: 5,
+    max_tick_pool_num: None,
+    allow_batch_operations: false,
+    single_only_pool: false,
+    allow_multi_pool_mint: false,
+    allow_halving: false,
+    allow_min_stake: false,
+    allow_maturity: false,
+    strict_tick_names: false,
   }
 }
 // start at block 800310
@@ -28,6 +53,42 @@ pub const fn koala() -> Config {
     allow_brc20_staking: true,
     allow_brc20s_staking: false,
     max_staked_pool_num: 128,
+    max_tick_pool_num: None,
+    allow_batch_operations: false,
+    single_only_pool: false,
+    allow_multi_pool_mint: false,
+    allow_halving: false,
+    allow_min_stake: false,
+    allow_maturity: false,
+    strict_tick_names: false,
+  }
+}
+
+// brc20s staking, earned ticks can be deposited into another pool to compound rewards.
+// batch operations, an inscription can carry an array of mints, deposits and withdrawals.
+// single only pool, an address stakes a tick into one `only` pool at a time.
+// multi pool mint, a mint can claim the rewards of several pools at once.
+// halving, a pool deploy can halve its earn rate every interval of blocks.
+// min stake, a pool deploy can reject deposits below a minimum amount.
+// maturity, a pool deploy can weight its rewards by how long a stake has been held.
+// strict tick names, a new earn tick can not reuse an existing name in another case.
+// tick pool limit, a tick can be distributed by at most 64 pools.
+// regtest only until an activation height is scheduled for the public networks.
+pub const fn panda() -> Config {
+  Config {
+    allow_share_pool: true,
+    allow_btc_staking: false,
+    allow_brc20_staking: true,
+    allow_brc20s_staking: true,
+    max_staked_pool_num: 128,
+    max_tick_pool_num: Some(64),
+    allow_batch_operations: true,
+    single_only_pool: true,
+    allow_multi_pool_mint: true,
+    allow_halving: true,
+    allow_min_stake: true,
+    allow_maturity: true,
+    strict_tick_names: true,
   }
 }
 
@@ -45,7 +106,7 @@ pub fn get_config_by_network(network: Network, blockheight: u64) -> Config {
       n if n >= 153382 => koala(),
       _ => zebra(),
     },
-    Network::Regtest => koala(),
+    Network::Regtest => panda(),
     _ => panic!("not support network"),
   }
 }
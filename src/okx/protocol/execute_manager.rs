@@ -3,77 +3,118 @@ use {
   crate::{
     okx::{
       datastore::{
-        balance::convert_pledged_tick_without_decimal, brc20 as brc20_store,
-        brc20s as brc20s_store, StateRWriter,
+        balance::convert_pledged_tick_without_decimal,
+        brc20 as brc20_store,
+        brc20s::{self as brc20s_store, DataStoreReadOnly as _, DataStoreReadWrite as _},
+        ord::{DataStoreReadWrite as _, Protocol},
+        ScriptKey, StateRWriter,
       },
-      protocol::{brc20 as brc20_proto, brc20s as brc20s_proto},
+      protocol::{brc20 as brc20_proto, brc20s as brc20s_proto, webhook::DeployNotification},
     },
     Result,
   },
+  anyhow::anyhow,
 };
 
 pub struct CallManager<'a, RW: StateRWriter> {
   state_store: &'a RW,
+  config: &'a ProtocolConfig,
 }
 
 impl<'a, RW: StateRWriter> CallManager<'a, RW> {
-  pub fn new(state_store: &'a RW) -> Self {
-    Self { state_store }
+  pub fn new(state_store: &'a RW, config: &'a ProtocolConfig) -> Self {
+    Self {
+      state_store,
+      config,
+    }
   }
 
   pub fn execute_message(&self, context: BlockContext, msg: &Message) -> Result {
     // execute message
-    let receipt = match msg {
+    let receipts = match msg {
       Message::BRC20(msg) => brc20_proto::execute(
         context,
+        brc20_proto::get_config_by_network(context.network, context.blockheight),
         self.state_store.ord(),
         self.state_store.brc20(),
-        &brc20_proto::ExecutionMessage::from_message(self.state_store.ord(), msg, context.network)?,
+        &brc20_proto::ExecutionMessage::from_message(
+          self.state_store.ord(),
+          msg,
+          context.network,
+          &self.config.burn_addresses,
+        )?,
       )
-      .map(|v| v.map(Receipt::BRC20))?,
+      .map(|v| v.map(Receipt::BRC20).into_iter().collect::<Vec<_>>())?,
       Message::BRC20S(msg) => brc20s::execute(
         context,
+        self.config.first_brc20s_height,
         brc20s::get_config_by_network(context.network, context.blockheight),
         self.state_store.brc20(),
         self.state_store.brc20s(),
-        &brc20s::ExecutionMessage::from_message(self.state_store.ord(), msg, context.network)?,
+        &brc20s::ExecutionMessage::from_message(
+          self.state_store.ord(),
+          msg,
+          context.network,
+          &self.config.burn_addresses,
+        )?,
       )
-      .map(|v| v.map(Receipt::BRC20S))?,
+      .map(|v| v.into_iter().map(Receipt::BRC20S).collect::<Vec<_>>())?,
     };
 
-    if receipt.is_none() {
-      return Ok(());
-    };
+    for receipt in receipts {
+      self.handle_receipt(context, msg, receipt)?;
+    }
+    Ok(())
+  }
+
+  fn handle_receipt(&self, context: BlockContext, msg: &Message, receipt: Receipt) -> Result {
+    if self.config.enable_address_history {
+      match &receipt {
+        Receipt::BRC20(receipt) => {
+          self.save_receipt_refs(context, msg, Protocol::BRC20, &receipt.from, &receipt.to)?
+        }
+        Receipt::BRC20S(receipt) => {
+          self.save_receipt_refs(context, msg, Protocol::BRC20S, &receipt.from, &receipt.to)?
+        }
+      }
+    }
+
+    if self.config.enable_balance_history {
+      if let Receipt::BRC20S(receipt) = &receipt {
+        self.save_balance_history(context, receipt)?;
+      }
+    }
+
+    if let Some(webhook) = &self.config.deploy_webhook {
+      for notification in deploy_notifications(context, msg, &receipt) {
+        webhook.notify(notification);
+      }
+    }
 
     // convert receipt to internal call message
-    match receipt.unwrap() {
+    match receipt {
       Receipt::BRC20(brc20_receipt) => {
-        if let Ok(brc20_store::Event::Transfer(brc20_transfer)) = brc20_receipt.result {
-          let ptick = brc20s_store::PledgedTick::BRC20Tick(brc20_transfer.tick.clone());
+        // burning also moves the tokens out of the sender's balance.
+        let transferred = match brc20_receipt.result {
+          Ok(brc20_store::Event::Transfer(transfer)) => Some((transfer.tick, transfer.amount)),
+          Ok(brc20_store::Event::Burn(burn)) => Some((burn.tick, burn.amount)),
+          _ => None,
+        };
+        if let Some((tick, amount)) = transferred {
+          let ptick = brc20s_store::PledgedTick::BRC20Tick(tick.clone());
           match convert_pledged_tick_without_decimal(
             &ptick,
-            brc20_transfer.amount,
+            amount,
             self.state_store.brc20s(),
             self.state_store.brc20(),
           ) {
             Ok(amt) => {
               let passive_unstake = brc20s_proto::PassiveUnStake {
-                stake: brc20_transfer.tick.to_string(),
+                stake: tick.to_string(),
                 amount: amt.to_string(),
               };
               if let Message::BRC20(_) = msg {
-                let passive_msg = convert_receipt_to_passive_msg(msg, passive_unstake);
-                brc20s::execute(
-                  context,
-                  brc20s::get_config_by_network(context.network, context.blockheight),
-                  self.state_store.brc20(),
-                  self.state_store.brc20s(),
-                  &brc20s::ExecutionMessage::from_message(
-                    self.state_store.ord(),
-                    &passive_msg,
-                    context.network,
-                  )?,
-                )?;
+                self.execute_passive_unstake(context, msg, passive_unstake)?;
               }
             }
             Err(e) => {
@@ -86,11 +127,16 @@ impl<'a, RW: StateRWriter> CallManager<'a, RW> {
       Receipt::BRC20S(brc20s_receipt) => {
         if let Ok(events) = brc20s_receipt.result {
           let mut events = events.into_iter();
-          while let Some(brc20s_store::Event::Transfer(brc20s_transfer)) = events.next() {
-            let ptick = brc20s_store::PledgedTick::BRC20STick(brc20s_transfer.tick_id);
+          // burning also moves the tokens out of the sender's balance.
+          while let Some((tick_id, amt)) = events.next().and_then(|event| match event {
+            brc20s_store::Event::Transfer(transfer) => Some((transfer.tick_id, transfer.amt)),
+            brc20s_store::Event::Burn(burn) => Some((burn.tick_id, burn.amt)),
+            _ => None,
+          }) {
+            let ptick = brc20s_store::PledgedTick::BRC20STick(tick_id);
             match convert_pledged_tick_without_decimal(
               &ptick,
-              brc20s_transfer.amt,
+              amt,
               self.state_store.brc20s(),
               self.state_store.brc20(),
             ) {
@@ -100,18 +146,7 @@ impl<'a, RW: StateRWriter> CallManager<'a, RW> {
                   amount: amt.to_string(),
                 };
                 if let Message::BRC20S(_) = msg {
-                  let passive_msg = convert_receipt_to_passive_msg(msg, passive_unstake);
-                  brc20s::execute(
-                    context,
-                    brc20s::get_config_by_network(context.network, context.blockheight),
-                    self.state_store.brc20(),
-                    self.state_store.brc20s(),
-                    &brc20s::ExecutionMessage::from_message(
-                      self.state_store.ord(),
-                      &passive_msg,
-                      context.network,
-                    )?,
-                  )?;
+                  self.execute_passive_unstake(context, msg, passive_unstake)?;
                 }
               }
               Err(e) => {
@@ -124,6 +159,147 @@ impl<'a, RW: StateRWriter> CallManager<'a, RW> {
       }
     }
   }
+
+  // Executes the passive unstake caused by a transfer and handles its receipts like the ones of
+  // any other message, so they reach the address history as well.
+  fn execute_passive_unstake(
+    &self,
+    context: BlockContext,
+    msg: &Message,
+    passive_unstake: brc20s_proto::PassiveUnStake,
+  ) -> Result {
+    let passive_msg = convert_receipt_to_passive_msg(msg, passive_unstake);
+    let receipts = brc20s::execute(
+      context,
+      self.config.first_brc20s_height,
+      brc20s::get_config_by_network(context.network, context.blockheight),
+      self.state_store.brc20(),
+      self.state_store.brc20s(),
+      &brc20s::ExecutionMessage::from_message(
+        self.state_store.ord(),
+        &passive_msg,
+        context.network,
+        &self.config.burn_addresses,
+      )?,
+    )?;
+    let passive_msg = Message::BRC20S(passive_msg);
+    for receipt in receipts {
+      self.handle_receipt(context, &passive_msg, Receipt::BRC20S(receipt))?;
+    }
+    Ok(())
+  }
+
+  // Records the transaction of a receipt for its sender and receiver.
+  fn save_receipt_refs(
+    &self,
+    context: BlockContext,
+    msg: &Message,
+    protocol: Protocol,
+    from: &ScriptKey,
+    to: &ScriptKey,
+  ) -> Result {
+    let txid = match msg {
+      Message::BRC20(msg) => msg.txid,
+      Message::BRC20S(msg) => msg.txid,
+    };
+    let mut script_keys = vec![from];
+    if to != from {
+      script_keys.push(to);
+    }
+    for script_key in script_keys {
+      self
+        .state_store
+        .ord()
+        .add_receipt_ref(script_key, context.blockheight, &txid, protocol)
+        .map_err(|e| anyhow!("failed to add receipt ref to state! error: {e}"))?;
+    }
+    Ok(())
+  }
+
+  // Records the balances changed by a brc20s receipt as of the current block.
+  fn save_balance_history(&self, context: BlockContext, receipt: &brc20s_store::Receipt) -> Result {
+    for (script_key, tick_id) in balance_changes(receipt) {
+      let balance = self
+        .state_store
+        .brc20s()
+        .get_balance(&script_key, &tick_id)
+        .map_err(|e| anyhow!("failed to get balance from state! error: {e}"))?;
+      if let Some(balance) = balance {
+        self
+          .state_store
+          .brc20s()
+          .set_balance_history(&script_key, &tick_id, context.blockheight, &balance)
+          .map_err(|e| anyhow!("failed to set balance history to state! error: {e}"))?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn balance_changes(receipt: &brc20s_store::Receipt) -> Vec<(ScriptKey, brc20s_store::TickId)> {
+  let Ok(events) = &receipt.result else {
+    return Vec::new();
+  };
+  let mut changes = Vec::new();
+  for event in events {
+    match event {
+      brc20s_store::Event::Mint(mint) => changes.push((
+        receipt.to.clone(),
+        brc20s_store::TickId::from(mint.pid.clone()),
+      )),
+      brc20s_store::Event::InscribeTransfer(transfer) => {
+        changes.push((receipt.to.clone(), transfer.tick_id))
+      }
+      brc20s_store::Event::Transfer(transfer) => {
+        changes.push((receipt.from.clone(), transfer.tick_id));
+        changes.push((receipt.to.clone(), transfer.tick_id));
+      }
+      brc20s_store::Event::Burn(burn) => changes.push((receipt.from.clone(), burn.tick_id)),
+      _ => {}
+    }
+  }
+  changes
+}
+
+fn deploy_notifications(
+  context: BlockContext,
+  msg: &Message,
+  receipt: &Receipt,
+) -> Vec<DeployNotification> {
+  let txid = match msg {
+    Message::BRC20(msg) => msg.txid,
+    Message::BRC20S(msg) => msg.txid,
+  };
+  match receipt {
+    Receipt::BRC20(receipt) => match &receipt.result {
+      Ok(brc20_store::Event::Deploy(deploy)) => vec![DeployNotification {
+        protocol: brc20_proto::params::PROTOCOL_LITERAL.to_string(),
+        tick: deploy.tick.to_string(),
+        tick_id: None,
+        deployer: receipt.to.to_string(),
+        block_height: context.blockheight,
+        txid,
+      }],
+      _ => Vec::new(),
+    },
+    Receipt::BRC20S(receipt) => match &receipt.result {
+      Ok(events) => events
+        .iter()
+        .filter_map(|event| match event {
+          brc20s_store::Event::DeployTick(deploy) => Some(DeployNotification {
+            protocol: brc20s_proto::params::PROTOCOL_LITERAL.to_string(),
+            tick: deploy.name.as_str().to_string(),
+            tick_id: Some(deploy.tick_id.hex()),
+            deployer: receipt.to.to_string(),
+            block_height: context.blockheight,
+            txid,
+          }),
+          _ => None,
+        })
+        .collect(),
+      Err(_) => Vec::new(),
+    },
+  }
 }
 
 fn convert_receipt_to_passive_msg(
@@ -3,7 +3,7 @@ use crate::okx::datastore::brc20s as brc20s_store;
 use crate::okx::protocol::brc20 as brc20_proto;
 use crate::okx::protocol::brc20s as brc20s_proto;
 
-#[allow(clippy::upper_case_acronyms)]
+#[allow(clippy::upper_case_acronyms, clippy::large_enum_variant)]
 pub enum Message {
   BRC20(brc20_proto::Message),
   BRC20S(brc20s_proto::Message),
@@ -6,8 +6,9 @@ pub(crate) mod ord;
 pub(crate) mod protocol_manager;
 pub(crate) mod resolve_manager;
 mod utils;
+pub(crate) mod webhook;
 
-pub use self::protocol_manager::ProtocolManager;
+pub use self::{protocol_manager::ProtocolManager, utils::JsonLimits, webhook::DeployWebhook};
 
 use {
   self::{
@@ -15,8 +16,9 @@ use {
     message::{Message, Receipt},
     resolve_manager::MsgResolveManager,
   },
-  crate::Options,
+  crate::{okx::datastore::ScriptKey, Options, Result},
   bitcoin::Network,
+  std::collections::HashSet,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -32,10 +34,20 @@ pub struct ProtocolConfig {
   first_brc20s_height: Option<u64>,
   enable_ord_receipts: bool,
   enable_index_bitmap: bool,
+  lenient_content_type: bool,
+  json_limits: JsonLimits,
+  enable_balance_history: bool,
+  enable_address_history: bool,
+  burn_addresses: HashSet<ScriptKey>,
+  deploy_webhook: Option<DeployWebhook>,
 }
 
 impl ProtocolConfig {
-  pub(crate) fn new_with_options(options: &Options) -> Self {
+  pub(crate) fn new_with_options(
+    options: &Options,
+    deploy_webhook: Option<DeployWebhook>,
+  ) -> Result<Self> {
+    let network = options.chain().network();
     let mut config = Self {
       first_inscription_height: options.first_inscription_height(),
       first_brc20_height: if options.enable_index_brc20 {
@@ -50,11 +62,26 @@ impl ProtocolConfig {
       },
       enable_ord_receipts: options.enable_save_ord_receipts,
       enable_index_bitmap: options.enable_index_bitmap,
+      lenient_content_type: options.lenient_content_type,
+      json_limits: options.protocol_json_limits(),
+      enable_balance_history: options.enable_balance_history,
+      enable_address_history: options.enable_address_history,
+      burn_addresses: options
+        .burn_address
+        .iter()
+        .map(|address| {
+          Ok(ScriptKey::from_script(
+            &address.clone().require_network(network)?.script_pubkey(),
+            network,
+          ))
+        })
+        .collect::<Result<_>>()?,
+      deploy_webhook,
     };
 
     if config.first_brc20s_height.is_some() && config.first_brc20_height.is_none() {
       config.first_brc20_height = Some(options.first_brc20_height());
     }
-    config
+    Ok(config)
   }
 }
@@ -1,7 +1,8 @@
 use {
+  super::{utils, BlockContext},
   crate::{
-    okx::datastore::ord::{DataStoreReadWrite, InscriptionOp},
-    Result,
+    okx::datastore::ord::{DataStoreReadWrite, InscriptionOp, Protocol},
+    unbound_outpoint, Result,
   },
   anyhow::anyhow,
   bitcoin::Txid,
@@ -17,3 +18,26 @@ pub fn save_transaction_operations<O: DataStoreReadWrite>(
     .save_transaction_operations(txid, tx_operations)
     .map_err(|e| anyhow!("failed to set transaction ordinals operations to state! error: {e}"))
 }
+
+// Records the transaction for the owners before and after every inscription operation.
+pub fn save_receipt_refs<O: DataStoreReadWrite>(
+  ord_store: &O,
+  context: BlockContext,
+  txid: &Txid,
+  tx_operations: &[InscriptionOp],
+) -> Result<()> {
+  for op in tx_operations {
+    let mut satpoints = vec![op.old_satpoint];
+    satpoints.extend(
+      op.new_satpoint
+        .filter(|satpoint| satpoint.outpoint != unbound_outpoint()),
+    );
+    for satpoint in satpoints {
+      let script_key = utils::get_script_key_on_satpoint(satpoint, ord_store, context.network)?;
+      ord_store
+        .add_receipt_ref(&script_key, context.blockheight, txid, Protocol::Ord)
+        .map_err(|e| anyhow!("failed to add receipt ref to state! error: {e}"))?;
+    }
+  }
+  Ok(())
+}
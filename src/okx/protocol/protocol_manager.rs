@@ -1,14 +1,18 @@
 use {
   super::*,
   crate::{
-    index::BlockData,
     okx::{
-      datastore::{ord::operation::InscriptionOp, StateRWriter},
+      datastore::{
+        brc20s::{DataStoreReadOnly as _, DataStoreReadWrite as _, OperationCounts},
+        ord::operation::InscriptionOp,
+        StateRWriter,
+      },
       protocol::ord as ord_proto,
     },
     Instant, Result,
   },
-  bitcoin::Txid,
+  anyhow::anyhow,
+  bitcoin::{Transaction, Txid},
   bitcoincore_rpc::Client,
   std::collections::HashMap,
 };
@@ -26,7 +30,7 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
     Self {
       state_store,
       config,
-      call_man: CallManager::new(state_store),
+      call_man: CallManager::new(state_store, config),
       resolve_man: MsgResolveManager::new(client, state_store, config),
     }
   }
@@ -34,14 +38,23 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
   pub(crate) fn index_block(
     &self,
     context: BlockContext,
-    block: &BlockData,
+    txdata: &[(Transaction, Txid)],
     operations: HashMap<Txid, Vec<InscriptionOp>>,
   ) -> Result {
+    // blocks without inscription operations have no protocol activity to index.
+    if operations.is_empty() {
+      log::debug!(
+        "Protocol Manager skipped block {} without inscription operations",
+        context.blockheight
+      );
+      return Ok(());
+    }
+
     let start = Instant::now();
     let mut inscriptions_size = 0;
     let mut messages_size = 0;
     // skip the coinbase transaction.
-    for (tx, txid) in block.txdata.iter() {
+    for (tx, txid) in txdata {
       // skip coinbase transaction.
       if tx
         .input
@@ -59,6 +72,9 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
           && context.blockheight >= self.config.first_inscription_height
         {
           ord_proto::save_transaction_operations(self.state_store.ord(), txid, tx_operations)?;
+          if self.config.enable_address_history {
+            ord_proto::save_receipt_refs(self.state_store.ord(), context, txid, tx_operations)?;
+          }
           inscriptions_size += tx_operations.len();
         }
 
@@ -76,6 +92,30 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
     if self.config.enable_index_bitmap {
       bitmap_count = ord_proto::bitmap::index_bitmap(self.state_store.ord(), context, &operations)?;
     }
+    // the operation counts of the block are kept for the stats api, so a range is summed without
+    // reading the receipts of its blocks again.
+    let mut operation_counts = OperationCounts::default();
+    for txid in operations.keys() {
+      operation_counts.merge(&OperationCounts::from_receipts(
+        &self
+          .state_store
+          .brc20s()
+          .get_txid_to_receipts(txid)
+          .map_err(|e| anyhow!("failed to get receipts from state! error: {e}"))?,
+      ));
+    }
+    if operation_counts.total() > 0 {
+      self
+        .state_store
+        .brc20s()
+        .set_block_operation_counts(context.blockheight, &operation_counts)
+        .map_err(|e| anyhow!("failed to set block operation counts to state! error: {e}"))?;
+    }
+    self
+      .state_store
+      .brc20s()
+      .save_block_mutations(context.blockheight)
+      .map_err(|e| anyhow!("failed to save block mutations to state! error: {e}"))?;
 
     log::info!(
       "Protocol Manager indexed block {} with ord inscriptions {}, messages {}, bitmap {} in {} ms",
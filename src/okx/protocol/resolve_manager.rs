@@ -3,6 +3,7 @@ use {
   crate::{
     okx::{
       datastore::{
+        brc20s::{DataStoreReadWrite as _, InscriptionOperation},
         ord::{operation::InscriptionOp, DataStoreReadWrite},
         StateRWriter,
       },
@@ -44,6 +45,7 @@ impl<'a, RW: StateRWriter> MsgResolveManager<'a, RW> {
       operations
     );
     let mut messages = Vec::new();
+    let mut brc20s_operations = Vec::new();
     let mut operation_iter = operations.iter().peekable();
     let new_inscriptions = Inscription::from_transaction(tx)
       .into_iter()
@@ -67,9 +69,13 @@ impl<'a, RW: StateRWriter> MsgResolveManager<'a, RW> {
           .map(|height| context.blockheight >= height)
           .unwrap_or(false)
         {
-          if let Some(msg) =
-            brc20::Message::resolve(self.state_store.brc20(), &new_inscriptions, operation)?
-          {
+          if let Some(msg) = brc20::Message::resolve(
+            self.state_store.brc20(),
+            &new_inscriptions,
+            operation,
+            self.config.lenient_content_type,
+            self.config.json_limits,
+          )? {
             log::debug!(
               "BRC20 resolved the message from {:?}, msg {:?}",
               operation,
@@ -93,6 +99,7 @@ impl<'a, RW: StateRWriter> MsgResolveManager<'a, RW> {
             self.state_store.brc20s(),
             &new_inscriptions,
             operation,
+            self.config.json_limits,
             &mut outpoint_to_txout_cache,
           )? {
             log::debug!(
@@ -100,6 +107,7 @@ impl<'a, RW: StateRWriter> MsgResolveManager<'a, RW> {
               operation,
               msg
             );
+            brc20s_operations.push(InscriptionOperation::from(operation));
             messages.push(Message::BRC20S(msg));
             continue;
           }
@@ -107,6 +115,15 @@ impl<'a, RW: StateRWriter> MsgResolveManager<'a, RW> {
       }
     }
     self.update_outpoint_to_txout(outpoint_to_txout_cache)?;
+    // keep what was resolved, to tell apart operations which were never resolved from the ones
+    // which failed to execute.
+    if !brc20s_operations.is_empty() {
+      self
+        .state_store
+        .brc20s()
+        .set_txid_to_inscription_receipts(&tx.txid(), &brc20s_operations)
+        .map_err(|e| anyhow!("failed to set brc20s inscription operations to state! error: {e}"))?;
+    }
     Ok(messages)
   }
 
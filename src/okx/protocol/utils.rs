@@ -3,7 +3,8 @@ use crate::{
   InscriptionId, Result, SatPoint,
 };
 use anyhow::anyhow;
-use bitcoin::Network;
+use bitcoin::{Network, ScriptBuf};
+use serde_json::Value;
 
 pub(super) fn get_script_key_on_satpoint<O: DataStoreReadOnly>(
   satpoint: SatPoint,
@@ -11,7 +12,17 @@ pub(super) fn get_script_key_on_satpoint<O: DataStoreReadOnly>(
   network: Network,
 ) -> Result<ScriptKey> {
   Ok(ScriptKey::from_script(
-    &ord_store
+    &get_script_pubkey_on_satpoint(satpoint, ord_store)?,
+    network,
+  ))
+}
+
+pub(super) fn get_script_pubkey_on_satpoint<O: DataStoreReadOnly>(
+  satpoint: SatPoint,
+  ord_store: &O,
+) -> Result<ScriptBuf> {
+  Ok(
+    ord_store
       .get_outpoint_to_txout(satpoint.outpoint)
       .map_err(|e| anyhow!("failed to get tx out from state! error: {e}",))?
       .ok_or(anyhow!(
@@ -19,8 +30,7 @@ pub(super) fn get_script_key_on_satpoint<O: DataStoreReadOnly>(
         satpoint.outpoint
       ))?
       .script_pubkey,
-    network,
-  ))
+  )
 }
 
 pub(super) fn get_inscription_number_by_id<O: DataStoreReadOnly>(
@@ -35,3 +45,91 @@ pub(super) fn get_inscription_number_by_id<O: DataStoreReadOnly>(
       inscription_id
     ))
 }
+
+/// Limits of the protocol json carried by an inscription. Content beyond the limits is rejected
+/// before parsing, so adversarial inscriptions can not exhaust memory or cpu of the indexer.
+/// Both limits are unset by default, leaving only serde_json's own recursion limit of 128. The
+/// limits change which inscriptions are protocol operations, so they must be set before the
+/// first protocol block and kept the same afterwards.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct JsonLimits {
+  /// Max bytes of the json content.
+  pub max_size: Option<usize>,
+  /// Max nesting depth of json objects and arrays.
+  pub max_depth: Option<usize>,
+}
+
+/// Parse json content, returns `None` if it's invalid or exceeds the limits.
+pub(super) fn from_str_bounded(s: &str, limits: JsonLimits) -> Option<Value> {
+  if limits.max_size.map_or(false, |max_size| s.len() > max_size)
+    || limits
+      .max_depth
+      .map_or(false, |max_depth| json_depth_exceeds(s, max_depth))
+  {
+    return None;
+  }
+  serde_json::from_str(s).ok()
+}
+
+fn json_depth_exceeds(s: &str, max_depth: usize) -> bool {
+  let mut depth: usize = 0;
+  let mut in_string = false;
+  let mut escaped = false;
+  for byte in s.bytes() {
+    if in_string {
+      match byte {
+        _ if escaped => escaped = false,
+        b'\\' => escaped = true,
+        b'"' => in_string = false,
+        _ => {}
+      }
+      continue;
+    }
+    match byte {
+      b'"' => in_string = true,
+      b'{' | b'[' => {
+        depth += 1;
+        if depth > max_depth {
+          return true;
+        }
+      }
+      b'}' | b']' => depth = depth.saturating_sub(1),
+      _ => {}
+    }
+  }
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_str_bounded() {
+    let limits = JsonLimits {
+      max_size: Some(64),
+      max_depth: Some(2),
+    };
+    assert_eq!(
+      from_str_bounded(r#"{"a":[1,2]}"#, limits),
+      Some(serde_json::json!({"a":[1,2]}))
+    );
+    assert_eq!(from_str_bounded(r#"{"a":[[1]]}"#, limits), None);
+    // brackets inside strings do not count
+    assert_eq!(
+      from_str_bounded(r#"{"a":"[[[{{{\"]]]"}"#, limits),
+      Some(serde_json::json!({"a":"[[[{{{\"]]]"}))
+    );
+    assert_eq!(
+      from_str_bounded(&format!(r#"{{"a":"{}"}}"#, "x".repeat(64)), limits),
+      None
+    );
+    assert_eq!(from_str_bounded("{", limits), None);
+
+    // unbounded by default
+    assert_eq!(
+      from_str_bounded(r#"{"a":[[1]]}"#, JsonLimits::default()),
+      Some(serde_json::json!({"a":[[1]]}))
+    );
+  }
+}
@@ -0,0 +1,232 @@
+use {
+  crate::Result,
+  anyhow::anyhow,
+  bitcoin::Txid,
+  hyper::{client::HttpConnector, Body, Client, Method, Request, Uri},
+  serde::{Deserialize, Serialize},
+  std::{
+    mem,
+    sync::{
+      mpsc::{self, Receiver, SyncSender, TrySendError},
+      Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+  },
+};
+
+// Notifications are dropped once this many are waiting for delivery.
+const QUEUE_SIZE: usize = 1024;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload posted to the deploy webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeployNotification {
+  pub protocol: String,
+  pub tick: String,
+  pub tick_id: Option<String>,
+  pub deployer: String,
+  pub block_height: u64,
+  pub txid: Txid,
+}
+
+/// Posts deploy notifications to a webhook from a background thread, so that a slow or
+/// unreachable endpoint never blocks indexing. Notifications wait in `pending` until the write
+/// transaction of their block is committed, a deploy rolled back with it is never announced.
+#[derive(Debug, Clone)]
+pub struct DeployWebhook {
+  sender: SyncSender<DeployNotification>,
+  pending: Arc<Mutex<Vec<DeployNotification>>>,
+}
+
+impl DeployWebhook {
+  pub(crate) fn new(url: &str) -> Result<Self> {
+    let url = Uri::try_from(url).map_err(|e| anyhow!("invalid deploy webhook url {url}: {e}"))?;
+    if url.scheme_str() != Some("http") {
+      return Err(anyhow!("deploy webhook url must be an http:// url: {url}"));
+    }
+
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+    thread::spawn(move || Self::deliver(url, receiver));
+
+    Ok(Self {
+      sender,
+      pending: Arc::new(Mutex::new(Vec::new())),
+    })
+  }
+
+  /// Holds the notification back until `flush` is called after the commit.
+  pub(crate) fn notify(&self, notification: DeployNotification) {
+    self.pending.lock().unwrap().push(notification);
+  }
+
+  /// Sends the notifications of the committed blocks.
+  pub(crate) fn flush(&self) {
+    let pending = mem::take(&mut *self.pending.lock().unwrap());
+    for notification in pending {
+      self.send(notification);
+    }
+  }
+
+  /// Drops the notifications of blocks whose write transaction is never committed.
+  pub(crate) fn discard(&self) {
+    self.pending.lock().unwrap().clear();
+  }
+
+  fn send(&self, notification: DeployNotification) {
+    match self.sender.try_send(notification) {
+      Ok(()) => {}
+      Err(TrySendError::Full(notification)) => log::warn!(
+        "deploy webhook queue is full, dropping notification of {} {}",
+        notification.protocol,
+        notification.tick
+      ),
+      Err(TrySendError::Disconnected(notification)) => log::warn!(
+        "deploy webhook is not running, dropping notification of {} {}",
+        notification.protocol,
+        notification.tick
+      ),
+    }
+  }
+
+  fn deliver(url: Uri, receiver: Receiver<DeployNotification>) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+      .enable_all()
+      .build()
+      .unwrap();
+    let client = Client::new();
+
+    for notification in receiver {
+      if let Err(e) = rt.block_on(Self::post(&client, &url, &notification)) {
+        log::warn!(
+          "failed to deliver deploy webhook of {} {}: {e}",
+          notification.protocol,
+          notification.tick
+        );
+      }
+    }
+  }
+
+  async fn post(
+    client: &Client<HttpConnector>,
+    url: &Uri,
+    notification: &DeployNotification,
+  ) -> Result {
+    let req = Request::builder()
+      .method(Method::POST)
+      .uri(url)
+      .header(hyper::header::CONTENT_TYPE, "application/json")
+      .body(Body::from(serde_json::to_string(notification)?))?;
+
+    let response = tokio::time::timeout(DELIVERY_TIMEOUT, client.request(req))
+      .await
+      .map_err(|_| anyhow!("timed out"))??;
+
+    if !response.status().is_success() {
+      return Err(anyhow!("unexpected status {}", response.status()));
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::{
+      io::{BufRead, BufReader, Read, Write},
+      net::TcpListener,
+      str::FromStr,
+    },
+  };
+
+  // Accept a single request and return its request line and body.
+  fn serve_once(listener: TcpListener) -> (String, String) {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+
+    let mut content_length = 0;
+    loop {
+      let mut line = String::new();
+      reader.read_line(&mut line).unwrap();
+      if line.trim().is_empty() {
+        break;
+      }
+      if let Some((name, value)) = line.split_once(':') {
+        if name.eq_ignore_ascii_case("content-length") {
+          content_length = value.trim().parse().unwrap();
+        }
+      }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    let mut stream = stream;
+    stream
+      .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+      .unwrap();
+
+    (request_line, String::from_utf8(body).unwrap())
+  }
+
+  #[test]
+  fn test_post_deploy_notification() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || serve_once(listener));
+
+    let webhook = DeployWebhook::new(&format!("http://127.0.0.1:{port}/deploy")).unwrap();
+    webhook.notify(DeployNotification {
+      protocol: "brc20-s".to_string(),
+      tick: "abcdef".to_string(),
+      tick_id: Some("f7c515d6b7".to_string()),
+      deployer: "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4".to_string(),
+      block_height: 100,
+      txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
+        .unwrap(),
+    });
+    webhook.flush();
+
+    let (request_line, body) = server.join().unwrap();
+    assert!(request_line.starts_with("POST /deploy "));
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+      serde_json::json!({
+        "protocol": "brc20-s",
+        "tick": "abcdef",
+        "tick_id": "f7c515d6b7",
+        "deployer": "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4",
+        "block_height": 100,
+        "txid": "b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735",
+      })
+    );
+  }
+
+  #[test]
+  fn test_discard_uncommitted_notifications() {
+    let webhook = DeployWebhook::new("http://127.0.0.1:1/deploy").unwrap();
+    webhook.notify(DeployNotification {
+      protocol: "brc20".to_string(),
+      tick: "abcd".to_string(),
+      tick_id: None,
+      deployer: "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4".to_string(),
+      block_height: 100,
+      txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
+        .unwrap(),
+    });
+    assert_eq!(webhook.pending.lock().unwrap().len(), 1);
+
+    webhook.discard();
+    assert!(webhook.pending.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_invalid_webhook_url() {
+    assert!(DeployWebhook::new("https://example.com/deploy").is_err());
+    assert!(DeployWebhook::new("not a url").is_err());
+  }
+}
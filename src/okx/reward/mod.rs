@@ -4,6 +4,21 @@ use std::str::FromStr;
 
 const PER_SHARE_MULTIPLIER: u8 = 18;
 
+/// How reward amounts below the smallest unit of the earn tick are settled.
+/// Rounding any reward up could pay the stakers of a pool more than it has accrued in total, so
+/// flooring is the only policy and deploys can not choose another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingPolicy {
+  /// Round towards zero, the fractional remainder is left in the pool.
+  Floor,
+}
+
+/// Rounding applied to every reward amount (pool minted, user pending reward and reward debt) of
+/// every pool.
+/// Flooring guarantees the rewards paid to stakers never exceed what the pool has accrued.
+/// This rule is part of consensus and must not be changed for already indexed heights.
+pub const REWARD_ROUNDING_POLICY: RoundingPolicy = RoundingPolicy::Floor;
+
 #[cfg(not(test))]
 use log::debug;
 #[cfg(test)]
@@ -23,8 +38,36 @@ pub fn query_reward(
 ) -> Result<u128, BRC20SError> {
   let mut user_temp = user;
   let mut pool_temp = pool;
-  update_pool(&mut pool_temp, block_num, staked_decimal)?;
-  withdraw_user_reward(&mut user_temp, &pool_temp, staked_decimal)
+  accrue_user_reward(&mut user_temp, &mut pool_temp, block_num, staked_decimal)
+}
+
+/// Returns the total reward the user could claim at `block_num`, without touching the given state.
+/// Runs the same accrual steps as the executor on copies of the pool and user info.
+pub fn preview_user_reward(
+  user: &UserInfo,
+  pool: &PoolInfo,
+  block_num: u64,
+  staked_decimal: u8,
+) -> Result<u128, BRC20SError> {
+  let mut user_temp = user.clone();
+  let mut pool_temp = pool.clone();
+  if user_temp.staked == 0 {
+    return Ok(user_temp.pending_reward);
+  }
+  accrue_user_reward(&mut user_temp, &mut pool_temp, block_num, staked_decimal)?;
+  Ok(user_temp.pending_reward)
+}
+
+// update the pool up to block_num and move the user's share into pending reward,
+// do not save pool and user info when failed
+pub fn accrue_user_reward(
+  user: &mut UserInfo,
+  pool: &mut PoolInfo,
+  block_num: u64,
+  staked_decimal: u8,
+) -> Result<u128, BRC20SError> {
+  update_pool(pool, block_num, staked_decimal)?;
+  withdraw_user_reward(user, pool, staked_decimal)
 }
 
 // do not save pool_info when failed
@@ -39,7 +82,6 @@ pub fn update_pool(
   debug!("update_pool in");
   let pool_minted = Into::<Num>::into(pool.minted);
   let pool_dmax = Into::<Num>::into(pool.dmax);
-  let erate = Into::<Num>::into(pool.erate);
   let pool_stake = Into::<Num>::into(pool.staked);
   let acc_reward_per_share = Num::from_str(pool.acc_reward_per_share.as_str())?;
 
@@ -59,15 +101,14 @@ pub fn update_pool(
     return Ok(());
   }
 
-  let nums = Into::<Num>::into(block_num - pool.last_update_block);
   //2 calc reward, update minted and block num
-  let mut rewards = erate.checked_mul(&nums)?;
+  let mut rewards = accrued_rewards(pool, pool.last_update_block, block_num)?;
   if pool.ptype == PoolType::Pool {
     if pool_minted.checked_add(&rewards)? > pool_dmax {
       rewards = pool_dmax.checked_sub(&pool_minted)?;
       debug!("  beyond minted, new rewards:{}", rewards);
     }
-    pool.minted = pool_minted.checked_add(&rewards)?.truncate_to_u128()?;
+    pool.minted = round_reward(&pool_minted.checked_add(&rewards)?, REWARD_ROUNDING_POLICY)?;
 
     // calculating accRewardPerShare
     pool.acc_reward_per_share = rewards
@@ -96,9 +137,10 @@ pub fn update_pool(
       );
     }
 
-    pool.minted = pool_minted
-      .checked_add(&estimate_reward)?
-      .truncate_to_u128()?;
+    pool.minted = round_reward(
+      &pool_minted.checked_add(&estimate_reward)?,
+      REWARD_ROUNDING_POLICY,
+    )?;
 
     // calculating accRewardPerShare
     pool.acc_reward_per_share = rewards
@@ -122,7 +164,7 @@ pub fn update_pool(
 // do not save pool and user info when failed
 pub fn withdraw_user_reward(
   user: &mut UserInfo,
-  pool: &PoolInfo,
+  pool: &mut PoolInfo,
   staked_decimal: u8,
 ) -> Result<u128, BRC20SError> {
   if pool.ptype != PoolType::Pool && pool.ptype != PoolType::Fixed {
@@ -158,11 +200,21 @@ pub fn withdraw_user_reward(
        .checked_sub(&reward_debt)?;
   }
 
+  if pool.maturity.is_some() {
+    let weighted = weigh_by_stake_age(&pending_reward, user, pool)?;
+    // the forfeited part is left to be minted again instead of counting towards dmax.
+    let forfeited = round_reward(&pending_reward, REWARD_ROUNDING_POLICY)?
+      .saturating_sub(round_reward(&weighted, REWARD_ROUNDING_POLICY)?);
+    pool.minted = pool.minted.saturating_sub(forfeited);
+    pending_reward = weighted;
+  }
+
   if pending_reward > Num::zero() {
     //3 update minted of user_info and pool
-    user.pending_reward = user_reward
-      .checked_add(&pending_reward)?
-      .truncate_to_u128()?;
+    user.pending_reward = round_reward(
+      &user_reward.checked_add(&pending_reward)?,
+      REWARD_ROUNDING_POLICY,
+    )?;
   }
 
   debug!("  pending reward:{}", pending_reward);
@@ -170,7 +222,75 @@ pub fn withdraw_user_reward(
   debug!("  {}", user);
   debug!("  {}", pool);
   debug!("withdraw_user_reward out");
-  pending_reward.truncate_to_u128()
+  round_reward(&pending_reward, REWARD_ROUNDING_POLICY)
+}
+
+/// Scales a reward by the age of the stake it accrued on, in pools deployed with a maturity.
+///
+/// The reward accrued over the blocks since the user was last settled,
+/// `user.latest_updated_block + 1 ..= pool.last_update_block`, and a block pays
+/// `min(age, maturity) / maturity` of its share with `age = block - user.stake_entry_block`.
+/// The reward is weighted by the average of that over its blocks, so a stake earns its full share
+/// once it is `maturity` blocks old and the total paid does not depend on how often it is settled.
+/// Pools without a maturity return the reward as is.
+fn weigh_by_stake_age(reward: &Num, user: &UserInfo, pool: &PoolInfo) -> Result<Num, BRC20SError> {
+  let Some(maturity) = pool.maturity.filter(|maturity| *maturity > 0) else {
+    return Ok(reward.clone());
+  };
+  let blocks = pool
+    .last_update_block
+    .saturating_sub(user.latest_updated_block);
+  if blocks == 0 {
+    return Ok(reward.clone());
+  }
+  // the summed weight of the blocks of a stake up to `age`, in units of 1 / maturity.
+  let weight_until = |age: u64| -> u128 {
+    let (age, maturity) = (u128::from(age), u128::from(maturity));
+    if age <= maturity {
+      age * (age + 1) / 2
+    } else {
+      maturity * (maturity + 1) / 2 + (age - maturity) * maturity
+    }
+  };
+  let weight = weight_until(
+    pool
+      .last_update_block
+      .saturating_sub(user.stake_entry_block),
+  ) - weight_until(
+    user
+      .latest_updated_block
+      .saturating_sub(user.stake_entry_block),
+  );
+  reward
+    .checked_mul(&Num::from(weight))?
+    .checked_div(&Num::from(u128::from(maturity) * u128::from(blocks)))
+}
+
+/// Moves the stake entry block of the user towards the current block of the pool for `amount`
+/// about to be staked, as the stake-weighted average
+/// `(staked * stake_entry_block + amount * last_update_block) / (staked + amount)`.
+/// Only pools with a maturity track the entry block. Must be called before `staked` is increased.
+pub fn update_stake_entry_block(
+  user: &mut UserInfo,
+  pool: &PoolInfo,
+  amount: &Num,
+) -> Result<(), BRC20SError> {
+  if pool.maturity.is_none() {
+    return Ok(());
+  }
+  let user_staked = Into::<Num>::into(user.staked);
+  let total = user_staked.checked_add(amount)?;
+  if total <= Num::zero() {
+    return Ok(());
+  }
+  let entry_block = user_staked
+    .checked_mul(&Num::from(user.stake_entry_block))?
+    .checked_add(&amount.checked_mul(&Num::from(pool.last_update_block))?)?
+    .checked_div(&total)?
+    .truncate_to_u128()?;
+  user.stake_entry_block = u64::try_from(entry_block)
+    .map_err(|_| BRC20SError::InternalError(format!("invalid entry block {entry_block}")))?;
+  Ok(())
 }
 
 // need to update staked  before, do not user info when failed
@@ -191,16 +311,20 @@ pub fn update_user_stake(
 
   //1 update user's reward_debt
   if pool.ptype == PoolType::Pool {
-    user.reward_debt = user_staked
-      .checked_mul(&acc_reward_per_share)?
-      .checked_div(&get_per_share_multiplier())?
-      .truncate_to_u128()?;
+    user.reward_debt = round_reward(
+      &user_staked
+        .checked_mul(&acc_reward_per_share)?
+        .checked_div(&get_per_share_multiplier())?,
+      REWARD_ROUNDING_POLICY,
+    )?;
   } else if pool.ptype == PoolType::Fixed {
-    user.reward_debt = user_staked
-      .checked_mul(&acc_reward_per_share)?
-      .checked_div(&get_num_by_decimal(staked_decimal)?)?
-      .checked_div(&get_per_share_multiplier())?
-      .truncate_to_u128()?;
+    user.reward_debt = round_reward(
+      &user_staked
+        .checked_mul(&acc_reward_per_share)?
+        .checked_div(&get_num_by_decimal(staked_decimal)?)?
+        .checked_div(&get_per_share_multiplier())?,
+      REWARD_ROUNDING_POLICY,
+    )?;
   }
 
   user.latest_updated_block = pool.last_update_block;
@@ -213,6 +337,100 @@ pub fn update_user_stake(
   Ok(())
 }
 
+// estimate the blocks until dmax is exhausted at the current stake and erate, following the
+// halving schedule of the pool. returns None if nothing is staked, since the emission is stalled,
+// or if the halved erate drops to zero before dmax is exhausted.
+pub fn blocks_remaining(pool: &PoolInfo, staked_decimal: u8) -> Result<Option<u64>, BRC20SError> {
+  if pool.ptype != PoolType::Pool && pool.ptype != PoolType::Fixed {
+    return Err(BRC20SError::UnknownPoolType);
+  }
+  if pool.staked == 0 {
+    return Ok(None);
+  }
+
+  let mut remaining = Into::<Num>::into(pool.dmax).checked_sub(&Into::<Num>::into(pool.minted))?;
+  let mut blocks = 0_u128;
+  let mut block = pool.last_update_block;
+  loop {
+    let (erate, period_end) = halving_period(pool, block);
+    let erate = Into::<Num>::into(erate);
+    let per_block = if pool.ptype == PoolType::Fixed {
+      Into::<Num>::into(pool.staked)
+        .checked_mul(&erate)?
+        .checked_div(&get_num_by_decimal(staked_decimal)?)?
+    } else {
+      erate
+    };
+    if per_block <= Num::zero() {
+      return Ok(None);
+    }
+
+    let needed = remaining
+      .checked_div(&per_block)?
+      .ceil()
+      .checked_to_u128()?;
+    let Some(period_end) = period_end.filter(|period_end| u128::from(period_end - block) < needed)
+    else {
+      blocks = blocks.saturating_add(needed);
+      break;
+    };
+    remaining = remaining.checked_sub(&per_block.checked_mul(&(period_end - block).into())?)?;
+    blocks += u128::from(period_end - block);
+    block = period_end;
+  }
+
+  Ok(Some(u64::try_from(blocks).map_err(|_| {
+    BRC20SError::Overflow {
+      op: String::from("to_u64"),
+      org: blocks.to_string(),
+      other: u64::MAX.to_string(),
+    }
+  })?))
+}
+
+// the erate of the pool at `block` and the block its halving period ends at, `None` if the pool
+// has no halving schedule. blocks of the n-th interval since the deploy block earn erate / 2^n.
+fn halving_period(pool: &PoolInfo, block: u64) -> (u128, Option<u64>) {
+  match pool.halving_interval.filter(|interval| *interval > 0) {
+    Some(interval) => {
+      let halvings = block.saturating_sub(pool.deploy_block) / interval;
+      let erate = u32::try_from(halvings)
+        .ok()
+        .and_then(|halvings| pool.erate.checked_shr(halvings))
+        .unwrap_or(0);
+      let period_end = pool
+        .deploy_block
+        .saturating_add(halvings.saturating_add(1).saturating_mul(interval));
+      (erate, Some(period_end))
+    }
+    None => (pool.erate, None),
+  }
+}
+
+// the rewards the pool emits from block `from` up to block `to`, before applying dmax.
+fn accrued_rewards(pool: &PoolInfo, from: u64, to: u64) -> Result<Num, BRC20SError> {
+  let mut rewards = Num::zero();
+  let mut block = from;
+  while block < to {
+    let (erate, period_end) = halving_period(pool, block);
+    let end = period_end.map_or(to, |period_end| period_end.min(to));
+    if erate == 0 || end <= block {
+      break;
+    }
+    rewards = rewards.checked_add(&Into::<Num>::into(erate).checked_mul(&(end - block).into())?)?;
+    block = end;
+  }
+  Ok(rewards)
+}
+
+/// Settles a reward amount to whole units of the earn tick, every reward of the executor and the
+/// previews is rounded here.
+pub fn round_reward(reward: &Num, policy: RoundingPolicy) -> Result<u128, BRC20SError> {
+  match policy {
+    RoundingPolicy::Floor => reward.truncate_to_u128(),
+  }
+}
+
 fn get_per_share_multiplier() -> Num {
   get_num_by_decimal(PER_SHARE_MULTIPLIER).unwrap()
 }
@@ -246,7 +464,7 @@ mod tests {
     {
       assert_eq!(update_pool(&mut pool, 1, STAKED_DECIMAL), Ok(()));
       assert_eq!(
-        withdraw_user_reward(&mut user, &pool, STAKED_DECIMAL).expect_err(""),
+        withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL).expect_err(""),
         BRC20SError::NoStaked("bca1dabca1d#1".to_string())
       );
       user.staked += 2 * stake_base;
@@ -258,7 +476,7 @@ mod tests {
     {
       assert_eq!(update_pool(&mut pool, 2, STAKED_DECIMAL), Ok(()));
       assert_eq!(
-        withdraw_user_reward(&mut user, &pool, STAKED_DECIMAL).unwrap(),
+        withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL).unwrap(),
         2 * erate_base
       );
       user.staked -= stake_base;
@@ -275,6 +493,374 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_reward_rounding_policy() {
+    const STAKED_DECIMAL: u8 = 3;
+    assert_eq!(REWARD_ROUNDING_POLICY, RoundingPolicy::Floor);
+    assert_eq!(
+      round_reward(&Num::from_str("6.999").unwrap(), REWARD_ROUNDING_POLICY),
+      Ok(6)
+    );
+    assert_eq!(
+      round_reward(&Num::from_str("0.5").unwrap(), REWARD_ROUNDING_POLICY),
+      Ok(0)
+    );
+
+    // pool: 10 reward per block shared by 1/3 and 2/3 of the stake
+    {
+      let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+      let mut pool = new_pool(&pid, PoolType::Pool, 10, 1000);
+      let mut user1 = new_user(&pid);
+      let mut user2 = new_user(&pid);
+
+      assert_eq!(update_pool(&mut pool, 1, STAKED_DECIMAL), Ok(()));
+      user1.staked += 1;
+      user2.staked += 2;
+      pool.staked += 3;
+      assert_eq!(update_user_stake(&mut user1, &pool, STAKED_DECIMAL), Ok(()));
+      assert_eq!(update_user_stake(&mut user2, &pool, STAKED_DECIMAL), Ok(()));
+
+      assert_eq!(update_pool(&mut pool, 2, STAKED_DECIMAL), Ok(()));
+      assert_eq!(pool.minted, 10);
+      // 3.33.. and 6.66.. are both rounded down
+      assert_eq!(
+        withdraw_user_reward(&mut user1, &mut pool, STAKED_DECIMAL).unwrap(),
+        3
+      );
+      assert_eq!(
+        withdraw_user_reward(&mut user2, &mut pool, STAKED_DECIMAL).unwrap(),
+        6
+      );
+      assert_eq!(user1.pending_reward, 3);
+      assert_eq!(user2.pending_reward, 6);
+      assert!(user1.pending_reward + user2.pending_reward <= pool.minted);
+    }
+
+    // fixed: 1 reward per staked token per block with 1.5 tokens staked
+    {
+      let pid = Pid::from_str("Bca1DaBca1D#2").unwrap();
+      let mut pool = new_pool(&pid, PoolType::Fixed, 1, 1000);
+      let mut user = new_user(&pid);
+
+      assert_eq!(update_pool(&mut pool, 1, STAKED_DECIMAL), Ok(()));
+      user.staked += 1500;
+      pool.staked += 1500;
+      assert_eq!(update_user_stake(&mut user, &pool, STAKED_DECIMAL), Ok(()));
+
+      assert_eq!(update_pool(&mut pool, 2, STAKED_DECIMAL), Ok(()));
+      assert_eq!(pool.minted, 1);
+      assert_eq!(
+        withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL).unwrap(),
+        1
+      );
+      assert_eq!(update_user_stake(&mut user, &pool, STAKED_DECIMAL), Ok(()));
+      assert_eq!(user.reward_debt, 1);
+    }
+  }
+
+  #[test]
+  fn test_reward_rounding_floor() {
+    const STAKED_DECIMAL: u8 = 3;
+    for (reward, floor) in [("0.5", 0), ("1.5", 1), ("2.51", 2), ("6.999", 6), ("7", 7)] {
+      let reward = Num::from_str(reward).unwrap();
+      assert_eq!(round_reward(&reward, RoundingPolicy::Floor), Ok(floor));
+    }
+
+    // the rewards of two users staking `stakes` in a pool emitting `erate` per block, previewed and
+    // settled by the executor after one block.
+    let rewards = |erate: u128, stakes: [u128; 2]| {
+      let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+      let mut pool = new_pool(&pid, PoolType::Pool, erate, 1000);
+      let mut users = stakes.map(|staked| {
+        let mut user = new_user(&pid);
+        user.staked = staked;
+        user
+      });
+      assert_eq!(update_pool(&mut pool, 1, STAKED_DECIMAL), Ok(()));
+      pool.staked = stakes.iter().sum();
+      for user in &mut users {
+        assert_eq!(update_user_stake(user, &pool, STAKED_DECIMAL), Ok(()));
+      }
+
+      let previews = users
+        .clone()
+        .map(|user| preview_user_reward(&user, &pool, 2, STAKED_DECIMAL).unwrap());
+      assert_eq!(update_pool(&mut pool, 2, STAKED_DECIMAL), Ok(()));
+      let settled = users.map(|mut user| {
+        withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL).unwrap();
+        user.pending_reward
+      });
+      // the preview endpoint and the executor round alike.
+      assert_eq!(previews, settled);
+      settled
+    };
+
+    // 3.33.. and 6.66..
+    assert_eq!(rewards(10, [1, 2]), [3, 6]);
+
+    // 3.5 each, never more than the 7 accrued in total.
+    assert_eq!(rewards(7, [1, 1]), [3, 3]);
+  }
+
+  #[test]
+  fn test_blocks_remaining() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    // pool: 300 left at 100 per block
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 1000);
+    pool.minted = 700;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(None));
+    pool.staked = 1;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(3)));
+    pool.minted = 650;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(4)));
+    pool.minted = 1000;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(0)));
+
+    // fixed: 2.5 tokens staked earn 250 per block, 1000 left
+    let mut pool = new_pool(&pid, PoolType::Fixed, 100, 1000);
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(None));
+    pool.staked = 2500;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(4)));
+    pool.staked = 3000;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(4)));
+  }
+
+  #[test]
+  fn test_preview_user_reward() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+    pool.staked = 1000;
+    pool.last_update_block = 10;
+    let mut user = new_user(&pid);
+    user.staked = 1000;
+    user.pending_reward = 5;
+    user.latest_updated_block = 10;
+
+    let (pool_before, user_before) = (pool.clone(), user.clone());
+    assert_eq!(
+      preview_user_reward(&user, &pool, 20, STAKED_DECIMAL),
+      Ok(1005)
+    );
+    assert_eq!(pool, pool_before);
+    assert_eq!(user, user_before);
+
+    // the executor settles to the same amount
+    accrue_user_reward(&mut user, &mut pool, 20, STAKED_DECIMAL).unwrap();
+    assert_eq!(user.pending_reward, 1005);
+
+    // nothing staked, only the pending reward is claimable
+    let mut user = new_user(&pid);
+    user.pending_reward = 7;
+    assert_eq!(preview_user_reward(&user, &pool, 30, STAKED_DECIMAL), Ok(7));
+  }
+
+  #[test]
+  fn test_halving_reward() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    // erate 100 halves every 10 blocks since the deploy block 0
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+    pool.staked = 1000;
+    pool.halving_interval = Some(10);
+    let mut user = new_user(&pid);
+    user.staked = 1000;
+
+    // up to the boundary the full erate is earned, after it only half
+    assert_eq!(
+      preview_user_reward(&user, &pool, 10, STAKED_DECIMAL),
+      Ok(1000)
+    );
+    assert_eq!(
+      preview_user_reward(&user, &pool, 15, STAKED_DECIMAL),
+      Ok(1250)
+    );
+    assert_eq!(
+      preview_user_reward(&user, &pool, 20, STAKED_DECIMAL),
+      Ok(1500)
+    );
+    assert_eq!(
+      preview_user_reward(&user, &pool, 25, STAKED_DECIMAL),
+      Ok(1625)
+    );
+
+    // without halving the erate stays constant
+    let mut constant = pool.clone();
+    constant.halving_interval = None;
+    assert_eq!(
+      preview_user_reward(&user, &constant, 15, STAKED_DECIMAL),
+      Ok(1500)
+    );
+
+    // accruing across the boundary in steps earns the same as at once
+    let mut stepped_pool = pool.clone();
+    update_pool(&mut stepped_pool, 7, STAKED_DECIMAL).unwrap();
+    assert_eq!(stepped_pool.minted, 700);
+    update_pool(&mut stepped_pool, 15, STAKED_DECIMAL).unwrap();
+    assert_eq!(stepped_pool.minted, 1250);
+    assert_eq!(
+      withdraw_user_reward(&mut user.clone(), &mut stepped_pool, STAKED_DECIMAL),
+      Ok(1250)
+    );
+
+    // fixed: 2 tokens staked earn twice the halved erate
+    let mut fixed = new_pool(&pid, PoolType::Fixed, 100, 100000);
+    fixed.staked = 2000;
+    fixed.halving_interval = Some(10);
+    let mut fixed_user = new_user(&pid);
+    fixed_user.staked = 2000;
+    assert_eq!(
+      preview_user_reward(&fixed_user, &fixed, 15, STAKED_DECIMAL),
+      Ok(2500)
+    );
+
+    // the halvings count from the deploy block
+    pool.deploy_block = 5;
+    pool.last_update_block = 5;
+    assert_eq!(
+      preview_user_reward(&user, &pool, 20, STAKED_DECIMAL),
+      Ok(1250)
+    );
+
+    // once the erate is halved to nothing the pool stops emitting
+    pool.erate = 1;
+    assert_eq!(
+      preview_user_reward(&user, &pool, 10000, STAKED_DECIMAL),
+      Ok(10)
+    );
+  }
+
+  #[test]
+  fn test_halving_blocks_remaining() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    // 1000 in the first 10 blocks, then 50 per block for the remaining 500
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 1500);
+    pool.staked = 1;
+    pool.halving_interval = Some(10);
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(20)));
+    pool.minted = 1000;
+    pool.last_update_block = 10;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(Some(10)));
+
+    // the halved emission converges to 2000 and never exhausts dmax
+    pool.dmax = 2100;
+    assert_eq!(blocks_remaining(&pool, STAKED_DECIMAL), Ok(None));
+  }
+
+  #[test]
+  fn test_maturity_weighted_reward() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    // settles the reward of the user at `block` and stakes `amount` more, as the executor does.
+    let stake = |user: &mut UserInfo, pool: &mut PoolInfo, block: u64, amount: u128| {
+      update_pool(pool, block, STAKED_DECIMAL).unwrap();
+      let reward = if user.staked > 0 {
+        withdraw_user_reward(user, pool, STAKED_DECIMAL).unwrap()
+      } else {
+        0
+      };
+      update_stake_entry_block(user, pool, &Num::from(amount)).unwrap();
+      user.staked += amount;
+      pool.staked += amount;
+      update_user_stake(user, pool, STAKED_DECIMAL).unwrap();
+      reward
+    };
+
+    // alice and bob stake the same amount, alice at block 0 and bob at block 10. both settle at
+    // blocks 10 and 20, so each has earned 500 over the blocks they shared.
+    let rewards = |maturity: Option<u64>| {
+      let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+      pool.maturity = maturity;
+      let mut alice = new_user(&pid);
+      let mut bob = new_user(&pid);
+      stake(&mut alice, &mut pool, 0, 1000);
+      let alice_early = stake(&mut alice, &mut pool, 10, 0);
+      stake(&mut bob, &mut pool, 10, 1000);
+      (
+        alice_early,
+        stake(&mut alice, &mut pool, 20, 0),
+        stake(&mut bob, &mut pool, 20, 0),
+      )
+    };
+
+    // without a maturity only the amount counts
+    assert_eq!(rewards(None), (1000, 500, 500));
+    // with a maturity of 20 blocks the blocks up to 10 pay alice 1/20 to 10/20 of their share,
+    // 55/200 of 1000 on average. the blocks up to 20 pay her 11/20 to 20/20, 155/200 of 500, and
+    // bob 1/20 to 10/20, 55/200 of 500.
+    assert_eq!(rewards(Some(20)), (275, 387, 137));
+
+    // staking more moves the entry block to the stake-weighted average
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+    pool.maturity = Some(20);
+    let mut user = new_user(&pid);
+    stake(&mut user, &mut pool, 10, 1000);
+    assert_eq!(user.stake_entry_block, 10);
+    stake(&mut user, &mut pool, 30, 3000);
+    assert_eq!(user.stake_entry_block, 25);
+
+    // pools without a maturity leave the entry block alone
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+    let mut user = new_user(&pid);
+    stake(&mut user, &mut pool, 10, 1000);
+    assert_eq!(user.stake_entry_block, 0);
+  }
+
+  #[test]
+  fn test_maturity_reward_independent_of_settlement() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    // a single staker earns 100 per block, settled every `interval` blocks up to block 20.
+    let paid = |interval: u64| {
+      let mut pool = new_pool(&pid, PoolType::Pool, 100, 100000);
+      pool.maturity = Some(10);
+      pool.staked = 1000;
+      let mut user = new_user(&pid);
+      user.staked = 1000;
+      let mut paid = 0;
+      for block in (interval..=20).step_by(usize::try_from(interval).unwrap()) {
+        paid += accrue_user_reward(&mut user, &mut pool, block, STAKED_DECIMAL).unwrap();
+        update_user_stake(&mut user, &pool, STAKED_DECIMAL).unwrap();
+      }
+      (paid, pool.minted)
+    };
+
+    // the first 10 blocks pay 10 to 100, the next 10 blocks pay 100 each.
+    assert_eq!(paid(1), (1550, 1550));
+    assert_eq!(paid(5), (1550, 1550));
+    assert_eq!(paid(20), (1550, 1550));
+  }
+
+  #[test]
+  fn test_maturity_pays_out_dmax() {
+    const STAKED_DECIMAL: u8 = 3;
+    let pid = Pid::from_str("Bca1DaBca1D#1").unwrap();
+
+    let mut pool = new_pool(&pid, PoolType::Pool, 100, 1000);
+    pool.maturity = Some(10);
+    pool.staked = 1000;
+    let mut user = new_user(&pid);
+    user.staked = 1000;
+
+    // the forfeited rewards of the young stake are minted again later instead of exhausting dmax.
+    let mut paid = 0;
+    for block in (2..=40).step_by(2) {
+      paid += accrue_user_reward(&mut user, &mut pool, block, STAKED_DECIMAL).unwrap();
+      update_user_stake(&mut user, &pool, STAKED_DECIMAL).unwrap();
+    }
+    assert_eq!(paid, 1000);
+    assert_eq!(pool.minted, 1000);
+    assert_eq!(user.pending_reward, 1000);
+  }
+
   #[test]
   fn test_complex_fix_one_user() {
     const STAKED_DECIMAL: u8 = 3;
@@ -1340,7 +1926,7 @@ mod tests {
     );
 
     assert_eq!(
-      withdraw_user_reward(&mut user, &pool, STAKED_DECIMAL),
+      withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL),
       Err(BRC20SError::UnknownPoolType)
     );
 
@@ -1728,7 +2314,7 @@ mod tests {
     assert_eq!(update_pool(&mut pool, 100, STAKED_DECIMAL), Ok(()));
 
     assert_eq!(
-      withdraw_user_reward(&mut user, &pool, STAKED_DECIMAL),
+      withdraw_user_reward(&mut user, &mut pool, STAKED_DECIMAL),
       Err(BRC20SError::NoStaked(pid.as_str().to_string()))
     );
 
@@ -3516,7 +4102,7 @@ mod tests {
 
     //first
     let _ = update_pool(&mut pool, 1, staked_decimal);
-    let _ = withdraw_user_reward(&mut user1, &pool, staked_decimal);
+    let _ = withdraw_user_reward(&mut user1, &mut pool, staked_decimal);
     user1.staked += stake_base
       .checked_mul(&stake1)
       .unwrap()
@@ -3527,11 +4113,11 @@ mod tests {
       .unwrap()
       .truncate_to_u128()
       .unwrap();
-    let _ = withdraw_user_reward(&mut user1, &pool, staked_decimal);
+    let _ = withdraw_user_reward(&mut user1, &mut pool, staked_decimal);
     let _ = update_user_stake(&mut user1, &pool, staked_decimal);
 
     let _ = update_pool(&mut pool, 1, staked_decimal);
-    let _ = withdraw_user_reward(&mut user2, &pool, staked_decimal);
+    let _ = withdraw_user_reward(&mut user2, &mut pool, staked_decimal);
     user2.staked += stake_base
       .checked_mul(&stake2)
       .unwrap()
@@ -3542,13 +4128,13 @@ mod tests {
       .unwrap()
       .truncate_to_u128()
       .unwrap();
-    let _ = withdraw_user_reward(&mut user2, &pool, staked_decimal);
+    let _ = withdraw_user_reward(&mut user2, &mut pool, staked_decimal);
     let _ = update_user_stake(&mut user2, &pool, staked_decimal);
 
     //second
     let _ = update_pool(&mut pool, 11, staked_decimal);
-    let reward1 = withdraw_user_reward(&mut user1, &pool, staked_decimal).unwrap();
-    let reward2 = withdraw_user_reward(&mut user2, &pool, staked_decimal).unwrap();
+    let reward1 = withdraw_user_reward(&mut user1, &mut pool, staked_decimal).unwrap();
+    let reward2 = withdraw_user_reward(&mut user2, &mut pool, staked_decimal).unwrap();
     assert_eq!(
       reward1,
       Num::from_str(expect1).unwrap().truncate_to_u128().unwrap()
@@ -3627,6 +4213,9 @@ mod tests {
       only: true,
       deploy_block: 0,
       deploy_block_time: 10000,
+      halving_interval: None,
+      min_stake: None,
+      maturity: None,
     }
   }
 
@@ -3638,6 +4227,7 @@ mod tests {
       pending_reward: 0,
       reward_debt: 0,
       latest_updated_block: 0,
+      stake_entry_block: 0,
     }
   }
 
@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::str::FromStr;
-use {super::*, bitcoincore_rpc::Auth};
+use {super::*, crate::okx::protocol::JsonLimits, bitcoincore_rpc::Auth};
 
 #[derive(Clone, Default, Debug, Parser)]
 #[command(group(
@@ -12,6 +12,13 @@ use {super::*, bitcoincore_rpc::Auth};
 pub(crate) struct Options {
   #[arg(long, default_value_t=LogLevel::default(), help = "log level")]
   pub(crate) log_level: LogLevel,
+  #[arg(
+    long,
+    value_enum,
+    default_value = "human",
+    help = "Write log lines in <LOG_FORMAT>."
+  )]
+  pub(crate) log_format: logger::LogFormat,
   #[arg(long, help = "write log in directory <LOG_DIR>")]
   pub(crate) log_dir: Option<PathBuf>,
   #[arg(long, help = "Load Bitcoin Core data dir from <BITCOIN_DATA_DIR>.")]
@@ -37,7 +44,9 @@ pub(crate) struct Options {
   pub(crate) data_dir: Option<PathBuf>,
   #[arg(
     long,
-    help = "Set index cache to <DB_CACHE_SIZE> bytes. By default takes 1/4 of available RAM."
+    alias = "redb-cache-size",
+    value_parser = parse_db_cache_size,
+    help = "Set index cache to <DB_CACHE_SIZE> bytes, at least 16 MiB. By default takes 1/4 of available RAM."
   )]
   pub(crate) db_cache_size: Option<usize>,
   #[arg(
@@ -83,6 +92,58 @@ pub(crate) struct Options {
   pub(crate) enable_save_ord_receipts: bool,
   #[arg(long, help = "Enable Index Bitmap Collection.")]
   pub(crate) enable_index_bitmap: bool,
+  #[arg(
+    long,
+    help = "POST a JSON notification to http://<DEPLOY_WEBHOOK> whenever a BRC20 or BRC20S tick is deployed."
+  )]
+  pub(crate) deploy_webhook: Option<String>,
+  #[arg(
+    long,
+    help = "Parse BRC20 inscriptions without a content type. Off by default, enabling it changes the indexed BRC20 state, so an index only opens with the setting it was created with."
+  )]
+  pub(crate) lenient_content_type: bool,
+  #[arg(
+    long,
+    help = "Record BRC20S balances at every block they change to serve historical balance queries. Costs one extra row per changed balance per block, enable it before the first BRC20S block."
+  )]
+  pub(crate) enable_balance_history: bool,
+  #[arg(
+    long,
+    help = "Index the transactions with ord, BRC20 or BRC20S receipts of every address to serve address activity queries, ord receipts are only indexed with --enable-save-ord-receipts. Enable it before the first inscription block."
+  )]
+  pub(crate) enable_address_history: bool,
+  #[arg(
+    long,
+    help = "Record the BRC20S balance and pool changes of the blocks a reorg can still roll back and serve them at /api/v1/brc20s/debug/block/:height/mutations."
+  )]
+  pub(crate) enable_block_mutations: bool,
+  #[arg(
+    long,
+    default_value = "10000",
+    help = "Cache the BRC20S operations parsed from up to <BRC20S_OPERATION_CACHE_SIZE> queried inscriptions. 0 disables the cache."
+  )]
+  pub(crate) brc20s_operation_cache_size: usize,
+  #[arg(
+    long,
+    help = "Treat <BURN_ADDRESS> as a burn sink: BRC20 and BRC20S transfers into it are burned instead of credited. May be repeated. Changes the indexed BRC20 and BRC20S state, so an index only opens with the burn addresses it was created with."
+  )]
+  pub(crate) burn_address: Vec<Address<NetworkUnchecked>>,
+  #[arg(
+    long,
+    default_value = "0",
+    help = "Execute the BRC20 and BRC20S operations of a block only once <PROTOCOL_CONFIRMATIONS> more blocks are indexed on top of it, so the protocol state of blocks a reorg could still replace is never served. Reorgs within the savepoint depth are rolled back with the rest of the index either way. The ord index itself stays at the chain tip."
+  )]
+  pub(crate) protocol_confirmations: u64,
+  #[arg(
+    long,
+    help = "Ignore BRC20 and BRC20S inscriptions whose json is larger than <PROTOCOL_JSON_MAX_SIZE> bytes. Unbounded by default. Changes the indexed BRC20 and BRC20S state, set it before the first BRC20 block."
+  )]
+  pub(crate) protocol_json_max_size: Option<usize>,
+  #[arg(
+    long,
+    help = "Ignore BRC20 and BRC20S inscriptions whose json nests objects and arrays deeper than <PROTOCOL_JSON_MAX_DEPTH>. Bounded only by the json parser's own limit of 128 by default. Changes the indexed BRC20 and BRC20S state, set it before the first BRC20 block."
+  )]
+  pub(crate) protocol_json_max_depth: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -107,6 +168,19 @@ impl FromStr for LogLevel {
   }
 }
 
+// smaller caches make redb thrash on every block.
+const MIN_DB_CACHE_SIZE: usize = 16 * 1024 * 1024;
+
+fn parse_db_cache_size(s: &str) -> Result<usize, String> {
+  let db_cache_size = s.parse::<usize>().map_err(|err| err.to_string())?;
+  if db_cache_size < MIN_DB_CACHE_SIZE {
+    return Err(format!(
+      "db cache size must be at least {MIN_DB_CACHE_SIZE} bytes"
+    ));
+  }
+  Ok(db_cache_size)
+}
+
 impl Options {
   pub(crate) fn chain(&self) -> Chain {
     if self.signet {
@@ -120,6 +194,17 @@ impl Options {
     }
   }
 
+  pub(crate) fn db_cache_size(&self) -> Result<usize> {
+    match self.db_cache_size {
+      Some(db_cache_size) => Ok(db_cache_size),
+      None => {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        Ok(usize::try_from(sys.total_memory() / 4)?)
+      }
+    }
+  }
+
   pub(crate) fn first_inscription_height(&self) -> u64 {
     if self.chain() == Chain::Regtest {
       self.first_inscription_height.unwrap_or(0)
@@ -156,6 +241,13 @@ impl Options {
     }
   }
 
+  pub(crate) fn protocol_json_limits(&self) -> JsonLimits {
+    JsonLimits {
+      max_size: self.protocol_json_max_size,
+      max_depth: self.protocol_json_max_depth,
+    }
+  }
+
   pub(crate) fn rpc_url(&self) -> String {
     if let Some(rpc_url) = &self.rpc_url {
       format!("{rpc_url}/wallet/{}", self.wallet)
@@ -316,7 +408,6 @@ impl Options {
     Ok(client)
   }
 
-  #[allow(unused)]
   pub(crate) fn bitcoin_rpc_client_for_wallet_command(&self, create: bool) -> Result<Client> {
     let client = self.bitcoin_rpc_client()?;
 
@@ -848,4 +939,16 @@ mod tests {
       Arguments::try_parse_from(["ord", "--db-cache-size", "16000000000", "index", "run"]).unwrap();
     assert_eq!(arguments.options.db_cache_size, Some(16000000000));
   }
+
+  #[test]
+  fn setting_redb_cache_size() {
+    let arguments =
+      Arguments::try_parse_from(["ord", "--redb-cache-size", "16777216", "index", "run"]).unwrap();
+    assert_eq!(arguments.options.db_cache_size().unwrap(), 16777216);
+
+    assert!(
+      Arguments::try_parse_from(["ord", "--redb-cache-size", "16777215", "index", "run"]).is_err()
+    );
+    assert!(Arguments::try_parse_from(["ord", "--db-cache-size", "lots", "index", "run"]).is_err());
+  }
 }
@@ -1,21 +1,38 @@
 use super::*;
 
+mod audit;
 pub mod decode;
+mod export_brc20_balances;
+mod export_receipts;
 mod index;
 mod server;
+mod verify_brc20s;
 pub mod wallet;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+  #[command(about = "Check the consistency of protocol state in the index")]
+  Audit(audit::Audit),
+  #[command(about = "Write brc20 balances of all addresses to a CSV file")]
+  ExportBrc20Balances(export_brc20_balances::ExportBrc20Balances),
+  #[command(about = "Write protocol receipts of a block range as NDJSON")]
+  ExportReceipts(export_receipts::ExportReceipts),
   #[command(subcommand, about = "Index commands")]
   Index(index::IndexSubcommand),
   #[command(about = "Run the explorer server")]
   Server(server::Server),
+  #[command(about = "Verify brc20s supplies and transferable assets against the balances")]
+  VerifyBrc20s(verify_brc20s::VerifyBrc20s),
+  #[command(subcommand, about = "Wallet commands")]
+  Wallet(wallet::Wallet),
 }
 
 impl Subcommand {
   pub(crate) fn run(self, options: Options) -> SubcommandResult {
     match self {
+      Self::Audit(audit) => audit.run(options),
+      Self::ExportBrc20Balances(export) => export.run(options),
+      Self::ExportReceipts(export) => export.run(options),
       Self::Index(index) => index.run(options),
       Self::Server(server) => {
         let index = Arc::new(Index::open(&options)?);
@@ -23,6 +40,8 @@ impl Subcommand {
         LISTENERS.lock().unwrap().push(handle.clone());
         server.run(options, index, handle)
       }
+      Self::VerifyBrc20s(verify) => verify.run(options),
+      Self::Wallet(wallet) => wallet.run(options),
     }
   }
 }
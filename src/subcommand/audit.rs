@@ -0,0 +1,31 @@
+use {super::*, crate::okx::datastore::brc20::TransferableMismatch};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Audit {}
+
+#[derive(Serialize)]
+pub struct Output {
+  pub brc20_transferable: Vec<TransferableMismatch>,
+}
+
+impl Audit {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    index.update()?;
+
+    let brc20_transferable = index.brc20_audit_transferable()?;
+    for mismatch in &brc20_transferable {
+      log::error!(
+        "brc20 transferable mismatch: script {} tick {} overall {} transferable {} logs total {}",
+        mismatch.script,
+        mismatch.tick,
+        mismatch.overall_balance,
+        mismatch.transferable_balance,
+        mismatch.transferable_total
+      );
+    }
+
+    Ok(Box::new(Output { brc20_transferable }))
+  }
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct ExportBrc20Balances {
+  #[arg(long, help = "Export balances as of block <HEIGHT>")]
+  height: u64,
+  #[arg(long, help = "<CSV> file to write to")]
+  output: PathBuf,
+}
+
+impl ExportBrc20Balances {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    index.update()?;
+    index.export_brc20_balances(&self.output, self.height)?;
+
+    Ok(Box::new(Empty {}))
+  }
+}
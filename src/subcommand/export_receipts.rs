@@ -0,0 +1,59 @@
+use {super::*, crate::okx::datastore::ord::Protocol, clap::ValueEnum, std::io::BufWriter};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ReceiptProtocol {
+  Brc20,
+  Brc20s,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct ExportReceipts {
+  #[arg(long, help = "Export the receipts of <PROTOCOL>")]
+  protocol: ReceiptProtocol,
+  #[arg(long, help = "Export receipts from block <FROM_HEIGHT>")]
+  from_height: u64,
+  #[arg(long, help = "Export receipts up to and including block <TO_HEIGHT>")]
+  to_height: u64,
+  #[arg(long, help = "<NDJSON> file to write to")]
+  output: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub receipts: u64,
+}
+
+impl ExportReceipts {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    if self.from_height > self.to_height {
+      bail!(
+        "--from-height {} is above --to-height {}",
+        self.from_height,
+        self.to_height
+      );
+    }
+
+    let index = Index::open(&options)?;
+
+    index.update()?;
+
+    let protocol = match self.protocol {
+      ReceiptProtocol::Brc20 => Protocol::BRC20,
+      ReceiptProtocol::Brc20s => Protocol::BRC20S,
+    };
+
+    log::info!(
+      "exporting {:?} receipts from height {} to {} to {}",
+      protocol,
+      self.from_height,
+      self.to_height,
+      self.output.display()
+    );
+
+    let mut writer = BufWriter::new(File::create(&self.output)?);
+    let receipts =
+      index.export_receipts(protocol, self.from_height, self.to_height, &mut writer)?;
+
+    Ok(Box::new(Output { receipts }))
+  }
+}
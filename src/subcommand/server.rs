@@ -18,8 +18,9 @@ use {
     extract::{Extension, Json, Path, Query},
     headers::UserAgent,
     http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    middleware,
     response::{IntoResponse, Redirect, Response},
-    routing::get,
+    routing::{get, post},
     Router, TypedHeader,
   },
   axum_server::Handle,
@@ -40,15 +41,20 @@ use {
   utoipa::OpenApi,
 };
 
+mod address;
 mod api;
+mod batch;
 mod brc20;
 mod brc20s;
+mod debug;
 mod info;
 mod ord;
 mod types;
 
 mod accept_json;
+mod cache;
 mod error;
+mod page_size;
 mod response;
 
 use self::api::*;
@@ -142,6 +148,36 @@ pub(crate) struct Server {
   https: bool,
   #[arg(long, help = "Redirect HTTP traffic to HTTPS.")]
   redirect_http_to_https: bool,
+  #[arg(
+    long,
+    default_value = "16",
+    help = "Simulate at most <MAX_SIMULATIONS> unconfirmed transactions at once, excess requests are rejected with 429. Set to 0 to disable simulations."
+  )]
+  max_simulations: usize,
+  #[arg(
+    long,
+    help = "Serve /api/v1/debug/parse, which reports how each protocol parses a posted inscription payload."
+  )]
+  enable_debug_api: bool,
+  #[arg(
+    long,
+    default_value = "3",
+    help = "Respond to /api/v1/status with 503 when the index is more than <MAX_INDEX_LAG> blocks behind bitcoind."
+  )]
+  max_index_lag: u64,
+  #[arg(
+    long,
+    default_value = "true",
+    action = clap::ArgAction::Set,
+    help = "Compress responses with gzip or brotli when the client accepts them."
+  )]
+  enable_compression: bool,
+  #[arg(
+    long,
+    default_value = "500",
+    help = "Lower a `limit` above <MAX_PAGE_SIZE> to <MAX_PAGE_SIZE>, reporting it in X-Page-Limit."
+  )]
+  max_page_size: usize,
 }
 
 impl Server {
@@ -165,34 +201,60 @@ impl Server {
         paths(
           brc20::brc20_balance,
           brc20::brc20_all_balance,
+          brc20::brc20_batch_balance,
           brc20::brc20_tick_info,
           brc20::brc20_all_tick_info,
           brc20::brc20_tx_events,
           brc20::brc20_block_events,
+          brc20::brc20_tick_history,
           brc20::brc20_transferable,
           brc20::brc20_all_transferable,
 
           brc20s::brc20s_tick_info,
+          brc20s::brc20s_tick_deploy,
           brc20s::brc20s_all_tick_info,
+          brc20s::brc20s_deployed_ticks,
           brc20s::brc20s_balance,
+          brc20s::brc20s_balance_at,
           brc20s::brc20s_all_balance,
           brc20s::brc20s_pool_info,
+          brc20s::brc20s_tick_stake_pool,
           brc20s::brc20s_all_pool_info,
           brc20s::brc20s_all_pools_by_tid,
+          brc20s::brc20s_pools_by_stake,
+          brc20s::brc20s_pool_blocks_remaining,
+          brc20s::brc20s_stake_ticks,
+          brc20s::brc20s_earn_ticks_by_stake,
           brc20s::brc20s_txid_receipts,
+          brc20s::brc20s_txid_inscriptions,
           brc20s::brc20s_block_receipts,
+          brc20s::brc20s_operation_stats,
           brc20s::brc20s_transferable,
           brc20s::brc20s_all_transferable,
+          brc20s::brc20s_pending_transfers,
           brc20s::brc20s_userinfo,
+          brc20s::brc20s_user_reward,
+          brc20s::brc20s_address_rewards,
+          brc20s::brc20s_address_pools,
           brc20s::brc20s_stake_info,
+          brc20s::brc20s_address_stakes,
+
+          address::address_tokens,
+          address::address_history,
+
+          batch::batch,
 
           ord::ord_inscription_id,
           ord::ord_inscription_number,
           ord::ord_outpoint,
           ord::ord_txid_inscriptions,
+          ord::ord_txid_operations,
           ord::ord_block_inscriptions,
+          ord::ord_bitmap_district,
+          ord::ord_inscription_collections,
 
           info::node_info,
+          info::index_status,
         ),
         components(schemas(
 
@@ -201,6 +263,9 @@ impl Server {
           brc20::AllTickInfo,
           brc20::Balance,
           brc20::AllBalance,
+          brc20::BatchBalanceRequest,
+          brc20::AddressBalance,
+          brc20::BatchBalance,
           brc20::TxEvent,
           brc20::DeployEvent,
           brc20::MintEvent,
@@ -209,6 +274,8 @@ impl Server {
           brc20::ErrorEvent,
           brc20::TxEvents,
           brc20::BlockEvents,
+          brc20::TickEvent,
+          brc20::TickEvents,
           brc20::TransferableInscription,
           brc20::TransferableInscriptions,
 
@@ -217,8 +284,10 @@ impl Server {
           response::BRC20AllTick,
           response::BRC20Balance,
           response::BRC20AllBalance,
+          response::BRC20BatchBalance,
           response::BRC20TxEvents,
           response::BRC20BlockEvents,
+          response::BRC20TickEvents,
           response::BRC20Transferable,
 
           // BRC20S schemas
@@ -227,11 +296,18 @@ impl Server {
           brc20s::Stake,
           brc20s::Earn,
           brc20s::TickInfo,
+          brc20s::TickDeploy,
           brc20s::AllTickInfo,
+          brc20s::DeployedTicks,
           brc20s::Balance,
           brc20s::AllBalance,
           brc20s::Pool,
           brc20s::AllPoolInfo,
+          brc20s::PoolBlocksRemaining,
+          brc20s::StakeTick,
+          brc20s::StakeTicks,
+          brc20s::EarnTick,
+          brc20s::EarnTicks,
           brc20s::OperationType,
           brc20s::Event,
           brc20s::DeployTickEvent,
@@ -244,23 +320,63 @@ impl Server {
           brc20s::TransferEvent,
           brc20s::Receipt,
           brc20s::TxReceipts,
+          brc20s::TxInscription,
+          brc20s::TxInscriptions,
           brc20s::BlockReceipts,
+          brc20s::OperationStats,
           brc20s::TransferableInscription,
           brc20s::Transferable,
+          brc20s::PendingTransfer,
+          brc20s::PendingTransfers,
           brc20s::UserInfo,
+          brc20s::UserReward,
+          brc20s::PoolReward,
+          brc20s::TickReward,
+          brc20s::AddressRewards,
+          brc20s::UserPool,
+          brc20s::AddressPools,
           brc20s::StakedInfo,
           brc20s::StakedPid,
+          brc20s::AddressStakes,
 
           // BRC20S responses schemas
           response::BRC20STick,
+          response::BRC20STickDeploy,
           response::BRC20SAllTick,
+          response::BRC20SDeployedTicks,
           response::BRC20SBalance,
           response::BRC20SAllBalance,
           response::BRC20SPool,
           response::BRC20SAllPool,
+          response::BRC20SPoolBlocksRemaining,
+          response::BRC20SStakeTicks,
+          response::BRC20SEarnTicks,
           response::BRC20STxReceipts,
+          response::BRC20STxInscriptions,
           response::BRC20SBlockReceipts,
-          response::BRC20Transferable,
+          response::BRC20SOperationStats,
+          response::BRC20STransferable,
+          response::BRC20SPendingTransfers,
+          response::BRC20SUserInfo,
+          response::BRC20SUserReward,
+          response::BRC20SAddressRewards,
+          response::BRC20SAddressPools,
+          response::BRC20SStakedInfo,
+          response::BRC20SAddressStakes,
+
+          // Address schemas
+          address::Tokens,
+          address::TxActivity,
+          address::History,
+
+          // Address responses schemas
+          response::AddressTokens,
+          response::AddressHistory,
+
+          // Batch schemas
+          batch::Call,
+          batch::Batch,
+          response::Batch,
 
           // Ord schemas
           ord::OrdInscription,
@@ -271,19 +387,26 @@ impl Server {
           ord::TxInscription,
           ord::TxInscriptions,
           ord::BlockInscriptions,
+          ord::BitmapDistrict,
+          ord::InscriptionCollections,
 
           // Ord responses schemas
           response::OrdOrdInscription,
           response::OrdTxInscriptions,
           response::OrdBlockInscriptions,
           response::OrdOutPointResult,
+          response::OrdBitmapDistrict,
+          response::OrdInscriptionCollections,
 
 
           // Node Info schemas
           info::NodeInfo,
           info::ChainInfo,
+          info::IndexStatus,
           types::ScriptPubkey,
+          types::SatInfo,
           response::Node,
+          response::Status,
           ApiError
         ))
       )]
@@ -305,6 +428,7 @@ impl Server {
           get(|| async { ApiDoc::openapi().to_pretty_json().unwrap() }),
         )
         .route("/node/info", get(info::node_info))
+        .route("/status", get(info::index_status))
         .route("/ord/id/:id/inscription", get(ord::ord_inscription_id))
         .route(
           "/ord/number/:number/inscription",
@@ -315,16 +439,29 @@ impl Server {
           "/ord/tx/:txid/inscriptions",
           get(ord::ord_txid_inscriptions),
         )
+        .route(
+          "/ord/tx/:txid/operations",
+          get(ord::ord_txid_operations),
+        )
         .route(
           "/ord/block/:blockhash/inscriptions",
           get(ord::ord_block_inscriptions),
         )
+        .route("/ord/bitmap/:number", get(ord::ord_bitmap_district))
+        .route(
+          "/ord/inscription/:id/collections",
+          get(ord::ord_inscription_collections),
+        )
         .route(
           "/ord/debug/bitmap/district/:number",
           get(ord::ord_debug_bitmap_district),
         )
-        .route("/brc20/tick/:tick", get(brc20::brc20_tick_info))
+        .route(
+          "/brc20/tick/:tick",
+          get(brc20::brc20_tick_info).layer(middleware::from_fn(cache::revalidate)),
+        )
         .route("/brc20/tick", get(brc20::brc20_all_tick_info))
+        .route("/brc20/tick/:tick/history", get(brc20::brc20_tick_history))
         .route(
           "/brc20/tick/:tick/address/:address/balance",
           get(brc20::brc20_balance),
@@ -333,6 +470,7 @@ impl Server {
           "/brc20/address/:address/balance",
           get(brc20::brc20_all_balance),
         )
+        .route("/brc20/balances", post(brc20::brc20_batch_balance))
         .route(
           "/brc20/tick/:tick/address/:address/transferable",
           get(brc20::brc20_transferable),
@@ -341,24 +479,55 @@ impl Server {
           "/brc20/address/:address/transferable",
           get(brc20::brc20_all_transferable),
         )
-        .route("/brc20/tx/:txid/events", get(brc20::brc20_tx_events))
+        .route(
+          "/brc20/tx/:txid/events",
+          get(brc20::brc20_tx_events).layer(middleware::from_fn(cache::revalidate)),
+        )
         .route("/brc20/tx/:txid", get(brc20::brc20_tx))
+        .route(
+          "/brc20/tx/:txid/operations",
+          get(brc20::brc20_tx_operations),
+        )
         .route(
           "/brc20/block/:block_hash/events",
-          get(brc20::brc20_block_events),
+          get(brc20::brc20_block_events).layer(middleware::from_fn(cache::immutable)),
         )
+        .route("/address/:address/tokens", get(address::address_tokens))
+        .route("/address/:address/history", get(address::address_history))
+        .route("/batch", post(batch::batch))
         .route("/brc20s/tick", get(brc20s::brc20s_all_tick_info))
-        .route("/brc20s/tick/:tick_id", get(brc20s::brc20s_tick_info))
+        .route("/brc20s/ticks", get(brc20s::brc20s_deployed_ticks))
+        .route(
+          "/brc20s/tick/:tick_id",
+          get(brc20s::brc20s_tick_info).layer(middleware::from_fn(cache::revalidate)),
+        )
+        .route(
+          "/brc20s/tick/:tick_id/deploy",
+          get(brc20s::brc20s_tick_deploy),
+        )
         .route(
           "/brc20s/debug/tick/:tick_id",
           get(brc20s::brc20s_debug_tick_info),
         )
         .route("/brc20s/pool", get(brc20s::brc20s_all_pool_info))
         .route("/brc20s/pool/:pid", get(brc20s::brc20s_pool_info))
+        .route(
+          "/brc20s/tick/:tick_id/stake/:stake_tick/pool",
+          get(brc20s::brc20s_tick_stake_pool),
+        )
+        .route(
+          "/brc20s/pool/:pid/blocks-remaining",
+          get(brc20s::brc20s_pool_blocks_remaining),
+        )
+        .route("/brc20s/stake-ticks", get(brc20s::brc20s_stake_ticks))
         .route(
           "/brc20s/pool/tid/:tick_id",
           get(brc20s::brc20s_all_pools_by_tid),
         )
+        .route(
+          "/brc20s/pool/stake/:tick",
+          get(brc20s::brc20s_pools_by_stake),
+        )
         .route(
           "/brc20s/debug/pool/:pid",
           get(brc20s::brc20s_debug_pool_info),
@@ -371,6 +540,22 @@ impl Server {
           "/brc20s/pool/:pid/address/:address/userinfo",
           get(brc20s::brc20s_userinfo),
         )
+        .route(
+          "/brc20s/pool/:pid/address/:address/reward",
+          get(brc20s::brc20s_user_reward),
+        )
+        .route(
+          "/brc20s/address/:address/rewards",
+          get(brc20s::brc20s_address_rewards),
+        )
+        .route(
+          "/brc20s/address/:address/pools",
+          get(brc20s::brc20s_address_pools),
+        )
+        .route(
+          "/brc20s/address/:address/stakes",
+          get(brc20s::brc20s_address_stakes),
+        )
         .route(
           "/brc20s/debug/pool/:pid/address/:address/reward",
           get(brc20s::brc20s_user_pending_reward),
@@ -391,6 +576,10 @@ impl Server {
           "/brc20s/address/:address/balance",
           get(brc20s::brc20s_all_balance),
         )
+        .route(
+          "/brc20s/address/:address/tick/:tick_id/balance",
+          get(brc20s::brc20s_balance_at),
+        )
         .route(
           "/brc20s/tick/:tick_id/address/:address/transferable",
           get(brc20s::brc20s_transferable),
@@ -399,9 +588,17 @@ impl Server {
           "/brc20s/address/:address/transferable",
           get(brc20s::brc20s_all_transferable),
         )
+        .route(
+          "/brc20s/address/:address/transferable/pending",
+          get(brc20s::brc20s_pending_transfers),
+        )
         .route(
           "/brc20s/tx/:txid/receipts",
-          get(brc20s::brc20s_txid_receipts),
+          get(brc20s::brc20s_txid_receipts).layer(middleware::from_fn(cache::revalidate)),
+        )
+        .route(
+          "/brc20s/tx/:txid/inscriptions",
+          get(brc20s::brc20s_txid_inscriptions),
         )
         .route(
           "/brc20s/debug/tx/:txid/receipts",
@@ -409,14 +606,32 @@ impl Server {
         )
         .route(
           "/brc20s/block/:blockhash/receipts",
-          get(brc20s::brc20s_block_receipts),
+          get(brc20s::brc20s_block_receipts).layer(middleware::from_fn(cache::immutable)),
+        )
+        .route("/brc20s/stats", get(brc20s::brc20s_operation_stats))
+        .route(
+          "/brc20s/debug/block/:height/mutations",
+          get(brc20s::brc20s_debug_block_mutations),
         )
         .route(
           "/brc20s/stake/:address/:tick",
           get(brc20s::brc20s_stake_info),
+        )
+        // shares the parameter name of the stake info route, which the router requires.
+        .route(
+          "/brc20s/stake/:address/ticks",
+          get(brc20s::brc20s_earn_ticks_by_stake),
         );
+      let api_v1_router = if self.enable_debug_api {
+        api_v1_router.route("/debug/parse", post(debug::debug_parse))
+      } else {
+        api_v1_router
+      };
 
-      let api_router = Router::new().nest("/v1", api_v1_router);
+      let api_router = Router::new().nest(
+        "/v1",
+        api_v1_router.layer(middleware::from_fn(page_size::clamp_limit)),
+      );
 
       let router = Router::new()
         .route("/", get(Self::home))
@@ -461,6 +676,9 @@ impl Server {
         .layer(Extension(index))
         .layer(Extension(page_config))
         .layer(Extension(Arc::new(config)))
+        .layer(Extension(ord::SimulationLimiter::new(self.max_simulations)))
+        .layer(Extension(Arc::new(info::IndexLag::new(self.max_index_lag))))
+        .layer(Extension(page_size::MaxPageSize(self.max_page_size)))
         .layer(SetResponseHeaderLayer::if_not_present(
           header::CONTENT_SECURITY_POLICY,
           HeaderValue::from_static("default-src 'self'"),
@@ -471,10 +689,15 @@ impl Server {
         ))
         .layer(
           CorsLayer::new()
-            .allow_methods([http::Method::GET])
+            .allow_methods([http::Method::GET, http::Method::POST])
+            .allow_headers([http::header::CONTENT_TYPE])
             .allow_origin(Any),
         )
-        .layer(CompressionLayer::new())
+        .layer(
+          CompressionLayer::new()
+            .gzip(self.enable_compression)
+            .br(self.enable_compression),
+        )
         .with_state(server_config);
 
       match (self.http_port(), self.https_port()) {
@@ -1916,6 +2139,107 @@ mod tests {
     TestServer::new().assert_response("/status", StatusCode::OK, "OK");
   }
 
+  #[test]
+  fn index_status() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(2);
+
+    assert_eq!(
+      server.get_json::<serde_json::Value>("/api/v1/status")["data"],
+      serde_json::json!({
+        "chain": "regtest",
+        "block_height": 2,
+        "bitcoind_height": 2,
+        "lag": 0,
+        "protocol_height": 2,
+        "protocol_lag": 0,
+        "brc20s_enabled": true,
+        "brc20_enabled": false,
+      })
+    );
+  }
+
+  #[test]
+  fn index_status_protocol_lag() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &[
+        "--chain",
+        "regtest",
+        "--enable-index-brc20",
+        "--protocol-confirmations",
+        "2",
+      ],
+      &[],
+    );
+    server.mine_blocks(3);
+
+    let status = server.get_json::<serde_json::Value>("/api/v1/status")["data"].clone();
+    assert_eq!(status["block_height"], 3);
+    assert_eq!(status["protocol_height"], 1);
+    assert_eq!(status["protocol_lag"], 2);
+  }
+
+  #[test]
+  fn index_status_lagging() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--height-limit", "1"],
+      &["--max-index-lag", "2"],
+    );
+    server.mine_blocks(4);
+
+    server.assert_response(
+      "/api/v1/status",
+      StatusCode::SERVICE_UNAVAILABLE,
+      r#"{"code":5,"msg":"index is 4 blocks behind bitcoind, at most 2 allowed"}"#,
+    );
+  }
+
+  #[test]
+  fn cors_preflight_allows_json_posts() {
+    let server = TestServer::new();
+
+    let response = reqwest::blocking::Client::new()
+      .request(
+        reqwest::Method::OPTIONS,
+        server.join_url("/api/v1/brc20/balances"),
+      )
+      .header(reqwest::header::ORIGIN, "https://example.com")
+      .header(reqwest::header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+      .header(
+        reqwest::header::ACCESS_CONTROL_REQUEST_HEADERS,
+        "content-type",
+      )
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[reqwest::header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    assert!(headers[reqwest::header::ACCESS_CONTROL_ALLOW_METHODS]
+      .to_str()
+      .unwrap()
+      .contains("POST"));
+    assert_eq!(
+      headers[reqwest::header::ACCESS_CONTROL_ALLOW_HEADERS],
+      "content-type"
+    );
+  }
+
   #[test]
   fn block_count_endpoint() {
     let test_server = TestServer::new();
@@ -2216,125 +2540,2206 @@ mod tests {
   }
 
   #[test]
-  fn unbound_output_returns_200() {
-    TestServer::new().assert_response_regex(
-      "/output/0000000000000000000000000000000000000000000000000000000000000000:0",
-      StatusCode::OK,
-      ".*",
+  fn openapi_documents_brc20s_routes() {
+    fn collect_refs<'a>(value: &'a serde_json::Value, refs: &mut Vec<&'a str>) {
+      match value {
+        serde_json::Value::Object(map) => {
+          for (key, value) in map {
+            match value.as_str() {
+              Some(reference) if key == "$ref" => refs.push(reference),
+              _ => collect_refs(value, refs),
+            }
+          }
+        }
+        serde_json::Value::Array(values) => values.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+      }
+    }
+
+    let server = TestServer::new_with_regtest();
+    let spec = server.get_json::<serde_json::Value>("/api/v1/api-docs/openapi.json");
+
+    for path in [
+      "/api/v1/brc20s/tick",
+      "/api/v1/brc20s/ticks",
+      "/api/v1/brc20s/tick/{tick_id}",
+      "/api/v1/brc20s/tick/{tick_id}/deploy",
+      "/api/v1/brc20s/pool",
+      "/api/v1/brc20s/pool/{pid}",
+      "/api/v1/brc20s/pool/{pid}/blocks-remaining",
+      "/api/v1/brc20s/tick/{tick_id}/stake/{stake_tick}/pool",
+      "/api/v1/brc20s/stake-ticks",
+      "/api/v1/brc20s/pool/tid/{tid}",
+      "/api/v1/brc20s/pool/stake/{tick}",
+      "/api/v1/brc20s/pool/{pid}/address/{address}/userinfo",
+      "/api/v1/brc20s/pool/{pid}/address/{address}/reward",
+      "/api/v1/brc20s/address/{address}/rewards",
+      "/api/v1/brc20s/tick/{tick_id}/address/{address}/balance",
+      "/api/v1/brc20s/address/{address}/balance",
+      "/api/v1/brc20s/address/{address}/tick/{tick_id}/balance",
+      "/api/v1/brc20s/tick/{tick_id}/address/{address}/transferable",
+      "/api/v1/brc20s/address/{address}/transferable",
+      "/api/v1/brc20s/address/{address}/transferable/pending",
+      "/api/v1/brc20s/tx/{txid}/receipts",
+      "/api/v1/brc20s/tx/{txid}/inscriptions",
+      "/api/v1/brc20s/block/{blockhash}/receipts",
+      "/api/v1/brc20s/stats",
+      "/api/v1/brc20s/stake/{address}/{tick}",
+      "/api/v1/brc20s/address/{address}/stakes",
+      "/api/v1/brc20s/stake/{tick}/ticks",
+    ] {
+      assert!(spec["paths"][path]["get"].is_object(), "{path} is missing");
+    }
+
+    let mut refs = Vec::new();
+    collect_refs(&spec, &mut refs);
+    for reference in refs {
+      let name = reference.trim_start_matches("#/components/schemas/");
+      assert!(
+        spec["components"]["schemas"][name].is_object(),
+        "schema {name} is missing"
+      );
+    }
+
+    // untagged, so the schema is the stake value itself rather than an object keyed by variant
+    let stake = &spec["components"]["schemas"]["brc20s.Stake"];
+    assert_eq!(
+      stake["oneOf"][0]["$ref"], "#/components/schemas/brc20s.StakeValue",
+      "{stake}"
     );
   }
 
   #[test]
-  fn invalid_output_returns_400() {
-    TestServer::new().assert_response(
-      "/output/foo:0",
-      StatusCode::BAD_REQUEST,
-      "Invalid URL: error parsing TXID",
+  fn brc20s_pending_transfers_is_empty_for_unknown_address() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/api/v1/brc20s/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw/transferable/pending",
+      StatusCode::OK,
+      r#"{"code":0,"msg":"ok","data":{"inscriptions":[]}}"#,
     );
   }
 
   #[test]
-  fn home() {
-    let test_server = TestServer::new();
+  fn brc20s_pending_transfers_have_inscription_numbers() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
 
-    test_server.mine_blocks(1);
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
 
-    test_server.assert_response_regex(
-      "/",
-      StatusCode::OK,
-      ".*<title>Ordinals</title>.*
-<div class=block>
-  <h2><a href=/block/1>Block 1</a></h2>
-  <div class=thumbnails>
-  </div>
-</div>
-<div class=block>
-  <h2><a href=/block/0>Block 0</a></h2>
-  <div class=thumbnails>
-  </div>
-</div>
-</ol>.*",
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let script_key = ScriptKey::from_address(
+      Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
     );
-  }
+    // the transfer is the second inscription, so its number is 1.
+    let mut inscription_ids = Vec::new();
+    for block in 1..=2 {
+      let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          block,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        )],
+        ..Default::default()
+      });
+      server.mine_blocks(1);
+      inscription_ids.push(InscriptionId { txid, index: 0 });
+    }
 
-  #[test]
-  fn nav_displays_chain() {
-    TestServer::new_with_regtest().assert_response_regex(
-      "/",
+    let tick_id = brc20s::TickId::from_str("a01234567f").unwrap();
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_transferable_assets(
+            &script_key,
+            &tick_id,
+            &inscription_ids[1],
+            &brc20s::TransferableAsset {
+              inscription_id: inscription_ids[1],
+              amount: 100,
+              tick_id: tick_id.clone(),
+              owner: script_key.clone(),
+            },
+          )
+          .unwrap();
+        store
+          .insert_inscribe_transfer_inscription(
+            inscription_ids[1],
+            brc20s::TransferInfo {
+              tick_id: tick_id.clone(),
+              tick_name: brc20s::Tick::from_str("ordi1").unwrap(),
+              amt: 100,
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    server.assert_response(
+      format!("/api/v1/brc20s/address/{address}/transferable/pending"),
       StatusCode::OK,
-      ".*<a href=/>Ordinals<sup>regtest</sup></a>.*",
+      &format!(
+        r#"{{"code":0,"msg":"ok","data":{{"inscriptions":[{{"tick":{{"id":"a01234567f","name":"ordi1"}},"inscriptionId":"{}","inscriptionNumber":1,"amount":"100"}}]}}}}"#,
+        inscription_ids[1]
+      ),
     );
   }
 
   #[test]
-  fn home_block_limit() {
-    let test_server = TestServer::new();
-
-    test_server.mine_blocks(101);
-
-    test_server.assert_response_regex(
-    "/",
-    StatusCode::OK,
-    ".*<ol start=96 reversed class=block-list>\n(  <li><a href=/block/[[:xdigit:]]{64}>[[:xdigit:]]{64}</a></li>\n){95}</ol>.*"
-  );
-  }
+  fn api_errors_have_stable_codes() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
 
-  #[test]
-  fn block_not_found() {
-    TestServer::new().assert_response(
-      "/block/467a86f0642b1d284376d13a98ef58310caa49502b0f9a560ee222e0a122fe16",
+    server.assert_response(
+      "/api/v1/brc20/tick/abcd",
       StatusCode::NOT_FOUND,
-      "block 467a86f0642b1d284376d13a98ef58310caa49502b0f9a560ee222e0a122fe16 not found",
+      r#"{"code":2002,"msg":"tick not found"}"#,
     );
-  }
-
-  #[test]
-  fn unmined_sat() {
-    TestServer::new().assert_response_regex(
-      "/sat/0",
-      StatusCode::OK,
-      ".*<dt>timestamp</dt><dd><time>2009-01-03 18:15:05 UTC</time></dd>.*",
+    server.assert_response(
+      "/api/v1/brc20/tick/abcdefg",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2001,"msg":"ticker must be 4 bytes length"}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20s/pool/abcd",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3003,"msg":"pid must be 13 hex length"}"#,
+    );
+    server.assert_response(
+      "/api/v1/ord/bitmap/0",
+      StatusCode::NOT_FOUND,
+      r#"{"code":1003,"msg":"bitmap indexing is disabled"}"#,
     );
   }
 
   #[test]
-  fn mined_sat() {
-    TestServer::new().assert_response_regex(
-      "/sat/5000000000",
-      StatusCode::OK,
-      ".*<dt>timestamp</dt><dd><time>.*</time> \\(expected\\)</dd>.*",
+  fn brc20_unconfirmed_tx_operations() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+
+    let response: serde_json::Value = server.get_json(format!(
+      "/api/v1/brc20/tx/{txid}/operations?unconfirmed=true"
+    ));
+    let data = &response["data"];
+    assert_eq!(data["txid"], txid.to_string());
+    assert_eq!(data["confirmed"], false);
+    assert_eq!(data["simulated"], true);
+    assert_eq!(
+      data["inscriptions"][0]["inscriptionId"],
+      format!("{txid}i0")
+    );
+    assert_eq!(
+      data["inscriptions"][0]["operation"],
+      serde_json::json!({"type": "inscribeTransfer", "tick": "ordi", "amt": "10"})
     );
-  }
 
-  #[test]
-  fn static_asset() {
-    TestServer::new().assert_response_regex(
-      "/static/index.css",
-      StatusCode::OK,
-      r".*\.rare \{
-  background-color: var\(--rare\);
-}.*",
+    server.assert_response(
+      format!("/api/v1/brc20/tx/{txid}/operations"),
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2009,"msg":"only unconfirmed transactions can be simulated, set unconfirmed=true"}"#,
     );
-  }
 
-  #[test]
-  fn favicon() {
-    TestServer::new().assert_response_regex("/favicon.ico", StatusCode::OK, r".*");
-  }
+    server.mine_blocks(1);
 
-  #[test]
-  fn clock_updates() {
-    let test_server = TestServer::new();
-    test_server.assert_response_regex("/clock", StatusCode::OK, ".*<text.*>0</text>.*");
-    test_server.mine_blocks(1);
-    test_server.assert_response_regex("/clock", StatusCode::OK, ".*<text.*>1</text>.*");
+    server.assert_response(
+      format!("/api/v1/brc20/tx/{txid}/operations?unconfirmed=true"),
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2008,"msg":"transaction is confirmed, query its receipts instead"}"#,
+    );
   }
 
   #[test]
-  fn block_by_hash() {
-    let test_server = TestServer::new();
+  fn brc20_tx_operations_rejected_when_simulations_exhausted() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest"],
+      &["--max-simulations", "0"],
+    );
+    server.mine_blocks(1);
 
-    test_server.mine_blocks(1);
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+
+    server.assert_response(
+      format!("/api/v1/brc20/tx/{txid}/operations?unconfirmed=true"),
+      StatusCode::TOO_MANY_REQUESTS,
+      r#"{"code":4,"msg":"too many simulations in progress"}"#,
+    );
+    server.assert_response(
+      format!("/api/v1/brc20/tx/{txid}"),
+      StatusCode::TOO_MANY_REQUESTS,
+      r#"{"code":4,"msg":"too many simulations in progress"}"#,
+    );
+  }
+
+  #[test]
+  fn ord_tx_operations() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-save-ord-receipts"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let inscribe = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let operations = |txid: Txid, query: &str| {
+      server.get_json::<serde_json::Value>(format!("/api/v1/ord/tx/{txid}/operations{query}"))
+        ["data"]["inscriptions"]
+        .clone()
+    };
+
+    // the confirmed inscription has the number it was indexed with.
+    let inscriptions = operations(inscribe, "");
+    assert_eq!(inscriptions.as_array().unwrap().len(), 1);
+    assert_eq!(inscriptions[0]["inscriptionId"], format!("{inscribe}i0"));
+    assert_eq!(inscriptions[0]["inscriptionNumber"], 0);
+    assert_eq!(
+      inscriptions[0]["action"],
+      serde_json::json!({"new": {"cursed": false, "unbound": false}})
+    );
+
+    // a transfer in the mempool is simulated, with the number of the inscription it moves.
+    let transfer = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      ..Default::default()
+    });
+    server.assert_response(
+      format!("/api/v1/ord/tx/{transfer}/operations"),
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2,"msg":"the transaction is unconfirmed, set unconfirmed=true to simulate it"}"#,
+    );
+    let inscriptions = operations(transfer, "?unconfirmed=true");
+    assert_eq!(inscriptions[0]["inscriptionId"], format!("{inscribe}i0"));
+    assert_eq!(inscriptions[0]["inscriptionNumber"], 0);
+    assert_eq!(inscriptions[0]["action"], "transfer");
+
+    server.mine_blocks(1);
+    assert_eq!(operations(transfer, "")[0]["inscriptionNumber"], 0);
+  }
+
+  #[test]
+  fn address_history() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &[
+        "--chain",
+        "regtest",
+        "--enable-index-brc20",
+        "--enable-save-ord-receipts",
+        "--enable-address-history",
+      ],
+      &[],
+    );
+    server.mine_blocks(3);
+
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let recipient = Some(
+      Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    let mut txids = Vec::new();
+    for (height, content) in [
+      r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"1000","lim":"100"}"#,
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"100"}"#,
+      r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"10"}"#,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      txids.push(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          height + 1,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", content).to_witness(),
+        )],
+        recipient: recipient.clone(),
+        ..Default::default()
+      }));
+      server.mine_blocks(1);
+    }
+
+    let response: serde_json::Value =
+      server.get_json(format!("/api/v1/address/{address}/history?limit=2"));
+    let data = &response["data"];
+    let history = data["history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["height"], 6);
+    assert_eq!(history[0]["txid"], txids[2].to_string());
+    assert_eq!(history[0]["ord"][0]["to"]["address"], address);
+    assert_eq!(history[0]["brc20"][0]["type"], "inscribeTransfer");
+    assert_eq!(history[0]["brc20s"], serde_json::json!([]));
+    assert_eq!(history[1]["height"], 5);
+    assert_eq!(history[1]["txid"], txids[1].to_string());
+    assert_eq!(history[1]["brc20"][0]["type"], "mint");
+
+    let cursor = data["nextCursor"].as_str().unwrap();
+    let response: serde_json::Value = server.get_json(format!(
+      "/api/v1/address/{address}/history?limit=2&cursor={cursor}"
+    ));
+    let data = &response["data"];
+    let history = data["history"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["height"], 4);
+    assert_eq!(history[0]["txid"], txids[0].to_string());
+    assert_eq!(history[0]["brc20"][0]["type"], "deploy");
+    assert_eq!(data["nextCursor"], serde_json::Value::Null);
+
+    server.assert_response(
+      format!("/api/v1/address/{address}/history?cursor=!!"),
+      StatusCode::BAD_REQUEST,
+      r#"{"code":1005,"msg":"invalid cursor"}"#,
+    );
+  }
+
+  #[test]
+  fn address_history_requires_address_history() {
+    TestServer::new_with_regtest().assert_response(
+      "/api/v1/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw/history",
+      StatusCode::NOT_FOUND,
+      r#"{"code":1004,"msg":"address history is not enabled"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_txid_inscriptions() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(1);
+    // the commit transaction, the sender of a deposit is the owner of its input.
+    server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Default::default())],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        1,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc20-s","op":"deposit","pid":"a3668daeaa#01","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    // the deposit is resolved even though the pool does not exist and it fails to execute.
+    let response: serde_json::Value =
+      server.get_json(format!("/api/v1/brc20s/tx/{txid}/inscriptions"));
+    assert_eq!(
+      response["data"],
+      serde_json::json!({
+        "inscriptions": [{
+          "action": {"new": {"cursed": false, "unbound": false}},
+          "inscriptionNumber": 0,
+          "inscriptionId": format!("{txid}i0"),
+          "oldSatpoint": format!("{}:0:0", server.bitcoin_rpc_server.tx(2, 1).txid()),
+          "newSatpoint": format!("{txid}:0:0"),
+        }],
+        "txid": txid.to_string(),
+      })
+    );
+
+    // the failed deposit is counted in the operation stats stored for its block.
+    let stats =
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/stats?from=0&to=3")["data"].clone();
+    assert_eq!(stats["deposit"], 1);
+    assert_eq!(stats["total"], 1);
+    let stats =
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/stats?from=0&to=2")["data"].clone();
+    assert_eq!(stats["total"], 0);
+
+    let coinbase = server.bitcoin_rpc_server.tx(1, 0).txid();
+    server.assert_response(
+      format!("/api/v1/brc20s/tx/{coinbase}/inscriptions"),
+      StatusCode::OK,
+      &format!(r#"{{"code":0,"msg":"ok","data":{{"inscriptions":[],"txid":"{coinbase}"}}}}"#),
+    );
+  }
+
+  #[test]
+  fn debug_parse() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest"],
+      &["--enable-debug-api"],
+    );
+    let parse = |payload: serde_json::Value| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url("/api/v1/debug/parse"))
+        .json(&payload)
+        .send()
+        .unwrap()
+    };
+
+    let response = parse(serde_json::json!({
+      "content_type": "text/plain;charset=utf-8",
+      "body": r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","erate":"10","dmax":"12000000","dec":"18","total":"21000000","only":"1"}"#,
+    }));
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = response.json::<serde_json::Value>().unwrap()["data"].clone();
+    assert_eq!(data["protocol"], "brc20s");
+    assert_eq!(data["operation"]["op"], "deploy");
+    assert_eq!(data["operation"]["pid"], "a3668daeaa#1f");
+    assert_eq!(data["operation"]["erate"], "10");
+
+    let response = parse(serde_json::json!({
+      "content_type": "text/plain;charset=utf-8",
+      "body": r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+    }));
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = response.json::<serde_json::Value>().unwrap()["data"].clone();
+    assert_eq!(data["protocol"], "brc20");
+    assert_eq!(data["operation"]["op"], "mint");
+
+    // a brc20s deploy without its erate.
+    let response = parse(serde_json::json!({
+      "content_type": "text/plain;charset=utf-8",
+      "body": r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a3668daeaa#1f","stake":"btc","earn":"ordi","dmax":"12000000"}"#,
+    }));
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(error["code"], 2);
+    let msg = error["msg"].as_str().unwrap();
+    assert!(
+      msg.starts_with("brc20: not brc20 json, brc20s: parse operation json error: "),
+      "{msg}"
+    );
+
+    let response = parse(serde_json::json!({
+      "content_type": "image/png",
+      "body": r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+    }));
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      response.json::<serde_json::Value>().unwrap()["msg"],
+      "brc20: unsupport content type, brc20s: unsupport content type"
+    );
+  }
+
+  #[test]
+  fn debug_parse_requires_flag() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest"],
+      &[],
+    );
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/api/v1/debug/parse"))
+      .json(&serde_json::json!({
+        "content_type": "text/plain",
+        "body": r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+      }))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
+  #[test]
+  fn brc20s_operation_stats() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(2);
+
+    assert_eq!(
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/stats?from=0&to=2")["data"],
+      serde_json::json!({
+        "from": 0,
+        "to": 2,
+        "deploy": 0,
+        "mint": 0,
+        "deposit": 0,
+        "withdraw": 0,
+        "passiveWithdraw": 0,
+        "inscribeTransfer": 0,
+        "transfer": 0,
+        "total": 0,
+      })
+    );
+    server.assert_response(
+      "/api/v1/brc20s/stats?from=2&to=1",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3014,"msg":"from must not be greater than to"}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20s/stats?from=1&to=3",
+      StatusCode::NOT_FOUND,
+      r#"{"code":3007,"msg":"block receipts not found"}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20s/stats?from=0&to=10000",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3018,"msg":"the range must not span more than 10000 blocks"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_receipts_by_status() {
+    use crate::okx::{
+      datastore::{
+        brc20s::{self, DataStoreReadWrite},
+        ScriptKey,
+      },
+      protocol::brc20s::BRC20SError as ProtocolError,
+    };
+
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let txid = Txid::all_zeros();
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    let receipt = |n: u32, result| brc20s::Receipt {
+      inscription_id: inscription_id(n),
+      inscription_number: n.into(),
+      old_satpoint: SatPoint::from_str(&format!("{txid}:{n}:0")).unwrap(),
+      new_satpoint: SatPoint::from_str(&format!("{txid}:{n}:0")).unwrap(),
+      op: brc20s::OperationType::Mint,
+      from: owner.clone(),
+      to: owner.clone(),
+      result,
+    };
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .add_transaction_receipt(&txid, &receipt(1, Ok(Vec::new())))
+          .unwrap();
+        store
+          .add_transaction_receipt(&txid, &receipt(2, Err(ProtocolError::InvalidZeroAmount)))
+          .unwrap();
+      })
+      .unwrap();
+
+    let receipts = |query: &str| -> Vec<(String, bool, String)> {
+      server.get_json::<serde_json::Value>(format!("/api/v1/brc20s/tx/{txid}/receipts{query}"))
+        ["data"]["receipts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|receipt| {
+          (
+            receipt["inscriptionId"].as_str().unwrap().to_string(),
+            receipt["valid"].as_bool().unwrap(),
+            receipt["msg"].as_str().unwrap().to_string(),
+          )
+        })
+        .collect()
+    };
+
+    let success = (inscription_id(1).to_string(), true, "ok".to_string());
+    let error = (
+      inscription_id(2).to_string(),
+      false,
+      "zero amount not allowed".to_string(),
+    );
+    assert_eq!(receipts(""), vec![success.clone(), error.clone()]);
+    assert_eq!(receipts("?status=success"), vec![success]);
+    assert_eq!(receipts("?status=error"), vec![error]);
+    server.assert_response(
+      format!("/api/v1/brc20s/tx/{txid}/receipts?status=failed"),
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3015,"msg":"status must be one of success or error"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_txid_receipts_etag() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let txid = Txid::all_zeros();
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .add_transaction_receipt(
+            &txid,
+            &brc20s::Receipt {
+              inscription_id: inscription_id(1),
+              inscription_number: 1,
+              old_satpoint: SatPoint::from_str(&format!("{txid}:1:0")).unwrap(),
+              new_satpoint: SatPoint::from_str(&format!("{txid}:1:0")).unwrap(),
+              op: brc20s::OperationType::Mint,
+              from: owner.clone(),
+              to: owner.clone(),
+              result: Ok(Vec::new()),
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    let url = server.join_url(&format!("/api/v1/brc20s/tx/{txid}/receipts"));
+    let client = reqwest::blocking::Client::new();
+
+    let response = client.get(url.clone()).send().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "no-cache"
+    );
+    let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+    let response = client
+      .get(url.clone())
+      .header(header::IF_NONE_MATCH, etag.clone())
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), etag);
+    assert_eq!(response.text().unwrap(), "");
+
+    let response = client
+      .get(url)
+      .header(header::IF_NONE_MATCH, "\"stale\"")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), etag);
+
+    // errors are not cached.
+    let response = server.get("/api/v1/brc20s/tx/abc/receipts");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response.headers().get(header::ETAG).is_none());
+  }
+
+  #[test]
+  fn brc20_tick_history() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20"],
+      &[],
+    );
+    server.mine_blocks(2);
+    // the commit transactions, the sender of an operation is the owner of its input.
+    for block in 1..=2 {
+      server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(block, 0, 0, Default::default())],
+        ..Default::default()
+      });
+    }
+    server.mine_blocks(1);
+
+    let deploy = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        1,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"1000","lim":"10","dec":"0"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+    let mint = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        2,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let history = |query: &str| -> serde_json::Value {
+      server.get_json::<serde_json::Value>(format!("/api/v1/brc20/tick/ORDI/history{query}"))
+        ["data"]
+        .clone()
+    };
+
+    let data = history("");
+    assert_eq!(data["total"], 2);
+    assert_eq!(data["events"][0]["type"], "mint");
+    assert_eq!(data["events"][0]["txid"], mint.to_string());
+    assert_eq!(data["events"][0]["height"], 5);
+    assert_eq!(data["events"][0]["amount"], "10");
+    assert_eq!(data["events"][1]["type"], "deploy");
+    assert_eq!(data["events"][1]["txid"], deploy.to_string());
+    assert_eq!(data["events"][1]["height"], 4);
+
+    let data = history("?type=deploy");
+    assert_eq!(data["total"], 1);
+    assert_eq!(data["events"][0]["txid"], deploy.to_string());
+    let data = history("?type=mint&start=0&limit=1");
+    assert_eq!(data["total"], 1);
+    assert_eq!(data["events"][0]["txid"], mint.to_string());
+    assert_eq!(
+      history("?type=inscribeTransfer"),
+      serde_json::json!({"events": [], "total": 0})
+    );
+    assert_eq!(
+      history("?type=transfer"),
+      serde_json::json!({"events": [], "total": 0})
+    );
+
+    server.assert_response(
+      "/api/v1/brc20/tick/ordi/history?type=burn",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2010,"msg":"event type must be one of deploy, mint, inscribeTransfer or transfer"}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20/tick/sats/history",
+      StatusCode::NOT_FOUND,
+      r#"{"code":2002,"msg":"tick not found"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_earn_ticks_by_stake_empty() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    server.assert_response(
+      "/api/v1/brc20s/stake/ordi/ticks",
+      StatusCode::OK,
+      r#"{"code":0,"msg":"ok","data":{"ticks":[]}}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20s/stake/toolongtick/ticks",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":2001,"msg":"ticker must be 4 bytes length"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_address_pools_empty() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    server.assert_response(
+      "/api/v1/brc20s/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw/pools",
+      StatusCode::OK,
+      r#"{"code":0,"msg":"ok","data":{"pools":[],"total":0,"height":1}}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_userinfo_pool_share() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let addresses = [
+      "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw",
+      "bcrt1qvd26a8c26d4mu5fzyh74pvcp9ykgutxt9fktqf",
+    ];
+    let staked = brc20s::Pid::from_str("a01234567f#01").unwrap();
+    let unstaked = brc20s::Pid::from_str("a01234567f#02").unwrap();
+    server
+      .index
+      .brc20s_write(|store| {
+        for (pid, total_staked) in [(&staked, 1000), (&unstaked, 0)] {
+          store
+            .set_pid_to_poolinfo(
+              pid,
+              &brc20s::PoolInfo::new(
+                pid,
+                &brc20s::PoolType::Pool,
+                &inscription_id(1),
+                &brc20s::PledgedTick::Native,
+                10,
+                0,
+                total_staked,
+                1000,
+                "0".to_string(),
+                0,
+                false,
+                0,
+                0,
+                None,
+                None,
+                None,
+              ),
+            )
+            .unwrap();
+        }
+        for (address, amount) in addresses.iter().zip([600, 400]) {
+          let script_key = ScriptKey::from_address(
+            Address::from_str(address)
+              .unwrap()
+              .require_network(bitcoin::Network::Regtest)
+              .unwrap(),
+          );
+          store
+            .set_pid_to_use_info(
+              &script_key,
+              &staked,
+              &brc20s::UserInfo {
+                staked: amount,
+                ..brc20s::UserInfo::default(&staked)
+              },
+            )
+            .unwrap();
+          store
+            .set_pid_to_use_info(
+              &script_key,
+              &unstaked,
+              &brc20s::UserInfo::default(&unstaked),
+            )
+            .unwrap();
+        }
+      })
+      .unwrap();
+
+    let pool_share = |pid: &brc20s::Pid, address: &str| {
+      server.get_json::<serde_json::Value>(format!(
+        "/api/v1/brc20s/pool/{}/address/{address}/userinfo",
+        pid.as_str().replace('#', "%23")
+      ))["data"]["poolShare"]
+        .clone()
+    };
+
+    assert_eq!(pool_share(&staked, addresses[0]), "60");
+    assert_eq!(pool_share(&staked, addresses[1]), "40");
+    assert_eq!(pool_share(&unstaked, addresses[0]), serde_json::Value::Null);
+  }
+
+  #[test]
+  fn brc20s_address_stakes() {
+    use crate::okx::datastore::{
+      brc20,
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let addresses = [
+      "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw",
+      "bcrt1qvd26a8c26d4mu5fzyh74pvcp9ykgutxt9fktqf",
+    ];
+    let script_key = |address: &str| {
+      ScriptKey::from_address(
+        Address::from_str(address)
+          .unwrap()
+          .require_network(bitcoin::Network::Regtest)
+          .unwrap(),
+      )
+    };
+    let btc = brc20s::PledgedTick::Native;
+    let ordi = brc20s::PledgedTick::BRC20Tick(brc20::Tick::from_str("ordi").unwrap());
+    let pid = |pid: &str| brc20s::Pid::from_str(pid).unwrap();
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_user_stakeinfo(
+            &script_key(addresses[0]),
+            &ordi,
+            &brc20s::StakeInfo::new(vec![(pid("a01234567f#01"), false, 300)], &ordi, 300, 0),
+          )
+          .unwrap();
+        store
+          .set_user_stakeinfo(
+            &script_key(addresses[0]),
+            &btc,
+            &brc20s::StakeInfo::new(vec![(pid("b01234567f#01"), true, 5000)], &btc, 0, 5000),
+          )
+          .unwrap();
+        // another address pledging the same token is listed apart.
+        store
+          .set_user_stakeinfo(
+            &script_key(addresses[1]),
+            &ordi,
+            &brc20s::StakeInfo::new(vec![(pid("a01234567f#01"), false, 7)], &ordi, 7, 0),
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    server.assert_response(
+      format!("/api/v1/brc20s/address/{}/stakes", addresses[0]),
+      StatusCode::OK,
+      r#"{"code":0,"msg":"ok","data":{"stakes":[{"type":"BTC","tick":"btc","max_share":"0","total_only":"5000","staked_pids":[{"pid":"b01234567f#01","only":true,"stake":"5000"}]},{"type":"BRC20","tick":"ordi","max_share":"300","total_only":"0","staked_pids":[{"pid":"a01234567f#01","only":false,"stake":"300"}]}]}}"#,
+    );
+    server.assert_response(
+      format!("/api/v1/brc20s/address/{}/stakes", addresses[1]),
+      StatusCode::OK,
+      r#"{"code":0,"msg":"ok","data":{"stakes":[{"type":"BRC20","tick":"ordi","max_share":"7","total_only":"0","staked_pids":[{"pid":"a01234567f#01","only":false,"stake":"7"}]}]}}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_tick_stake_pool() {
+    use crate::okx::datastore::{
+      brc20,
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    // the pool detail reports the number of the deploy inscription.
+    let deploy = InscriptionId {
+      txid: server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "deploy").to_witness())],
+        ..Default::default()
+      }),
+      index: 0,
+    };
+    server.mine_blocks(1);
+
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    let tick_id = brc20s::TickId::from_str("a01234567f").unwrap();
+    let pid = brc20s::Pid::from_str("a01234567f#01").unwrap();
+    let stake = brc20s::PledgedTick::BRC20Tick(brc20::Tick::from_str("ordi").unwrap());
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_tick_info(
+            &tick_id,
+            &brc20s::TickInfo::new(
+              tick_id,
+              &brc20s::Tick::from_str("ordi1").unwrap(),
+              &deploy,
+              1000,
+              0,
+              0,
+              1000,
+              &owner,
+              0,
+              0,
+              0,
+              vec![pid.clone()],
+            ),
+          )
+          .unwrap();
+        store
+          .set_pid_to_poolinfo(
+            &pid,
+            &brc20s::PoolInfo::new(
+              &pid,
+              &brc20s::PoolType::Pool,
+              &deploy,
+              &stake,
+              10,
+              0,
+              100,
+              1000,
+              "0".to_string(),
+              0,
+              false,
+              0,
+              0,
+              None,
+              None,
+              None,
+            ),
+          )
+          .unwrap();
+        store
+          .set_tickid_stake_to_pid(&tick_id, &stake, &pid)
+          .unwrap();
+      })
+      .unwrap();
+
+    let pool = server
+      .get_json::<serde_json::Value>("/api/v1/brc20s/tick/a01234567f/stake/ordi/pool")["data"]
+      .clone();
+    assert_eq!(pool["pid"], "a01234567f#01");
+    assert_eq!(pool["stake"]["tick"], "ordi");
+    assert_eq!(pool["earn"]["id"], "a01234567f");
+    assert_eq!(pool["earn"]["name"], "ordi1");
+    assert_eq!(pool["inscriptionId"], deploy.to_string());
+    assert_eq!(pool["totalStaked"], "100");
+
+    server.assert_response(
+      "/api/v1/brc20s/tick/a01234567f/stake/btc/pool",
+      StatusCode::NOT_FOUND,
+      r#"{"code":3008,"msg":"pool info not found"}"#,
+    );
+    server.assert_response(
+      "/api/v1/brc20s/tick/a0123/stake/ordi/pool",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3001,"msg":"tid must be 10 hex length"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_ticks_by_deploy_height() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    // the tickers are deployed in blocks 2, 3 and 4, the tick list reports their inscription number.
+    let deploys = (1..=3)
+      .map(|n| {
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+          inputs: &[(n, 0, 0, inscription("text/plain", "deploy").to_witness())],
+          ..Default::default()
+        });
+        server.mine_blocks(1);
+        InscriptionId { txid, index: 0 }
+      })
+      .collect::<Vec<_>>();
+
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    server
+      .index
+      .brc20s_write(|store| {
+        for (deploy, (tick_id, name, height)) in deploys.iter().zip([
+          ("c01234567f", "ordi1", 2),
+          ("b01234567f", "ordi2", 3),
+          ("a01234567f", "ordi3", 4),
+        ]) {
+          let tick_id = brc20s::TickId::from_str(tick_id).unwrap();
+          store
+            .set_tick_info(
+              &tick_id,
+              &brc20s::TickInfo::new(
+                tick_id,
+                &brc20s::Tick::from_str(name).unwrap(),
+                deploy,
+                1000,
+                0,
+                0,
+                1000,
+                &owner,
+                height,
+                0,
+                0,
+                Vec::new(),
+              ),
+            )
+            .unwrap();
+          store.set_deploy_height_to_tickid(height, &tick_id).unwrap();
+        }
+      })
+      .unwrap();
+
+    let ticks = |query: &str| -> Vec<(String, u64)> {
+      let data = server.get_json::<serde_json::Value>(format!("/api/v1/brc20s/ticks{query}"))
+        ["data"]
+        .clone();
+      data["tokens"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|token| {
+          (
+            token["tick"]["name"].as_str().unwrap().to_string(),
+            token["deployHeight"].as_u64().unwrap(),
+          )
+        })
+        .collect()
+    };
+
+    assert_eq!(
+      ticks("?from_height=3&to_height=4"),
+      [("ordi2".to_string(), 3), ("ordi3".to_string(), 4)]
+    );
+    assert_eq!(
+      ticks("?from_height=3&to_height=3"),
+      [("ordi2".to_string(), 3)]
+    );
+    assert_eq!(
+      ticks(""),
+      [
+        ("ordi1".to_string(), 2),
+        ("ordi2".to_string(), 3),
+        ("ordi3".to_string(), 4)
+      ]
+    );
+    assert_eq!(ticks("?to_height=2"), [("ordi1".to_string(), 2)]);
+    assert_eq!(ticks("?from_height=5"), []);
+    assert_eq!(
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/ticks?from_height=2&start=1&limit=1")
+        ["data"]["total"],
+      3
+    );
+
+    server.assert_response(
+      "/api/v1/brc20s/ticks?from_height=4&to_height=3",
+      StatusCode::BAD_REQUEST,
+      r#"{"code":3014,"msg":"from must not be greater than to"}"#,
+    );
+  }
+
+  #[test]
+  fn oversized_limits_are_clamped() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest"],
+      &["--max-page-size", "2"],
+    );
+    server.mine_blocks(3);
+    // the tick list reports the number of each deploy inscription.
+    let deploys = (1..=3)
+      .map(|n| InscriptionId {
+        txid: server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+          inputs: &[(n, 0, 0, inscription("text/plain", "deploy").to_witness())],
+          ..Default::default()
+        }),
+        index: 0,
+      })
+      .collect::<Vec<_>>();
+    server.mine_blocks(1);
+
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    server
+      .index
+      .brc20s_write(|store| {
+        for (deploy, (tick_id, name)) in deploys.iter().zip([
+          ("a01234567f", "ordi1"),
+          ("b01234567f", "ordi2"),
+          ("c01234567f", "ordi3"),
+        ]) {
+          let tick_id = brc20s::TickId::from_str(tick_id).unwrap();
+          store
+            .set_tick_info(
+              &tick_id,
+              &brc20s::TickInfo::new(
+                tick_id,
+                &brc20s::Tick::from_str(name).unwrap(),
+                deploy,
+                1000,
+                0,
+                0,
+                1000,
+                &owner,
+                0,
+                0,
+                0,
+                Vec::new(),
+              ),
+            )
+            .unwrap();
+        }
+      })
+      .unwrap();
+
+    let response = server.get("/api/v1/brc20s/tick?limit=100000");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("x-page-limit").unwrap(), "2");
+    let page = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(page["data"]["tokens"].as_array().unwrap().len(), 2);
+    assert_eq!(page["data"]["total"], 3);
+    assert!(page["data"]["nextCursor"].is_string());
+
+    // a limit within the max page size is left as is.
+    let response = server.get("/api/v1/brc20s/tick?limit=1");
+    assert!(response.headers().get("x-page-limit").is_none());
+    assert_eq!(
+      response.json::<serde_json::Value>().unwrap()["data"]["tokens"]
+        .as_array()
+        .unwrap()
+        .len(),
+      1
+    );
+  }
+
+  #[test]
+  fn brc20s_tick_deploy() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let deploy = r#"{"p":"brc20-s","op":"deploy","t":"pool","pid":"a01234567f#01","stake":"btc","earn":"ordi1","erate":"10.50","dmax":"21000","dec":"2","total":"21000000","only":"1"}"#;
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain;charset=utf-8", deploy).to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+    let deploy_inscription_id = InscriptionId { txid, index: 0 };
+
+    let tick_id = brc20s::TickId::from_str("a01234567f").unwrap();
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_tick_info(
+            &tick_id,
+            &brc20s::TickInfo::new(
+              tick_id,
+              &brc20s::Tick::from_str("ordi1").unwrap(),
+              &deploy_inscription_id,
+              2100000,
+              2,
+              0,
+              2100000000,
+              &ScriptKey::from_address(
+                Address::from_str(address)
+                  .unwrap()
+                  .require_network(bitcoin::Network::Regtest)
+                  .unwrap(),
+              ),
+              2,
+              1687245485,
+              0,
+              vec![brc20s::Pid::from_str("a01234567f#01").unwrap()],
+            ),
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    // the parameters come back as inscribed, the options left out of the deploy are null.
+    assert_eq!(
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/tick/a01234567f/deploy")["data"],
+      serde_json::json!({
+        "tick": {"id": "a01234567f", "name": "ordi1"},
+        "poolType": "pool",
+        "pid": "a01234567f#01",
+        "stake": "btc",
+        "earn": "ordi1",
+        "erate": "10.50",
+        "dmax": "21000",
+        "dec": "2",
+        "total": "21000000",
+        "only": "1",
+        "halving": null,
+        "minStake": null,
+        "maturity": null,
+        "deployer": {"address": address},
+        "inscriptionId": deploy_inscription_id.to_string(),
+        "inscriptionNumber": 0,
+        "deployHeight": 2,
+        "deployBlocktime": 1687245485,
+      })
+    );
+
+    // the deploy inscription is read and parsed once, the second query hits the cache.
+    assert_eq!(server.index.brc20s_operation_cache_stats(), (0, 1));
+    assert_eq!(
+      server.get_json::<serde_json::Value>("/api/v1/brc20s/tick/a01234567f/deploy")["data"]
+        ["erate"],
+      "10.50"
+    );
+    assert_eq!(server.index.brc20s_operation_cache_stats(), (1, 1));
+
+    server.assert_response(
+      "/api/v1/brc20s/tick/b01234567f/deploy",
+      StatusCode::NOT_FOUND,
+      r#"{"code":3004,"msg":"tid not found"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_address_rewards_of_two_earn_ticks() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let owner = ScriptKey::from_address(
+      Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    // the address is the only staker of a pool of each ticker, pools earn `erate` per block.
+    server
+      .index
+      .brc20s_write(|store| {
+        for (n, tick_id, name, erate, minted) in [
+          (1, "a01234567f", "ordi1", 10, 5),
+          (2, "b01234567f", "ordi2", 20, 0),
+        ] {
+          let tick_id = brc20s::TickId::from_str(tick_id).unwrap();
+          let pid = brc20s::Pid::from_str(&format!("{}#01", tick_id.hex())).unwrap();
+          store
+            .set_tick_info(
+              &tick_id,
+              &brc20s::TickInfo::new(
+                tick_id,
+                &brc20s::Tick::from_str(name).unwrap(),
+                &inscription_id(n),
+                1000,
+                0,
+                minted,
+                1000,
+                &owner,
+                0,
+                0,
+                0,
+                vec![pid.clone()],
+              ),
+            )
+            .unwrap();
+          store
+            .set_pid_to_poolinfo(
+              &pid,
+              &brc20s::PoolInfo::new(
+                &pid,
+                &brc20s::PoolType::Pool,
+                &inscription_id(n),
+                &brc20s::PledgedTick::Native,
+                erate,
+                minted,
+                100,
+                1000,
+                "0".to_string(),
+                0,
+                false,
+                0,
+                0,
+                None,
+                None,
+                None,
+              ),
+            )
+            .unwrap();
+          store
+            .set_pid_to_use_info(
+              &owner,
+              &pid,
+              &brc20s::UserInfo {
+                staked: 100,
+                minted,
+                ..brc20s::UserInfo::default(&pid)
+              },
+            )
+            .unwrap();
+        }
+      })
+      .unwrap();
+
+    let rewards =
+      server.get_json::<serde_json::Value>(format!("/api/v1/brc20s/address/{address}/rewards"));
+    assert_eq!(
+      rewards["data"],
+      serde_json::json!({
+        "ticks": [
+          {
+            "tick": { "id": "a01234567f", "name": "ordi1" },
+            "realized": "5",
+            "unrealized": "10",
+            "pools": [{ "pid": "a01234567f#01", "realized": "5", "unrealized": "10" }],
+          },
+          {
+            "tick": { "id": "b01234567f", "name": "ordi2" },
+            "realized": "0",
+            "unrealized": "20",
+            "pools": [{ "pid": "b01234567f#01", "realized": "0", "unrealized": "20" }],
+          },
+        ],
+        "height": 1,
+      })
+    );
+  }
+
+  #[test]
+  fn brc20s_balance_at_requires_balance_history() {
+    let path = "/api/v1/brc20s/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw/tick/f7c515d6b7/balance?block=1";
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    server.assert_response(
+      path,
+      StatusCode::NOT_FOUND,
+      r#"{"code":3013,"msg":"balance history is not enabled"}"#,
+    );
+
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-balance-history"],
+      &[],
+    );
+    server.mine_blocks(1);
+    server.assert_response(
+      path,
+      StatusCode::NOT_FOUND,
+      r#"{"code":3005,"msg":"balance not found"}"#,
+    );
+  }
+
+  #[test]
+  fn brc20s_block_mutations_require_block_mutations() {
+    let path = "/api/v1/brc20s/debug/block/1/mutations";
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    server.assert_response(
+      path,
+      StatusCode::NOT_FOUND,
+      r#"{"code":3017,"msg":"block mutations are not enabled"}"#,
+    );
+
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-block-mutations"],
+      &[],
+    );
+    server.mine_blocks(1);
+    server.assert_response(path, StatusCode::OK, r#"{"code":0,"msg":"ok","data":[]}"#);
+  }
+
+  #[test]
+  fn brc20_batch_balance() {
+    use crate::okx::datastore::{
+      brc20::{self, DataStoreReadWrite as _},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let addresses = [0x51, 0x52, 0x53]
+      .map(|opcode| Address::p2wsh(Script::from_bytes(&[opcode]), bitcoin::Network::Regtest));
+    server
+      .index
+      .brc20_write(|store| {
+        for (tick, decimal) in [("ordi", 18), ("sats", 0)] {
+          let tick = brc20::Tick::from_str(tick).unwrap();
+          store
+            .insert_token_info(
+              &tick,
+              &brc20::TokenInfo {
+                tick: tick.clone(),
+                inscription_id: inscription_id(1),
+                inscription_number: 0,
+                supply: 1_000_000,
+                minted: 0,
+                limit_per_mint: 1000,
+                decimal,
+                deploy_by: ScriptKey::from_address(addresses[0].clone()),
+                deployed_number: 0,
+                deployed_timestamp: 0,
+                latest_mint_number: 0,
+                burned_supply: 0,
+                completed_block_height: None,
+                completed_block_time: None,
+              },
+            )
+            .unwrap();
+        }
+        // the first address holds both tickers, the second only sats, the third nothing.
+        for (address, tick, overall_balance) in [
+          (&addresses[0], "ordi", 1_500_000_000_000_000_000),
+          (&addresses[0], "sats", 10),
+          (&addresses[1], "sats", 20),
+        ] {
+          store
+            .update_token_balance(
+              &ScriptKey::from_address(address.clone()),
+              brc20::Balance {
+                tick: brc20::Tick::from_str(tick).unwrap(),
+                overall_balance,
+                transferable_balance: 0,
+              },
+            )
+            .unwrap();
+        }
+      })
+      .unwrap();
+
+    let query = |request: serde_json::Value| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url("/api/v1/brc20/balances"))
+        .json(&request)
+        .send()
+        .unwrap()
+    };
+    let ticks = |request: serde_json::Value| -> Vec<(String, Vec<String>)> {
+      let response = query(request);
+      assert_eq!(response.status(), StatusCode::OK);
+      response.json::<serde_json::Value>().unwrap()["data"]["balances"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|balances| {
+          (
+            balances["address"].as_str().unwrap().to_string(),
+            balances["balance"]
+              .as_array()
+              .unwrap()
+              .iter()
+              .map(|balance| {
+                format!(
+                  "{} {}",
+                  balance["tick"].as_str().unwrap(),
+                  balance["overallBalanceFormatted"].as_str().unwrap()
+                )
+              })
+              .collect(),
+          )
+        })
+        .collect()
+    };
+    let addresses = addresses.map(|address| address.to_string());
+
+    let mut expected = vec![
+      (
+        addresses[0].clone(),
+        vec!["ordi 1.5".to_string(), "sats 10".to_string()],
+      ),
+      (addresses[1].clone(), vec!["sats 20".to_string()]),
+      (addresses[2].clone(), vec![]),
+    ];
+    let mut all = ticks(serde_json::json!({ "addresses": addresses }));
+    all[0].1.sort();
+    assert_eq!(all, expected);
+
+    expected[0].1.remove(0);
+    assert_eq!(
+      ticks(serde_json::json!({ "addresses": addresses, "tick": "SATS" })),
+      expected
+    );
+
+    let response = query(serde_json::json!({ "addresses": addresses, "tick": "pepe" }));
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response =
+      query(serde_json::json!({ "addresses": ["bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4"] }));
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = query(serde_json::json!({ "addresses": vec![&addresses[0]; 101] }));
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      response.text().unwrap(),
+      r#"{"code":2,"msg":"at most 100 addresses can be queried at once"}"#
+    );
+  }
+
+  #[test]
+  fn batch() {
+    use crate::okx::datastore::{
+      brc20::{self, DataStoreReadWrite as _},
+      ScriptKey,
+    };
+
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let address = Address::p2wsh(Script::from_bytes(&[0x51]), bitcoin::Network::Regtest);
+    let tick = brc20::Tick::from_str("ordi").unwrap();
+    server
+      .index
+      .brc20_write(|store| {
+        store
+          .insert_token_info(
+            &tick,
+            &brc20::TokenInfo {
+              tick: tick.clone(),
+              inscription_id: inscription_id(1),
+              inscription_number: 0,
+              supply: 1000,
+              minted: 10,
+              limit_per_mint: 10,
+              decimal: 0,
+              deploy_by: ScriptKey::from_address(address.clone()),
+              deployed_number: 0,
+              deployed_timestamp: 0,
+              latest_mint_number: 0,
+              burned_supply: 0,
+              completed_block_height: None,
+              completed_block_time: None,
+            },
+          )
+          .unwrap();
+        store
+          .update_token_balance(
+            &ScriptKey::from_address(address.clone()),
+            brc20::Balance {
+              tick: tick.clone(),
+              overall_balance: 10,
+              transferable_balance: 0,
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/api/v1/batch"))
+      .json(&serde_json::json!([
+        {"method": "brc20_tick_info", "params": {"tick": "ordi"}},
+        {"method": "brc20_balance", "params": {"tick": "ordi", "address": address.to_string()}},
+        {"method": "brc20_tick_info", "params": {"tick": "pepe"}},
+        {"method": "brc20_tick_info"},
+        {"method": "brc20_supply", "params": {}},
+      ]))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results = response.json::<serde_json::Value>().unwrap()["data"]["results"].clone();
+
+    // each result is the body of the rest endpoint, errors only fail their own call.
+    assert_eq!(
+      results[0],
+      server.get_json::<serde_json::Value>("/api/v1/brc20/tick/ordi")
+    );
+    assert_eq!(
+      results[1],
+      server.get_json::<serde_json::Value>(&format!(
+        "/api/v1/brc20/tick/ordi/address/{address}/balance"
+      ))
+    );
+    assert_eq!(results[1]["data"]["overallBalance"], "10");
+    assert_eq!(
+      results[2],
+      reqwest::blocking::get(server.join_url("/api/v1/brc20/tick/pepe"))
+        .unwrap()
+        .json::<serde_json::Value>()
+        .unwrap()
+    );
+    assert_eq!(results[3]["code"], 2);
+    assert_eq!(
+      results[4],
+      serde_json::json!({"code": 2, "msg": "unknown method brc20_supply"})
+    );
+    assert_eq!(results.as_array().unwrap().len(), 5);
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/api/v1/batch"))
+      .json(&vec![
+        serde_json::json!({"method": "brc20_tick_info", "params": {"tick": "ordi"}});
+        101
+      ])
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[test]
+  fn transferable_age_blocks() {
+    use crate::okx::datastore::{
+      brc20::{self, DataStoreReadWrite as _},
+      brc20s::{self, DataStoreReadWrite as _},
+      ScriptKey,
+    };
+
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let tick_id = brc20s::TickId::from_str("f7c515d6b7").unwrap();
+    let owner = ScriptKey::from_address(
+      Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    server
+      .index
+      .brc20_write(|store| {
+        let tick = brc20::Tick::from_str("ordi").unwrap();
+        store
+          .insert_transferable(
+            &owner,
+            &tick,
+            brc20::TransferableLog {
+              inscription_id,
+              inscription_number: 0,
+              amount: 10,
+              tick: tick.clone(),
+              owner: owner.clone(),
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_tick_info(
+            &tick_id,
+            &brc20s::TickInfo::new(
+              tick_id,
+              &brc20s::Tick::from_str("ordi1").unwrap(),
+              &inscription_id,
+              1000,
+              0,
+              100,
+              1000,
+              &owner,
+              1,
+              0,
+              1,
+              Vec::new(),
+            ),
+          )
+          .unwrap();
+        store
+          .set_transferable_assets(
+            &owner,
+            &tick_id,
+            &inscription_id,
+            &brc20s::TransferableAsset {
+              inscription_id,
+              amount: 10,
+              tick_id,
+              owner: owner.clone(),
+            },
+          )
+          .unwrap();
+      })
+      .unwrap();
+
+    let age_blocks = || {
+      [
+        format!("/api/v1/brc20/tick/ordi/address/{address}/transferable"),
+        format!("/api/v1/brc20/address/{address}/transferable"),
+        format!(
+          "/api/v1/brc20s/tick/{}/address/{address}/transferable",
+          tick_id.hex()
+        ),
+        format!("/api/v1/brc20s/address/{address}/transferable"),
+      ]
+      .into_iter()
+      .map(|path| {
+        server.get_json::<serde_json::Value>(path)["data"]["inscriptions"][0]["ageBlocks"].clone()
+      })
+      .collect::<Vec<_>>()
+    };
+
+    // inscribed in the indexed tip.
+    assert_eq!(age_blocks(), vec![serde_json::json!(0); 4]);
+
+    server.mine_blocks(3);
+    assert_eq!(age_blocks(), vec![serde_json::json!(3); 4]);
+  }
+
+  #[test]
+  fn brc20s_balance_transferable_count() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let address = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let tick_id = brc20s::TickId::from_str("f7c515d6b7").unwrap();
+    let owner = ScriptKey::from_address(
+      Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    server
+      .index
+      .brc20s_write(|store| {
+        store
+          .set_tick_info(
+            &tick_id,
+            &brc20s::TickInfo::new(
+              tick_id,
+              &brc20s::Tick::from_str("ordi1").unwrap(),
+              &inscription_id(1),
+              1000,
+              0,
+              100,
+              1000,
+              &owner,
+              1,
+              0,
+              1,
+              Vec::new(),
+            ),
+          )
+          .unwrap();
+        store
+          .set_token_balance(
+            &owner,
+            &tick_id,
+            brc20s::Balance {
+              tick_id,
+              overall_balance: 100,
+              transferable_balance: 60,
+            },
+          )
+          .unwrap();
+        // the transferable balance is split over three transfer inscriptions.
+        for (n, amount) in [(2, 10), (3, 20), (4, 30)] {
+          store
+            .set_transferable_assets(
+              &owner,
+              &tick_id,
+              &inscription_id(n),
+              &brc20s::TransferableAsset {
+                inscription_id: inscription_id(n),
+                amount,
+                tick_id,
+                owner: owner.clone(),
+              },
+            )
+            .unwrap();
+        }
+      })
+      .unwrap();
+
+    let balance = server.get_json::<serde_json::Value>(format!(
+      "/api/v1/brc20s/tick/{}/address/{address}/balance",
+      tick_id.hex()
+    ))["data"]
+      .clone();
+    assert_eq!(balance["transferable"], "60");
+    assert_eq!(balance["overall"], "100");
+    assert_eq!(balance["transferableCount"], 3);
+
+    let balances = server
+      .get_json::<serde_json::Value>(format!("/api/v1/brc20s/address/{address}/balance"))["data"]
+      ["balance"]
+      .clone();
+    assert_eq!(balances, serde_json::json!([balance]));
+  }
+
+  #[test]
+  fn bitmap_api_returns_404_when_bitmap_indexing_disabled() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    assert_eq!(
+      server.get("/api/v1/ord/bitmap/0").status(),
+      StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+      server
+        .get(format!(
+          "/api/v1/ord/inscription/{}/collections",
+          inscription_id(1)
+        ))
+        .status(),
+      StatusCode::NOT_FOUND
+    );
+  }
+
+  #[test]
+  fn bitmap_api() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-bitmap"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain;charset=utf-8", "0.bitmap").to_witness(),
+      )],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    server.assert_response(
+      "/api/v1/ord/bitmap/0",
+      StatusCode::OK,
+      &format!(
+        r#"{{"code":0,"msg":"ok","data":{{"number":0,"inscriptionId":"{inscription_id}"}}}}"#
+      ),
+    );
+    assert_eq!(
+      server.get("/api/v1/ord/bitmap/1").status(),
+      StatusCode::NOT_FOUND
+    );
+    server.assert_response(
+      format!("/api/v1/ord/inscription/{inscription_id}/collections"),
+      StatusCode::OK,
+      &format!(
+        r#"{{"code":0,"msg":"ok","data":{{"inscriptionId":"{inscription_id}","collections":["bitmap"]}}}}"#
+      ),
+    );
+  }
+
+  #[test]
+  fn unbound_output_returns_200() {
+    TestServer::new().assert_response_regex(
+      "/output/0000000000000000000000000000000000000000000000000000000000000000:0",
+      StatusCode::OK,
+      ".*",
+    );
+  }
+
+  #[test]
+  fn invalid_output_returns_400() {
+    TestServer::new().assert_response(
+      "/output/foo:0",
+      StatusCode::BAD_REQUEST,
+      "Invalid URL: error parsing TXID",
+    );
+  }
+
+  #[test]
+  fn home() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(1);
+
+    test_server.assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*<title>Ordinals</title>.*
+<div class=block>
+  <h2><a href=/block/1>Block 1</a></h2>
+  <div class=thumbnails>
+  </div>
+</div>
+<div class=block>
+  <h2><a href=/block/0>Block 0</a></h2>
+  <div class=thumbnails>
+  </div>
+</div>
+</ol>.*",
+    );
+  }
+
+  #[test]
+  fn nav_displays_chain() {
+    TestServer::new_with_regtest().assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*<a href=/>Ordinals<sup>regtest</sup></a>.*",
+    );
+  }
+
+  #[test]
+  fn home_block_limit() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(101);
+
+    test_server.assert_response_regex(
+    "/",
+    StatusCode::OK,
+    ".*<ol start=96 reversed class=block-list>\n(  <li><a href=/block/[[:xdigit:]]{64}>[[:xdigit:]]{64}</a></li>\n){95}</ol>.*"
+  );
+  }
+
+  #[test]
+  fn block_not_found() {
+    TestServer::new().assert_response(
+      "/block/467a86f0642b1d284376d13a98ef58310caa49502b0f9a560ee222e0a122fe16",
+      StatusCode::NOT_FOUND,
+      "block 467a86f0642b1d284376d13a98ef58310caa49502b0f9a560ee222e0a122fe16 not found",
+    );
+  }
+
+  #[test]
+  fn unmined_sat() {
+    TestServer::new().assert_response_regex(
+      "/sat/0",
+      StatusCode::OK,
+      ".*<dt>timestamp</dt><dd><time>2009-01-03 18:15:05 UTC</time></dd>.*",
+    );
+  }
+
+  #[test]
+  fn mined_sat() {
+    TestServer::new().assert_response_regex(
+      "/sat/5000000000",
+      StatusCode::OK,
+      ".*<dt>timestamp</dt><dd><time>.*</time> \\(expected\\)</dd>.*",
+    );
+  }
+
+  #[test]
+  fn static_asset() {
+    TestServer::new().assert_response_regex(
+      "/static/index.css",
+      StatusCode::OK,
+      r".*\.rare \{
+  background-color: var\(--rare\);
+}.*",
+    );
+  }
+
+  #[test]
+  fn favicon() {
+    TestServer::new().assert_response_regex("/favicon.ico", StatusCode::OK, r".*");
+  }
+
+  #[test]
+  fn clock_updates() {
+    let test_server = TestServer::new();
+    test_server.assert_response_regex("/clock", StatusCode::OK, ".*<text.*>0</text>.*");
+    test_server.mine_blocks(1);
+    test_server.assert_response_regex("/clock", StatusCode::OK, ".*<text.*>1</text>.*");
+  }
+
+  #[test]
+  fn block_by_hash() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(1);
     let transaction = TransactionTemplate {
       inputs: &[(1, 0, 0, Default::default())],
       fee: 0,
@@ -3124,6 +5529,78 @@ mod tests {
     );
   }
 
+  #[test]
+  fn large_json_responses_are_compressed() {
+    use crate::okx::datastore::{
+      brc20s::{self, DataStoreReadWrite},
+      ScriptKey,
+    };
+
+    let txid = Txid::all_zeros();
+    let owner = ScriptKey::from_address(
+      Address::from_str("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw")
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap(),
+    );
+    let receipts_response = |server_args: &[&str], accept_encoding: Option<&str>| {
+      let server = TestServer::new_server(
+        test_bitcoincore_rpc::builder()
+          .network(bitcoin::Network::Regtest)
+          .build(),
+        None,
+        &["--chain", "regtest", "--enable-index-brc20s"],
+        server_args,
+      );
+      server.mine_blocks(1);
+      server
+        .index
+        .brc20s_write(|store| {
+          for n in 0..200 {
+            store
+              .add_transaction_receipt(
+                &txid,
+                &brc20s::Receipt {
+                  inscription_id: InscriptionId { txid, index: n },
+                  inscription_number: n.into(),
+                  old_satpoint: SatPoint::from_str(&format!("{txid}:{n}:0")).unwrap(),
+                  new_satpoint: SatPoint::from_str(&format!("{txid}:{n}:0")).unwrap(),
+                  op: brc20s::OperationType::Mint,
+                  from: owner.clone(),
+                  to: owner.clone(),
+                  result: Ok(Vec::new()),
+                },
+              )
+              .unwrap();
+          }
+        })
+        .unwrap();
+
+      let mut request = reqwest::blocking::Client::new()
+        .get(server.join_url(&format!("/api/v1/brc20s/tx/{txid}/receipts")));
+      if let Some(accept_encoding) = accept_encoding {
+        request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+      }
+      let response = request.send().unwrap();
+      assert_eq!(response.status(), StatusCode::OK);
+      (
+        response.headers().get(header::CONTENT_ENCODING).cloned(),
+        response.bytes().unwrap().len(),
+      )
+    };
+
+    let (encoding, uncompressed_len) = receipts_response(&[], None);
+    assert_eq!(encoding, None);
+
+    let (encoding, compressed_len) = receipts_response(&[], Some("gzip"));
+    assert_eq!(encoding.unwrap(), "gzip");
+    assert!(compressed_len * 10 < uncompressed_len);
+
+    let (encoding, len) = receipts_response(&["--enable-compression", "false"], Some("gzip"));
+    assert_eq!(encoding, None);
+    assert_eq!(len, uncompressed_len);
+  }
+
   #[test]
   fn inscriptions_can_be_hidden_with_config() {
     let bitcoin_rpc_server = test_bitcoincore_rpc::spawn();
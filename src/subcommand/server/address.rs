@@ -0,0 +1,290 @@
+use {
+  super::{ord::OrdError, types::ScriptPubkey, *},
+  crate::okx::datastore::{ord::ReceiptRef, ScriptKey},
+  axum::Json,
+  base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine},
+  utoipa::ToSchema,
+};
+
+// number of transactions in a history page when no limit is given.
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = address::Tokens)]
+pub(crate) struct Tokens {
+  /// BRC20 balances of the address.
+  #[schema(value_type = Vec<brc20::Balance>)]
+  pub brc20: Vec<brc20::Balance>,
+  /// BRC20S balances of the address.
+  #[schema(value_type = Vec<brc20s::Balance>)]
+  pub brc20s: Vec<brc20s::Balance>,
+}
+
+// address/:address/tokens
+/// Get all token balances of the address.
+///
+/// Retrieve both BRC20 and BRC20S asset balances of the address in one call.
+#[utoipa::path(
+  get,
+  path = "/api/v1/address/{address}/tokens",
+  params(
+      ("address" = String, Path, description = "Address")
+),
+  responses(
+    (status = 200, description = "Obtain all token balances by query address.", body = AddressTokens),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn address_tokens(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+) -> ApiResult<Tokens> {
+  log::debug!("rpc: get address_tokens: {}", address);
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let (brc20_balances, brc20s_balances) = index.get_address_tokens(&address)?;
+
+  log::debug!(
+    "rpc: get address_tokens: {} {:?} {:?}",
+    address,
+    brc20_balances,
+    brc20s_balances
+  );
+
+  Ok(Json(ApiResponse::ok(Tokens {
+    brc20: brc20_balances
+      .iter()
+      .map(|balance| {
+        let token_info = index
+          .brc20_get_tick_info(&balance.tick)?
+          .ok_or(anyhow!("tick not found {}", balance.tick))?;
+        Ok(brc20::Balance::new(balance, token_info.decimal))
+      })
+      .collect::<Result<Vec<_>>>()?,
+    brc20s: brc20s_balances
+      .iter()
+      .map(|(tick_id, balance)| {
+        let tick_info = index
+          .brc20s_tick_info(tick_id)?
+          .ok_or(anyhow!("tid not found {}", tick_id.hex()))?;
+        Ok(brc20s::Balance::new(balance, &tick_info))
+      })
+      .collect::<Result<Vec<_>>>()?,
+  })))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = address::TxActivity)]
+pub(crate) struct TxActivity {
+  /// Height of the block the transaction is in.
+  #[schema(format = "uint64")]
+  pub height: u64,
+  /// The transaction id.
+  pub txid: String,
+  /// Inscription actions of the transaction sent or received by the address.
+  #[schema(value_type = Vec<ord::TxInscription>)]
+  pub ord: Vec<ord::TxInscription>,
+  /// BRC20 events of the transaction sent or received by the address.
+  #[schema(value_type = Vec<brc20::TxEvent>)]
+  pub brc20: Vec<brc20::TxEvent>,
+  /// BRC20S receipts of the transaction sent or received by the address.
+  #[schema(value_type = Vec<brc20s::Receipt>)]
+  pub brc20s: Vec<brc20s::Receipt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = address::History)]
+pub(crate) struct History {
+  /// Transactions of the address, the newest block first.
+  #[schema(value_type = Vec<address::TxActivity>)]
+  pub history: Vec<TxActivity>,
+  /// Cursor of the next page, absent when there are no more transactions.
+  pub next_cursor: Option<String>,
+}
+
+fn encode_history_cursor(receipt_ref: &ReceiptRef) -> String {
+  URL_SAFE_NO_PAD.encode(format!("{:016x}_{}", receipt_ref.height, receipt_ref.txid))
+}
+
+fn decode_history_cursor(cursor: &str) -> Option<(u64, Txid)> {
+  let cursor = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+  let (height, txid) = cursor.split_once('_')?;
+  Some((
+    u64::from_str_radix(height, 16).ok()?,
+    Txid::from_str(txid).ok()?,
+  ))
+}
+
+// address/:address/history
+/// Get the protocol activity of the address.
+///
+/// Retrieve the transactions with ord, BRC20 or BRC20S receipts sent or received by the address,
+/// the newest block first. Pass the `nextCursor` of the previous page to get the next one.
+#[utoipa::path(
+  get,
+  path = "/api/v1/address/{address}/history",
+  params(
+    ("address" = String, Path, description = "Address"),
+    Pagination,
+    CursorPagination
+),
+  responses(
+    (status = 200, description = "Obtain the activity of the address.", body = AddressHistory),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn address_history(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+  Query(page): Query<Pagination>,
+  Query(cursor): Query<CursorPagination>,
+) -> ApiResult<History> {
+  log::debug!("rpc: get address_history: {}", address);
+
+  if !index.has_address_history() {
+    return Err(ApiError::from(OrdError::AddressHistoryDisabled));
+  }
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let before = cursor
+    .cursor
+    .map(|cursor| decode_history_cursor(&cursor).ok_or(OrdError::IncorrectCursorFormat))
+    .transpose()?;
+  let limit = page.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+
+  let receipt_refs = index.ord_receipt_refs(&address, before, limit)?;
+
+  log::debug!("rpc: get address_history: {} {:?}", address, receipt_refs);
+
+  let next_cursor = match receipt_refs.last() {
+    Some(last) if receipt_refs.len() == limit => Some(encode_history_cursor(last)),
+    _ => None,
+  };
+
+  let script_key = ScriptKey::from_address(address.clone());
+  let script_pubkey: ScriptPubkey = script_key.clone().into();
+  let mut history = Vec::new();
+  for receipt_ref in &receipt_refs {
+    let (ord_ops, brc20_receipts, brc20s_receipts) = index.receipts_of_ref(receipt_ref)?;
+
+    let mut ord = Vec::new();
+    for op in ord_ops {
+      let tx_inscription = ord::TxInscription::new(op, index.clone())?;
+      if tx_inscription.from == script_pubkey || tx_inscription.to.as_ref() == Some(&script_pubkey)
+      {
+        ord.push(tx_inscription);
+      }
+    }
+
+    history.push(TxActivity {
+      height: receipt_ref.height,
+      txid: receipt_ref.txid.to_string(),
+      ord,
+      brc20: brc20_receipts
+        .iter()
+        .filter(|receipt| receipt.from == script_key || receipt.to == script_key)
+        .map(|receipt| receipt.into())
+        .collect(),
+      brc20s: brc20s_receipts
+        .iter()
+        .filter(|receipt| receipt.from == script_key || receipt.to == script_key)
+        .map(|receipt| brc20s::Receipt::from(receipt, index.clone()))
+        .collect::<Result<Vec<_>>>()?,
+    });
+  }
+
+  Ok(Json(ApiResponse::ok(History {
+    history,
+    next_cursor,
+  })))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn history_cursor_round_trip() {
+    let receipt_ref = ReceiptRef {
+      height: 840000,
+      txid: Txid::from_str("b61b0172d95e266c18aea0c624db987e971a5d6d4ebc2aaed85da4642d635735")
+        .unwrap(),
+      protocols: Vec::new(),
+    };
+    let cursor = encode_history_cursor(&receipt_ref);
+    assert_eq!(
+      decode_history_cursor(&cursor),
+      Some((receipt_ref.height, receipt_ref.txid))
+    );
+    assert_eq!(decode_history_cursor("!!"), None);
+    assert_eq!(
+      decode_history_cursor(&URL_SAFE_NO_PAD.encode("00000000000cd140")),
+      None
+    );
+  }
+
+  #[test]
+  fn test_serialize_empty_tokens() {
+    let tokens = Tokens {
+      brc20: Vec::new(),
+      brc20s: Vec::new(),
+    };
+    assert_eq!(
+      serde_json::to_string(&tokens).unwrap(),
+      r#"{"brc20":[],"brc20s":[]}"#
+    );
+  }
+
+  #[test]
+  fn test_serialize_formatted_brc20_balance() {
+    let balance = crate::okx::datastore::brc20::Balance {
+      tick: crate::okx::datastore::brc20::Tick::from_str("ordi").unwrap(),
+      overall_balance: 1_500_000_000_000_000_001,
+      transferable_balance: 500_000_000_000_000_000,
+    };
+    assert_eq!(
+      serde_json::to_value(brc20::Balance::new(&balance, 18)).unwrap(),
+      serde_json::json!({
+        "tick": "ordi",
+        "availableBalance": "1000000000000000001",
+        "transferableBalance": "500000000000000000",
+        "overallBalance": "1500000000000000001",
+        "decimal": 18,
+        "availableBalanceFormatted": "1.000000000000000001",
+        "transferableBalanceFormatted": "0.5",
+        "overallBalanceFormatted": "1.500000000000000001",
+      })
+    );
+
+    let balance = crate::okx::datastore::brc20::Balance {
+      tick: crate::okx::datastore::brc20::Tick::from_str("ordi").unwrap(),
+      overall_balance: 1000,
+      transferable_balance: 0,
+    };
+    assert_eq!(
+      serde_json::to_value(brc20::Balance::new(&balance, 0)).unwrap(),
+      serde_json::json!({
+        "tick": "ordi",
+        "availableBalance": "1000",
+        "transferableBalance": "0",
+        "overallBalance": "1000",
+        "decimal": 0,
+        "availableBalanceFormatted": "1000",
+        "transferableBalanceFormatted": "0",
+        "overallBalanceFormatted": "1000",
+      })
+    );
+  }
+}
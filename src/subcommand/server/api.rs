@@ -8,6 +8,82 @@ pub struct Pagination {
   pub limit: Option<usize>,
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct PoolQuery {
+  /// Only list the pools which earn the ticker with this name.
+  pub earn: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EventTypeQuery {
+  /// Only list the events of this type, one of `deploy`, `mint`, `inscribeTransfer` or `transfer`.
+  #[serde(rename = "type")]
+  pub event: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ReceiptStatusQuery {
+  /// Only list the receipts with this result, `success` or `error`.
+  pub status: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BalanceSortQuery {
+  /// Order of the balances, one of `tick`, `available` or `transferable`. Amounts are ordered
+  /// largest first by their value in whole tokens, ties are ordered by ticker name.
+  pub sort: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BlockRangeQuery {
+  /// First block height of the range.
+  pub from: u64,
+  /// Last block height of the range, inclusive.
+  pub to: u64,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeployHeightQuery {
+  /// First deploy height of the range, defaults to the first block.
+  pub from_height: Option<u64>,
+  /// Last deploy height of the range, inclusive, defaults to the latest indexed height.
+  pub to_height: Option<u64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct CursorPagination {
+  /// Opaque cursor returned as `nextCursor` by the previous page.
+  pub cursor: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AtBlockQuery {
+  /// Block height to query at, defaults to the latest indexed height.
+  pub block: Option<u64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct UnconfirmedQuery {
+  /// Simulate the transaction against the latest indexed state if it is still in the mempool.
+  pub unconfirmed: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct IncludeQuery {
+  /// Comma separated list of optional fields to include, e.g. `sat`.
+  pub include: Option<String>,
+}
+
+impl IncludeQuery {
+  pub fn sat(&self) -> bool {
+    self
+      .include
+      .as_deref()
+      .map(|include| include.split(',').any(|field| field.trim() == "sat"))
+      .unwrap_or_default()
+  }
+}
+
 pub(crate) type ApiResult<T> = Result<axum::Json<ApiResponse<T>>, ApiError>;
 
 pub(super) trait ApiOptionExt<T> {
@@ -29,3 +105,20 @@ impl<T> ApiOptionExt<T> for Option<T> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn include_query_sat() {
+    let query = |include: Option<&str>| IncludeQuery {
+      include: include.map(str::to_string),
+    };
+    assert!(!query(None).sat());
+    assert!(!query(Some("")).sat());
+    assert!(!query(Some("sats")).sat());
+    assert!(query(Some("sat")).sat());
+    assert!(query(Some("number, sat")).sat());
+  }
+}
@@ -0,0 +1,109 @@
+use {super::*, axum::Json, serde::de::DeserializeOwned, utoipa::ToSchema};
+
+// the most calls a single batch may hold.
+const MAX_BATCH_CALLS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = batch::Call)]
+pub struct Call {
+  /// The query to run, one of `brc20_tick_info`, `brc20_balance` or `brc20_tx_events`.
+  pub method: String,
+  /// The path parameters of the query by name, e.g. `{"tick": "ordi"}`.
+  #[serde(default)]
+  #[schema(value_type = Object)]
+  pub params: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = batch::Batch)]
+pub struct Batch {
+  /// The response of every call in the order of the request, each exactly the body the REST
+  /// endpoint of the query responds with, `{"code": 0, "msg": "ok", "data": ...}` or an error.
+  #[schema(value_type = Vec<Object>)]
+  pub results: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct TickParams {
+  tick: String,
+}
+
+#[derive(Deserialize)]
+struct TickAddressParams {
+  tick: String,
+  address: String,
+}
+
+#[derive(Deserialize)]
+struct TxidParams {
+  txid: String,
+}
+
+/// Run many queries at once.
+///
+/// Run up to 100 queries in one request, each as it would run through its REST endpoint. A query
+/// that fails only fails its own result.
+#[utoipa::path(
+    post,
+    path = "/api/v1/batch",
+    request_body = Vec<batch::Call>,
+    responses(
+      (status = 200, description = "Obtain the result of every call.", body = Batch),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    )
+  )]
+pub(crate) async fn batch(
+  Extension(index): Extension<Arc<Index>>,
+  Json(calls): Json<Vec<Call>>,
+) -> ApiResult<Batch> {
+  log::debug!("rpc: get batch: {:?}", calls);
+
+  if calls.len() > MAX_BATCH_CALLS {
+    return Err(ApiError::bad_request(format!(
+      "at most {MAX_BATCH_CALLS} calls can be batched at once"
+    )));
+  }
+
+  let mut results = Vec::new();
+  for call in calls {
+    // a failed call is answered with its error body, the other calls still run.
+    results.push(match run(&index, call).await {
+      Ok(value) => value,
+      Err(err) => serde_json::to_value(err).map_err(ApiError::internal)?,
+    });
+  }
+
+  Ok(Json(ApiResponse::ok(Batch { results })))
+}
+
+// the response body the rest endpoint of the call would answer with.
+async fn run(index: &Arc<Index>, call: Call) -> Result<serde_json::Value, ApiError> {
+  let index = Extension(index.clone());
+  match call.method.as_str() {
+    "brc20_tick_info" => {
+      let params = params::<TickParams>(call.params)?;
+      to_value(brc20::brc20_tick_info(index, Path(params.tick)).await?)
+    }
+    "brc20_balance" => {
+      let params = params::<TickAddressParams>(call.params)?;
+      to_value(brc20::brc20_balance(index, Path((params.tick, params.address))).await?)
+    }
+    "brc20_tx_events" => {
+      let params = params::<TxidParams>(call.params)?;
+      to_value(brc20::brc20_tx_events(index, Path(params.txid)).await?)
+    }
+    method => Err(ApiError::bad_request(format!("unknown method {method}"))),
+  }
+}
+
+fn params<T: DeserializeOwned>(params: serde_json::Value) -> Result<T, ApiError> {
+  serde_json::from_value(params).map_err(|err| ApiError::bad_request(format!("bad params: {err}")))
+}
+
+fn to_value<T: Serialize>(
+  Json(response): Json<ApiResponse<T>>,
+) -> Result<serde_json::Value, ApiError> {
+  serde_json::to_value(response).map_err(ApiError::internal)
+}
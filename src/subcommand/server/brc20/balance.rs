@@ -1,4 +1,13 @@
-use {super::*, crate::okx::datastore::brc20::Tick, axum::Json, utoipa::ToSchema};
+use {
+  super::*,
+  crate::okx::{
+    datastore::brc20::{self, Tick},
+    protocol::brc20::{params::MAX_DECIMAL_WIDTH, Num},
+  },
+  axum::Json,
+  std::collections::HashMap,
+  utoipa::ToSchema,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -15,6 +24,35 @@ pub struct Balance {
   /// Overall balance.
   #[schema(format = "uint64")]
   pub overall_balance: String,
+  /// The decimal of the ticker.
+  #[schema(format = "uint8")]
+  pub decimal: u8,
+  /// Available balance formatted with the decimal of the ticker.
+  #[schema(example = "1.5")]
+  pub available_balance_formatted: String,
+  /// Transferable balance formatted with the decimal of the ticker.
+  #[schema(example = "0.5")]
+  pub transferable_balance_formatted: String,
+  /// Overall balance formatted with the decimal of the ticker.
+  #[schema(example = "2")]
+  pub overall_balance_formatted: String,
+}
+
+impl Balance {
+  pub fn new(balance: &brc20::Balance, decimal: u8) -> Self {
+    let available_balance = balance.overall_balance - balance.transferable_balance;
+    Self {
+      tick: balance.tick.to_string(),
+      available_balance: available_balance.to_string(),
+      transferable_balance: balance.transferable_balance.to_string(),
+      overall_balance: balance.overall_balance.to_string(),
+      decimal,
+      available_balance_formatted: Num::from(available_balance).format_scaled(decimal),
+      transferable_balance_formatted: Num::from(balance.transferable_balance)
+        .format_scaled(decimal),
+      overall_balance_formatted: Num::from(balance.overall_balance).format_scaled(decimal),
+    }
+  }
 }
 
 /// Get the ticker balance of the address.
@@ -40,8 +78,7 @@ pub(crate) async fn brc20_balance(
 ) -> ApiResult<Balance> {
   log::debug!("rpc: get brc20_balance: {} {}", tick, address);
 
-  let tick =
-    Tick::from_str(&tick).map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
+  let tick = Tick::from_str(&tick).map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
@@ -49,18 +86,18 @@ pub(crate) async fn brc20_balance(
 
   let balance = index
     .brc20_get_balance_by_address(&tick, &address)?
-    .ok_or_api_not_found(BRC20Error::BalanceNotFound)?;
+    .ok_or(BRC20Error::BalanceNotFound)?;
 
-  let available_balance = balance.overall_balance - balance.transferable_balance;
+  let token_info = index
+    .brc20_get_tick_info(&tick)?
+    .ok_or(BRC20Error::TickNotFound)?;
 
   log::debug!("rpc: get brc20_balance: {} {} {:?}", tick, address, balance);
 
-  Ok(Json(ApiResponse::ok(Balance {
-    tick: balance.tick.to_string(),
-    available_balance: available_balance.to_string(),
-    transferable_balance: balance.transferable_balance.to_string(),
-    overall_balance: balance.overall_balance.to_string(),
-  })))
+  Ok(Json(ApiResponse::ok(Balance::new(
+    &balance,
+    token_info.decimal,
+  ))))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -71,18 +108,63 @@ pub struct AllBalance {
   pub balance: Vec<Balance>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BalanceSort {
+  Tick,
+  Available,
+  Transferable,
+}
+
+fn parse_balance_sort(sort: &str) -> Result<BalanceSort, BRC20Error> {
+  match sort {
+    "tick" => Ok(BalanceSort::Tick),
+    "available" => Ok(BalanceSort::Available),
+    "transferable" => Ok(BalanceSort::Transferable),
+    _ => Err(BRC20Error::IncorrectSortOrder),
+  }
+}
+
+// the amount in units of the smallest decimal, so that tickers of different decimals compare by
+// their value in whole tokens.
+fn scaled_amount(amount: u128, decimal: u8) -> u128 {
+  10u128
+    .checked_pow(u32::from(MAX_DECIMAL_WIDTH.saturating_sub(decimal)))
+    .map_or(u128::MAX, |factor| amount.saturating_mul(factor))
+}
+
+fn sort_balances(balances: &mut [(brc20::Balance, u8)], sort: BalanceSort) {
+  let amount = |(balance, decimal): &(brc20::Balance, u8)| match sort {
+    BalanceSort::Tick => 0,
+    BalanceSort::Available => scaled_amount(
+      balance.overall_balance - balance.transferable_balance,
+      *decimal,
+    ),
+    BalanceSort::Transferable => scaled_amount(balance.transferable_balance, *decimal),
+  };
+  balances.sort_by(|a, b| {
+    amount(b).cmp(&amount(a)).then_with(|| {
+      a.0
+        .tick
+        .to_lowercase()
+        .as_str()
+        .cmp(b.0.tick.to_lowercase().as_str())
+    })
+  });
+}
+
 /// Get all ticker balances of the address.
 ///
-/// Retrieve all BRC20 protocol asset balances associated with a address.
+/// Retrieve all BRC20 protocol asset balances associated with a address, optionally sorted.
 #[utoipa::path(
     get,
     path = "/api/v1/brc20/address/{address}/balance",
     params(
-        ("address" = String, Path, description = "Address")
+        ("address" = String, Path, description = "Address"),
+        BalanceSortQuery
   ),
     responses(
       (status = 200, description = "Obtain account balances by query address.", body = BRC20AllBalance),
-      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::from(BRC20Error::IncorrectSortOrder))),
       (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
       (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
     )
@@ -90,8 +172,10 @@ pub struct AllBalance {
 pub(crate) async fn brc20_all_balance(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(query): Query<BalanceSortQuery>,
 ) -> ApiResult<AllBalance> {
-  log::debug!("rpc: get brc20_all_balance: {}", address);
+  log::debug!("rpc: get brc20_all_balance: {} {:?}", address, query.sort);
+  let sort = query.sort.as_deref().map(parse_balance_sort).transpose()?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
@@ -101,15 +185,202 @@ pub(crate) async fn brc20_all_balance(
 
   log::debug!("rpc: get brc20_all_balance: {} {:?}", address, all_balance);
 
+  let mut balances = all_balance
+    .into_iter()
+    .map(|balance| {
+      let token_info = index
+        .brc20_get_tick_info(&balance.tick)?
+        .ok_or(BRC20Error::TickNotFound)?;
+      Ok((balance, token_info.decimal))
+    })
+    .collect::<Result<Vec<_>, ApiError>>()?;
+  if let Some(sort) = sort {
+    sort_balances(&mut balances, sort);
+  }
+
   Ok(Json(ApiResponse::ok(AllBalance {
-    balance: all_balance
+    balance: balances
       .iter()
-      .map(|bal| Balance {
-        tick: bal.tick.to_string(),
-        available_balance: (bal.overall_balance - bal.transferable_balance).to_string(),
-        transferable_balance: bal.transferable_balance.to_string(),
-        overall_balance: bal.overall_balance.to_string(),
-      })
+      .map(|(balance, decimal)| Balance::new(balance, *decimal))
       .collect(),
   })))
 }
+
+/// The most addresses a single batch balance query may ask for.
+const MAX_BATCH_ADDRESSES: usize = 100;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(as = brc20::BatchBalanceRequest)]
+pub struct BatchBalanceRequest {
+  /// The addresses to query, at most 100.
+  pub addresses: Vec<String>,
+  /// Only query the balance of this ticker.
+  pub tick: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = brc20::AddressBalance)]
+pub struct AddressBalance {
+  /// The address.
+  pub address: String,
+  /// The ticker balances of the address, empty if it holds none.
+  #[schema(value_type = Vec<brc20::Balance>)]
+  pub balance: Vec<Balance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = brc20::BatchBalance)]
+pub struct BatchBalance {
+  /// The balances, in the order of the requested addresses.
+  #[schema(value_type = Vec<brc20::AddressBalance>)]
+  pub balances: Vec<AddressBalance>,
+}
+
+/// Get the ticker balances of many addresses.
+///
+/// Retrieve the BRC20 protocol asset balances of up to 100 addresses at once, optionally only
+/// those of the given ticker.
+#[utoipa::path(
+    post,
+    path = "/api/v1/brc20/balances",
+    request_body = brc20::BatchBalanceRequest,
+    responses(
+      (status = 200, description = "Obtain the balances of the addresses.", body = BRC20BatchBalance),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+      (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    )
+  )]
+pub(crate) async fn brc20_batch_balance(
+  Extension(index): Extension<Arc<Index>>,
+  Json(request): Json<BatchBalanceRequest>,
+) -> ApiResult<BatchBalance> {
+  log::debug!("rpc: get brc20_batch_balance: {:?}", request);
+
+  if request.addresses.len() > MAX_BATCH_ADDRESSES {
+    return Err(ApiError::bad_request(format!(
+      "at most {MAX_BATCH_ADDRESSES} addresses can be queried at once"
+    )));
+  }
+
+  let tick = request
+    .tick
+    .as_deref()
+    .map(|tick| Tick::from_str(tick).map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat)))
+    .transpose()?;
+
+  let addresses = request
+    .addresses
+    .iter()
+    .map(|address| {
+      Address::from_str(address)
+        .and_then(|address| address.require_network(index.get_chain_network()))
+        .map_err(ApiError::bad_request)
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  // the decimals of the tickers looked up so far, by lowercase ticker.
+  let mut decimals = HashMap::new();
+  let mut decimal = |tick: &Tick| -> Result<u8, ApiError> {
+    let key = tick.as_str().to_lowercase();
+    if let Some(decimal) = decimals.get(&key) {
+      return Ok(*decimal);
+    }
+    let decimal = index
+      .brc20_get_tick_info(tick)?
+      .ok_or(BRC20Error::TickNotFound)?
+      .decimal;
+    decimals.insert(key, decimal);
+    Ok(decimal)
+  };
+  if let Some(tick) = &tick {
+    decimal(tick)?;
+  }
+
+  let mut balances = Vec::new();
+  for (address, address_balances) in addresses
+    .iter()
+    .zip(index.brc20_get_balances_by_addresses(&addresses, tick.as_ref())?)
+  {
+    balances.push(AddressBalance {
+      address: address.to_string(),
+      balance: address_balances
+        .iter()
+        .map(|balance| Ok(Balance::new(balance, decimal(&balance.tick)?)))
+        .collect::<Result<_, ApiError>>()?,
+    });
+  }
+
+  Ok(Json(ApiResponse::ok(BatchBalance { balances })))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn balance(tick: &str, overall_balance: u128, transferable_balance: u128) -> brc20::Balance {
+    brc20::Balance {
+      tick: Tick::from_str(tick).unwrap(),
+      overall_balance,
+      transferable_balance,
+    }
+  }
+
+  fn sorted_ticks(balances: &[(brc20::Balance, u8)], sort: &str) -> Vec<String> {
+    let mut balances = balances.to_vec();
+    sort_balances(&mut balances, parse_balance_sort(sort).unwrap());
+    balances
+      .iter()
+      .map(|(balance, _)| balance.tick.to_string())
+      .collect()
+  }
+
+  #[test]
+  fn test_sort_balances() {
+    // available: 90, 200 and 0 tokens, transferable: 10, 0 and 50 tokens.
+    let balances = vec![
+      (balance("bbbb", 20000, 0), 2),
+      (
+        balance(
+          "CCCC",
+          50_000_000_000_000_000_000,
+          50_000_000_000_000_000_000,
+        ),
+        18,
+      ),
+      (balance("aaaa", 100, 10), 0),
+    ];
+
+    assert_eq!(sorted_ticks(&balances, "tick"), ["aaaa", "bbbb", "CCCC"]);
+    assert_eq!(
+      sorted_ticks(&balances, "available"),
+      ["bbbb", "aaaa", "CCCC"]
+    );
+    assert_eq!(
+      sorted_ticks(&balances, "transferable"),
+      ["CCCC", "aaaa", "bbbb"]
+    );
+
+    // equal values fall back to the ticker name.
+    let balances = vec![
+      (balance("cccc", 5, 0), 0),
+      (balance("BBBB", 500, 0), 2),
+      (balance("aaaa", 5, 0), 0),
+    ];
+    assert_eq!(
+      sorted_ticks(&balances, "available"),
+      ["aaaa", "BBBB", "cccc"]
+    );
+    assert_eq!(
+      sorted_ticks(&balances, "transferable"),
+      ["aaaa", "BBBB", "cccc"]
+    );
+
+    assert!(matches!(
+      parse_balance_sort("overall"),
+      Err(BRC20Error::IncorrectSortOrder)
+    ));
+  }
+}
@@ -1,4 +1,7 @@
-use super::{types::ScriptPubkey, *};
+use super::{
+  types::{SatInfo, ScriptPubkey},
+  *,
+};
 mod balance;
 mod receipt;
 mod ticker;
@@ -19,6 +22,49 @@ pub(super) enum BRC20Error {
   EventsNotFound,
   #[error("block not found")]
   BlockNotFound,
+  #[error("transaction not found")]
+  TransactionNotFound,
+  #[error("transaction is confirmed, query its receipts instead")]
+  TransactionConfirmed,
+  #[error("only unconfirmed transactions can be simulated, set unconfirmed=true")]
+  UnconfirmedRequired,
+  #[error("event type must be one of deploy, mint, inscribeTransfer or transfer")]
+  IncorrectEventType,
+  #[error("sort must be one of tick, available or transferable")]
+  IncorrectSortOrder,
+}
+
+impl BRC20Error {
+  /// Stable code of the error in api responses. Codes are never reused.
+  pub(crate) fn code(&self) -> i32 {
+    match self {
+      Self::IncorrectTickFormat => 2001,
+      Self::TickNotFound => 2002,
+      Self::BalanceNotFound => 2003,
+      Self::OperationNotFound => 2004,
+      Self::EventsNotFound => 2005,
+      Self::BlockNotFound => 2006,
+      Self::TransactionNotFound => 2007,
+      Self::TransactionConfirmed => 2008,
+      Self::UnconfirmedRequired => 2009,
+      Self::IncorrectEventType => 2010,
+      Self::IncorrectSortOrder => 2011,
+    }
+  }
+}
+
+impl From<BRC20Error> for ApiError {
+  fn from(error: BRC20Error) -> Self {
+    let status = match error {
+      BRC20Error::IncorrectTickFormat
+      | BRC20Error::TransactionConfirmed
+      | BRC20Error::UnconfirmedRequired
+      | BRC20Error::IncorrectEventType
+      | BRC20Error::IncorrectSortOrder => StatusCode::BAD_REQUEST,
+      _ => StatusCode::NOT_FOUND,
+    };
+    ApiError::new(status, error.code(), error)
+  }
 }
 
 pub(super) use {balance::*, receipt::*, ticker::*, transaction::*, transferable::*};
@@ -142,7 +142,7 @@ pub struct InscribeTransferEvent {
 #[schema(as = brc20::TransferEvent)]
 #[serde(rename_all = "camelCase")]
 pub struct TransferEvent {
-  /// Event type.
+  /// Event type, `burn` if transferred to an OP_RETURN output.
   #[serde(rename = "type")]
   pub event: String,
   /// The ticker of transfer.
@@ -226,6 +226,19 @@ impl From<&brc20_store::Receipt> for TxEvent {
         msg: trans2.msg.clone().unwrap_or("ok".to_string()),
         event: "transfer".to_string(),
       }),
+      Ok(brc20_store::Event::Burn(burn)) => Self::Transfer(TransferEvent {
+        tick: burn.tick.to_string(),
+        inscription_id: event.inscription_id.to_string(),
+        inscription_number: event.inscription_number,
+        old_satpoint: event.old_satpoint.to_string(),
+        new_satpoint: event.new_satpoint.to_string(),
+        amount: burn.amount.to_string(),
+        from: event.from.clone().into(),
+        to: event.to.clone().into(),
+        valid: true,
+        msg: "ok".to_string(),
+        event: "burn".to_string(),
+      }),
       Err(err) => Self::Error(ErrorEvent {
         inscription_id: event.inscription_id.to_string(),
         inscription_number: event.inscription_number,
@@ -279,7 +292,7 @@ pub(crate) async fn brc20_tx_events(
   let txid = bitcoin::Txid::from_str(&txid).map_err(|e| ApiError::bad_request(e.to_string()))?;
   let tx_events = index
     .brc20_get_tx_events_by_txid(&txid)?
-    .ok_or_api_not_found(BRC20Error::EventsNotFound)?;
+    .ok_or(BRC20Error::EventsNotFound)?;
 
   log::debug!("rpc: get brc20_tx_events: {} {:?}", txid, tx_events);
 
@@ -311,6 +324,7 @@ pub struct BlockEvents {
       (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
       (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
       (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+      (status = 503, description = "The operations of the block wait for --protocol-confirmations.", body = ApiError, example = json!(&ApiError::service_unavailable("operations of block 000000000000000000000000000000000000000000000000000000000000000a are not executed yet"))),
     )
   )]
 pub(crate) async fn brc20_block_events(
@@ -324,17 +338,26 @@ pub(crate) async fn brc20_block_events(
   let blockinfo = index
     .get_block_info_by_hash(blockhash)
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(BRC20Error::BlockNotFound)?;
+    .ok_or(BRC20Error::BlockNotFound)?;
 
   // get blockhash from redb.
   let blockhash = index
     .block_hash(Some(u64::try_from(blockinfo.height).unwrap()))
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(BRC20Error::BlockNotFound)?;
+    .ok_or(BRC20Error::BlockNotFound)?;
 
   // check blockhash.
   if blockinfo.hash != blockhash {
-    return Err(ApiError::NotFound(BRC20Error::BlockNotFound.to_string()));
+    return Err(ApiError::from(BRC20Error::BlockNotFound));
+  }
+
+  // the events are final once the operations of the block are executed.
+  if index.protocol_block_height()?.map_or(true, |height| {
+    u64::try_from(blockinfo.height).unwrap() > height
+  }) {
+    return Err(ApiError::service_unavailable(format!(
+      "operations of block {blockhash} are not executed yet"
+    )));
   }
 
   let block_events = index
@@ -357,3 +380,85 @@ pub(crate) async fn brc20_block_events(
       .collect(),
   })))
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20::TickEvent)]
+#[serde(rename_all = "camelCase")]
+pub struct TickEvent {
+  #[serde(flatten)]
+  #[schema(value_type = brc20::TxEvent)]
+  pub event: TxEvent,
+  /// The transaction of the event.
+  pub txid: String,
+  /// The height of the block that the event executed in.
+  #[schema(format = "uint64")]
+  pub height: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20::TickEvents)]
+#[serde(rename_all = "camelCase")]
+pub struct TickEvents {
+  #[schema(value_type = Vec<brc20::TickEvent>)]
+  pub events: Vec<TickEvent>,
+  pub total: usize,
+}
+
+fn parse_event_type(event: &str) -> Result<brc20_store::OperationType, BRC20Error> {
+  match event {
+    "deploy" => Ok(brc20_store::OperationType::Deploy),
+    "mint" => Ok(brc20_store::OperationType::Mint),
+    "inscribeTransfer" => Ok(brc20_store::OperationType::InscribeTransfer),
+    "transfer" => Ok(brc20_store::OperationType::Transfer),
+    _ => Err(BRC20Error::IncorrectEventType),
+  }
+}
+
+/// Get the event history of the ticker.
+///
+/// Retrieve the successful events of the ticker, the latest block first. With `type`, only the
+/// events of that type are listed, burns are listed as transfers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/brc20/tick/{ticker}/history",
+    params(
+      ("ticker" = String, Path, description = "Token ticker", min_length = 4, max_length = 4),
+      EventTypeQuery,
+      Pagination
+  ),
+    responses(
+      (status = 200, description = "Obtain the events of the ticker", body = BRC20TickEvents),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::from(BRC20Error::IncorrectEventType))),
+      (status = 404, description = "Ticker not found.", body = ApiError, example = json!(&ApiError::from(BRC20Error::TickNotFound))),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    )
+  )]
+pub(crate) async fn brc20_tick_history(
+  Extension(index): Extension<Arc<Index>>,
+  Path(tick): Path<String>,
+  Query(query): Query<EventTypeQuery>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<TickEvents> {
+  log::debug!("rpc: get brc20_tick_history: {} {:?}", tick, query.event);
+  let tick = brc20_store::Tick::from_str(&tick)
+    .map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))?;
+  let op = query.event.as_deref().map(parse_event_type).transpose()?;
+
+  let (receipts, total) = index
+    .brc20_get_tick_receipts(&tick, op, page.start.unwrap_or(0), page.limit)?
+    .ok_or(BRC20Error::TickNotFound)?;
+
+  log::debug!("rpc: get brc20_tick_history: {:?} {:?}", tick, receipts);
+
+  Ok(Json(ApiResponse::ok(TickEvents {
+    events: receipts
+      .iter()
+      .map(|receipt| TickEvent {
+        event: (&receipt.receipt).into(),
+        txid: receipt.txid.to_string(),
+        height: receipt.height,
+      })
+      .collect(),
+    total,
+  })))
+}
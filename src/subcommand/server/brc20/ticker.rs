@@ -29,6 +29,9 @@ pub struct TickInfo {
   /// The amount of the ticker that has been minted.
   #[schema(format = "uint64")]
   pub minted: String,
+  /// The amount of the ticker that has been burned by transferring to OP_RETURN outputs.
+  #[schema(format = "uint64")]
+  pub burned_supply: String,
   /// The decimal of the ticker.<br>
   /// Number of decimals cannot exceed 18 (default).
   #[schema(
@@ -50,6 +53,12 @@ pub struct TickInfo {
   /// The timestamp of the block that the ticker deployed.
   #[schema(format = "uint32")]
   pub deploy_blocktime: u32,
+  /// The height of the block whose mint reached the supply, null while the ticker is mintable.
+  #[schema(format = "uint64")]
+  pub completed_block_height: Option<u64>,
+  /// The timestamp of the block whose mint reached the supply, null while the ticker is mintable.
+  #[schema(format = "uint32")]
+  pub completed_block_time: Option<u32>,
 }
 
 impl From<TokenInfo> for TickInfo {
@@ -61,11 +70,14 @@ impl From<TokenInfo> for TickInfo {
       supply: tick_info.supply.to_string(),
       limit_per_mint: tick_info.limit_per_mint.to_string(),
       minted: tick_info.minted.to_string(),
+      burned_supply: tick_info.burned_supply.to_string(),
       decimal: tick_info.decimal,
       deploy_by: tick_info.deploy_by.clone().into(),
       txid: tick_info.inscription_id.txid.to_string(),
       deploy_height: tick_info.deployed_number,
       deploy_blocktime: tick_info.deployed_timestamp,
+      completed_block_height: tick_info.completed_block_height,
+      completed_block_time: tick_info.completed_block_time,
     }
   }
 }
@@ -81,8 +93,8 @@ impl From<TokenInfo> for TickInfo {
   ),
     responses(
       (status = 200, description = "Obtain matching BRC20 ticker by query.", body = BRC20Tick),
-      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request(BRC20Error::IncorrectTickFormat))),
-      (status = 404, description = "Ticker not found.", body = ApiError, example = json!(&ApiError::not_found(BRC20Error::TickNotFound))),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::from(BRC20Error::IncorrectTickFormat))),
+      (status = 404, description = "Ticker not found.", body = ApiError, example = json!(&ApiError::from(BRC20Error::TickNotFound))),
       (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
     )
   )]
@@ -91,11 +103,10 @@ pub(crate) async fn brc20_tick_info(
   Path(tick): Path<String>,
 ) -> ApiResult<TickInfo> {
   log::debug!("rpc: get brc20_tick_info: {}", tick);
-  let tick =
-    Tick::from_str(&tick).map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
+  let tick = Tick::from_str(&tick).map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))?;
   let tick_info = index
     .brc20_get_tick_info(&tick)?
-    .ok_or_api_not_found(BRC20Error::TickNotFound)?;
+    .ok_or(BRC20Error::TickNotFound)?;
 
   log::debug!("rpc: get brc20_tick_info: {:?} {:?}", tick, tick_info);
 
@@ -134,3 +145,40 @@ pub(crate) async fn brc20_all_tick_info(
     tokens: all_tick_info.into_iter().map(|t| t.into()).collect(),
   })))
 }
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::okx::datastore::ScriptKey, std::str::FromStr};
+
+  #[test]
+  fn serialize_tick_info_deploy_block() {
+    let tick_info = TickInfo::from(TokenInfo {
+      tick: Tick::from_str("abcd").unwrap(),
+      inscription_id: InscriptionId::from_str(
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      )
+      .unwrap(),
+      inscription_number: 1,
+      supply: 1000,
+      minted: 0,
+      limit_per_mint: 1000,
+      decimal: 18,
+      deploy_by: ScriptKey::from_address(
+        Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+          .unwrap()
+          .assume_checked(),
+      ),
+      deployed_number: 779832,
+      deployed_timestamp: 1677731361,
+      latest_mint_number: 779832,
+      burned_supply: 0,
+      completed_block_height: None,
+      completed_block_time: None,
+    });
+
+    let json = serde_json::to_value(&tick_info).unwrap();
+    assert_eq!(json["deployHeight"], 779832);
+    assert_eq!(json["deployBlocktime"], 1677731361);
+    assert_eq!(json["completedBlockHeight"], serde_json::Value::Null);
+  }
+}
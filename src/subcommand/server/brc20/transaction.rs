@@ -1,9 +1,6 @@
 use {
   super::*,
-  crate::okx::{
-    datastore::{brc20::redb as brc20_db, ScriptKey},
-    protocol::brc20 as brc20_proto,
-  },
+  crate::okx::{datastore::brc20::redb as brc20_db, protocol::brc20 as brc20_proto},
   axum::Json,
 };
 
@@ -13,6 +10,8 @@ pub struct TxInscriptionInfo {
   pub txid: String,
   pub blockhash: Option<String>,
   pub confirmed: bool,
+  // operations of an unconfirmed transaction are simulated against the latest indexed state
+  pub simulated: bool,
   pub inscriptions: Vec<InscriptionInfo>,
 }
 
@@ -124,21 +123,53 @@ impl From<brc20_proto::Transfer> for Transfer {
 
 pub(crate) async fn brc20_tx(
   Extension(index): Extension<Arc<Index>>,
+  Extension(simulations): Extension<ord::SimulationLimiter>,
   Path(txid): Path<String>,
 ) -> ApiResult<TxInscriptionInfo> {
   log::debug!("rpc: get brc20_tx: {}", txid);
   let txid = bitcoin::Txid::from_str(&txid).map_err(|e| ApiError::bad_request(e.to_string()))?;
 
+  let _permit = simulations.try_acquire()?;
   let tx_info = get_operations_by_txid(&index, &txid, true)?;
 
   if tx_info.inscriptions.is_empty() {
-    return Err(ApiError::not_found(BRC20Error::OperationNotFound));
+    return Err(ApiError::from(BRC20Error::OperationNotFound));
   }
 
   log::debug!("rpc: get brc20_tx: {} {:?}", txid, tx_info);
   Ok(Json(ApiResponse::ok(tx_info)))
 }
 
+// /brc20/tx/:txid/operations
+/// Simulate the BRC20 operations of an unconfirmed transaction in the mempool.
+pub(crate) async fn brc20_tx_operations(
+  Extension(index): Extension<Arc<Index>>,
+  Extension(simulations): Extension<ord::SimulationLimiter>,
+  Path(txid): Path<String>,
+  Query(query): Query<UnconfirmedQuery>,
+) -> ApiResult<TxInscriptionInfo> {
+  log::debug!("rpc: get brc20_tx_operations: {}", txid);
+  let txid = bitcoin::Txid::from_str(&txid).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+  if !query.unconfirmed.unwrap_or(false) {
+    return Err(ApiError::from(BRC20Error::UnconfirmedRequired));
+  }
+
+  let _permit = simulations.try_acquire()?;
+
+  let tx = index
+    .get_transaction_info(&txid)?
+    .ok_or(BRC20Error::TransactionNotFound)?;
+  if tx.confirmations.is_some() {
+    return Err(ApiError::from(BRC20Error::TransactionConfirmed));
+  }
+
+  let tx_info = get_operations_by_txid(&index, &txid, true)?;
+
+  log::debug!("rpc: get brc20_tx_operations: {} {:?}", txid, tx_info);
+  Ok(Json(ApiResponse::ok(tx_info)))
+}
+
 fn get_operations_by_txid(
   index: &Arc<Index>,
   txid: &bitcoin::Txid,
@@ -151,10 +182,13 @@ fn get_operations_by_txid(
     .ok_or(anyhow!("can't get transaction info: {txid}"))?;
 
   // get inscription operations
-  let operations = ord::get_ord_operations_by_txid(index, txid, with_unconfirmed)?;
+  let operations =
+    ord::get_ord_operations_by_txid(index, txid, with_unconfirmed)?.unwrap_or_default();
+
+  let tx = tx_result.transaction()?;
 
   // get new inscriptions
-  let new_inscriptions = Inscription::from_transaction(&tx_result.transaction()?)
+  let new_inscriptions = Inscription::from_transaction(&tx)
     .into_iter()
     .map(|i| i.inscription)
     .collect::<Vec<Inscription>>();
@@ -162,7 +196,13 @@ fn get_operations_by_txid(
   let rtx = index.begin_read()?.0;
   let brc20_store = brc20_db::DataStoreReader::new(&rtx);
   for operation in operations {
-    match brc20_proto::Message::resolve(&brc20_store, &new_inscriptions, &operation)? {
+    match brc20_proto::Message::resolve(
+      &brc20_store,
+      &new_inscriptions,
+      &operation,
+      index.lenient_content_type(),
+      index.protocol_json_limits(),
+    )? {
       None => continue,
       Some(msg) => brc20_operation_infos.push(InscriptionInfo {
         action: match msg.op {
@@ -173,20 +213,13 @@ fn get_operations_by_txid(
           .get_inscription_entry(msg.inscription_id)?
           .map(|entry| entry.number),
         inscription_id: msg.inscription_id.to_string(),
-        from: index
-          .get_outpoint_entry(msg.old_satpoint.outpoint)?
-          .map(|txout| {
-            ScriptKey::from_script(&txout.script_pubkey, index.get_chain_network()).into()
-          })
-          .ok_or(anyhow!("outpoint not found {}", msg.old_satpoint.outpoint))?,
+        from: ord::get_script_key_on_outpoint(index, &tx, msg.old_satpoint.outpoint)?,
         to: match msg.new_satpoint {
-          Some(satpoint) => match index.get_outpoint_entry(satpoint.outpoint) {
-            Ok(Some(txout)) => {
-              Some(ScriptKey::from_script(&txout.script_pubkey, index.get_chain_network()).into())
-            }
-            Ok(None) => return Err(anyhow!("outpoint not found {}", satpoint.outpoint)),
-            Err(e) => return Err(e),
-          },
+          Some(satpoint) => Some(ord::get_script_key_on_outpoint(
+            index,
+            &tx,
+            satpoint.outpoint,
+          )?),
           None => None,
         },
         old_satpoint: msg.old_satpoint.to_string(),
@@ -200,13 +233,15 @@ fn get_operations_by_txid(
     txid: txid.to_string(),
     blockhash: tx_result.blockhash.map(|v| v.to_string()),
     confirmed: tx_result.blockhash.is_some(),
+    simulated: tx_result.confirmations.is_none(),
     inscriptions: brc20_operation_infos,
   })
 }
+
 #[cfg(test)]
 mod tests {
 
-  use super::*;
+  use {super::*, crate::okx::datastore::ScriptKey};
   #[test]
   fn serialize_deploy() {
     let deploy = Deploy {
@@ -15,6 +15,12 @@ pub struct TransferableInscription {
   pub tick: String,
   /// The address to which the transfer will be made.
   pub owner: String,
+  /// The number of blocks since the inscription was inscribed, relative to the indexed tip.
+  pub age_blocks: u64,
+  /// The sat which carries the inscription, only present with `include=sat`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(value_type = Option<SatInfo>)]
+  pub sat: Option<SatInfo>,
 }
 
 impl From<&brc20_store::TransferableLog> for TransferableInscription {
@@ -25,6 +31,8 @@ impl From<&brc20_store::TransferableLog> for TransferableInscription {
       amount: trans.amount.to_string(),
       tick: trans.tick.as_str().to_string(),
       owner: trans.owner.to_string(),
+      age_blocks: 0,
+      sat: None,
     }
   }
 }
@@ -37,7 +45,8 @@ impl From<&brc20_store::TransferableLog> for TransferableInscription {
   path = "/api/v1/brc20/tick/{ticker}/address/{address}/transferable",
   params(
       ("ticker" = String, Path, description = "Token ticker", min_length = 4, max_length = 4),
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      IncludeQuery
 ),
   responses(
     (status = 200, description = "Obtain account transferable inscriptions of ticker.", body = BRC20Transferable),
@@ -49,11 +58,12 @@ impl From<&brc20_store::TransferableLog> for TransferableInscription {
 pub(crate) async fn brc20_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path((tick, address)): Path<(String, String)>,
+  Query(query): Query<IncludeQuery>,
 ) -> ApiResult<TransferableInscriptions> {
   log::debug!("rpc: get brc20_transferable: {tick} {address}");
 
   let tick = brc20_store::Tick::from_str(&tick)
-    .map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
+    .map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
@@ -67,10 +77,31 @@ pub(crate) async fn brc20_transferable(
   );
 
   Ok(Json(ApiResponse::ok(TransferableInscriptions {
-    inscriptions: transferable.iter().map(|trans| trans.into()).collect(),
+    inscriptions: transferable_inscriptions(&index, &transferable, query.sat())?,
   })))
 }
 
+fn transferable_inscriptions(
+  index: &Index,
+  transferable: &[brc20_store::TransferableLog],
+  include_sat: bool,
+) -> Result<Vec<TransferableInscription>, ApiError> {
+  let tip = index.block_height()?.map(|height| height.n()).unwrap_or(0);
+  transferable
+    .iter()
+    .map(|trans| {
+      let mut inscription = TransferableInscription::from(trans);
+      if let Some(entry) = index.get_inscription_entry(trans.inscription_id)? {
+        inscription.age_blocks = tip.saturating_sub(entry.height);
+        if include_sat {
+          inscription.sat = entry.sat.map(SatInfo::from);
+        }
+      }
+      Ok(inscription)
+    })
+    .collect()
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20::TransferableInscriptions)]
 #[serde(rename_all = "camelCase")]
@@ -86,7 +117,8 @@ pub struct TransferableInscriptions {
   get,
   path = "/api/v1/brc20/address/{address}/transferable",
   params(
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      IncludeQuery
 ),
   responses(
     (status = 200, description = "Obtain account all transferable inscriptions.", body = BRC20Transferable),
@@ -98,6 +130,7 @@ pub struct TransferableInscriptions {
 pub(crate) async fn brc20_all_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(query): Query<IncludeQuery>,
 ) -> ApiResult<TransferableInscriptions> {
   log::debug!("rpc: get brc20_all_transferable: {address}");
 
@@ -112,6 +145,6 @@ pub(crate) async fn brc20_all_transferable(
   );
 
   Ok(Json(ApiResponse::ok(TransferableInscriptions {
-    inscriptions: transferable.iter().map(|trans| trans.into()).collect(),
+    inscriptions: transferable_inscriptions(&index, &transferable, query.sat())?,
   })))
 }
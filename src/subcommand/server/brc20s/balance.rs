@@ -1,4 +1,7 @@
-use {super::*, axum::Json, utoipa::ToSchema};
+use {
+  super::*, crate::okx::protocol::brc20s::Num, axum::Json, std::collections::HashMap,
+  utoipa::ToSchema,
+};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(as = brc20s::Balance)]
@@ -12,27 +15,44 @@ pub(crate) struct Balance {
   /// Overall balance.
   #[schema(format = "uint64")]
   pub overall: String,
+  /// The decimal of the ticker.
+  #[schema(format = "uint8")]
+  pub decimal: u8,
+  /// Transferable balance formatted with the decimal of the ticker.
+  #[schema(example = "0.5")]
+  pub transferable_formatted: String,
+  /// Overall balance formatted with the decimal of the ticker.
+  #[schema(example = "2")]
+  pub overall_formatted: String,
+  /// The number of transfer inscriptions making up the transferable balance. Not returned for
+  /// balances at a block.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(format = "uint64")]
+  pub transferable_count: Option<u64>,
 }
 
 impl Balance {
-  pub fn set_tick_name(&mut self, name: String) {
-    self.tick.name = name;
-  }
-}
-
-impl From<&brc20s::Balance> for Balance {
-  fn from(balance: &brc20s::Balance) -> Self {
+  pub fn new(balance: &brc20s::Balance, tick_info: &brc20s::TickInfo) -> Self {
     let tick = Tick {
       id: balance.tick_id.hex(),
-      name: "".to_string(),
+      name: tick_info.name.as_str().to_string(),
     };
 
     Self {
       tick,
       transferable: balance.transferable_balance.to_string(),
       overall: balance.overall_balance.to_string(),
+      decimal: tick_info.decimal,
+      transferable_formatted: Num::from(balance.transferable_balance)
+        .format_scaled(tick_info.decimal),
+      overall_formatted: Num::from(balance.overall_balance).format_scaled(tick_info.decimal),
+      transferable_count: None,
     }
   }
+
+  pub fn set_transferable_count(&mut self, transferable_count: usize) {
+    self.transferable_count = Some(transferable_count as u64);
+  }
 }
 
 // brc20s/tick/:tickId/address/:address/balance
@@ -65,22 +85,22 @@ pub(crate) async fn brc20s_balance(
   );
 
   let tick_id = brc20s::TickId::from_str(&tick_id)
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
   let balance = &index
     .brc20s_balance(&tick_id, &address)?
-    .ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
-
-  let mut balance_result = Balance::from(balance);
+    .ok_or(BRC20SError::BalanceNotFound)?;
 
   let tick_info = &index
     .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+    .ok_or(BRC20SError::TickIdNotFound)?;
 
-  balance_result.set_tick_name(tick_info.name.as_str().to_string());
+  let mut balance_result = Balance::new(balance, tick_info);
+  balance_result
+    .set_transferable_count(index.brc20s_tickid_transferable(&tick_id, &address)?.len());
   log::debug!(
     "rpc: get brc20s_balance: {:?} {:?}",
     tick_id.hex(),
@@ -90,6 +110,68 @@ pub(crate) async fn brc20s_balance(
   Ok(Json(ApiResponse::ok(balance_result)))
 }
 
+// brc20s/address/:address/tick/:tickId/balance
+
+/// Get the ticker balance of the address at a block.
+///
+/// Returns the balance as of the end of the block, from the history recorded with
+/// `--enable-balance-history`.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/address/{address}/tick/{tick_id}/balance",
+  params(
+      ("address" = String, Path, description = "Address"),
+      ("tick_id" = String, Path, description = "Token ticker ID", min_length = 10, max_length = 10),
+      AtBlockQuery
+),
+  responses(
+    (status = 200, description = "Obtain account balance by query ticker at the block.", body = BRC20SBalance),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found or balance history disabled.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_balance_at(
+  Extension(index): Extension<Arc<Index>>,
+  Path((address, tick_id)): Path<(String, String)>,
+  Query(query): Query<AtBlockQuery>,
+) -> ApiResult<Balance> {
+  log::debug!(
+    "rpc: get brc20s_balance_at: address:{}, tickId:{}, block:{:?}",
+    address,
+    tick_id,
+    query.block
+  );
+
+  if !index.has_balance_history() {
+    return Err(ApiError::from(BRC20SError::BalanceHistoryDisabled));
+  }
+
+  let tick_id = brc20s::TickId::from_str(&tick_id)
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let block = match query.block {
+    Some(block) => block,
+    None => index.height()?.map(|height| height.n()).unwrap_or_default(),
+  };
+
+  let balance = &index
+    .brc20s_balance_at(&tick_id, &address, block)?
+    .ok_or(BRC20SError::BalanceNotFound)?;
+
+  let tick_info = &index
+    .brc20s_tick_info(&tick_id)?
+    .ok_or(BRC20SError::TickIdNotFound)?;
+
+  let balance_result = Balance::new(balance, tick_info);
+
+  Ok(Json(ApiResponse::ok(balance_result)))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(as = brc20s::AllBalance)]
@@ -125,6 +207,10 @@ pub(crate) async fn brc20s_all_balance(
     .map_err(ApiError::bad_request)?;
 
   let all_balance = index.brc20s_all_balance(&address)?;
+  let mut transferable_counts = HashMap::new();
+  for asset in index.brc20s_all_transferable(&address)? {
+    *transferable_counts.entry(asset.tick_id.hex()).or_insert(0) += 1;
+  }
 
   log::debug!("rpc: get brc20s_all_balance: {} {:?}", address, all_balance);
 
@@ -132,11 +218,15 @@ pub(crate) async fn brc20s_all_balance(
     balance: all_balance
       .iter()
       .map(|(tick_id, balance)| {
-        let mut balance_result = Balance::from(balance);
-
         let tick_info = &index.brc20s_tick_info(tick_id).unwrap().unwrap();
 
-        balance_result.set_tick_name(tick_info.name.as_str().to_string());
+        let mut balance_result = Balance::new(balance, tick_info);
+        balance_result.set_transferable_count(
+          transferable_counts
+            .get(&tick_id.hex())
+            .copied()
+            .unwrap_or_default(),
+        );
         log::debug!(
           "rpc: get brc20s_userinfo: {:?} {:?}",
           tick_id,
@@ -159,13 +249,13 @@ pub(crate) async fn brc20s_debug_balance(
   );
 
   let tick_id = brc20s::TickId::from_str(&tick_id)
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
   let balance = index
     .brc20s_balance(&tick_id, &address)?
-    .ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
+    .ok_or(BRC20SError::BalanceNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_balance: {:?} {:?}",
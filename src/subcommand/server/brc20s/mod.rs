@@ -1,22 +1,31 @@
 use {
-  super::{types::ScriptPubkey, *},
+  super::{
+    types::{SatInfo, ScriptPubkey},
+    *,
+  },
   crate::okx::datastore::brc20s,
   utoipa::ToSchema,
 };
 
 mod balance;
+mod mutation;
 mod pool;
 mod receipt;
 mod reward;
+mod stats;
 mod ticker;
 mod transferable;
 
-pub(super) use {balance::*, pool::*, receipt::*, reward::*, ticker::*, transferable::*};
+pub(super) use {
+  balance::*, mutation::*, pool::*, receipt::*, reward::*, stats::*, ticker::*, transferable::*,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum BRC20SError {
   #[error("tid must be 10 hex length")]
   IncorrectTickIdFormat,
+  #[error("tick must be 4 to 6 bytes length")]
+  IncorrectTickFormat,
   #[error("pid must be 13 hex length")]
   IncorrectPidFormat,
   #[error("tid not found")]
@@ -33,6 +42,65 @@ pub enum BRC20SError {
   StakeInfoNotFound,
   #[error("user info not found")]
   UserInfoNotFound,
+  #[error("invalid cursor")]
+  IncorrectCursorFormat,
+  #[error("block is earlier than the user's latest update")]
+  BlockBeforeLatestUpdate,
+  #[error("balance history is not enabled")]
+  BalanceHistoryDisabled,
+  #[error("from must not be greater than to")]
+  IncorrectBlockRange,
+  #[error("status must be one of success or error")]
+  IncorrectReceiptStatus,
+  #[error("deploy inscription not found")]
+  DeployNotFound,
+  #[error("block mutations are not enabled")]
+  BlockMutationsDisabled,
+  #[error("the range must not span more than {} blocks", stats::MAX_STATS_BLOCKS)]
+  BlockRangeTooLarge,
+}
+
+impl BRC20SError {
+  /// Stable code of the error in api responses. Codes are never reused.
+  pub(crate) fn code(&self) -> i32 {
+    match self {
+      Self::IncorrectTickIdFormat => 3001,
+      Self::IncorrectTickFormat => 3002,
+      Self::IncorrectPidFormat => 3003,
+      Self::TickIdNotFound => 3004,
+      Self::BalanceNotFound => 3005,
+      Self::ReceiptsNotFound => 3006,
+      Self::BlockReceiptsNotFound => 3007,
+      Self::PoolInfoNotFound => 3008,
+      Self::StakeInfoNotFound => 3009,
+      Self::UserInfoNotFound => 3010,
+      Self::IncorrectCursorFormat => 3011,
+      Self::BlockBeforeLatestUpdate => 3012,
+      Self::BalanceHistoryDisabled => 3013,
+      Self::IncorrectBlockRange => 3014,
+      Self::IncorrectReceiptStatus => 3015,
+      Self::DeployNotFound => 3016,
+      Self::BlockMutationsDisabled => 3017,
+      Self::BlockRangeTooLarge => 3018,
+    }
+  }
+}
+
+impl From<BRC20SError> for ApiError {
+  fn from(error: BRC20SError) -> Self {
+    let status = match error {
+      BRC20SError::IncorrectTickIdFormat
+      | BRC20SError::IncorrectTickFormat
+      | BRC20SError::IncorrectPidFormat
+      | BRC20SError::IncorrectCursorFormat
+      | BRC20SError::BlockBeforeLatestUpdate
+      | BRC20SError::IncorrectBlockRange
+      | BRC20SError::BlockRangeTooLarge
+      | BRC20SError::IncorrectReceiptStatus => StatusCode::BAD_REQUEST,
+      _ => StatusCode::NOT_FOUND,
+    };
+    ApiError::new(status, error.code(), error)
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -54,13 +122,21 @@ pub(crate) struct StakeValue {
   tick: String,
 }
 
+impl From<&brc20s::PledgedTick> for StakeValue {
+  fn from(pledged_tick: &brc20s::PledgedTick) -> Self {
+    Self {
+      type_field: pledged_tick.to_type(),
+      tick: pledged_tick.to_string(),
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20s::Stake)]
-#[serde(rename_all = "camelCase")]
+#[serde(untagged)]
 pub(crate) enum Stake {
   /// Stake BRC20 Ticker.
   #[schema(value_type = brc20s::StakeValue)]
-  #[serde(untagged)]
   BRC20(StakeValue),
 }
 
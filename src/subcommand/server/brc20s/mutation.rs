@@ -0,0 +1,22 @@
+use {super::*, crate::okx::datastore::brc20s, axum::Json};
+
+// brc20s/debug/block/:height/mutations
+/// Get the balance and pool changes applied by the brc20s operations of a block, in the order
+/// they were applied with the state before and after each change. Only served with
+/// `--enable-block-mutations`, for the blocks a reorg can still roll back.
+pub(crate) async fn brc20s_debug_block_mutations(
+  Extension(index): Extension<Arc<Index>>,
+  Path(height): Path<u64>,
+) -> ApiResult<Vec<brc20s::Mutation>> {
+  log::debug!("rpc: get brc20s_debug_block_mutations: {}", height);
+
+  if !index.has_block_mutations() {
+    return Err(ApiError::from(BRC20SError::BlockMutationsDisabled));
+  }
+
+  let mutations = index
+    .brc20s_block_mutations(height)?
+    .ok_or(BRC20SError::BlockReceiptsNotFound)?;
+
+  Ok(Json(ApiResponse::ok(mutations)))
+}
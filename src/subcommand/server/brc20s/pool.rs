@@ -1,5 +1,12 @@
 use {
   super::*,
+  crate::{
+    okx::{
+      datastore::{brc20, brc20s::PledgedTick},
+      protocol::brc20s::params::{NATIVE_TOKEN, TICK_ID_STR_COUNT},
+    },
+    subcommand::server::brc20::BRC20Error,
+  },
   axum::Json,
   brc20s::{Pid, PoolInfo, TickId},
 };
@@ -46,6 +53,13 @@ pub(crate) struct Pool {
   pub deploy_blocktime: u32,
   /// A hex encoded 32 byte transaction ID that the ticker deployed.
   pub txid: String,
+  /// The amount staked by all current stakers of the pool. Only returned by the pool detail.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub total_staked: Option<String>,
+  /// The number of addresses that currently have a stake in the pool. Only returned by the pool detail.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(format = "uint64")]
+  pub staker_count: Option<u64>,
 }
 
 impl Pool {
@@ -61,6 +75,11 @@ impl Pool {
   pub fn set_deployer(&mut self, deployer: ScriptPubkey) {
     self.deployer = deployer;
   }
+
+  pub fn set_participation(&mut self, total_staked: u128, staker_count: usize) {
+    self.total_staked = Some(total_staked.to_string());
+    self.staker_count = Some(staker_count as u64);
+  }
 }
 
 impl From<&PoolInfo> for Pool {
@@ -90,6 +109,8 @@ impl From<&PoolInfo> for Pool {
       deploy_height: pool_info.deploy_block,
       deploy_blocktime: pool_info.deploy_block_time,
       txid: pool_info.inscription_id.txid.to_string(),
+      total_staked: None,
+      staker_count: None,
     }
   }
 }
@@ -118,12 +139,11 @@ pub(crate) async fn brc20s_pool_info(
 ) -> ApiResult<Pool> {
   log::debug!("rpc: get brc20s_pool_info: {}", pid);
 
-  let pid =
-    Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
 
   let pool_info = &index
     .brc20s_pool_info(&pid)?
-    .ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
+    .ok_or(BRC20SError::PoolInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_pool_info: {:?} {:?}",
@@ -135,11 +155,16 @@ pub(crate) async fn brc20s_pool_info(
     return Err(ApiError::internal("db: not match"));
   }
 
-  let tick_id = TickId::from(pid);
+  Ok(Json(ApiResponse::ok(pool_detail(&index, pool_info)?)))
+}
+
+// the pool info with its earn ticker, deployer and participation, as returned by the pool detail.
+fn pool_detail(index: &Index, pool_info: &PoolInfo) -> Result<Pool, ApiError> {
+  let tick_id = TickId::from(pool_info.pid.clone());
 
   let tick_info = &index
     .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+    .ok_or(BRC20SError::TickIdNotFound)?;
 
   let inscription_number = &index
     .get_inscription_entry(pool_info.inscription_id)
@@ -151,7 +176,250 @@ pub(crate) async fn brc20s_pool_info(
   pool.set_inscription_num(inscription_number.number);
   pool.set_deployer(tick_info.deployer.clone().into());
 
-  Ok(Json(ApiResponse::ok(pool)))
+  let staker_count = index.brc20s_pool_staker_count(&pool_info.pid)?;
+  pool.set_participation(pool_info.staked, staker_count);
+
+  Ok(pool)
+}
+
+// brc20s/tick/:tick_id/stake/:stake_tick/pool
+
+/// Get the pool of the ticker staking the stake ticker.
+///
+/// Retrieve the pool a ticker deployed for the stake ticker, for clients which don't know its pid.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/tick/{tick_id}/stake/{stake_tick}/pool",
+  params(
+      ("tick_id" = String, Path, description = "Ticker ID", min_length = 10, max_length = 10, example = "a12345678f"),
+      ("stake_tick" = String, Path, description = "The stake ticker, `btc`, a BRC20 ticker name or a BRC20-S ticker ID", example = "ordi"),
+),
+  responses(
+    (status = 200, description = "Obtain the pool of the ticker staking the stake ticker", body = BRC20SPool),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_tick_stake_pool(
+  Extension(index): Extension<Arc<Index>>,
+  Path((tick_id, stake_tick)): Path<(String, String)>,
+) -> ApiResult<Pool> {
+  log::debug!(
+    "rpc: get brc20s_tick_stake_pool: {}, {}",
+    tick_id,
+    stake_tick
+  );
+
+  let tick_id =
+    TickId::from_str(&tick_id).map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
+  let stake = parse_stake_tick(&stake_tick)?;
+
+  let pool_info = &index
+    .brc20s_pool_info_by_stake(&tick_id, &stake)?
+    .ok_or(BRC20SError::PoolInfoNotFound)?;
+
+  log::debug!(
+    "rpc: get brc20s_tick_stake_pool: {:?} {:?}",
+    tick_id.hex(),
+    pool_info
+  );
+
+  Ok(Json(ApiResponse::ok(pool_detail(&index, pool_info)?)))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::PoolBlocksRemaining)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PoolBlocksRemaining {
+  /// Pool id.
+  pub pid: String,
+  /// Estimated number of blocks until dmax is exhausted, null if nothing is staked.
+  #[schema(format = "uint64")]
+  pub blocks_remaining: Option<u64>,
+}
+
+// brc20s/pool/:pid/blocks-remaining
+
+/// Get the estimated blocks until the pool fully distributes.
+///
+/// Estimate the number of blocks until dmax is exhausted at the current stake and erate.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/pool/{pid}/blocks-remaining",
+  params(
+      ("pid" = String, Path, description = "Pool ID", min_length = 13, max_length = 13, example= "a01234567f#0f"),
+),
+  responses(
+    (status = 200, description = "Obtain the estimated blocks remaining of the pool", body = BRC20SPoolBlocksRemaining),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_pool_blocks_remaining(
+  Extension(index): Extension<Arc<Index>>,
+  Path(pid): Path<String>,
+) -> ApiResult<PoolBlocksRemaining> {
+  log::debug!("rpc: get brc20s_pool_blocks_remaining: {}", pid);
+
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
+
+  let pool_info = index
+    .brc20s_pool_info(&pid)?
+    .ok_or(BRC20SError::PoolInfoNotFound)?;
+
+  let blocks_remaining = index.brc20s_pool_blocks_remaining(&pool_info)?;
+
+  log::debug!(
+    "rpc: get brc20s_pool_blocks_remaining: {:?} {:?}",
+    pid.as_str(),
+    blocks_remaining
+  );
+
+  Ok(Json(ApiResponse::ok(PoolBlocksRemaining {
+    pid: pid.as_str().to_string(),
+    blocks_remaining,
+  })))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::StakeTick)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StakeTick {
+  /// The stake ticker.
+  #[schema(value_type = brc20s::StakeValue)]
+  pub stake: StakeValue,
+  /// The number of pools staking the ticker.
+  pub pool_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::StakeTicks)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StakeTicks {
+  #[schema(value_type = Vec<brc20s::StakeTick>)]
+  pub ticks: Vec<StakeTick>,
+}
+
+fn stake_ticks(pools: &[PoolInfo]) -> Vec<StakeTick> {
+  let mut ticks: Vec<(&brc20s::PledgedTick, usize)> = Vec::new();
+  for pool in pools {
+    match ticks.iter_mut().find(|(stake, _)| **stake == pool.stake) {
+      Some((_, count)) => *count += 1,
+      None => ticks.push((&pool.stake, 1)),
+    }
+  }
+  ticks
+    .into_iter()
+    .map(|(stake, pool_count)| StakeTick {
+      stake: stake.into(),
+      pool_count,
+    })
+    .collect()
+}
+
+// brc20s/stake-ticks
+
+/// Get all of the stake tickers.
+///
+/// Retrieve the distinct stake tickers of all deployed pools with the number of pools of each.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/stake-ticks",
+  responses(
+    (status = 200, description = "Obtain all of the stake tickers.", body = BRC20SStakeTicks),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_stake_ticks(
+  Extension(index): Extension<Arc<Index>>,
+) -> ApiResult<StakeTicks> {
+  log::debug!("rpc: get brc20s_stake_ticks");
+
+  let (all_pool_info, _) = index.brc20s_all_pool_info(0, None)?;
+  let ticks = stake_ticks(&all_pool_info);
+
+  log::debug!("rpc: get brc20s_stake_ticks: {:?}", ticks);
+
+  Ok(Json(ApiResponse::ok(StakeTicks { ticks })))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::EarnTick)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EarnTick {
+  /// The ticker earned by staking.
+  #[schema(value_type = brc20s::Tick)]
+  pub tick: Tick,
+  /// The number of pools of the ticker accepting the stake.
+  pub pool_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::EarnTicks)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EarnTicks {
+  #[schema(value_type = Vec<brc20s::EarnTick>)]
+  pub ticks: Vec<EarnTick>,
+}
+
+// the distinct tick ids of the pools with the number of pools of each, in order of appearance.
+fn earn_tick_ids(pids: &[Pid]) -> Vec<(TickId, usize)> {
+  let mut tick_ids: Vec<(TickId, usize)> = Vec::new();
+  for pid in pids {
+    let tick_id = TickId::from(pid.clone());
+    match tick_ids.iter_mut().find(|(id, _)| *id == tick_id) {
+      Some((_, count)) => *count += 1,
+      None => tick_ids.push((tick_id, 1)),
+    }
+  }
+  tick_ids
+}
+
+// brc20s/stake/:tick/ticks
+
+/// Get the tickers which can be earned by staking the ticker.
+///
+/// Retrieve the distinct tickers of all pools staking the ticker with the number of pools of each.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/stake/{tick}/ticks",
+  params(
+      ("tick" = String, Path, description = "The stake ticker, `btc`, a BRC20 ticker name or a BRC20-S ticker ID", example = "ordi"),
+),
+  responses(
+    (status = 200, description = "Obtain the tickers earned by staking the ticker.", body = BRC20SEarnTicks),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_earn_ticks_by_stake(
+  Extension(index): Extension<Arc<Index>>,
+  Path(tick): Path<String>,
+) -> ApiResult<EarnTicks> {
+  log::debug!("rpc: get brc20s_earn_ticks_by_stake: {}", tick);
+
+  let stake = parse_stake_tick(&tick)?;
+  let pids = index.brc20s_pids_by_stake(&stake)?;
+
+  let mut ticks = Vec::new();
+  for (tick_id, pool_count) in earn_tick_ids(&pids) {
+    let tick_info = index
+      .brc20s_tick_info(&tick_id)?
+      .ok_or(anyhow!("tick not found, tid: {}", tick_id.hex()))?;
+    ticks.push(EarnTick {
+      tick: Tick {
+        id: tick_id.hex(),
+        name: tick_info.name.as_str().to_string(),
+      },
+      pool_count,
+    });
+  }
+
+  log::debug!("rpc: get brc20s_earn_ticks_by_stake: {:?}", ticks);
+
+  Ok(Json(ApiResponse::ok(EarnTicks { ticks })))
 }
 
 pub(crate) async fn brc20s_debug_pool_info(
@@ -160,12 +428,11 @@ pub(crate) async fn brc20s_debug_pool_info(
 ) -> ApiResult<PoolInfo> {
   log::debug!("rpc: get brc20s_debug_pool_info: {}", pid);
 
-  let pid =
-    Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
 
   let pool_info = index
     .brc20s_pool_info(&pid)?
-    .ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
+    .ok_or(BRC20SError::PoolInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_pool_info: {:?} {:?}",
@@ -187,11 +454,14 @@ pub(crate) struct AllPoolInfo {
 
 // brc20s/pool
 /// Get the all of pool infomations.
+///
+/// With `earn`, only the pools earning the ticker of that name are listed.
 #[utoipa::path(
   get,
   path = "/api/v1/brc20s/pool",
   params(
-    Pagination
+    Pagination,
+    PoolQuery
 ),
   responses(
     (status = 200, description = "Obtain all of pool infomations", body = BRC20SAllPool),
@@ -203,9 +473,17 @@ pub(crate) struct AllPoolInfo {
 pub(crate) async fn brc20s_all_pool_info(
   Extension(index): Extension<Arc<Index>>,
   Query(page): Query<Pagination>,
+  Query(query): Query<PoolQuery>,
 ) -> ApiResult<AllPoolInfo> {
-  log::debug!("rpc: get brc20s_all_pool_info");
-  let (all_pool_info, total) = index.brc20s_all_pool_info(page.start.unwrap_or(0), page.limit)?;
+  log::debug!("rpc: get brc20s_all_pool_info: {:?}", query.earn);
+  let (all_pool_info, total) = match query.earn {
+    Some(earn) => {
+      let earn = brc20s::Tick::from_str(&earn)
+        .map_err(|_| ApiError::from(BRC20SError::IncorrectTickFormat))?;
+      index.brc20s_pools_by_earn(&earn, page.start.unwrap_or(0), page.limit)?
+    }
+    None => index.brc20s_all_pool_info(page.start.unwrap_or(0), page.limit)?,
+  };
   log::debug!("rpc: get brc20s_all_pool_info: {:?}", all_pool_info);
   Ok(Json(ApiResponse::ok(AllPoolInfo {
     pools: all_pool_info
@@ -253,13 +531,13 @@ pub(crate) async fn brc20s_all_pools_by_tid(
 ) -> ApiResult<AllPoolInfo> {
   log::debug!("rpc: get brc20s_all_pools_by_tid: {}", tick_id);
 
-  let tick_id = TickId::from_str(&tick_id)
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+  let tick_id =
+    TickId::from_str(&tick_id).map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
   let all_pool_info = index.brc20s_all_pools_by_tid(&tick_id)?;
 
   let _ = index
     .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+    .ok_or(BRC20SError::TickIdNotFound)?;
 
   log::debug!("rpc: get brc20s_all_pools_by_tid: {:?}", all_pool_info);
   Ok(Json(ApiResponse::ok(AllPoolInfo {
@@ -284,3 +562,154 @@ pub(crate) async fn brc20s_all_pools_by_tid(
     total: all_pool_info.len(),
   })))
 }
+
+// /brc20s/pool/stake/:tick
+/// Get the pool infomations by stake ticker.
+///
+/// Get the pool information of all pools staking the ticker, ordered by pid.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/pool/stake/{tick}",
+  params(
+      ("tick" = String, Path, description = "The stake ticker, `btc`, a BRC20 ticker name or a BRC20-S ticker ID", example = "ordi"),
+      Pagination
+),
+  responses(
+    (status = 200, description = "Obtain pool infomations by stake ticker", body = BRC20SAllPool),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_pools_by_stake(
+  Extension(index): Extension<Arc<Index>>,
+  Path(tick): Path<String>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<AllPoolInfo> {
+  log::debug!("rpc: get brc20s_pools_by_stake: {}", tick);
+
+  let stake = parse_stake_tick(&tick)?;
+  let (all_pool_info, total) =
+    index.brc20s_pools_by_stake(&stake, page.start.unwrap_or(0), page.limit)?;
+
+  log::debug!("rpc: get brc20s_pools_by_stake: {:?}", all_pool_info);
+  Ok(Json(ApiResponse::ok(AllPoolInfo {
+    pools: all_pool_info
+      .iter()
+      .map(|pool| {
+        let tick_id = TickId::from(pool.pid.clone());
+        let tick_info = &index.brc20s_tick_info(&tick_id).unwrap().unwrap();
+
+        let inscription_number = &index
+          .get_inscription_entry(pool.inscription_id)
+          .unwrap()
+          .unwrap();
+
+        let mut pool_result = Pool::from(pool);
+        pool_result.set_earn(tick_info.tick_id.hex(), tick_info.name.as_str().to_string());
+        pool_result.set_inscription_num(inscription_number.number);
+        pool_result.set_deployer(tick_info.deployer.clone().into());
+        pool_result
+      })
+      .collect(),
+    total,
+  })))
+}
+
+fn parse_stake_tick(tick: &str) -> Result<PledgedTick, ApiError> {
+  if tick.to_lowercase() == NATIVE_TOKEN {
+    return Ok(PledgedTick::Native);
+  }
+  if tick.len() == TICK_ID_STR_COUNT {
+    return TickId::from_str(tick)
+      .map(PledgedTick::BRC20STick)
+      .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat));
+  }
+  brc20::Tick::from_str(tick)
+    .map(PledgedTick::BRC20Tick)
+    .map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::okx::datastore::brc20s::PoolType};
+
+  fn pool_info(pid: &str, stake: PledgedTick) -> PoolInfo {
+    PoolInfo::new(
+      &Pid::from_str(pid).unwrap(),
+      &PoolType::Pool,
+      &InscriptionId::from_str(
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      )
+      .unwrap(),
+      &stake,
+      10,
+      0,
+      0,
+      100,
+      "0".to_string(),
+      0,
+      true,
+      0,
+      0,
+      None,
+      None,
+      None,
+    )
+  }
+
+  #[test]
+  fn test_stake_ticks() {
+    let ordi = PledgedTick::BRC20Tick(brc20::Tick::from_str("ordi").unwrap());
+    let btc = PledgedTick::Native;
+    let pools = vec![
+      pool_info("a12345678f#01", ordi.clone()),
+      pool_info("a12345678f#02", btc),
+      pool_info("b12345678f#01", ordi),
+    ];
+
+    assert_eq!(
+      serde_json::to_string(&StakeTicks {
+        ticks: stake_ticks(&pools)
+      })
+      .unwrap(),
+      r#"{"ticks":[{"stake":{"type":"BRC20","tick":"ordi"},"poolCount":2},{"stake":{"type":"BTC","tick":"btc"},"poolCount":1}]}"#
+    );
+  }
+
+  #[test]
+  fn test_earn_tick_ids() {
+    let pids = ["a12345678f#01", "a12345678f#02", "b12345678f#01"]
+      .into_iter()
+      .map(|pid| Pid::from_str(pid).unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      earn_tick_ids(&pids),
+      vec![
+        (TickId::from_str("a12345678f").unwrap(), 2),
+        (TickId::from_str("b12345678f").unwrap(), 1),
+      ]
+    );
+    assert_eq!(earn_tick_ids(&[]), vec![]);
+  }
+
+  #[test]
+  fn test_parse_stake_tick() {
+    assert_eq!(parse_stake_tick("btc").ok(), Some(PledgedTick::Native));
+    assert_eq!(parse_stake_tick("BTC").ok(), Some(PledgedTick::Native));
+    assert_eq!(
+      parse_stake_tick("ordi").ok(),
+      Some(PledgedTick::BRC20Tick(
+        brc20::Tick::from_str("ordi").unwrap()
+      ))
+    );
+    assert_eq!(
+      parse_stake_tick("a12345678f").ok(),
+      Some(PledgedTick::BRC20STick(
+        TickId::from_str("a12345678f").unwrap()
+      ))
+    );
+    assert!(parse_stake_tick("a12345678z").is_err());
+    assert!(parse_stake_tick("toolongtick").is_err());
+  }
+}
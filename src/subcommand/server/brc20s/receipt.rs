@@ -1,4 +1,8 @@
-use {super::*, crate::okx::datastore::brc20s, axum::Json};
+use {
+  super::{ord::InscriptionAction, *},
+  crate::okx::datastore::brc20s,
+  axum::Json,
+};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20s::Receipt)]
 #[serde(rename_all = "camelCase")]
@@ -6,21 +10,21 @@ pub struct Receipt {
   /// Operation type.
   #[schema(value_type = brc20s::OperationType)]
   pub op: OperationType,
-  /// THe inscription number.
-  pub inscription_number: Option<i64>,
-  /// The inscription id.
-  pub inscription_id: Option<String>,
+  /// THe inscription number. For a passive withdraw, the transfer inscription that triggered it.
+  pub inscription_number: i64,
+  /// The inscription id. For a passive withdraw, the transfer inscription that triggered it.
+  pub inscription_id: String,
   /// The inscription satpoint of the transaction input.
-  pub old_satpoint: Option<String>,
+  pub old_satpoint: String,
   /// The inscription satpoint of the transaction output.
-  pub new_satpoint: Option<String>,
+  pub new_satpoint: String,
   /// The message sender which is an address or script pubkey hash.
   pub from: ScriptPubkey,
   /// The message receiver which is an address or script pubkey hash.
   pub to: Option<ScriptPubkey>,
   /// Whether the receipt is valid.
   pub valid: bool,
-  /// The message of the receipt.
+  /// The message of the receipt, `ok` or the reason the operation failed.
   pub msg: String,
   /// The events of the receipt.
   ///
@@ -29,25 +33,15 @@ pub struct Receipt {
 }
 
 impl Receipt {
-  pub(super) fn from(receipt: &brc20s::Receipt, index: Arc<Index>) -> Result<Self> {
-    let mut result = Self {
+  // every receipt carries the inscription of its message, a passive withdraw the transfer
+  // inscription that triggered it.
+  fn without_events(receipt: &brc20s::Receipt) -> Self {
+    Self {
       op: receipt.op.clone().into(),
-      inscription_number: match receipt.op {
-        brc20s::OperationType::PassiveUnStake => None,
-        _ => Some(receipt.inscription_number),
-      },
-      inscription_id: match receipt.op {
-        brc20s::OperationType::PassiveUnStake => None,
-        _ => Some(receipt.inscription_id.to_string()),
-      },
-      old_satpoint: match receipt.op {
-        brc20s::OperationType::PassiveUnStake => None,
-        _ => Some(receipt.old_satpoint.to_string()),
-      },
-      new_satpoint: match receipt.op {
-        brc20s::OperationType::PassiveUnStake => None,
-        _ => Some(receipt.new_satpoint.to_string()),
-      },
+      inscription_number: receipt.inscription_number,
+      inscription_id: receipt.inscription_id.to_string(),
+      old_satpoint: receipt.old_satpoint.to_string(),
+      new_satpoint: receipt.new_satpoint.to_string(),
       from: receipt.from.clone().into(),
       to: match receipt.op {
         brc20s::OperationType::PassiveUnStake => None,
@@ -59,7 +53,11 @@ impl Receipt {
         Err(e) => e.to_string(),
       },
       events: vec![],
-    };
+    }
+  }
+
+  pub(crate) fn from(receipt: &brc20s::Receipt, index: Arc<Index>) -> Result<Self> {
+    let mut result = Self::without_events(receipt);
 
     if let Ok(events) = receipt.result.clone() {
       let mut receipt_events = Vec::new();
@@ -96,6 +94,17 @@ impl Receipt {
             receipt.to.clone().into(),
             index.clone(),
           )?),
+          // a burn shows as a transfer into the burn address.
+          brc20s::Event::Burn(burn) => Event::Transfer(TransferEvent::new(
+            brc20s::TransferEvent {
+              tick_id: burn.tick_id,
+              amt: burn.amt,
+              msg: Some("burn".to_string()),
+            },
+            receipt.from.clone().into(),
+            receipt.to.clone().into(),
+            index.clone(),
+          )?),
         });
       }
       result.events = receipt_events;
@@ -254,6 +263,9 @@ pub struct DepositEvent {
   amount: String,
   /// The owner of the deposit.
   owner: ScriptPubkey,
+  /// The total staked in the pool after the deposit.
+  #[schema(format = "uint64")]
+  pool_total_after: String,
 }
 
 impl DepositEvent {
@@ -262,6 +274,7 @@ impl DepositEvent {
       pid: event.pid.as_str().to_string(),
       amount: event.amt.to_string(),
       owner,
+      pool_total_after: event.pool_total_after.to_string(),
     }
   }
 }
@@ -277,6 +290,9 @@ pub struct WithdrawEvent {
   amount: String,
   /// The owner of the withdraw.
   owner: ScriptPubkey,
+  /// The total staked in the pool after the withdraw.
+  #[schema(format = "uint64")]
+  pool_total_after: String,
 }
 
 impl WithdrawEvent {
@@ -285,6 +301,7 @@ impl WithdrawEvent {
       pid: event.pid.as_str().to_string(),
       amount: event.amt.to_string(),
       owner,
+      pool_total_after: event.pool_total_after.to_string(),
     }
   }
 }
@@ -300,6 +317,9 @@ pub struct PassiveWithdrawEvent {
   amount: String,
   /// The owner of the passive withdraw.
   owner: ScriptPubkey,
+  /// The total staked in the pool after the passive withdraw.
+  #[schema(format = "uint64")]
+  pool_total_after: String,
 }
 
 impl PassiveWithdrawEvent {
@@ -308,6 +328,7 @@ impl PassiveWithdrawEvent {
       pid: event.pid.as_str().to_string(),
       amount: event.amt.to_string(),
       owner,
+      pool_total_after: event.pool_total_after.to_string(),
     }
   }
 }
@@ -409,6 +430,19 @@ impl TransferEvent {
     })
   }
 }
+// `None` keeps every receipt, `Some(true)` only the successful and `Some(false)` only the failed.
+fn parse_receipt_status(status: &str) -> Result<bool, BRC20SError> {
+  match status {
+    "success" => Ok(true),
+    "error" => Ok(false),
+    _ => Err(BRC20SError::IncorrectReceiptStatus),
+  }
+}
+
+fn has_status(receipt: &brc20s::Receipt, success: Option<bool>) -> bool {
+  success.map_or(true, |success| receipt.result.is_ok() == success)
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20s::TxReceipts)]
 #[serde(rename_all = "camelCase")]
@@ -421,12 +455,14 @@ pub struct TxReceipts {
 // brc20s/tx/:txid/receipts
 /// Get the transaction receipts by txid.
 ///
-/// Get all receipts of the transaction.
+/// Get all receipts of the transaction. With `status`, only the successful or the failed receipts
+/// are listed, a failed receipt carries the reason in `msg`.
 #[utoipa::path(
   get,
   path = "/api/v1/brc20s/tx/{txid}/receipts",
   params(
-      ("txid" = String, Path, description = "transaction ID")
+      ("txid" = String, Path, description = "transaction ID"),
+      ReceiptStatusQuery
 ),
   responses(
     (status = 200, description = "Obtain transaction receipts by txid", body = BRC20STxReceipts),
@@ -438,18 +474,27 @@ pub struct TxReceipts {
 pub(crate) async fn brc20s_txid_receipts(
   Extension(index): Extension<Arc<Index>>,
   Path(txid): Path<String>,
+  Query(query): Query<ReceiptStatusQuery>,
 ) -> ApiResult<TxReceipts> {
   log::debug!("rpc: get brc20s_txid_receipts: {}", txid);
   let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
+  let success = query
+    .status
+    .as_deref()
+    .map(parse_receipt_status)
+    .transpose()?;
 
   let all_receipt = index
     .brc20s_txid_receipts(&txid)?
-    .ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
+    .ok_or(BRC20SError::ReceiptsNotFound)?;
 
   log::debug!("rpc: get brc20s_txid_receipts: {:?}", all_receipt);
 
   let mut receipts = Vec::new();
-  for receipt in all_receipt.iter() {
+  for receipt in all_receipt
+    .iter()
+    .filter(|receipt| has_status(receipt, success))
+  {
     match Receipt::from(receipt, index.clone()) {
       Ok(receipt) => {
         receipts.push(receipt);
@@ -466,6 +511,87 @@ pub(crate) async fn brc20s_txid_receipts(
   })))
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::TxInscription)]
+#[serde(rename_all = "camelCase")]
+pub struct TxInscription {
+  /// The action of the inscription.
+  #[schema(value_type = ord::InscriptionAction)]
+  pub action: InscriptionAction,
+  /// The inscription number.
+  pub inscription_number: Option<i64>,
+  /// The inscription id.
+  pub inscription_id: String,
+  /// The inscription satpoint of the transaction input.
+  pub old_satpoint: String,
+  /// The inscription satpoint of the transaction output.
+  pub new_satpoint: Option<String>,
+}
+
+impl From<brc20s::InscriptionOperation> for TxInscription {
+  fn from(op: brc20s::InscriptionOperation) -> Self {
+    Self {
+      // only inscriptions which are neither cursed nor unbound are resolved as brc20s.
+      action: if op.inscribe {
+        InscriptionAction::New {
+          cursed: false,
+          unbound: false,
+        }
+      } else {
+        InscriptionAction::Transfer
+      },
+      inscription_number: op.inscription_number,
+      inscription_id: op.inscription_id.to_string(),
+      old_satpoint: op.old_satpoint.to_string(),
+      new_satpoint: op.new_satpoint.map(|satpoint| satpoint.to_string()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::TxInscriptions)]
+#[serde(rename_all = "camelCase")]
+pub struct TxInscriptions {
+  #[schema(value_type = Vec<brc20s::TxInscription>)]
+  pub inscriptions: Vec<TxInscription>,
+  pub txid: String,
+}
+
+// brc20s/tx/:txid/inscriptions
+/// Get the brc20s inscriptions of the transaction by txid.
+///
+/// Get the inscription operations of the transaction which were resolved as brc20s, before they
+/// are executed. Compare with the receipts of the transaction to find out why an operation did or
+/// did not take effect.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/tx/{txid}/inscriptions",
+  params(
+      ("txid" = String, Path, description = "transaction ID")
+),
+  responses(
+    (status = 200, description = "Obtain brc20s inscription operations by txid", body = BRC20STxInscriptions),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_txid_inscriptions(
+  Extension(index): Extension<Arc<Index>>,
+  Path(txid): Path<String>,
+) -> ApiResult<TxInscriptions> {
+  log::debug!("rpc: get brc20s_txid_inscriptions: {}", txid);
+  let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
+
+  let operations = index.brc20s_txid_inscription_operations(&txid)?;
+
+  log::debug!("rpc: get brc20s_txid_inscriptions: {:?}", operations);
+
+  Ok(Json(ApiResponse::ok(TxInscriptions {
+    inscriptions: operations.into_iter().map(TxInscription::from).collect(),
+    txid: txid.to_string(),
+  })))
+}
+
 // brc20s/debug/tx/:txid/receipts
 pub(crate) async fn brc20s_debug_txid_receipts(
   Extension(index): Extension<Arc<Index>>,
@@ -476,7 +602,7 @@ pub(crate) async fn brc20s_debug_txid_receipts(
 
   let all_receipt = index
     .brc20s_txid_receipts(&txid)?
-    .ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
+    .ok_or(BRC20SError::ReceiptsNotFound)?;
 
   log::debug!("rpc: get brc20s_debug_txid_receipts: {:?}", all_receipt);
 
@@ -494,44 +620,60 @@ pub struct BlockReceipts {
 // brc20s/block/:blockhash/receipts
 /// Get the block receipts by blockhash.
 ///
-/// Get all receipts of the block.
+/// Get all receipts of the block. With `status`, only the successful or the failed receipts are
+/// listed, leaving out transactions without any.
 #[utoipa::path(
   get,
   path = "/api/v1/brc20s/block/{blockhash}/receipts",
   params(
-      ("blockhash" = String, Path, description = "block hash")
+      ("blockhash" = String, Path, description = "block hash"),
+      ReceiptStatusQuery
 ),
   responses(
     (status = 200, description = "Obtain block receipts by block hash", body = BRC20SBlockReceipts),
     (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
     (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
     (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    (status = 503, description = "The operations of the block wait for --protocol-confirmations.", body = ApiError, example = json!(&ApiError::service_unavailable("operations of block 000000000000000000000000000000000000000000000000000000000000000a are not executed yet"))),
   )
 )]
 pub(crate) async fn brc20s_block_receipts(
   Extension(index): Extension<Arc<Index>>,
   Path(blockhash): Path<String>,
+  Query(query): Query<ReceiptStatusQuery>,
 ) -> ApiResult<BlockReceipts> {
   log::debug!("rpc: get brc20s_block_receipts: {}", blockhash);
+  let success = query
+    .status
+    .as_deref()
+    .map(parse_receipt_status)
+    .transpose()?;
 
   let blockhash = bitcoin::BlockHash::from_str(&blockhash).map_err(ApiError::bad_request)?;
   // get block from btc client.
   let blockinfo = index
     .get_block_info_by_hash(blockhash)
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(BRC20SError::BlockReceiptsNotFound)?;
+    .ok_or(BRC20SError::BlockReceiptsNotFound)?;
 
   // get blockhash from redb.
   let blockhash = index
     .block_hash(Some(u64::try_from(blockinfo.height).unwrap()))
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(BRC20SError::BlockReceiptsNotFound)?;
+    .ok_or(BRC20SError::BlockReceiptsNotFound)?;
 
   // check of conflicting block.
   if blockinfo.hash != blockhash {
-    return Err(ApiError::NotFound(
-      BRC20SError::BlockReceiptsNotFound.to_string(),
-    ));
+    return Err(ApiError::from(BRC20SError::BlockReceiptsNotFound));
+  }
+
+  // the receipts are final once the operations of the block are executed.
+  if index.protocol_block_height()?.map_or(true, |height| {
+    u64::try_from(blockinfo.height).unwrap() > height
+  }) {
+    return Err(ApiError::service_unavailable(format!(
+      "operations of block {blockhash} are not executed yet"
+    )));
   }
 
   let block_receipts = index
@@ -542,7 +684,10 @@ pub(crate) async fn brc20s_block_receipts(
   let mut api_block_receipts = Vec::new();
   for (txid, tx_receipts) in block_receipts.iter() {
     let mut api_tx_receipts = Vec::new();
-    for receipt in tx_receipts.iter() {
+    for receipt in tx_receipts
+      .iter()
+      .filter(|receipt| has_status(receipt, success))
+    {
       match Receipt::from(receipt, index.clone()) {
         Ok(receipt) => {
           api_tx_receipts.push(receipt);
@@ -554,6 +699,9 @@ pub(crate) async fn brc20s_block_receipts(
         }
       }
     }
+    if api_tx_receipts.is_empty() {
+      continue;
+    }
     api_block_receipts.push(TxReceipts {
       receipts: api_tx_receipts,
       txid: txid.to_string(),
@@ -564,3 +712,51 @@ pub(crate) async fn brc20s_block_receipts(
     block: api_block_receipts,
   })))
 }
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::{datastore::ScriptKey, protocol::brc20s::BRC20SError as ProtocolError},
+  };
+
+  #[test]
+  fn every_receipt_carries_its_inscription() {
+    let address =
+      Address::from_str("bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e").unwrap();
+    let inscription_id =
+      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    let old_satpoint =
+      SatPoint::from_str("1111111111111111111111111111111111111111111111111111111111111111:1:1")
+        .unwrap();
+    let new_satpoint =
+      SatPoint::from_str("1111111111111111111111111111111111111111111111111111111111111111:2:1")
+        .unwrap();
+
+    for op in [
+      brc20s::OperationType::Deploy,
+      brc20s::OperationType::Mint,
+      brc20s::OperationType::Stake,
+      brc20s::OperationType::UnStake,
+      brc20s::OperationType::PassiveUnStake,
+      brc20s::OperationType::InscribeTransfer,
+      brc20s::OperationType::Transfer,
+    ] {
+      let receipt = Receipt::without_events(&brc20s::Receipt {
+        inscription_id,
+        inscription_number: 7,
+        old_satpoint,
+        new_satpoint,
+        op: op.clone(),
+        from: ScriptKey::Address(address.clone()),
+        to: ScriptKey::Address(address.clone()),
+        result: Err(ProtocolError::InvalidZeroAmount),
+      });
+      assert_eq!(receipt.inscription_id, inscription_id.to_string(), "{op:?}");
+      assert_eq!(receipt.inscription_number, 7, "{op:?}");
+      assert_eq!(receipt.old_satpoint, old_satpoint.to_string(), "{op:?}");
+      assert_eq!(receipt.new_satpoint, new_satpoint.to_string(), "{op:?}");
+    }
+  }
+}
@@ -3,8 +3,9 @@ use {
   crate::{
     okx::datastore::{
       brc20,
-      brc20s::{self, Pid, PledgedTick},
+      brc20s::{self, Pid, PledgedTick, TickId},
     },
+    okx::protocol::brc20s::Num,
     subcommand::server::brc20::BRC20Error,
   },
   axum::Json,
@@ -30,6 +31,29 @@ pub struct UserInfo {
   /// The latest update block number.
   #[schema(format = "uint64")]
   pub latest_update_block: u64,
+  /// The percentage of the total stake of the pool held by the user, null if nothing is staked in
+  /// the pool.
+  #[schema(example = "60")]
+  pub pool_share: Option<String>,
+}
+
+impl UserInfo {
+  pub fn set_pool_share(&mut self, staked: u128, total_staked: u128) {
+    self.pool_share = pool_share(staked, total_staked);
+  }
+}
+
+// the percentage `staked` is of `total_staked`, truncated to 6 decimal places.
+fn pool_share(staked: u128, total_staked: u128) -> Option<String> {
+  if total_staked == 0 {
+    return None;
+  }
+  let share = Num::from(staked)
+    .checked_mul(&Num::from(100_000_000_u64))
+    .and_then(|share| share.checked_div(&Num::from(total_staked)))
+    .and_then(|share| share.truncate_to_u128())
+    .ok()?;
+  Some(Num::from(share).format_scaled(6))
 }
 
 impl From<&brc20s::UserInfo> for UserInfo {
@@ -41,6 +65,7 @@ impl From<&brc20s::UserInfo> for UserInfo {
       pending_reward: user_info.pending_reward.to_string(),
       reward_debt: user_info.reward_debt.to_string(),
       latest_update_block: user_info.latest_updated_block,
+      pool_share: None,
     }
   }
 }
@@ -57,6 +82,110 @@ pub struct UserReward {
   pub block_num: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::PoolReward)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolReward {
+  /// The pool id.
+  pub pid: String,
+  /// The amount of reward tokens already minted.
+  #[schema(format = "uint64")]
+  pub realized: String,
+  /// The amount of reward tokens claimable at the latest block.
+  #[schema(format = "uint64")]
+  pub unrealized: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::TickReward)]
+#[serde(rename_all = "camelCase")]
+pub struct TickReward {
+  /// The earned ticker.
+  #[schema(value_type = brc20s::Tick)]
+  pub tick: Tick,
+  /// The total amount of reward tokens already minted.
+  #[schema(format = "uint64")]
+  pub realized: String,
+  /// The total amount of reward tokens claimable at the latest block.
+  #[schema(format = "uint64")]
+  pub unrealized: String,
+  /// The rewards of each pool earning the ticker.
+  #[schema(value_type = Vec<brc20s::PoolReward>)]
+  pub pools: Vec<PoolReward>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::AddressRewards)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressRewards {
+  /// The rewards grouped by earned ticker.
+  #[schema(value_type = Vec<brc20s::TickReward>)]
+  pub ticks: Vec<TickReward>,
+  /// The height of the block the unrealized rewards are accrued to.
+  #[schema(format = "uint64")]
+  pub height: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::UserPool)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPool {
+  /// The pool id.
+  pub pid: String,
+  /// The amount of staked tokens.
+  #[schema(format = "uint64")]
+  pub staked: String,
+  /// The amount of reward tokens claimable at the latest block.
+  #[schema(format = "uint64")]
+  pub pending_reward: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::AddressPools)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressPools {
+  /// The pools the address has staked into, ordered by pool id.
+  #[schema(value_type = Vec<brc20s::UserPool>)]
+  pub pools: Vec<UserPool>,
+  /// The number of pools the address has staked into.
+  pub total: usize,
+  /// The height of the block the pending rewards are accrued to.
+  #[schema(format = "uint64")]
+  pub height: u64,
+}
+
+fn tick_rewards(rewards: &[(brc20s::UserInfo, u128)]) -> Vec<TickReward> {
+  let mut ticks: Vec<(TickId, u128, u128, Vec<PoolReward>)> = Vec::new();
+  for (user_info, unrealized) in rewards {
+    let tick_id = TickId::from(user_info.pid.clone());
+    let pool = PoolReward {
+      pid: user_info.pid.as_str().to_string(),
+      realized: user_info.minted.to_string(),
+      unrealized: unrealized.to_string(),
+    };
+    match ticks.iter_mut().find(|(id, ..)| *id == tick_id) {
+      Some((_, realized, total_unrealized, pools)) => {
+        *realized += user_info.minted;
+        *total_unrealized += unrealized;
+        pools.push(pool);
+      }
+      None => ticks.push((tick_id, user_info.minted, *unrealized, vec![pool])),
+    }
+  }
+  ticks
+    .into_iter()
+    .map(|(tick_id, realized, unrealized, pools)| TickReward {
+      tick: Tick {
+        id: tick_id.hex(),
+        name: "".to_string(),
+      },
+      realized: realized.to_string(),
+      unrealized: unrealized.to_string(),
+      pools,
+    })
+    .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20s::StakedInfo)]
 #[serde(rename_all = "camelCase")]
@@ -92,6 +221,15 @@ pub struct StakedPid {
   pub stake: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::AddressStakes)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressStakes {
+  /// The stake info of every token the address has pledged.
+  #[schema(value_type = Vec<brc20s::StakedInfo>)]
+  pub stakes: Vec<StakedInfo>,
+}
+
 impl From<&brc20s::StakeInfo> for StakedInfo {
   fn from(stake: &brc20s::StakeInfo) -> Self {
     Self {
@@ -119,8 +257,7 @@ pub(crate) async fn brc20s_user_pending_reward(
 ) -> ApiResult<UserReward> {
   log::debug!("rpc: get brc20s_user_pending_reward: {}, {}", pid, address);
 
-  let pid =
-    Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
@@ -139,9 +276,164 @@ pub(crate) async fn brc20s_user_pending_reward(
   })))
 }
 
+// brc20s/pool/:pid/address/:address/reward
+
+/// Preview the claimable reward of the user in the pool at a block.
+///
+/// The reward is simulated from the current pool and user state with the same formula used by the
+/// indexer, nothing is written. Blocks earlier than the user's latest update are rejected.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/pool/{pid}/address/{address}/reward",
+  params(
+      ("pid" = String, Path, description = "Pool ID", min_length = 13, max_length = 13, example= "a01234567f#0f"),
+      ("address" = String, Path, description = "Address"),
+      AtBlockQuery
+),
+  responses(
+    (status = 200, description = "Obtain the claimable reward of the user at the block", body = BRC20SUserReward),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_user_reward(
+  Extension(index): Extension<Arc<Index>>,
+  Path((pid, address)): Path<(String, String)>,
+  Query(query): Query<AtBlockQuery>,
+) -> ApiResult<UserReward> {
+  log::debug!("rpc: get brc20s_user_reward: {}, {}", pid, address);
+
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let user_info = index
+    .brc20s_user_info(&pid, &address)?
+    .ok_or(BRC20SError::UserInfoNotFound)?;
+
+  let block = match query.block {
+    Some(block) => block,
+    None => index.height()?.map(|height| height.n()).unwrap_or_default(),
+  };
+  if block < user_info.latest_updated_block {
+    return Err(ApiError::from(BRC20SError::BlockBeforeLatestUpdate));
+  }
+
+  let reward = index.brc20s_preview_user_reward(&user_info, block)?;
+
+  log::debug!(
+    "rpc: get brc20s_user_reward: {:?}, {}, {}",
+    pid.as_str(),
+    reward,
+    block,
+  );
+
+  Ok(Json(ApiResponse::ok(UserReward {
+    pending_reward: reward.to_string(),
+    block_num: block.to_string(),
+  })))
+}
+
+// brc20s/address/:address/rewards
+
+/// Get the realized and unrealized rewards of the address.
+///
+/// Realized rewards are the tokens already minted from each pool, unrealized rewards are the tokens
+/// claimable at the latest block. Rewards are grouped by the earned ticker.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/address/{address}/rewards",
+  params(
+      ("address" = String, Path, description = "Address"),
+),
+  responses(
+    (status = 200, description = "Obtain the rewards of the address", body = BRC20SAddressRewards),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_address_rewards(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+) -> ApiResult<AddressRewards> {
+  log::debug!("rpc: get brc20s_address_rewards: {}", address);
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let (rewards, height) = index.brc20s_user_rewards(&address)?;
+
+  log::debug!("rpc: get brc20s_address_rewards: {:?}", rewards);
+
+  let mut ticks = tick_rewards(&rewards);
+  for tick in ticks.iter_mut() {
+    let tick_id = TickId::from_str(&tick.tick.id).map_err(|e| ApiError::internal(e.to_string()))?;
+    let tick_info = index
+      .brc20s_tick_info(&tick_id)?
+      .ok_or(BRC20SError::TickIdNotFound)?;
+    tick.tick.name = tick_info.name.as_str().to_string();
+  }
+
+  Ok(Json(ApiResponse::ok(AddressRewards { ticks, height })))
+}
+
+// brc20s/address/:address/pools
+
+/// Get the pools the address has staked into.
+///
+/// Every pool the address has ever staked into is listed with its current staked amount and the
+/// reward claimable at the latest block.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/address/{address}/pools",
+  params(
+      ("address" = String, Path, description = "Address"),
+      Pagination
+),
+  responses(
+    (status = 200, description = "Obtain the pools of the address", body = BRC20SAddressPools),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_address_pools(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<AddressPools> {
+  log::debug!("rpc: get brc20s_address_pools: {}", address);
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let (pools, total, height) =
+    index.brc20s_user_pools(&address, page.start.unwrap_or(0), page.limit)?;
+
+  log::debug!("rpc: get brc20s_address_pools: {:?}", pools);
+
+  Ok(Json(ApiResponse::ok(AddressPools {
+    pools: pools
+      .iter()
+      .map(|(user_info, pending_reward)| UserPool {
+        pid: user_info.pid.as_str().to_string(),
+        staked: user_info.staked.to_string(),
+        pending_reward: pending_reward.to_string(),
+      })
+      .collect(),
+    total,
+    height,
+  })))
+}
+
 // brc20s/pool/:pid/address/:address/userinfo
 
 /// Get the user info of the pool.
+///
+/// The share of the user is taken from the total stake of all current stakers of the pool.
 #[utoipa::path(
   get,
   path = "/api/v1/brc20s/pool/{pid}/address/{address}/userinfo",
@@ -162,8 +454,7 @@ pub(crate) async fn brc20s_userinfo(
 ) -> ApiResult<UserInfo> {
   log::debug!("rpc: get brc20s_userinfo: {}, {}", pid, address);
 
-  let pid =
-    Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
@@ -171,7 +462,7 @@ pub(crate) async fn brc20s_userinfo(
 
   let user_info = &index
     .brc20s_user_info(&pid, &address)?
-    .ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
+    .ok_or(BRC20SError::UserInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_userinfo: {:?} {:?}",
@@ -183,7 +474,13 @@ pub(crate) async fn brc20s_userinfo(
     return Err(ApiError::internal("db: not match"));
   }
 
-  Ok(Json(ApiResponse::ok(user_info.into())))
+  let pool_info = index
+    .brc20s_pool_info(&pid)?
+    .ok_or(BRC20SError::PoolInfoNotFound)?;
+  let mut info = UserInfo::from(user_info);
+  info.set_pool_share(user_info.staked, pool_info.staked);
+
+  Ok(Json(ApiResponse::ok(info)))
 }
 
 // brc20s/debug/pool/:pid/address/:address/userinfo
@@ -193,14 +490,13 @@ pub(crate) async fn brc20s_debug_userinfo(
 ) -> ApiResult<brc20s::UserInfo> {
   log::debug!("rpc: get brc20s_debug_userinfo: {}, {}", pid, address);
 
-  let pid =
-    Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+  let pid = Pid::from_str(&pid).map_err(|_| ApiError::from(BRC20SError::IncorrectPidFormat))?;
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
   let user_info = index
     .brc20s_user_info(&pid, &address)?
-    .ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
+    .ok_or(BRC20SError::UserInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_userinfo: {:?} {:?}",
@@ -243,12 +539,12 @@ pub(crate) async fn brc20s_stake_info(
     address
   );
 
-  let tick = brc20::Tick::from_str(&tick)
-    .map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
+  let tick =
+    brc20::Tick::from_str(&tick).map_err(|_| ApiError::from(BRC20Error::IncorrectTickFormat))?;
 
   let tick = index
     .brc20_get_tick_info(&tick)?
-    .ok_or_api_not_found(BRC20Error::TickNotFound)?
+    .ok_or(BRC20Error::TickNotFound)?
     .tick;
 
   let address: bitcoin::Address = Address::from_str(&address)
@@ -257,7 +553,7 @@ pub(crate) async fn brc20s_stake_info(
 
   let stake_info = index
     .brc20s_stake_info(&address, &PledgedTick::BRC20Tick(tick.clone()))?
-    .ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
+    .ok_or(BRC20SError::StakeInfoNotFound)?;
 
   log::debug!("rpc: get brc20s_stake_info: {:?}", stake_info);
 
@@ -267,6 +563,49 @@ pub(crate) async fn brc20s_stake_info(
   Ok(Json(ApiResponse::ok(result)))
 }
 
+// brc20s/address/:address/stakes
+
+/// Get the stake info of every token pledged by the address.
+///
+/// Stakes are ordered by the pledged token, BTC, BRC20 tickers and BRC20-S ticker IDs alike.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/address/{address}/stakes",
+  params(
+      ("address" = String, Path, description = "Address"),
+),
+  responses(
+    (status = 200, description = "Obtain the stake info of every pledged token", body = BRC20SAddressStakes),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_address_stakes(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+) -> ApiResult<AddressStakes> {
+  log::debug!("rpc: get brc20s_address_stakes: {}", address);
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let stake_infos = index.brc20s_all_stake_info(&address)?;
+
+  log::debug!("rpc: get brc20s_address_stakes: {:?}", stake_infos);
+
+  Ok(Json(ApiResponse::ok(AddressStakes {
+    stakes: stake_infos
+      .iter()
+      .map(|stake_info| StakedInfo {
+        type_field: stake_info.stake.to_type(),
+        tick: stake_info.stake.to_string(),
+        ..StakedInfo::from(stake_info)
+      })
+      .collect(),
+  })))
+}
+
 pub(crate) async fn brc20s_debug_stake_info(
   Extension(index): Extension<Arc<Index>>,
   Path((address, tick)): Path<(String, String)>,
@@ -279,9 +618,69 @@ pub(crate) async fn brc20s_debug_stake_info(
 
   let stake_info = index
     .brc20s_stake_info(&address, &PledgedTick::from_str(tick.as_str()))?
-    .ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
+    .ok_or(BRC20SError::StakeInfoNotFound)?;
 
   log::debug!("rpc: get brc20s_debug_stake_info: {:?}", stake_info);
 
   Ok(Json(ApiResponse::ok(stake_info)))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn user_info(pid: &str, minted: u128) -> brc20s::UserInfo {
+    brc20s::UserInfo {
+      minted,
+      ..brc20s::UserInfo::default(&Pid::from_str(pid).unwrap())
+    }
+  }
+
+  #[test]
+  fn test_tick_rewards() {
+    let rewards = vec![
+      (user_info("a01234567f#01", 100), 20),
+      (user_info("b01234567f#01", 5), 0),
+      (user_info("a01234567f#02", 50), 30),
+    ];
+
+    assert_eq!(
+      tick_rewards(&rewards),
+      vec![
+        TickReward {
+          tick: Tick {
+            id: "a01234567f".to_string(),
+            name: "".to_string(),
+          },
+          realized: "150".to_string(),
+          unrealized: "50".to_string(),
+          pools: vec![
+            PoolReward {
+              pid: "a01234567f#01".to_string(),
+              realized: "100".to_string(),
+              unrealized: "20".to_string(),
+            },
+            PoolReward {
+              pid: "a01234567f#02".to_string(),
+              realized: "50".to_string(),
+              unrealized: "30".to_string(),
+            },
+          ],
+        },
+        TickReward {
+          tick: Tick {
+            id: "b01234567f".to_string(),
+            name: "".to_string(),
+          },
+          realized: "5".to_string(),
+          unrealized: "0".to_string(),
+          pools: vec![PoolReward {
+            pid: "b01234567f#01".to_string(),
+            realized: "5".to_string(),
+            unrealized: "0".to_string(),
+          }],
+        },
+      ]
+    );
+  }
+}
@@ -0,0 +1,104 @@
+use {super::*, crate::okx::datastore::brc20s, axum::Json};
+
+// the most blocks a single stats request sums up.
+pub(crate) const MAX_STATS_BLOCKS: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::OperationStats)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStats {
+  /// First block height of the range.
+  #[schema(format = "uint64")]
+  pub from: u64,
+  /// Last block height of the range, inclusive.
+  #[schema(format = "uint64")]
+  pub to: u64,
+  /// Number of deploy operations.
+  #[schema(format = "uint64")]
+  pub deploy: u64,
+  /// Number of mint operations.
+  #[schema(format = "uint64")]
+  pub mint: u64,
+  /// Number of deposit operations.
+  #[schema(format = "uint64")]
+  pub deposit: u64,
+  /// Number of withdraw operations.
+  #[schema(format = "uint64")]
+  pub withdraw: u64,
+  /// Number of passive withdraw operations.
+  #[schema(format = "uint64")]
+  pub passive_withdraw: u64,
+  /// Number of inscribe transfer operations.
+  #[schema(format = "uint64")]
+  pub inscribe_transfer: u64,
+  /// Number of transfer operations.
+  #[schema(format = "uint64")]
+  pub transfer: u64,
+  /// Number of operations of any type.
+  #[schema(format = "uint64")]
+  pub total: u64,
+}
+
+impl OperationStats {
+  fn new(from: u64, to: u64, counts: brc20s::OperationCounts) -> Self {
+    Self {
+      from,
+      to,
+      deploy: counts.deploy,
+      mint: counts.mint,
+      deposit: counts.stake,
+      withdraw: counts.unstake,
+      passive_withdraw: counts.passive_unstake,
+      inscribe_transfer: counts.inscribe_transfer,
+      transfer: counts.transfer,
+      total: counts.total(),
+    }
+  }
+}
+
+// brc20s/stats?from=A&to=B
+
+/// Get the operation counts of a block range.
+///
+/// Count the receipts of every operation type, valid or not, in the blocks from `from` to `to`.
+/// A range spans at most 10000 blocks.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/stats",
+  params(
+      BlockRangeQuery
+),
+  responses(
+    (status = 200, description = "Obtain the operation counts of the block range", body = BRC20SOperationStats),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_operation_stats(
+  Extension(index): Extension<Arc<Index>>,
+  Query(range): Query<BlockRangeQuery>,
+) -> ApiResult<OperationStats> {
+  log::debug!(
+    "rpc: get brc20s_operation_stats: {} {}",
+    range.from,
+    range.to
+  );
+
+  if range.from > range.to {
+    return Err(ApiError::from(BRC20SError::IncorrectBlockRange));
+  }
+  if range.to - range.from >= MAX_STATS_BLOCKS {
+    return Err(ApiError::from(BRC20SError::BlockRangeTooLarge));
+  }
+
+  let counts = index
+    .brc20s_operation_counts(range.from, range.to)?
+    .ok_or(BRC20SError::BlockReceiptsNotFound)?;
+
+  log::debug!("rpc: get brc20s_operation_stats: {:?}", counts);
+
+  Ok(Json(ApiResponse::ok(OperationStats::new(
+    range.from, range.to, counts,
+  ))))
+}
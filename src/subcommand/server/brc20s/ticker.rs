@@ -1,4 +1,9 @@
-use {super::*, crate::okx::datastore::brc20s, axum::Json};
+use {
+  super::*,
+  crate::okx::{datastore::brc20s, protocol::brc20s as brc20s_proto},
+  axum::Json,
+  base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine},
+};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[schema(as = brc20s::TickInfo)]
 #[serde(rename_all = "camelCase")]
@@ -10,9 +15,15 @@ pub(crate) struct TickInfo {
   pub inscription_id: String,
   /// The inscription number.
   pub inscription_number: i64,
-  /// The minted amount.
+  /// The minted amount, summed over every pool distributing the ticker.
   #[schema(format = "uint64")]
   pub minted: String,
+  /// The circulating amount, the minted amount less the burned amount.
+  #[schema(format = "uint64")]
+  pub circulating: String,
+  /// The amount transferred to a burn address.
+  #[schema(format = "uint64")]
+  pub burned: String,
   /// The total supply.
   #[schema(format = "uint64")]
   pub supply: String,
@@ -28,12 +39,20 @@ pub(crate) struct TickInfo {
   /// The timestamp of the block that the ticker deployed.
   #[schema(format = "uint64")]
   pub deploy_blocktime: u32,
+  /// The number of addresses that currently hold the ticker. Only returned by the ticker detail.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(format = "uint64")]
+  pub holders: Option<u64>,
 }
 
 impl TickInfo {
   pub fn set_inscription_number(&mut self, inscription_number: i64) {
     self.inscription_number = inscription_number;
   }
+
+  pub fn set_holders(&mut self, holders: usize) {
+    self.holders = Some(holders as u64);
+  }
 }
 
 impl From<&brc20s::TickInfo> for TickInfo {
@@ -47,13 +66,16 @@ impl From<&brc20s::TickInfo> for TickInfo {
       tick,
       inscription_id: tick_info.inscription_id.to_string(),
       inscription_number: 0,
-      minted: tick_info.circulation.to_string(),
+      minted: (tick_info.circulation + tick_info.burned).to_string(),
+      circulating: tick_info.circulation.to_string(),
+      burned: tick_info.burned.to_string(),
       supply: tick_info.supply.to_string(),
       decimal: tick_info.decimal,
       deployer: tick_info.deployer.clone().into(),
       txid: tick_info.inscription_id.txid.to_string(),
       deploy_height: tick_info.deploy_block,
       deploy_blocktime: tick_info.deploy_block_time,
+      holders: None,
     }
   }
 }
@@ -80,11 +102,11 @@ pub(crate) async fn brc20s_tick_info(
   log::debug!("rpc: get brc20s_tick_info: {}", tick_id);
 
   let tick_id = brc20s::TickId::from_str(tick_id.as_str())
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
 
   let tick_info = &index
     .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+    .ok_or(BRC20SError::TickIdNotFound)?;
 
   log::debug!("rpc: get brc20s_tick_info: {:?} {:?}", tick_id, tick_info);
 
@@ -99,10 +121,129 @@ pub(crate) async fn brc20s_tick_info(
 
   let mut brc20s_tick = TickInfo::from(tick_info);
   brc20s_tick.set_inscription_number(inscription_number.number);
+  brc20s_tick.set_holders(index.brc20s_tick_holder_count(&tick_id)?);
 
   Ok(Json(ApiResponse::ok(brc20s_tick)))
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::TickDeploy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TickDeploy {
+  /// Ticker.
+  #[schema(value_type = brc20s::Tick)]
+  pub tick: Tick,
+  /// The pool type, `pool` or `fixed`.
+  pub pool_type: String,
+  /// The pool id.
+  pub pid: String,
+  /// The staked ticker, `btc` for native btc.
+  pub stake: String,
+  /// The earned ticker.
+  pub earn: String,
+  /// The earn rate as inscribed.
+  pub erate: String,
+  /// The distribution max as inscribed.
+  pub dmax: String,
+  /// The decimals as inscribed, null when the deploy left it out.
+  pub dec: Option<String>,
+  /// The total supply as inscribed, null when the deploy left it out.
+  pub total: Option<String>,
+  /// Whether the pool is the only one the assets are staked in, null when the deploy left it out.
+  pub only: Option<String>,
+  /// The halving interval as inscribed, null when the deploy left it out.
+  pub halving: Option<String>,
+  /// The minimum stake as inscribed, null when the deploy left it out.
+  pub min_stake: Option<String>,
+  /// The maturity in blocks as inscribed, null when the deploy left it out.
+  pub maturity: Option<String>,
+  /// The deployer.
+  pub deployer: ScriptPubkey,
+  /// The inscription id.
+  pub inscription_id: String,
+  /// The inscription number.
+  pub inscription_number: i64,
+  /// The height of the block that the ticker deployed.
+  #[schema(format = "uint64")]
+  pub deploy_height: u64,
+  /// The timestamp of the block that the ticker deployed.
+  #[schema(format = "uint64")]
+  pub deploy_blocktime: u32,
+}
+
+// brc20s/tick/:tickId/deploy
+/// Get the deploy operation that created a specified BRC20S protocol ticker.
+///
+/// The parameters are read back from the deploy inscription exactly as they were inscribed.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/tick/{tick_id}/deploy",
+  params(
+    ("tick_id" = String, Path, description = "The ticker ID", min_length = 10, max_length = 10, example = "a12345678f")
+),
+  responses(
+    (status = 200, description = "Obtain the deploy of the BRC20S ticker.", body = BRC20STickDeploy),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_tick_deploy(
+  Extension(index): Extension<Arc<Index>>,
+  Path(tick_id): Path<String>,
+) -> ApiResult<TickDeploy> {
+  log::debug!("rpc: get brc20s_tick_deploy: {}", tick_id);
+
+  let tick_id = brc20s::TickId::from_str(tick_id.as_str())
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
+
+  let tick_info = index
+    .brc20s_tick_info(&tick_id)?
+    .ok_or(BRC20SError::TickIdNotFound)?;
+
+  let inscription_number = index
+    .get_inscription_entry(tick_info.inscription_id)?
+    .ok_or(BRC20SError::DeployNotFound)?
+    .number;
+
+  let deploy = match index.brc20s_inscription_operation(tick_info.inscription_id)? {
+    Some(brc20s_proto::Operation::Deploy(deploy)) if deploy.get_tick_id() == tick_id => deploy,
+    _ => {
+      return Err(ApiError::internal(format!(
+        "inscription {} is not the deploy of {}",
+        tick_info.inscription_id,
+        tick_id.hex()
+      )))
+    }
+  };
+
+  log::debug!("rpc: get brc20s_tick_deploy: {:?} {:?}", tick_id, deploy);
+
+  Ok(Json(ApiResponse::ok(TickDeploy {
+    tick: Tick {
+      id: tick_info.tick_id.hex(),
+      name: tick_info.name.as_str().to_string(),
+    },
+    pool_type: deploy.pool_type,
+    pid: deploy.pool_id,
+    stake: deploy.stake,
+    earn: deploy.earn,
+    erate: deploy.earn_rate,
+    dmax: deploy.distribution_max,
+    dec: deploy.decimals,
+    total: deploy.total_supply,
+    only: deploy.only,
+    halving: deploy.halving,
+    min_stake: deploy.min_stake,
+    maturity: deploy.maturity,
+    deployer: tick_info.deployer.into(),
+    inscription_id: tick_info.inscription_id.to_string(),
+    inscription_number,
+    deploy_height: tick_info.deploy_block,
+    deploy_blocktime: tick_info.deploy_block_time,
+  })))
+}
+
 // /brc20s/tick/:tickId
 pub(crate) async fn brc20s_debug_tick_info(
   Extension(index): Extension<Arc<Index>>,
@@ -111,11 +252,11 @@ pub(crate) async fn brc20s_debug_tick_info(
   log::debug!("rpc: get brc20s_debug_tick_info: {}", tick_id);
 
   let tick_id = brc20s::TickId::from_str(&tick_id)
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
 
   let tick_info = index
     .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+    .ok_or(BRC20SError::TickIdNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_tick_info: {:?} {:?}",
@@ -133,15 +274,31 @@ pub(crate) struct AllTickInfo {
   #[schema(value_type = Vec<brc20s::TickInfo>)]
   pub tokens: Vec<TickInfo>,
   pub total: usize,
+  /// Cursor of the next page, absent when there are no more tickers.
+  pub next_cursor: Option<String>,
+}
+
+fn encode_tick_cursor(tick_id: &brc20s::TickId) -> String {
+  URL_SAFE_NO_PAD.encode(tick_id.hex())
+}
+
+fn decode_tick_cursor(cursor: &str) -> Option<brc20s::TickId> {
+  let hex = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+  brc20s::TickId::from_str(std::str::from_utf8(&hex).ok()?).ok()
 }
 
 // brc20s/tick
 /// Get all BRC20S tickers info.
+///
+/// Paging with `cursor` is preferred: pass the `nextCursor` of the previous page to get a stable
+/// page while new tickers are deployed. `start` is kept for compatibility and is ignored when
+/// `cursor` is set.
 #[utoipa::path(
   get,
   path = "/api/v1/brc20s/tick",
   params(
-    Pagination
+    Pagination,
+    CursorPagination
 ),
   responses(
     (status = 200, description = "Obtain matching all BRC20S tickers.", body = BRC20SAllTick),
@@ -153,12 +310,27 @@ pub(crate) struct AllTickInfo {
 pub(crate) async fn brc20s_all_tick_info(
   Extension(index): Extension<Arc<Index>>,
   Query(page): Query<Pagination>,
+  Query(cursor): Query<CursorPagination>,
 ) -> ApiResult<AllTickInfo> {
   log::debug!("rpc: get brc20s_all_tick_info");
 
-  let (all_tick_info, total) = index.brc20s_all_tick_info(page.start.unwrap_or(0), page.limit)?;
+  let (all_tick_info, total) = match cursor.cursor {
+    Some(cursor) => {
+      let after = decode_tick_cursor(&cursor)
+        .ok_or_else(|| ApiError::from(BRC20SError::IncorrectCursorFormat))?;
+      index.brc20s_all_tick_info_after(Some(&after), page.limit)?
+    }
+    None => index.brc20s_all_tick_info(page.start.unwrap_or(0), page.limit)?,
+  };
   log::debug!("rpc: get brc20s_all_tick_info: {:?}", all_tick_info);
 
+  let next_cursor = match (page.limit, all_tick_info.last()) {
+    (Some(limit), Some(last)) if all_tick_info.len() == limit => {
+      Some(encode_tick_cursor(&last.tick_id))
+    }
+    _ => None,
+  };
+
   Ok(Json(ApiResponse::ok(AllTickInfo {
     tokens: all_tick_info
       .iter()
@@ -174,5 +346,131 @@ pub(crate) async fn brc20s_all_tick_info(
       })
       .collect(),
     total,
+    next_cursor,
   })))
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::DeployedTicks)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeployedTicks {
+  #[schema(value_type = Vec<brc20s::TickInfo>)]
+  pub tokens: Vec<TickInfo>,
+  /// The number of tickers deployed in the range.
+  pub total: usize,
+}
+
+// brc20s/ticks?from_height=A&to_height=B
+
+/// Get the BRC20S tickers deployed in a block range.
+///
+/// The tickers deployed from `from_height` to `to_height` are listed by deploy height, then by
+/// ticker id.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/ticks",
+  params(
+    DeployHeightQuery,
+    Pagination
+),
+  responses(
+    (status = 200, description = "Obtain the BRC20S tickers deployed in the block range.", body = BRC20SDeployedTicks),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_deployed_ticks(
+  Extension(index): Extension<Arc<Index>>,
+  Query(range): Query<DeployHeightQuery>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<DeployedTicks> {
+  log::debug!(
+    "rpc: get brc20s_deployed_ticks: {:?} {:?}",
+    range.from_height,
+    range.to_height
+  );
+
+  let from = range.from_height.unwrap_or(0);
+  let to = range.to_height.unwrap_or(u64::MAX);
+  if from > to {
+    return Err(ApiError::from(BRC20SError::IncorrectBlockRange));
+  }
+
+  let (ticks, total) =
+    index.brc20s_tick_info_by_deploy_height(from, to, page.start.unwrap_or(0), page.limit)?;
+
+  log::debug!("rpc: get brc20s_deployed_ticks: {:?}", ticks);
+
+  Ok(Json(ApiResponse::ok(DeployedTicks {
+    tokens: ticks
+      .iter()
+      .map(|tick_info| {
+        let inscription_number = &index
+          .get_inscription_entry(tick_info.inscription_id)
+          .unwrap()
+          .unwrap();
+
+        let mut brc20s_tick = TickInfo::from(tick_info);
+        brc20s_tick.set_inscription_number(inscription_number.number);
+        brc20s_tick
+      })
+      .collect(),
+    total,
+  })))
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::okx::datastore::ScriptKey};
+
+  #[test]
+  fn tick_cursor_round_trip() {
+    let tick_id = brc20s::TickId::from_str("a12345678f").unwrap();
+    let cursor = encode_tick_cursor(&tick_id);
+    assert_eq!(cursor, "YTEyMzQ1Njc4Zg");
+    assert_eq!(decode_tick_cursor(&cursor), Some(tick_id));
+    assert_eq!(decode_tick_cursor("a12345678f"), None);
+    assert_eq!(decode_tick_cursor("!!"), None);
+  }
+
+  #[test]
+  fn serialize_tick_info_circulating() {
+    let owner = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick_id = brc20s::TickId::from_str("a12345678f").unwrap();
+    let mut tick_info = brc20s::TickInfo::new(
+      tick_id,
+      &brc20s::Tick::from_str("ordi1").unwrap(),
+      &InscriptionId::from_str(
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      )
+      .unwrap(),
+      800,
+      18,
+      300,
+      1000,
+      &owner,
+      779832,
+      1677731361,
+      779840,
+      Vec::new(),
+    );
+
+    let json = serde_json::to_value(TickInfo::from(&tick_info)).unwrap();
+    assert_eq!(json["supply"], "1000");
+    assert_eq!(json["minted"], "300");
+    assert_eq!(json["circulating"], "300");
+    assert_eq!(json["burned"], "0");
+
+    // a burn takes the amount out of the circulation, the minted amount stays.
+    tick_info.circulation -= 100;
+    tick_info.burned += 100;
+    let json = serde_json::to_value(TickInfo::from(&tick_info)).unwrap();
+    assert_eq!(json["minted"], "300");
+    assert_eq!(json["circulating"], "200");
+    assert_eq!(json["burned"], "100");
+  }
+}
@@ -16,6 +16,12 @@ pub(crate) struct TransferableInscription {
   pub amount: String,
   /// The owner.
   pub owner: String,
+  /// The number of blocks since the inscription was inscribed, relative to the indexed tip.
+  pub age_blocks: u64,
+  /// The sat which carries the inscription, only present with `include=sat`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(value_type = Option<SatInfo>)]
+  pub sat: Option<SatInfo>,
 }
 
 impl TransferableInscription {
@@ -26,6 +32,14 @@ impl TransferableInscription {
   pub fn set_inscription_number(&mut self, inscription_number: i64) {
     self.inscription_number = inscription_number;
   }
+
+  pub fn set_age_blocks(&mut self, age_blocks: u64) {
+    self.age_blocks = age_blocks;
+  }
+
+  pub fn set_sat(&mut self, sat: Option<Sat>) {
+    self.sat = sat.map(SatInfo::from);
+  }
 }
 
 impl From<&brc20s::TransferableAsset> for TransferableInscription {
@@ -41,6 +55,8 @@ impl From<&brc20s::TransferableAsset> for TransferableInscription {
       inscription_number: 0,
       amount: asset.amount.to_string(),
       owner: asset.owner.to_string(),
+      age_blocks: 0,
+      sat: None,
     }
   }
 }
@@ -53,7 +69,8 @@ impl From<&brc20s::TransferableAsset> for TransferableInscription {
   path = "/api/v1/brc20s/tick/{tick_id}/address/{address}/transferable",
   params(
       ("tick_id" = String, Path, description = "Ticker ID", min_length = 10, max_length = 10, example = "a12345678f"),
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      IncludeQuery
 ),
   responses(
     (status = 200, description = "Obtain account transferable inscriptions of ticker ID.", body = BRC20STransferable),
@@ -65,16 +82,18 @@ impl From<&brc20s::TransferableAsset> for TransferableInscription {
 pub(crate) async fn brc20s_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path((tick_id, address)): Path<(String, String)>,
+  Query(query): Query<IncludeQuery>,
 ) -> ApiResult<Transferable> {
   log::debug!("rpc: get brc20s_transferable: {},{}", tick_id, address);
 
   let tick_id = brc20s::TickId::from_str(&tick_id)
-    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+    .map_err(|_| ApiError::from(BRC20SError::IncorrectTickIdFormat))?;
 
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
   let all_transfer = &index.brc20s_tickid_transferable(&tick_id, &address)?;
+  let tip = index.block_height()?.map(|height| height.n()).unwrap_or(0);
 
   log::debug!(
     "rpc: get brc20s_transferable: {:?} {:?}",
@@ -97,6 +116,10 @@ pub(crate) async fn brc20s_transferable(
 
         inscription.set_tick_name(tick_info.name.as_str().to_string());
         inscription.set_inscription_number(inscription_number.number);
+        inscription.set_age_blocks(tip.saturating_sub(inscription_number.height));
+        if query.sat() {
+          inscription.set_sat(inscription_number.sat);
+        }
         inscription
       })
       .collect(),
@@ -117,7 +140,8 @@ pub(crate) struct Transferable {
   get,
   path = "/api/v1/brc20s/address/{address}/transferable",
   params(
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      IncludeQuery
 ),
   responses(
     (status = 200, description = "Obtain account all transferable inscriptions.", body = BRC20STransferable),
@@ -129,6 +153,7 @@ pub(crate) struct Transferable {
 pub(crate) async fn brc20s_all_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(query): Query<IncludeQuery>,
 ) -> ApiResult<Transferable> {
   log::debug!("rpc: get brc20s_all_transferable: {}", address);
 
@@ -137,6 +162,7 @@ pub(crate) async fn brc20s_all_transferable(
     .map_err(ApiError::bad_request)?;
 
   let all = index.brc20s_all_transferable(&address)?;
+  let tip = index.block_height()?.map(|height| height.n()).unwrap_or(0);
 
   log::debug!("rpc: get brc20s_all_transferable: {} {:?}", address, all);
 
@@ -155,8 +181,128 @@ pub(crate) async fn brc20s_all_transferable(
 
         inscription.set_tick_name(tick_info.name.as_str().to_string());
         inscription.set_inscription_number(inscription_number.number);
+        inscription.set_age_blocks(tip.saturating_sub(inscription_number.height));
+        if query.sat() {
+          inscription.set_sat(inscription_number.sat);
+        }
         inscription
       })
       .collect(),
   })))
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::PendingTransfer)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PendingTransfer {
+  /// Ticker.
+  #[schema(value_type = brc20s::Tick)]
+  pub tick: Tick,
+  /// The inscription id.
+  pub inscription_id: String,
+  /// The inscription number.
+  pub inscription_number: i64,
+  /// The amount.
+  #[schema(format = "uint64")]
+  pub amount: String,
+}
+
+impl PendingTransfer {
+  pub fn new(
+    inscription_id: InscriptionId,
+    inscription_number: i64,
+    info: &brc20s::TransferInfo,
+  ) -> Self {
+    Self {
+      tick: Tick {
+        id: info.tick_id.hex(),
+        name: info.tick_name.as_str().to_string(),
+      },
+      inscription_id: inscription_id.to_string(),
+      inscription_number,
+      amount: info.amt.to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::PendingTransfers)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PendingTransfers {
+  #[schema(value_type = Vec<brc20s::PendingTransfer>)]
+  pub inscriptions: Vec<PendingTransfer>,
+}
+
+// brc20s/address/:address/transferable/pending
+/// Retrieve the inscribed transfer inscriptions not yet sent from the given address.
+#[utoipa::path(
+  get,
+  path = "/api/v1/brc20s/address/{address}/transferable/pending",
+  params(
+      ("address" = String, Path, description = "Address"),
+),
+  responses(
+    (status = 200, description = "Obtain account inscribed but not yet sent transfer inscriptions.", body = BRC20SPendingTransfers),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_pending_transfers(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+) -> ApiResult<PendingTransfers> {
+  log::debug!("rpc: get brc20s_pending_transfers: {}", address);
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let pending = index.brc20s_pending_transfers(&address)?;
+
+  log::debug!(
+    "rpc: get brc20s_pending_transfers: {} {:?}",
+    address,
+    pending
+  );
+
+  let mut inscriptions = Vec::new();
+  for (inscription_id, info) in pending {
+    let entry = index
+      .get_inscription_entry(inscription_id)?
+      .ok_or_else(|| ApiError::internal(format!("inscription {inscription_id} not found")))?;
+    inscriptions.push(PendingTransfer::new(inscription_id, entry.number, &info));
+  }
+
+  Ok(Json(ApiResponse::ok(PendingTransfers { inscriptions })))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialize_transferable_inscription_sat() {
+    let mut inscription = TransferableInscription {
+      tick: Tick {
+        id: "a12345678f".to_string(),
+        name: "ordi1".to_string(),
+      },
+      inscription_id: "1111111111111111111111111111111111111111111111111111111111111111i1"
+        .to_string(),
+      inscription_number: 1,
+      amount: "100".to_string(),
+      owner: "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4".to_string(),
+      age_blocks: 0,
+      sat: None,
+    };
+    assert!(!serde_json::to_string(&inscription)
+      .unwrap()
+      .contains("\"sat\""));
+
+    inscription.set_sat(Some(Sat(50 * COIN_VALUE)));
+    assert_eq!(
+      serde_json::to_value(&inscription).unwrap()["sat"],
+      serde_json::json!({"number": 5000000000_u64, "rarity": "uncommon"})
+    );
+  }
+}
@@ -0,0 +1,84 @@
+use {
+  super::*,
+  axum::{http::Request, middleware::Next},
+  bitcoin::hashes::{sha256, Hash},
+};
+
+/// Caches may keep the response for good, e.g. the receipts of a block which are pinned by its
+/// hash. A reorg makes the block unknown instead of changing its receipts.
+pub(super) async fn immutable<B>(request: Request<B>, next: Next<B>) -> Response {
+  with_etag(request, next, "public, max-age=31536000, immutable").await
+}
+
+/// Caches may keep the response, but have to revalidate it with its `ETag` before reuse.
+pub(super) async fn revalidate<B>(request: Request<B>, next: Next<B>) -> Response {
+  with_etag(request, next, "no-cache").await
+}
+
+// tags a successful response with the hash of its content, and answers a request already holding
+// that content with 304.
+async fn with_etag<B>(request: Request<B>, next: Next<B>, cache_control: &'static str) -> Response {
+  let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+  let response = next.run(request).await;
+  if response.status() != StatusCode::OK {
+    return response;
+  }
+
+  let (mut parts, body) = response.into_parts();
+  let content = match hyper::body::to_bytes(body).await {
+    Ok(content) => content,
+    Err(err) => {
+      return ServerError::Internal(anyhow!("failed to read response body: {err}")).into_response()
+    }
+  };
+
+  let etag = HeaderValue::from_str(&format!("\"{}\"", sha256::Hash::hash(&content))).unwrap();
+  parts.headers.insert(
+    header::CACHE_CONTROL,
+    HeaderValue::from_static(cache_control),
+  );
+
+  if if_none_match.map_or(false, |tags| etag_matches(&tags, &etag)) {
+    parts.status = StatusCode::NOT_MODIFIED;
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::ETAG, etag);
+    return Response::from_parts(parts, body::boxed(body::Empty::new()));
+  }
+
+  parts.headers.insert(header::ETAG, etag);
+  Response::from_parts(parts, body::boxed(body::Full::from(content)))
+}
+
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+  if_none_match.to_str().map_or(false, |tags| {
+    tags
+      .split(',')
+      .map(str::trim)
+      .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn if_none_match() {
+    let etag = HeaderValue::from_static("\"abc\"");
+    for (tags, matches) in [
+      ("\"abc\"", true),
+      ("W/\"abc\"", true),
+      ("\"def\", \"abc\"", true),
+      ("*", true),
+      ("\"def\"", false),
+      ("abc", false),
+    ] {
+      assert_eq!(
+        etag_matches(&HeaderValue::from_static(tags), &etag),
+        matches,
+        "{tags}"
+      );
+    }
+  }
+}
@@ -0,0 +1,112 @@
+use {
+  super::*,
+  crate::{
+    okx::{
+      datastore::ord::Action,
+      protocol::{brc20 as brc20_proto, brc20s as brc20s_proto},
+    },
+    Inscription,
+  },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParseRequest {
+  /// The content type of the inscription, omitted for an inscription without one.
+  pub content_type: Option<String>,
+  /// The body of the inscription.
+  pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedOperation {
+  /// The protocol which recognized the payload, `brc20` or `brc20s`.
+  pub protocol: String,
+  /// The parsed operation, with `op` naming the operation.
+  pub operation: serde_json::Value,
+}
+
+// debug/parse
+/// Parse an inscription payload the way a new inscription is resolved by the indexer.
+///
+/// Returns the protocol which recognized the payload and the parsed operation, or a bad request
+/// error carrying the reason every protocol rejected it.
+pub(crate) async fn debug_parse(
+  Extension(index): Extension<Arc<Index>>,
+  Json(request): Json<ParseRequest>,
+) -> ApiResult<ParsedOperation> {
+  log::debug!("rpc: debug_parse: {:?}", request);
+
+  let inscription = Inscription::new(
+    request.content_type.map(String::into_bytes),
+    Some(request.body.into_bytes()),
+  );
+  let action = Action::New {
+    cursed: false,
+    unbound: false,
+    inscription: inscription.clone(),
+  };
+
+  let brc20_error = match brc20_proto::deserialize_brc20_operation(
+    &inscription,
+    &action,
+    index.lenient_content_type(),
+    index.protocol_json_limits(),
+  ) {
+    Ok(operation) => {
+      let operation = match operation {
+        brc20_proto::Operation::Deploy(deploy) => operation_json("deploy", deploy),
+        brc20_proto::Operation::Mint(mint) => operation_json("mint", mint),
+        brc20_proto::Operation::InscribeTransfer(transfer)
+        | brc20_proto::Operation::Transfer(transfer) => operation_json("transfer", transfer),
+      };
+      return Ok(Json(ApiResponse::ok(ParsedOperation {
+        protocol: "brc20".to_string(),
+        operation,
+      })));
+    }
+    Err(error) => error,
+  };
+
+  let brc20s_error = match brc20s_proto::deserialize_brc20s_operation(
+    &inscription,
+    &action,
+    index.protocol_json_limits(),
+  ) {
+    Ok(operation) => {
+      let operation = brc20s_operation_json(operation);
+      return Ok(Json(ApiResponse::ok(ParsedOperation {
+        protocol: "brc20s".to_string(),
+        operation,
+      })));
+    }
+    Err(error) => error,
+  };
+
+  Err(ApiError::bad_request(format!(
+    "brc20: {brc20_error}, brc20s: {brc20s_error}"
+  )))
+}
+
+fn brc20s_operation_json(operation: brc20s_proto::Operation) -> serde_json::Value {
+  match operation {
+    brc20s_proto::Operation::Deploy(deploy) => operation_json("deploy", deploy),
+    brc20s_proto::Operation::Mint(mint) => operation_json("mint", mint),
+    brc20s_proto::Operation::Stake(stake) => operation_json("deposit", stake),
+    brc20s_proto::Operation::UnStake(unstake) => operation_json("withdraw", unstake),
+    brc20s_proto::Operation::PassiveUnStake(unstake) => operation_json("passive_withdraw", unstake),
+    brc20s_proto::Operation::InscribeTransfer(transfer)
+    | brc20s_proto::Operation::Transfer(transfer) => operation_json("transfer", transfer),
+    brc20s_proto::Operation::Batch(operations) => {
+      operations.into_iter().map(brc20s_operation_json).collect()
+    }
+  }
+}
+
+fn operation_json<T: Serialize>(op: &str, operation: T) -> serde_json::Value {
+  let mut value = serde_json::to_value(operation).unwrap_or_default();
+  if let Some(fields) = value.as_object_mut() {
+    fields.insert("op".to_string(), op.into());
+  }
+  value
+}
@@ -1,5 +1,3 @@
-use serde::ser::SerializeStruct;
-
 use {super::*, utoipa::ToSchema};
 
 pub(super) enum ServerError {
@@ -48,65 +46,53 @@ impl From<Error> for ServerError {
   }
 }
 
-#[repr(i32)]
-#[derive(ToSchema)]
-pub(crate) enum ApiError {
-  /// Internal server error.
-  #[schema(example = json!(&ApiError::internal("internal error")))]
-  Internal(String) = 1,
-
-  /// Bad request.
-  #[schema(example = json!(&ApiError::internal("bad request")))]
-  BadRequest(String) = 2,
-
-  /// Resource not found.
-  #[schema(example = json!(&ApiError::internal("not found")))]
-  NotFound(String) = 3,
+/// Error body of the api. Codes 1, 2, 3, 4 and 5 are generic internal, bad request, not found,
+/// too many requests and service unavailable errors, the others identify a specific error, see `OrdError`, `BRC20Error` and `BRC20SError`.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct ApiError {
+  /// Error code.
+  #[schema(format = "int32", example = 3)]
+  code: i32,
+  /// Error message.
+  #[schema(example = "not found")]
+  msg: String,
+  #[serde(skip)]
+  status: StatusCode,
 }
 
 impl ApiError {
-  pub(crate) fn code(&self) -> i32 {
-    match self {
-      Self::Internal(_) => 1,
-      Self::BadRequest(_) => 2,
-      Self::NotFound(_) => 3,
+  pub(crate) fn new<S: ToString>(status: StatusCode, code: i32, message: S) -> Self {
+    Self {
+      code,
+      msg: message.to_string(),
+      status,
     }
   }
 
   pub(crate) fn not_found<S: ToString>(message: S) -> Self {
-    Self::NotFound(message.to_string())
+    Self::new(StatusCode::NOT_FOUND, 3, message)
   }
 
   pub(crate) fn internal<S: ToString>(message: S) -> Self {
-    Self::Internal(message.to_string())
+    Self::new(StatusCode::INTERNAL_SERVER_ERROR, 1, message)
   }
 
   pub(crate) fn bad_request<S: ToString>(message: S) -> Self {
-    Self::BadRequest(message.to_string())
+    Self::new(StatusCode::BAD_REQUEST, 2, message)
   }
-}
-impl Serialize for ApiError {
-  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-    let mut state = serializer.serialize_struct("ApiError", 2)?;
-    match self {
-      ApiError::Internal(msg) | ApiError::BadRequest(msg) | ApiError::NotFound(msg) => {
-        state.serialize_field("code", &self.code())?;
-        state.serialize_field("msg", &msg)?;
-        state.end()
-      }
-    }
+
+  pub(crate) fn too_many_requests<S: ToString>(message: S) -> Self {
+    Self::new(StatusCode::TOO_MANY_REQUESTS, 4, message)
+  }
+
+  pub(crate) fn service_unavailable<S: ToString>(message: S) -> Self {
+    Self::new(StatusCode::SERVICE_UNAVAILABLE, 5, message)
   }
 }
 
 impl IntoResponse for ApiError {
   fn into_response(self) -> Response {
-    let status_code = match &self {
-      Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
-      Self::BadRequest(_) => StatusCode::BAD_REQUEST,
-      Self::NotFound(_) => StatusCode::NOT_FOUND,
-    };
-
-    (status_code, axum::Json(self)).into_response()
+    (self.status, axum::Json(self)).into_response()
   }
 }
 
@@ -133,5 +119,13 @@ mod tests {
     let api_error = ApiError::not_found("not found");
     let json = serde_json::to_string(&api_error).unwrap();
     assert_eq!(json, r#"{"code":3,"msg":"not found"}"#);
+
+    let api_error = ApiError::too_many_requests("too many requests");
+    let json = serde_json::to_string(&api_error).unwrap();
+    assert_eq!(json, r#"{"code":4,"msg":"too many requests"}"#);
+
+    let api_error = ApiError::service_unavailable("service unavailable");
+    let json = serde_json::to_string(&api_error).unwrap();
+    assert_eq!(json, r#"{"code":5,"msg":"service unavailable"}"#);
   }
 }
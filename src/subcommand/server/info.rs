@@ -74,3 +74,103 @@ pub(crate) async fn node_info(
 
   Ok(Json(ApiResponse::ok(node_info)))
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct IndexStatus {
+  /// The chain the indexer runs on.
+  pub chain: String,
+  /// The height of the latest indexed block, absent before the first block is indexed.
+  #[schema(format = "uint64")]
+  pub block_height: Option<u64>,
+  /// The height of the latest block of bitcoind.
+  #[schema(format = "uint64")]
+  pub bitcoind_height: u64,
+  /// The number of blocks bitcoind is ahead of the indexer.
+  #[schema(format = "uint64")]
+  pub lag: u64,
+  /// The height of the latest block whose brc20 and brc20s operations are executed, absent before
+  /// the first one is.
+  #[schema(format = "uint64")]
+  pub protocol_height: Option<u64>,
+  /// The number of indexed blocks whose brc20 and brc20s operations wait for
+  /// `--protocol-confirmations`.
+  #[schema(format = "uint64")]
+  pub protocol_lag: u64,
+  /// Whether brc20s operations are indexed.
+  pub brc20s_enabled: bool,
+  /// Whether brc20 operations are indexed.
+  pub brc20_enabled: bool,
+}
+
+/// Answers the status endpoint, which load balancers may poll every few seconds. The height of
+/// bitcoind is kept for a short while so they don't turn into a stream of rpc calls.
+pub(crate) struct IndexLag {
+  max_lag: u64,
+  bitcoind_height: Mutex<Option<(Instant, u64)>>,
+}
+
+impl IndexLag {
+  const BITCOIND_HEIGHT_TTL: Duration = Duration::from_secs(2);
+
+  pub(crate) fn new(max_lag: u64) -> Self {
+    Self {
+      max_lag,
+      bitcoind_height: Mutex::new(None),
+    }
+  }
+
+  fn bitcoind_height(&self, index: &Index) -> Result<u64> {
+    let mut cached = self.bitcoind_height.lock().unwrap();
+    if let Some((fetched, height)) = *cached {
+      if fetched.elapsed() < Self::BITCOIND_HEIGHT_TTL {
+        return Ok(height);
+      }
+    }
+    let height = index.bitcoind_block_count()?;
+    *cached = Some((Instant::now(), height));
+    Ok(height)
+  }
+}
+
+/// Retrieve how far the indexer is behind bitcoind.
+///
+/// Responds with 503 when the indexer is more blocks behind bitcoind than the server allows, so load balancers can take the node out of rotation until it catches up.
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    responses(
+      (status = 200, description = "The indexer is in sync with bitcoind.", body = Status),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+      (status = 503, description = "The indexer lags behind bitcoind.", body = ApiError, example = json!(&ApiError::service_unavailable("index is 10 blocks behind bitcoind"))),
+    )
+  )]
+pub(crate) async fn index_status(
+  Extension(index): Extension<Arc<Index>>,
+  Extension(index_lag): Extension<Arc<IndexLag>>,
+) -> ApiResult<IndexStatus> {
+  log::debug!("rpc: get index_status");
+
+  let bitcoind_height = index_lag.bitcoind_height(&index)?;
+  let block_count = index.block_count()?;
+  let lag = (bitcoind_height + 1).saturating_sub(block_count);
+
+  if lag > index_lag.max_lag {
+    return Err(ApiError::service_unavailable(format!(
+      "index is {lag} blocks behind bitcoind, at most {} allowed",
+      index_lag.max_lag
+    )));
+  }
+
+  let protocol_height = index.protocol_block_height()?;
+
+  Ok(Json(ApiResponse::ok(IndexStatus {
+    chain: index.get_chain_network().to_string(),
+    block_height: block_count.checked_sub(1),
+    bitcoind_height,
+    lag,
+    protocol_height,
+    protocol_lag: block_count.saturating_sub(protocol_height.map_or(0, |height| height + 1)),
+    brc20s_enabled: index.has_brc20s_index(),
+    brc20_enabled: index.has_brc20_index(),
+  })))
+}
@@ -0,0 +1,97 @@
+use {
+  super::{error::ApiError, *},
+  axum::Json,
+  utoipa::ToSchema,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::BitmapDistrict)]
+#[serde(rename_all = "camelCase")]
+pub struct BitmapDistrict {
+  /// The bitmap district number.
+  #[schema(format = "uint64")]
+  pub number: u64,
+  /// The inscription id representing the district.
+  pub inscription_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::InscriptionCollections)]
+#[serde(rename_all = "camelCase")]
+pub struct InscriptionCollections {
+  /// The inscription id.
+  pub inscription_id: String,
+  /// Collections of the inscription.
+  pub collections: Vec<String>,
+}
+
+// /ord/bitmap/:number
+/// Retrieve the inscription representing the specified bitmap district.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/bitmap/{number}",
+  params(
+      ("number" = u64, Path, description = "bitmap district number")
+),
+  responses(
+    (status = 200, description = "Obtain the inscription of bitmap district.", body = OrdBitmapDistrict),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found or bitmap indexing disabled.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_bitmap_district(
+  Extension(index): Extension<Arc<Index>>,
+  Path(number): Path<u64>,
+) -> ApiResult<BitmapDistrict> {
+  log::debug!("rpc: get ord_bitmap_district: {number}");
+
+  if !index.has_bitmap_index() {
+    return Err(ApiError::from(OrdError::BitmapIndexDisabled));
+  }
+
+  let inscription_id = index
+    .ord_get_district_inscription_id(number)?
+    .ok_or_api_not_found(format!("district {number} not found"))?;
+
+  Ok(Json(ApiResponse::ok(BitmapDistrict {
+    number,
+    inscription_id: inscription_id.to_string(),
+  })))
+}
+
+// /ord/inscription/:id/collections
+/// Retrieve the collections of the specified inscription.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/inscription/{id}/collections",
+  params(
+      ("id" = String, Path, description = "inscription ID")
+),
+  responses(
+    (status = 200, description = "Obtain the collections of inscription.", body = OrdInscriptionCollections),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found or bitmap indexing disabled.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_inscription_collections(
+  Extension(index): Extension<Arc<Index>>,
+  Path(id): Path<String>,
+) -> ApiResult<InscriptionCollections> {
+  log::debug!("rpc: get ord_inscription_collections: {id}");
+  let id = InscriptionId::from_str(&id).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+  if !index.has_bitmap_index() {
+    return Err(ApiError::from(OrdError::BitmapIndexDisabled));
+  }
+
+  let collections = index
+    .ord_get_collections_by_inscription_id(id)?
+    .unwrap_or_default();
+
+  Ok(Json(ApiResponse::ok(InscriptionCollections {
+    inscription_id: id.to_string(),
+    collections: collections.iter().map(|c| c.to_string()).collect(),
+  })))
+}
@@ -1,13 +1,18 @@
 use {
-  super::*,
-  crate::okx::datastore::ord::{Action, InscriptionOp},
+  super::{types::ScriptPubkey, *},
+  crate::okx::datastore::{
+    ord::{Action, InscriptionOp},
+    ScriptKey,
+  },
+  tokio::sync::{Semaphore, SemaphorePermit},
 };
 
+mod collections;
 mod inscription;
 mod outpoint;
 mod transaction;
 
-pub(super) use {inscription::*, outpoint::*, transaction::*};
+pub(super) use {collections::*, inscription::*, outpoint::*, transaction::*};
 
 #[derive(Debug, thiserror::Error)]
 pub enum OrdError {
@@ -15,6 +20,35 @@ pub enum OrdError {
   OperationNotFound,
   #[error("block not found")]
   BlockNotFound,
+  #[error("bitmap indexing is disabled")]
+  BitmapIndexDisabled,
+  #[error("address history is not enabled")]
+  AddressHistoryDisabled,
+  #[error("invalid cursor")]
+  IncorrectCursorFormat,
+}
+
+impl OrdError {
+  /// Stable code of the error in api responses. Codes are never reused.
+  pub(crate) fn code(&self) -> i32 {
+    match self {
+      Self::OperationNotFound => 1001,
+      Self::BlockNotFound => 1002,
+      Self::BitmapIndexDisabled => 1003,
+      Self::AddressHistoryDisabled => 1004,
+      Self::IncorrectCursorFormat => 1005,
+    }
+  }
+}
+
+impl From<OrdError> for ApiError {
+  fn from(error: OrdError) -> Self {
+    let status = match error {
+      OrdError::IncorrectCursorFormat => StatusCode::BAD_REQUEST,
+      _ => StatusCode::NOT_FOUND,
+    };
+    ApiError::new(status, error.code(), error)
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -36,11 +70,33 @@ struct Flotsam {
   origin: Origin,
 }
 
+/// Caps the number of unconfirmed transactions simulated at once, every simulation queries
+/// bitcoind for the transaction and its inputs.
+#[derive(Clone)]
+pub(crate) struct SimulationLimiter(Arc<Semaphore>);
+
+impl SimulationLimiter {
+  pub(crate) fn new(max_simulations: usize) -> Self {
+    Self(Arc::new(Semaphore::new(max_simulations)))
+  }
+
+  /// The simulation slot is held until the returned permit is dropped, so it is released on
+  /// success, on error and when the request is cancelled.
+  pub(crate) fn try_acquire(&self) -> Result<SemaphorePermit<'_>, ApiError> {
+    self
+      .0
+      .try_acquire()
+      .map_err(|_| ApiError::too_many_requests("too many simulations in progress"))
+  }
+}
+
+/// Returns the inscription operations of the transaction, `None` if it is confirmed in a block
+/// which is not indexed yet.
 pub(super) fn get_ord_operations_by_txid(
   index: &Arc<Index>,
   txid: &bitcoin::Txid,
   with_unconfirmed: bool,
-) -> Result<Vec<InscriptionOp>> {
+) -> Result<Option<Vec<InscriptionOp>>> {
   let tx = index
     .get_transaction_info(txid)?
     .ok_or(anyhow!("can't get transaction info: {txid}"))?;
@@ -49,16 +105,44 @@ pub(super) fn get_ord_operations_by_txid(
     None => {
       if with_unconfirmed {
         // If the transaction is not confirmed, simulate indexing the transaction. Otherwise, retrieve it from the database.
-        simulate_index_ord_transaction(index, &tx.transaction()?, tx.txid)
+        simulate_index_ord_transaction(index, &tx.transaction()?, tx.txid).map(Some)
       } else {
         Err(anyhow!("transaction not confirmed: {txid}"))
       }
     }
-    // TODO: retrieve it from the database.
-    Some(_) => Err(anyhow!("not implemented")),
+    Some(_) => index.ord_txid_inscriptions(txid),
   }
 }
 
+// Outputs of an unconfirmed transaction are not indexed yet, so they are read from the
+// transaction itself or fetched from bitcoind.
+pub(super) fn get_script_key_on_outpoint(
+  index: &Arc<Index>,
+  tx: &Transaction,
+  outpoint: OutPoint,
+) -> Result<ScriptPubkey> {
+  let tx_out = if outpoint.txid == tx.txid() {
+    tx.output
+      .get(usize::try_from(outpoint.vout).unwrap())
+      .cloned()
+  } else if let Some(tx_out) = index.get_outpoint_entry(outpoint)? {
+    Some(tx_out)
+  } else {
+    index
+      .get_transaction_with_retries(outpoint.txid)?
+      .and_then(|prev_tx| {
+        prev_tx
+          .output
+          .get(usize::try_from(outpoint.vout).unwrap())
+          .cloned()
+      })
+  };
+
+  tx_out
+    .map(|tx_out| ScriptKey::from_script(&tx_out.script_pubkey, index.get_chain_network()).into())
+    .ok_or(anyhow!("outpoint not found {outpoint}"))
+}
+
 /// Simulate the execution of a transaction and parse out the inscription operation.
 fn simulate_index_ord_transaction(
   index: &Arc<Index>,
@@ -163,6 +247,8 @@ fn simulate_index_ord_transaction(
     }
   }
 
+  // the sort is stable, so inscriptions sharing an offset stay in the order they were collected,
+  // transfers by their position on the input and then new inscriptions, as the updater does.
   floating_inscriptions.sort_by_key(|flotsam| flotsam.offset);
   let mut inscriptions = floating_inscriptions.into_iter().peekable();
 
@@ -184,6 +270,7 @@ fn simulate_index_ord_transaction(
       };
 
       let flotsam = inscriptions.next().unwrap();
+      let inscription_number = inscription_number(index, &flotsam)?;
 
       // Find the inscription with the output position and add it to the vector.
       operations.push(InscriptionOp {
@@ -200,8 +287,7 @@ fn simulate_index_ord_transaction(
           },
           Origin::Old => Action::Transfer,
         },
-        // Unknown number, replaced with zero.
-        inscription_number: None,
+        inscription_number,
         inscription_id: flotsam.inscription_id,
         old_satpoint: flotsam.old_satpoint,
         new_satpoint: Some(new_satpoint),
@@ -212,26 +298,158 @@ fn simulate_index_ord_transaction(
   }
 
   // Inscription not found with matching output position.
-  operations.extend(inscriptions.map(|flotsam| InscriptionOp {
-    txid: flotsam.txid,
-    action: match flotsam.origin {
-      Origin::New {
-        cursed,
-        unbound,
-        inscription,
-      } => Action::New {
-        cursed,
-        unbound,
-        inscription,
+  for flotsam in inscriptions {
+    operations.push(InscriptionOp {
+      txid: flotsam.txid,
+      inscription_number: inscription_number(index, &flotsam)?,
+      action: match flotsam.origin {
+        Origin::New {
+          cursed,
+          unbound,
+          inscription,
+        } => Action::New {
+          cursed,
+          unbound,
+          inscription,
+        },
+        Origin::Old => Action::Transfer,
       },
-      Origin::Old => Action::Transfer,
-    },
-    inscription_number: None,
-    inscription_id: flotsam.inscription_id,
-    old_satpoint: flotsam.old_satpoint,
-    // We use a zero satpoint to represent the default position.
-    new_satpoint: None,
-  }));
+      inscription_id: flotsam.inscription_id,
+      old_satpoint: flotsam.old_satpoint,
+      // We use a zero satpoint to represent the default position.
+      new_satpoint: None,
+    });
+  }
 
   Ok(operations)
 }
+
+// the number of a transferred inscription, a new one has no number until its block is indexed.
+fn inscription_number(index: &Arc<Index>, flotsam: &Flotsam) -> Result<Option<i64>> {
+  Ok(match flotsam.origin {
+    Origin::Old => index
+      .get_inscription_entry(flotsam.inscription_id)?
+      .map(|entry| entry.number),
+    Origin::New { .. } => None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::Witness, tempfile::TempDir, test_bitcoincore_rpc::TransactionTemplate};
+
+  #[test]
+  fn simulation_limiter_rejects_excess_simulations() {
+    let limiter = SimulationLimiter::new(2);
+
+    let first = limiter.try_acquire().unwrap();
+    let _second = limiter.try_acquire().unwrap();
+    assert_eq!(
+      limiter.try_acquire().unwrap_err(),
+      ApiError::too_many_requests("too many simulations in progress")
+    );
+
+    drop(first);
+    assert!(limiter.try_acquire().is_ok());
+  }
+
+  #[test]
+  fn simulation_limiter_releases_permit_on_error() {
+    let limiter = SimulationLimiter::new(1);
+
+    let simulate = || -> Result<(), ApiError> {
+      let _permit = limiter.try_acquire()?;
+      Err(ApiError::internal("simulation failed"))
+    };
+    assert!(simulate().is_err());
+    assert!(simulate().is_err());
+
+    assert!(limiter.try_acquire().is_ok());
+  }
+
+  #[test]
+  fn simulated_inscriptions_sharing_an_offset_keep_index_order() {
+    let rpc_server = test_bitcoincore_rpc::builder()
+      .network(Network::Regtest)
+      .build();
+    let tempdir = TempDir::new().unwrap();
+    let cookie_file = tempdir.path().join("cookie");
+    fs::write(&cookie_file, "username:password").unwrap();
+    let options = Options::try_parse_from([
+      "ord".as_ref(),
+      "--rpc-url".as_ref(),
+      rpc_server.url().as_ref(),
+      "--data-dir".as_ref(),
+      tempdir.path().as_os_str(),
+      "--cookie-file".as_ref(),
+      cookie_file.as_os_str(),
+      "--regtest".as_ref(),
+    ])
+    .unwrap();
+    let index = Arc::new(Index::open(&options).unwrap());
+
+    rpc_server.mine_blocks(1);
+    let first = InscriptionId {
+      txid: rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "first").to_witness())],
+        ..Default::default()
+      }),
+      index: 0,
+    };
+    rpc_server.mine_blocks(1);
+    // the reinscription lands on the sat of the first inscription.
+    let second = InscriptionId {
+      txid: rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 1, 0, inscription("text/plain", "second").to_witness())],
+        ..Default::default()
+      }),
+      index: 0,
+    };
+    rpc_server.mine_blocks(1);
+    index.update().unwrap();
+
+    let spend = TransactionTemplate {
+      inputs: &[(3, 1, 0, Witness::new())],
+      ..Default::default()
+    };
+    let tx = Transaction {
+      version: 2,
+      lock_time: bitcoin::absolute::LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: second.txid,
+          vout: 0,
+        },
+        ..Default::default()
+      }],
+      output: vec![TxOut {
+        value: 50 * COIN_VALUE,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+    let operations = simulate_index_ord_transaction(&index, &tx, tx.txid()).unwrap();
+    assert_eq!(
+      operations
+        .iter()
+        .map(|operation| (
+          operation.inscription_id,
+          operation.new_satpoint.unwrap().offset
+        ))
+        .collect::<Vec<(InscriptionId, u64)>>(),
+      [(first, 0), (second, 0)]
+    );
+
+    let txid = rpc_server.broadcast_tx(spend);
+    rpc_server.mine_blocks(1);
+    index.update().unwrap();
+    assert_eq!(
+      index
+        .get_inscriptions_on_output_with_satpoints(OutPoint { txid, vout: 0 })
+        .unwrap()
+        .into_iter()
+        .map(|(_, inscription_id)| inscription_id)
+        .collect::<Vec<InscriptionId>>(),
+      [first, second]
+    );
+  }
+}
@@ -51,7 +51,7 @@ pub struct TxInscription {
 }
 
 impl TxInscription {
-  pub(super) fn new(op: InscriptionOp, index: Arc<Index>) -> Result<Self> {
+  pub(crate) fn new(op: InscriptionOp, index: Arc<Index>) -> Result<Self> {
     let from = index
       .get_outpoint_entry(op.old_satpoint.outpoint)?
       .map(|txout| ScriptKey::from_script(&txout.script_pubkey, index.get_chain_network()))
@@ -89,6 +89,29 @@ impl TxInscription {
       new_satpoint: op.new_satpoint.map(|v| v.to_string()),
     })
   }
+
+  /// Same as `new`, for an operation of `tx` whose outputs need not be indexed yet.
+  pub(crate) fn new_in_transaction(
+    op: InscriptionOp,
+    index: &Arc<Index>,
+    tx: &Transaction,
+  ) -> Result<Self> {
+    let to = match op.new_satpoint {
+      Some(new_satpoint) if new_satpoint.outpoint != unbound_outpoint() => Some(
+        get_script_key_on_outpoint(index, tx, new_satpoint.outpoint)?,
+      ),
+      _ => None,
+    };
+    Ok(TxInscription {
+      from: get_script_key_on_outpoint(index, tx, op.old_satpoint.outpoint)?,
+      to,
+      action: op.action.into(),
+      inscription_number: op.inscription_number,
+      inscription_id: op.inscription_id.to_string(),
+      old_satpoint: op.old_satpoint.to_string(),
+      new_satpoint: op.new_satpoint.map(|v| v.to_string()),
+    })
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -132,7 +155,7 @@ pub(crate) async fn ord_txid_inscriptions(
 
   let ops = index
     .ord_txid_inscriptions(&txid)?
-    .ok_or_api_not_found(OrdError::OperationNotFound)?;
+    .ok_or(OrdError::OperationNotFound)?;
 
   log::debug!("rpc: get ord_txid_inscriptions: {:?}", ops);
 
@@ -156,6 +179,64 @@ pub(crate) async fn ord_txid_inscriptions(
   })))
 }
 
+// ord/tx/:txid/operations
+/// Retrieve the inscription operations of the given transaction.
+///
+/// The operations of a confirmed transaction are read from the index, those of an unconfirmed
+/// one are simulated against the latest indexed state with `unconfirmed=true`. The number of an
+/// inscription is null until it is indexed.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/tx/{txid}/operations",
+  params(
+      ("txid" = String, Path, description = "transaction ID"),
+      UnconfirmedQuery
+),
+  responses(
+    (status = 200, description = "Obtain inscription operations by txid", body = OrdTxInscriptions),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 429, description = "Too many simulations.", body = ApiError, example = json!(&ApiError::too_many_requests("too many simulations in progress"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_txid_operations(
+  Extension(index): Extension<Arc<Index>>,
+  Extension(simulations): Extension<SimulationLimiter>,
+  Path(txid): Path<String>,
+  Query(query): Query<UnconfirmedQuery>,
+) -> ApiResult<TxInscriptions> {
+  log::debug!("rpc: get ord_txid_operations: {}", txid);
+  let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
+  let tx_info = index
+    .get_transaction_info(&txid)?
+    .ok_or(OrdError::OperationNotFound)?;
+
+  let _permit = if tx_info.confirmations.is_none() {
+    if !query.unconfirmed.unwrap_or(false) {
+      return Err(ApiError::bad_request(
+        "the transaction is unconfirmed, set unconfirmed=true to simulate it",
+      ));
+    }
+    Some(simulations.try_acquire()?)
+  } else {
+    None
+  };
+
+  let tx = tx_info.transaction().map_err(ApiError::internal)?;
+  let ops = get_ord_operations_by_txid(&index, &txid, true)?.ok_or(OrdError::OperationNotFound)?;
+
+  log::debug!("rpc: get ord_txid_operations: {:?}", ops);
+
+  Ok(Json(ApiResponse::ok(TxInscriptions {
+    inscriptions: ops
+      .into_iter()
+      .map(|op| TxInscription::new_in_transaction(op, &index, &tx))
+      .collect::<Result<_>>()?,
+    txid: txid.to_string(),
+  })))
+}
+
 // ord/block/:blockhash/inscriptions
 /// Retrieve the inscription actions from the given block.
 #[utoipa::path(
@@ -182,17 +263,17 @@ pub(crate) async fn ord_block_inscriptions(
   let blockinfo = index
     .get_block_info_by_hash(blockhash)
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(OrdError::BlockNotFound)?;
+    .ok_or(OrdError::BlockNotFound)?;
 
   // get blockhash from redb.
   let blockhash = index
     .block_hash(Some(u64::try_from(blockinfo.height).unwrap()))
     .map_err(ApiError::internal)?
-    .ok_or_api_not_found(OrdError::BlockNotFound)?;
+    .ok_or(OrdError::BlockNotFound)?;
 
   // check of conflicting block.
   if blockinfo.hash != blockhash {
-    return Err(ApiError::NotFound(OrdError::BlockNotFound.to_string()));
+    return Err(ApiError::from(OrdError::BlockNotFound));
   }
 
   let block_inscriptions = index
@@ -0,0 +1,93 @@
+use {
+  super::*,
+  axum::{
+    http::{Request, Uri},
+    middleware::Next,
+  },
+};
+
+pub(crate) const PAGE_LIMIT_HEADER: &str = "x-page-limit";
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MaxPageSize(pub(crate) usize);
+
+/// Lowers a `limit` above the max page size of the server to the max page size before the request
+/// reaches its handler. The response of a clamped request carries the applied limit in
+/// `X-Page-Limit`.
+pub(super) async fn clamp_limit<B>(
+  Extension(max_page_size): Extension<MaxPageSize>,
+  mut request: Request<B>,
+  next: Next<B>,
+) -> Response {
+  let Some(query) = request
+    .uri()
+    .query()
+    .and_then(|query| clamp_query(query, max_page_size.0))
+  else {
+    return next.run(request).await;
+  };
+
+  let mut parts = request.uri().clone().into_parts();
+  parts.path_and_query = match format!("{}?{query}", request.uri().path()).parse() {
+    Ok(path_and_query) => Some(path_and_query),
+    Err(err) => {
+      return ServerError::Internal(anyhow!("invalid query {query}: {err}")).into_response()
+    }
+  };
+  *request.uri_mut() = match Uri::from_parts(parts) {
+    Ok(uri) => uri,
+    Err(err) => return ServerError::Internal(anyhow!("invalid uri: {err}")).into_response(),
+  };
+
+  let mut response = next.run(request).await;
+  response
+    .headers_mut()
+    .insert(PAGE_LIMIT_HEADER, HeaderValue::from(max_page_size.0));
+  response
+}
+
+// the query with every `limit` above the max page size replaced by it, none if nothing is clamped.
+// A limit which is not a number is left for the handler to reject.
+fn clamp_query(query: &str, max_page_size: usize) -> Option<String> {
+  let mut clamped = false;
+  let query = query
+    .split('&')
+    .map(|pair| {
+      match pair
+        .strip_prefix("limit=")
+        .and_then(|limit| limit.parse::<usize>().ok())
+      {
+        Some(limit) if limit > max_page_size => {
+          clamped = true;
+          format!("limit={max_page_size}")
+        }
+        _ => pair.to_string(),
+      }
+    })
+    .collect::<Vec<String>>()
+    .join("&");
+  clamped.then_some(query)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn clamp() {
+    for (query, clamped) in [
+      ("limit=100000", Some("limit=500")),
+      (
+        "start=10&limit=501&cursor=abc",
+        Some("start=10&limit=500&cursor=abc"),
+      ),
+      ("limit=500", None),
+      ("limit=1", None),
+      ("start=100000", None),
+      ("limit=abc", None),
+      ("", None),
+    ] {
+      assert_eq!(clamp_query(query, 500).as_deref(), clamped, "{query}");
+    }
+  }
+}
@@ -1,5 +1,8 @@
 use {
-  super::{info::NodeInfo, *},
+  super::{
+    info::{IndexStatus, NodeInfo},
+    *,
+  },
   utoipa::ToSchema,
 };
 #[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -8,29 +11,51 @@ use {
   BRC20AllTick = ApiResponse<brc20::AllTickInfo>,
   BRC20Balance = ApiResponse<brc20::Balance>,
   BRC20AllBalance = ApiResponse<brc20::AllBalance>,
+  BRC20BatchBalance = ApiResponse<brc20::BatchBalance>,
   BRC20TxEvents = ApiResponse<brc20::TxEvents>,
   BRC20BlockEvents = ApiResponse<brc20::BlockEvents>,
+  BRC20TickEvents = ApiResponse<brc20::TickEvents>,
   BRC20Transferable = ApiResponse<brc20::TransferableInscriptions>,
 
   BRC20STick = ApiResponse<brc20s::TickInfo>,
+  BRC20STickDeploy = ApiResponse<brc20s::TickDeploy>,
   BRC20SAllTick = ApiResponse<brc20s::AllTickInfo>,
+  BRC20SDeployedTicks = ApiResponse<brc20s::DeployedTicks>,
   BRC20SBalance = ApiResponse<brc20s::Balance>,
   BRC20SAllBalance = ApiResponse<brc20s::AllBalance>,
   BRC20SPool = ApiResponse<brc20s::Pool>,
   BRC20SAllPool = ApiResponse<brc20s::AllPoolInfo>,
+  BRC20SPoolBlocksRemaining = ApiResponse<brc20s::PoolBlocksRemaining>,
+  BRC20SStakeTicks = ApiResponse<brc20s::StakeTicks>,
+  BRC20SEarnTicks = ApiResponse<brc20s::EarnTicks>,
   BRC20STxReceipts = ApiResponse<brc20s::TxReceipts>,
+  BRC20STxInscriptions = ApiResponse<brc20s::TxInscriptions>,
   BRC20SBlockReceipts = ApiResponse<brc20s::BlockReceipts>,
+  BRC20SOperationStats = ApiResponse<brc20s::OperationStats>,
   BRC20STransferable = ApiResponse<brc20s::Transferable>,
+  BRC20SPendingTransfers = ApiResponse<brc20s::PendingTransfers>,
   BRC20SUserInfo = ApiResponse<brc20s::UserInfo>,
+  BRC20SUserReward = ApiResponse<brc20s::UserReward>,
+  BRC20SAddressRewards = ApiResponse<brc20s::AddressRewards>,
+  BRC20SAddressPools = ApiResponse<brc20s::AddressPools>,
   BRC20SStakedInfo = ApiResponse<brc20s::StakedInfo>,
+  BRC20SAddressStakes = ApiResponse<brc20s::AddressStakes>,
+
+  AddressTokens = ApiResponse<address::Tokens>,
+  AddressHistory = ApiResponse<address::History>,
+
+  Batch = ApiResponse<batch::Batch>,
 
   OrdOrdInscription = ApiResponse<ord::OrdInscription>,
   OrdOutPointData = ApiResponse<ord::OutPointData>,
   OrdOutPointResult = ApiResponse<ord::OutPointResult>,
   OrdTxInscriptions = ApiResponse<ord::TxInscriptions>,
   OrdBlockInscriptions = ApiResponse<ord::BlockInscriptions>,
+  OrdBitmapDistrict = ApiResponse<ord::BitmapDistrict>,
+  OrdInscriptionCollections = ApiResponse<ord::InscriptionCollections>,
 
-  Node = ApiResponse<NodeInfo>
+  Node = ApiResponse<NodeInfo>,
+  Status = ApiResponse<IndexStatus>
 )]
 pub(crate) struct ApiResponse<T: Serialize> {
   pub code: i32,
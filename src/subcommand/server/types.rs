@@ -24,6 +24,25 @@ impl From<ScriptKey> for ScriptPubkey {
     }
   }
 }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SatInfo {
+  /// The sat number which carries the inscription.
+  #[schema(format = "uint64")]
+  pub number: u64,
+  /// The rarity of the sat.
+  pub rarity: String,
+}
+
+impl From<Sat> for SatInfo {
+  fn from(sat: Sat) -> Self {
+    Self {
+      number: sat.n(),
+      rarity: sat.rarity().to_string(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -58,4 +77,18 @@ mod tests {
       r#"{"nonStandard":"df65c8a338dce7900824e7bd18c336656ca19e57"}"#
     );
   }
+
+  #[test]
+  fn serialize_sat_info() {
+    let sat_info = SatInfo::from(Sat(0));
+    assert_eq!(
+      serde_json::to_string(&sat_info).unwrap(),
+      r#"{"number":0,"rarity":"mythic"}"#
+    );
+    let sat_info = SatInfo::from(Sat(50 * COIN_VALUE));
+    assert_eq!(
+      serde_json::to_string(&sat_info).unwrap(),
+      r#"{"number":5000000000,"rarity":"uncommon"}"#
+    );
+  }
 }
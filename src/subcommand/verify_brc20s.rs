@@ -0,0 +1,33 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct VerifyBrc20s {}
+
+impl VerifyBrc20s {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    // verify the state as indexed, without updating it first.
+    let report = index.brc20s_verify()?;
+    for mismatch in &report.supply {
+      log::error!(
+        "brc20s supply mismatch: tick {} circulation {:?} balances total {}",
+        mismatch.tick_id.hex(),
+        mismatch.circulation,
+        mismatch.balance_total
+      );
+    }
+    for mismatch in &report.transferable {
+      log::error!(
+        "brc20s transferable mismatch: script {} tick {} overall {} transferable {} assets total {}",
+        mismatch.script,
+        mismatch.tick_id.hex(),
+        mismatch.overall_balance,
+        mismatch.transferable_balance,
+        mismatch.transferable_total
+      );
+    }
+
+    Ok(Box::new(report))
+  }
+}
@@ -3,3 +3,18 @@ use super::*;
 pub mod create;
 pub mod inscribe;
 pub mod outputs;
+mod send_brc20s;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Wallet {
+  #[command(about = "Send a brc20s transfer inscription")]
+  SendBrc20s(send_brc20s::SendBrc20s),
+}
+
+impl Wallet {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    match self {
+      Self::SendBrc20s(send) => send.run(options),
+    }
+  }
+}
@@ -0,0 +1,245 @@
+use {
+  super::*,
+  bitcoin::{absolute::LockTime, Witness},
+  bitcoincore_rpc::json::AddressType,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct SendBrc20s {
+  #[arg(long, help = "Send the brc20s transfer <INSCRIPTION>.")]
+  inscription: InscriptionId,
+  #[arg(long, help = "Send the inscription to <TO>.")]
+  to: Address<NetworkUnchecked>,
+  #[arg(long, help = "Pay <FEE_RATE> sats/vB in fees.")]
+  fee_rate: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+}
+
+impl SendBrc20s {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let to = self.to.require_network(options.chain().network())?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs()?;
+    let inscriptions = index.get_inscriptions(unspent_outputs.clone())?;
+
+    let satpoint = index
+      .get_inscription_satpoint_by_id(self.inscription)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription))?;
+
+    let postage = *unspent_outputs
+      .get(&satpoint.outpoint)
+      .ok_or_else(|| anyhow!("inscription {} is not in the wallet", self.inscription))?;
+
+    if inscriptions.iter().any(|(other_satpoint, other_id)| {
+      other_satpoint.outpoint == satpoint.outpoint && *other_id != self.inscription
+    }) {
+      bail!(
+        "output {} holds other inscriptions besides {}",
+        satpoint.outpoint,
+        self.inscription
+      );
+    }
+
+    let owner = index
+      .get_transaction(satpoint.outpoint.txid)?
+      .and_then(|tx| {
+        tx.output
+          .into_iter()
+          .nth(satpoint.outpoint.vout.try_into().unwrap())
+      })
+      .ok_or_else(|| anyhow!("output {} not found", satpoint.outpoint))?
+      .script_pubkey;
+
+    if index
+      .brc20s_transferable_by_id(&owner, &self.inscription)?
+      .is_none()
+    {
+      bail!(
+        "inscription {} is not a brc20s transferable asset",
+        self.inscription
+      );
+    }
+
+    let inscribed_outputs = inscriptions
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<HashSet<OutPoint>>();
+
+    let cardinal_outputs = unspent_outputs
+      .into_iter()
+      .filter(|(outpoint, _)| !inscribed_outputs.contains(outpoint))
+      .collect::<Vec<(OutPoint, Amount)>>();
+
+    let change = client
+      .get_raw_change_address(Some(AddressType::Bech32m))?
+      .require_network(options.chain().network())?;
+
+    let unsigned_transaction = build_transaction(
+      satpoint,
+      postage,
+      cardinal_outputs,
+      &to,
+      &change,
+      self.fee_rate,
+    )?;
+
+    let signed_transaction = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let transaction = client.send_raw_transaction(&signed_transaction)?;
+
+    Ok(Box::new(Output { transaction }))
+  }
+}
+
+/// Builds a transaction moving the output holding the inscription at `satpoint` to `to`.
+///
+/// The inscription output is the first input and its whole value goes to the first output, so the
+/// inscribed sat keeps its offset and lands at `to`. Fees are paid by cardinal outputs, largest
+/// first, with the remainder going to `change`.
+fn build_transaction(
+  satpoint: SatPoint,
+  postage: Amount,
+  mut cardinal_outputs: Vec<(OutPoint, Amount)>,
+  to: &Address,
+  change: &Address,
+  fee_rate: u64,
+) -> Result<Transaction> {
+  cardinal_outputs
+    .sort_by(|(a_outpoint, a), (b_outpoint, b)| b.cmp(a).then_with(|| a_outpoint.cmp(b_outpoint)));
+
+  let mut transaction = Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![tx_in(satpoint.outpoint)],
+    output: vec![
+      TxOut {
+        value: postage.to_sat(),
+        script_pubkey: to.script_pubkey(),
+      },
+      TxOut {
+        value: 0,
+        script_pubkey: change.script_pubkey(),
+      },
+    ],
+  };
+
+  let dust_value = change.script_pubkey().dust_value().to_sat();
+
+  let mut cardinal_value = 0;
+  for (outpoint, amount) in cardinal_outputs {
+    transaction.input.push(tx_in(outpoint));
+    cardinal_value += amount.to_sat();
+
+    let fee = estimate_fee(&transaction, fee_rate);
+    if cardinal_value >= fee + dust_value {
+      transaction.output[1].value = cardinal_value - fee;
+      return Ok(transaction);
+    }
+  }
+
+  bail!(
+    "wallet does not contain enough cardinal UTXOs to pay a fee of {} sats",
+    estimate_fee(&transaction, fee_rate) + dust_value
+  );
+}
+
+fn tx_in(previous_output: OutPoint) -> TxIn {
+  TxIn {
+    previous_output,
+    script_sig: ScriptBuf::new(),
+    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+    witness: Witness::new(),
+  }
+}
+
+// the fee of the transaction once every input is signed with a taproot key path signature.
+fn estimate_fee(transaction: &Transaction, fee_rate: u64) -> u64 {
+  let mut signed = transaction.clone();
+  for input in &mut signed.input {
+    input.witness = Witness::from_slice(&[&[0; 64]]);
+  }
+  u64::try_from(signed.vsize()).unwrap() * fee_rate
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn inscription_sat_is_routed_to_destination() {
+    let satpoint = satpoint(1, 5000);
+    let to = recipient();
+    let change = change(0);
+
+    let transaction = build_transaction(
+      satpoint,
+      Amount::from_sat(10_000),
+      vec![
+        (outpoint(2), Amount::from_sat(1_000)),
+        (outpoint(3), Amount::from_sat(50_000)),
+      ],
+      &to,
+      &change,
+      2,
+    )
+    .unwrap();
+
+    assert_eq!(transaction.input[0].previous_output, satpoint.outpoint);
+    assert_eq!(transaction.input[1].previous_output, outpoint(3));
+    assert_eq!(transaction.input.len(), 2);
+
+    // the inscription input comes first, so its sat sits at the same offset in the outputs
+    let mut start = 0;
+    let destination = transaction
+      .output
+      .iter()
+      .find(|output| {
+        start += output.value;
+        satpoint.offset < start
+      })
+      .unwrap();
+    assert_eq!(destination.script_pubkey, to.script_pubkey());
+    assert_eq!(destination.value, 10_000);
+
+    let fee = estimate_fee(&transaction, 2);
+    assert_eq!(transaction.output[1].script_pubkey, change.script_pubkey());
+    assert_eq!(transaction.output[1].value, 50_000 - fee);
+    assert!(fee >= transaction.vsize() as u64 * 2);
+  }
+
+  #[test]
+  fn insufficient_cardinal_outputs() {
+    assert!(build_transaction(
+      satpoint(1, 0),
+      Amount::from_sat(10_000),
+      vec![(outpoint(2), Amount::from_sat(500))],
+      &recipient(),
+      &change(0),
+      10,
+    )
+    .unwrap_err()
+    .to_string()
+    .starts_with("wallet does not contain enough cardinal UTXOs"));
+
+    assert!(build_transaction(
+      satpoint(1, 0),
+      Amount::from_sat(10_000),
+      Vec::new(),
+      &recipient(),
+      &change(0),
+      1,
+    )
+    .is_err());
+  }
+}
@@ -123,6 +123,9 @@ pub struct TransactionTemplate<'a> {
   pub inputs: &'a [(usize, usize, usize, Witness)],
   pub output_values: &'a [u64],
   pub outputs: usize,
+  pub recipient: Option<Address>,
+  // recipients of the outputs by index, the outputs past them are sent to `recipient`.
+  pub recipients: &'a [Address],
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -154,6 +157,8 @@ impl<'a> Default for TransactionTemplate<'a> {
       inputs: &[],
       output_values: &[],
       outputs: 1,
+      recipient: None,
+      recipients: &[],
     }
   }
 }
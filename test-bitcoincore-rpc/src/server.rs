@@ -387,28 +387,33 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
     if verbose.unwrap_or(false) {
-      match self.state().transactions.get(&txid) {
-        Some(_) => Ok(
-          serde_json::to_value(GetRawTransactionResult {
-            in_active_chain: Some(true),
-            hex: Vec::new(),
-            txid: Txid::all_zeros(),
-            hash: Wtxid::all_zeros(),
-            size: 0,
-            vsize: 0,
-            version: 0,
-            locktime: 0,
-            vin: Vec::new(),
-            vout: Vec::new(),
-            blockhash: None,
-            confirmations: Some(1),
-            time: None,
-            blocktime: None,
-          })
-          .unwrap(),
-        ),
-        None => Err(Self::not_found()),
-      }
+      let state = self.state();
+      let (tx, confirmations) = match state.transactions.get(&txid) {
+        Some(tx) => (tx, Some(1)),
+        None => match state.mempool.iter().find(|tx| tx.txid() == txid) {
+          Some(tx) => (tx, None),
+          None => return Err(Self::not_found()),
+        },
+      };
+      Ok(
+        serde_json::to_value(GetRawTransactionResult {
+          in_active_chain: Some(true),
+          hex: serialize(tx),
+          txid,
+          hash: Wtxid::all_zeros(),
+          size: 0,
+          vsize: 0,
+          version: 0,
+          locktime: 0,
+          vin: Vec::new(),
+          vout: Vec::new(),
+          blockhash: None,
+          confirmations,
+          time: None,
+          blocktime: None,
+        })
+        .unwrap(),
+      )
     } else {
       match self.state().transactions.get(&txid) {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
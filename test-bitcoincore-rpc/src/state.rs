@@ -159,7 +159,12 @@ impl State {
             .get(i)
             .cloned()
             .unwrap_or(value_per_output),
-          script_pubkey: script::Builder::new().into_script(),
+          script_pubkey: template
+            .recipients
+            .get(i)
+            .or(template.recipient.as_ref())
+            .map(|recipient| recipient.script_pubkey())
+            .unwrap_or_else(|| script::Builder::new().into_script()),
         })
         .collect(),
     };